@@ -113,6 +113,12 @@ impl CreationContext<'_> {
     /// Create a new empty [CreationContext] for testing [App]s in kittest.
     #[doc(hidden)]
     pub fn _new_kittest(egui_ctx: egui::Context) -> Self {
+        Self::new_windowless(egui_ctx)
+    }
+
+    /// A [`CreationContext`] without a window, graphics context or storage,
+    /// e.g. for [`crate::HeadlessRunner`].
+    pub(crate) fn new_windowless(egui_ctx: egui::Context) -> Self {
         Self {
             egui_ctx,
             integration_info: IntegrationInfo::mock(),
@@ -729,6 +735,12 @@ impl Frame {
     /// Create a new empty [Frame] for testing [App]s in kittest.
     #[doc(hidden)]
     pub fn _new_kittest() -> Self {
+        Self::new_windowless()
+    }
+
+    /// A [`Frame`] without a window, graphics context or storage,
+    /// e.g. for [`crate::HeadlessRunner`].
+    pub(crate) fn new_windowless() -> Self {
         Self {
             #[cfg(feature = "glow")]
             gl: None,
@@ -0,0 +1,551 @@
+//! Run an [`App`] without a window, e.g. on a server.
+//!
+//! The [`HeadlessRunner`] drives the frames of an [`App`] with a virtual clock and a fixed viewport size.
+//! Nothing is shown on screen, but you can render any frame to an image on the CPU,
+//! using [`egui::epaint::SoftwareRenderer`].
+//!
+//! [`egui::ViewportCommand`]s are handled like a window would:
+//! * [`egui::ViewportCommand::Screenshot`] renders the frame, and sends the image back as [`egui::Event::Screenshot`].
+//!   The screenshots are also collected in the runner, see [`HeadlessRunner::take_screenshots`].
+//! * [`egui::ViewportCommand::Close`] stops the runner (unless the app responds with [`egui::ViewportCommand::CancelClose`]).
+//! * [`egui::ViewportCommand::InnerSize`] resizes the viewport.
+//! * Commands that only make sense for a real window (e.g. [`egui::ViewportCommand::StartDrag`]) are ignored.
+//!
+//! Paint callbacks (e.g. custom `wgpu` or `glow` rendering) are not supported.
+
+use std::{sync::Arc, time::Duration};
+
+use egui::{
+    Color32, ColorImage, Event, FullOutput, OutputCommand, RawInput, Rect, UserData, Vec2,
+    ViewportCommand, ViewportEvent, ViewportId, ViewportInfo, epaint::SoftwareRenderer,
+};
+
+use crate::{App, AppCreator, CreationContext, Error, Frame, Result};
+
+/// Options for [`run_headless`] and [`HeadlessRunner`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct HeadlessOptions {
+    /// The size of the viewport, in points when the app is not zoomed.
+    ///
+    /// Like for a window, the size in pixels stays the same when the app zooms in or out
+    /// (see [`egui::Context::set_zoom_factor`]): the app gets fewer or more points instead.
+    ///
+    /// Can be changed by the app with [`egui::ViewportCommand::InnerSize`].
+    ///
+    /// Default: `800x600`.
+    pub inner_size: Vec2,
+
+    /// The number of physical pixels per point, for the rendered images.
+    ///
+    /// Default: `1.0`.
+    pub pixels_per_point: f32,
+
+    /// How much the virtual clock advances each frame.
+    ///
+    /// If the app asks for a repaint further into the future
+    /// (with [`egui::Context::request_repaint_after`]),
+    /// the clock jumps ahead to that time instead, so there is no waiting.
+    ///
+    /// Default: 1/60 s.
+    pub frame_time: Duration,
+
+    /// Stop [`HeadlessRunner::run`] after this many frames, even if the app is still running.
+    ///
+    /// Default: `Some(10_000)`.
+    pub max_frames: Option<u64>,
+}
+
+impl Default for HeadlessOptions {
+    fn default() -> Self {
+        Self {
+            inner_size: Vec2::new(800.0, 600.0),
+            pixels_per_point: 1.0,
+            frame_time: Duration::from_secs(1) / 60,
+            max_frames: Some(10_000),
+        }
+    }
+}
+
+/// A screenshot taken with [`egui::ViewportCommand::Screenshot`] in a [`HeadlessRunner`].
+#[derive(Clone, Debug)]
+pub struct Screenshot {
+    /// The frame that was captured, counting from zero.
+    pub frame_nr: u64,
+
+    /// The time of the virtual clock for the frame, in seconds.
+    pub time: f64,
+
+    /// Whatever was passed to [`egui::ViewportCommand::Screenshot`].
+    pub user_data: UserData,
+
+    /// The rendered frame.
+    pub image: Arc<ColorImage>,
+}
+
+/// Runs an [`App`] until it closes itself with [`egui::ViewportCommand::Close`], without opening a window.
+///
+/// Returns the screenshots the app took with [`egui::ViewportCommand::Screenshot`].
+///
+/// The app also stops when it goes idle (no repaint requested and no pending input),
+/// or after [`HeadlessOptions::max_frames`].
+/// Use [`HeadlessRunner`] for more control, e.g. to send input or render any frame.
+///
+/// ```
+/// # use eframe::egui;
+/// #[derive(Default)]
+/// struct Report {
+///     screenshot_requested: bool,
+/// }
+///
+/// impl eframe::App for Report {
+///     fn ui(&mut self, ui: &mut egui::Ui, _frame: &mut eframe::Frame) {
+///         ui.heading("Monthly report");
+///         if !self.screenshot_requested {
+///             ui.ctx().send_viewport_cmd(egui::ViewportCommand::Screenshot(Default::default()));
+///             self.screenshot_requested = true;
+///         }
+///         let got_screenshot = ui.input(|i| {
+///             i.events.iter().any(|e| matches!(e, egui::Event::Screenshot { .. }))
+///         });
+///         if got_screenshot {
+///             ui.ctx().send_viewport_cmd(egui::ViewportCommand::Close);
+///         }
+///     }
+/// }
+///
+/// let options = eframe::HeadlessOptions {
+///     inner_size: egui::vec2(320.0, 240.0),
+///     pixels_per_point: 2.0,
+///     ..Default::default()
+/// };
+/// let screenshots = eframe::run_headless("report", options, Box::new(|_cc| Ok(Box::<Report>::default())))?;
+/// assert_eq!(screenshots.len(), 1);
+/// assert_eq!(screenshots[0].image.size, [640, 480]);
+/// # Ok::<(), eframe::Error>(())
+/// ```
+///
+/// # Errors
+/// This function fails if the app creator fails.
+pub fn run_headless(
+    app_name: &str,
+    options: HeadlessOptions,
+    app_creator: AppCreator<'_>,
+) -> Result<Vec<Screenshot>> {
+    let mut runner = HeadlessRunner::new(app_name, options, app_creator)?;
+    runner.run();
+    runner.close();
+    Ok(runner.take_screenshots())
+}
+
+/// Drives an [`App`] frame by frame, without a window.
+///
+/// See [the module docs](crate::headless) and [`run_headless`].
+pub struct HeadlessRunner<'app> {
+    options: HeadlessOptions,
+    app: Box<dyn 'app + App>,
+    frame: Frame,
+    ctx: egui::Context,
+    renderer: SoftwareRenderer,
+
+    /// Textures to free before the next frame.
+    ///
+    /// We keep them until then, so that we can still render the last frame.
+    pending_free: Vec<egui::TextureId>,
+
+    /// The tessellated output of the last frame.
+    last_frame: Vec<egui::ClippedPrimitive>,
+
+    /// What the last frame was tessellated with, see [`FullOutput::pixels_per_point`].
+    last_pixels_per_point: f32,
+
+    viewport_info: ViewportInfo,
+    inner_size: Vec2,
+    time: f64,
+    frame_nr: u64,
+
+    /// Input for the next frame.
+    events: Vec<Event>,
+    clipboard: String,
+
+    screenshots: Vec<Screenshot>,
+    idle: bool,
+    closed: bool,
+}
+
+impl<'app> HeadlessRunner<'app> {
+    /// Create the app.
+    ///
+    /// # Errors
+    /// This function fails if the app creator fails.
+    pub fn new(
+        app_name: &str,
+        options: HeadlessOptions,
+        app_creator: AppCreator<'app>,
+    ) -> Result<Self> {
+        let ctx = egui::Context::default();
+        // There are no windows for child viewports, so show them inside the root viewport:
+        ctx.set_embed_viewports(true);
+
+        // There is no window or graphics context to hand to the app:
+        let app = app_creator(&CreationContext::new_windowless(ctx.clone()))
+            .map_err(Error::AppCreation)?;
+
+        Ok(Self {
+            inner_size: options.inner_size,
+            last_pixels_per_point: options.pixels_per_point,
+            options,
+            app,
+            frame: Frame::new_windowless(),
+            ctx,
+            renderer: SoftwareRenderer::new(),
+            pending_free: Vec::new(),
+            last_frame: Vec::new(),
+            viewport_info: ViewportInfo {
+                title: Some(app_name.to_owned()),
+                focused: Some(true),
+                ..Default::default()
+            },
+            time: 0.0,
+            frame_nr: 0,
+            events: Vec::new(),
+            clipboard: String::new(),
+            screenshots: Vec::new(),
+            idle: false,
+            closed: false,
+        })
+    }
+
+    /// The [`egui::Context`] of the app.
+    pub fn ctx(&self) -> &egui::Context {
+        &self.ctx
+    }
+
+    /// The app being run.
+    pub fn app_mut(&mut self) -> &mut dyn App {
+        &mut *self.app
+    }
+
+    /// The current time of the virtual clock, in seconds.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// The number of frames run so far.
+    pub fn frame_nr(&self) -> u64 {
+        self.frame_nr
+    }
+
+    /// The current size of the viewport, in points when the app is not zoomed.
+    ///
+    /// See [`HeadlessOptions::inner_size`].
+    pub fn inner_size(&self) -> Vec2 {
+        self.inner_size
+    }
+
+    /// The current state of the viewport, e.g. the title set by the app.
+    pub fn viewport_info(&self) -> &ViewportInfo {
+        &self.viewport_info
+    }
+
+    /// Has the app closed (or been closed with [`Self::close`])?
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Did the last frame neither request a repaint nor leave any input for the next frame?
+    pub fn is_idle(&self) -> bool {
+        self.idle
+    }
+
+    /// The text the app last copied to the clipboard.
+    ///
+    /// Pasting ([`egui::ViewportCommand::RequestPaste`]) pastes this.
+    pub fn clipboard(&self) -> &str {
+        &self.clipboard
+    }
+
+    /// Add an input event for the next frame.
+    pub fn push_event(&mut self, event: Event) {
+        self.events.push(event);
+        self.idle = false;
+    }
+
+    /// Ask the app to close, like when the user clicks the close button of a window.
+    ///
+    /// The app sees this in the next frame, and can cancel it with [`egui::ViewportCommand::CancelClose`].
+    pub fn request_close(&mut self) {
+        self.viewport_info.events.push(ViewportEvent::Close);
+        self.idle = false;
+    }
+
+    /// Run frames until the app closes or goes idle, or [`HeadlessOptions::max_frames`] is reached.
+    pub fn run(&mut self) {
+        profiling::function_scope!();
+        let max_frames = self.options.max_frames.unwrap_or(u64::MAX);
+        while !self.closed && !self.idle && self.frame_nr < max_frames {
+            self.run_frame();
+        }
+    }
+
+    /// Run a single frame of the app, and advance the virtual clock.
+    ///
+    /// Does nothing if the app is closed.
+    pub fn run_frame(&mut self) {
+        profiling::function_scope!();
+        if self.closed {
+            return;
+        }
+
+        for id in self.pending_free.drain(..) {
+            self.renderer.free_texture(id);
+        }
+
+        let mut raw_input = self.raw_input();
+        let close_requested = raw_input.viewport().close_requested();
+        self.app.raw_input_hook(&self.ctx, &mut raw_input);
+
+        let app = &mut self.app;
+        let frame = &mut self.frame;
+        let mut full_output = self.ctx.run_ui(raw_input, |ui| {
+            app.logic(ui.ctx(), frame);
+            #[expect(deprecated)]
+            app.update(ui.ctx(), frame);
+            app.ui(ui, frame);
+        });
+
+        let FullOutput {
+            platform_output,
+            textures_delta,
+            shapes,
+            pixels_per_point,
+            viewport_output,
+        } = &mut full_output;
+
+        for (id, image_delta) in &textures_delta.set {
+            self.renderer.set_texture(*id, image_delta);
+        }
+        self.pending_free.extend_from_slice(&textures_delta.free);
+        self.last_frame = self
+            .ctx
+            .tessellate(std::mem::take(shapes), *pixels_per_point);
+        self.last_pixels_per_point = *pixels_per_point;
+
+        for command in std::mem::take(&mut platform_output.commands) {
+            if let OutputCommand::CopyText(text) = command {
+                self.clipboard = text;
+            }
+        }
+
+        let mut repaint_delay = Duration::MAX;
+        let mut close_canceled = false;
+        if let Some(output) = viewport_output.remove(&ViewportId::ROOT) {
+            repaint_delay = output.repaint_delay;
+            close_canceled = output.commands.contains(&ViewportCommand::CancelClose);
+            for command in output.commands {
+                self.process_viewport_command(command);
+            }
+        }
+
+        if close_requested && !close_canceled {
+            self.close();
+        }
+
+        self.frame_nr += 1;
+        let frame_time = self.options.frame_time;
+        let dt = if repaint_delay == Duration::MAX {
+            frame_time
+        } else {
+            repaint_delay.max(frame_time)
+        };
+        self.time += dt.as_secs_f64();
+
+        self.idle = repaint_delay == Duration::MAX
+            && self.events.is_empty()
+            && self.viewport_info.events.is_empty();
+    }
+
+    /// Render the last frame.
+    ///
+    /// The image has the size of the viewport times [`HeadlessOptions::pixels_per_point`],
+    /// whether or not the app zoomed in or out.
+    pub fn render(&self) -> ColorImage {
+        profiling::function_scope!();
+        let size = self.inner_size * self.options.pixels_per_point;
+        let size_in_pixels = [size.x.round() as usize, size.y.round() as usize];
+
+        let [r, g, b, a] = self.app.clear_color(&self.ctx.global_style().visuals);
+        let clear_color = Color32::from_rgba_premultiplied(
+            (r * 255.0).round() as u8,
+            (g * 255.0).round() as u8,
+            (b * 255.0).round() as u8,
+            (a * 255.0).round() as u8,
+        );
+
+        let mut image = ColorImage::filled(size_in_pixels, clear_color);
+        self.renderer
+            .paint_primitives(&mut image, &self.last_frame, self.last_pixels_per_point);
+        image
+    }
+
+    /// Take the screenshots the app has taken with [`egui::ViewportCommand::Screenshot`] so far.
+    pub fn take_screenshots(&mut self) -> Vec<Screenshot> {
+        std::mem::take(&mut self.screenshots)
+    }
+
+    /// Stop running the app, calling [`App::on_exit`].
+    ///
+    /// Does nothing if the app is already closed.
+    pub fn close(&mut self) {
+        if std::mem::replace(&mut self.closed, true) {
+            return;
+        }
+        log::debug!("Closing headless app");
+
+        #[cfg(feature = "glow")]
+        self.app.on_exit(None);
+
+        #[cfg(not(feature = "glow"))]
+        self.app.on_exit();
+    }
+
+    fn raw_input(&mut self) -> RawInput {
+        // Zooming keeps the size in pixels, and changes the size in points:
+        let size_in_points = self.inner_size / self.ctx.zoom_factor();
+        let inner_rect = Rect::from_min_size(egui::Pos2::ZERO, size_in_points);
+
+        let info = &mut self.viewport_info;
+        info.native_pixels_per_point = Some(self.options.pixels_per_point);
+        info.monitor_size = Some(size_in_points);
+        info.inner_rect = Some(inner_rect);
+        info.outer_rect = Some(inner_rect);
+        let viewport_info = info.clone();
+        info.events.clear();
+
+        RawInput {
+            viewport_id: ViewportId::ROOT,
+            viewports: std::iter::once((ViewportId::ROOT, viewport_info)).collect(),
+            screen_rect: Some(inner_rect),
+            max_texture_side: Some(8192),
+            time: Some(self.time),
+            predicted_dt: self.options.frame_time.as_secs_f32(),
+            events: std::mem::take(&mut self.events),
+            focused: true,
+            ..Default::default()
+        }
+    }
+
+    fn process_viewport_command(&mut self, command: ViewportCommand) {
+        log::trace!("Processing ViewportCommand::{command:?}");
+        let info = &mut self.viewport_info;
+        match command {
+            ViewportCommand::Close => info.events.push(ViewportEvent::Close),
+            ViewportCommand::InnerSize(size) => {
+                // The size is in the points of the app, which may be zoomed:
+                self.inner_size = size.max(Vec2::splat(1.0)) * self.ctx.zoom_factor();
+            }
+            ViewportCommand::Title(title) => info.title = Some(title),
+            ViewportCommand::Minimized(v) => info.minimized = Some(v),
+            ViewportCommand::Maximized(v) => info.maximized = Some(v),
+            ViewportCommand::Fullscreen(v) => info.fullscreen = Some(v),
+            ViewportCommand::Focus => info.focused = Some(true),
+            ViewportCommand::Screenshot(user_data) => {
+                let image = Arc::new(self.render());
+                self.screenshots.push(Screenshot {
+                    frame_nr: self.frame_nr,
+                    time: self.time,
+                    user_data: user_data.clone(),
+                    image: Arc::clone(&image),
+                });
+                self.events.push(Event::Screenshot {
+                    viewport_id: ViewportId::ROOT,
+                    user_data,
+                    image,
+                });
+            }
+            ViewportCommand::RequestCut => self.events.push(Event::Cut),
+            ViewportCommand::RequestCopy => self.events.push(Event::Copy),
+            ViewportCommand::RequestPaste => {
+                self.events.push(Event::Paste(self.clipboard.clone()));
+            }
+            _ => {
+                // Only meaningful for a real window.
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{Color32, Pos2, Rect, pos2, vec2};
+
+    use super::{HeadlessOptions, HeadlessRunner};
+
+    /// Paints a red square at `(10, 10)`, with a side of 20 points.
+    struct RedSquare {
+        zoom_factor: f32,
+    }
+
+    impl crate::App for RedSquare {
+        fn ui(&mut self, ui: &mut egui::Ui, _frame: &mut crate::Frame) {
+            ui.ctx().set_zoom_factor(self.zoom_factor);
+            ui.painter().rect_filled(
+                Rect::from_min_size(pos2(10.0, 10.0), vec2(20.0, 20.0)),
+                0.0,
+                Color32::RED,
+            );
+        }
+
+        fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
+            [0.0, 0.0, 0.0, 1.0]
+        }
+    }
+
+    fn red_square_runner(pixels_per_point: f32, zoom_factor: f32) -> HeadlessRunner<'static> {
+        let options = HeadlessOptions {
+            inner_size: vec2(100.0, 50.0),
+            pixels_per_point,
+            ..Default::default()
+        };
+        HeadlessRunner::new(
+            "test",
+            options,
+            Box::new(move |_cc| Ok(Box::new(RedSquare { zoom_factor }))),
+        )
+        .unwrap()
+    }
+
+    fn pixel(image: &egui::ColorImage, pos: Pos2) -> Color32 {
+        image[(pos.x as usize, pos.y as usize)]
+    }
+
+    #[test]
+    fn test_render_frame() {
+        let mut runner = red_square_runner(1.0, 1.0);
+        runner.run_frame();
+        let image = runner.render();
+        assert_eq!(image.size, [100, 50]);
+        assert_eq!(pixel(&image, pos2(20.0, 20.0)), Color32::RED);
+        assert_eq!(pixel(&image, pos2(5.0, 5.0)), Color32::BLACK);
+        assert_eq!(pixel(&image, pos2(35.0, 20.0)), Color32::BLACK);
+    }
+
+    #[test]
+    fn test_render_pixels_per_point() {
+        let mut runner = red_square_runner(2.0, 1.0);
+        runner.run_frame();
+        let image = runner.render();
+        assert_eq!(image.size, [200, 100]);
+        assert_eq!(pixel(&image, pos2(50.0, 50.0)), Color32::RED);
+        assert_eq!(pixel(&image, pos2(65.0, 50.0)), Color32::BLACK);
+
+        // Zooming keeps the size in pixels, so the app gets fewer points:
+        let mut runner = red_square_runner(1.0, 2.0);
+        runner.run();
+        let image = runner.render();
+        assert_eq!(runner.ctx().pixels_per_point(), 2.0);
+        assert_eq!(runner.ctx().content_rect().size(), vec2(50.0, 25.0));
+        assert_eq!(image.size, [100, 50]);
+        assert_eq!(pixel(&image, pos2(40.0, 40.0)), Color32::RED);
+        assert_eq!(pixel(&image, pos2(65.0, 40.0)), Color32::BLACK);
+    }
+}
@@ -0,0 +1,391 @@
+//! Automatic checks for common accessibility problems.
+//!
+//! Turn on [`crate::Options::audit_accessibility`] to run the checks at the end of each pass,
+//! and read the result with [`crate::Context::accessibility_issues`].
+//! In debug builds you can also turn on [`crate::style::DebugOptions::show_accessibility_issues`]
+//! to paint the issues on top of the ui.
+//!
+//! `egui_kittest` has `Harness::assert_accessible` to use this in tests.
+//!
+//! The checks are:
+//! * interactive widgets without an accessible name (e.g. an image button without alt text),
+//!   if AccessKit is on,
+//! * widgets that reuse the same [`Id`],
+//! * clickable widgets that can't be reached with the Tab key,
+//! * hit targets smaller than [`crate::style::Spacing::interact_size`] high,
+//! * text with too little contrast to its background (following WCAG 2).
+
+use ahash::HashMap;
+use epaint::{ClippedShape, Color32, Rect, Rgba, Shape, TextShape, emath::TSTransform};
+
+use crate::{Id, LayerId, Style, WidgetRects, WidgetType, id::IdSet};
+
+/// The kinds of problems found by the accessibility audit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AccessibilityIssueKind {
+    /// An interactive widget has no label, and isn't labelled by another widget.
+    ///
+    /// Screen readers can only say e.g. "button".
+    ///
+    /// Only checked with AccessKit turned on (see [`crate::Context::enable_accesskit`]),
+    /// since that is what knows about [`crate::Response::labelled_by`].
+    MissingName,
+
+    /// Two widgets use the same [`Id`].
+    DuplicateId,
+
+    /// A clickable widget can't be focused, so it can't be reached with the keyboard.
+    ///
+    /// This usually means it uses e.g. [`crate::Sense::CLICK`] instead of [`crate::Sense::click`].
+    NotFocusable,
+
+    /// The widget is smaller than the minimum interaction height.
+    SmallHitTarget,
+
+    /// Text has too little contrast to its background.
+    LowContrast,
+}
+
+/// A problem found by the accessibility audit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccessibilityIssue {
+    /// What kind of problem this is.
+    pub kind: AccessibilityIssueKind,
+
+    /// The widget with the problem, if it is about a widget.
+    pub id: Option<Id>,
+
+    /// Where the problem is, in screen coordinates.
+    pub rect: Rect,
+
+    /// Human readable description of the problem.
+    pub message: String,
+}
+
+impl std::fmt::Display for AccessibilityIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            kind,
+            id,
+            rect,
+            message,
+        } = self;
+        write!(f, "{kind:?}: {message} at {rect:?}")?;
+        if let Some(id) = id {
+            write!(f, " ({id:?})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Minimum contrast ratio for normal text (WCAG 2, level AA).
+pub const MIN_TEXT_CONTRAST: f32 = 4.5;
+
+/// Minimum contrast ratio for large text (WCAG 2, level AA).
+pub const MIN_LARGE_TEXT_CONTRAST: f32 = 3.0;
+
+/// Text at least this large (in points) only needs [`MIN_LARGE_TEXT_CONTRAST`].
+pub const LARGE_TEXT_SIZE: f32 = 24.0;
+
+/// The WCAG 2 contrast ratio between two opaque colors, from 1 (same luminance) to 21 (black on white).
+pub fn contrast_ratio(a: Color32, b: Color32) -> f32 {
+    fn luminance(color: Color32) -> f32 {
+        let rgba = Rgba::from(color.to_opaque());
+        0.2126 * rgba.r() + 0.7152 * rgba.g() + 0.0722 * rgba.b()
+    }
+    let (a, b) = (luminance(a), luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// Everything the audit looks at, collected at the end of a pass.
+pub(crate) struct AuditInput<'a> {
+    pub widgets: &'a WidgetRects,
+
+    /// Widgets that are labelled by another widget (e.g. with [`crate::Response::labelled_by`]).
+    ///
+    /// `None` if AccessKit is off, in which case we can't tell if a widget has a name.
+    pub labelled: Option<&'a IdSet>,
+
+    /// Ids that were used by more than one widget, with the rect of the second one.
+    pub id_clashes: &'a [(Id, Rect)],
+
+    /// All shapes of the pass, in painting order and screen coordinates.
+    pub shapes: &'a [ClippedShape],
+
+    pub style: &'a Style,
+    pub to_global: &'a HashMap<LayerId, TSTransform>,
+}
+
+pub(crate) fn audit(input: &AuditInput<'_>) -> Vec<AccessibilityIssue> {
+    profiling::function_scope!();
+
+    let AuditInput {
+        widgets,
+        labelled,
+        id_clashes,
+        shapes,
+        style,
+        to_global,
+    } = input;
+
+    let mut issues = vec![];
+    let mut disabled_rects = vec![];
+    let min_size = style.spacing.interact_size.y;
+
+    for (layer_id, rects) in widgets.layers() {
+        let transform = to_global.get(layer_id).copied().unwrap_or_default();
+        for widget in rects {
+            let rect = transform * widget.rect;
+            if !widget.enabled {
+                disabled_rects.push(rect);
+                continue;
+            }
+            if !widget.interact_rect.is_positive() {
+                continue; // Clipped away
+            }
+            let Some(info) = widgets.info(widget.id) else {
+                continue; // Not a widget that reports what it is
+            };
+            if !is_control(info.typ, widget.sense) {
+                continue;
+            }
+
+            let mut issue = |kind, message| {
+                issues.push(AccessibilityIssue {
+                    kind,
+                    id: Some(widget.id),
+                    rect,
+                    message,
+                });
+            };
+            let description = info.description();
+
+            let has_name = info.label.as_ref().is_some_and(|l| !l.trim().is_empty());
+            if !has_name && labelled.is_some_and(|labelled| !labelled.contains(&widget.id)) {
+                issue(
+                    AccessibilityIssueKind::MissingName,
+                    format!("'{description}' has no accessible name"),
+                );
+            }
+            if !widget.sense.is_focusable() {
+                issue(
+                    AccessibilityIssueKind::NotFocusable,
+                    format!("'{description}' can't be reached with the keyboard"),
+                );
+            }
+            if rect.width() < min_size || rect.height() < min_size {
+                issue(
+                    AccessibilityIssueKind::SmallHitTarget,
+                    format!(
+                        "'{description}' is {:.0}x{:.0}, smaller than the minimum {min_size:.0}x{min_size:.0}",
+                        rect.width(),
+                        rect.height()
+                    ),
+                );
+            }
+        }
+    }
+
+    for (id, rect) in *id_clashes {
+        issues.push(AccessibilityIssue {
+            kind: AccessibilityIssueKind::DuplicateId,
+            id: Some(*id),
+            rect: *rect,
+            message: "The same id is used by more than one widget".to_owned(),
+        });
+    }
+
+    let mut contrast = ContrastCheck {
+        backgrounds: vec![],
+        disabled_rects: &disabled_rects,
+        issues: &mut issues,
+    };
+    for ClippedShape { clip_rect, shape } in *shapes {
+        contrast.shape(*clip_rect, shape);
+    }
+
+    issues.sort_by(|a, b| {
+        (a.rect.min.y, a.rect.min.x, a.kind)
+            .partial_cmp(&(b.rect.min.y, b.rect.min.x, b.kind))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    issues
+}
+
+/// Widgets a user interacts with, which need a name and keyboard access.
+fn is_control(typ: WidgetType, sense: crate::Sense) -> bool {
+    match typ {
+        WidgetType::Link
+        | WidgetType::TextEdit
+        | WidgetType::Button
+        | WidgetType::Checkbox
+        | WidgetType::RadioButton
+        | WidgetType::SelectableLabel
+        | WidgetType::ComboBox
+        | WidgetType::Slider
+        | WidgetType::DragValue
+        | WidgetType::ColorButton
+        | WidgetType::CollapsingHeader => true,
+        WidgetType::Image => sense.senses_click(),
+        WidgetType::Label
+        | WidgetType::RadioGroup
+        | WidgetType::Panel
+        | WidgetType::ProgressIndicator
+        | WidgetType::Window
+        | WidgetType::ResizeHandle
+        | WidgetType::ScrollBar
+        | WidgetType::Other => false,
+    }
+}
+
+/// Finds the background of each text by looking at the filled rectangles painted before it.
+struct ContrastCheck<'a> {
+    /// Opaque backgrounds painted so far, in painting order.
+    backgrounds: Vec<(Rect, Color32)>,
+
+    /// Disabled widgets are exempt from contrast requirements.
+    disabled_rects: &'a [Rect],
+
+    issues: &'a mut Vec<AccessibilityIssue>,
+}
+
+impl ContrastCheck<'_> {
+    fn background_at(&self, pos: epaint::Pos2) -> Option<Color32> {
+        self.backgrounds
+            .iter()
+            .rev()
+            .find(|(rect, _)| rect.contains(pos))
+            .map(|(_, color)| *color)
+    }
+
+    fn shape(&mut self, clip_rect: Rect, shape: &Shape) {
+        match shape {
+            Shape::Vec(shapes) => {
+                for shape in shapes {
+                    self.shape(clip_rect, shape);
+                }
+            }
+            Shape::Rect(rect_shape) => {
+                let rect = rect_shape.rect.intersect(clip_rect);
+                if !rect.is_positive() || rect_shape.fill.a() == 0 {
+                    return;
+                }
+                let fill = if rect_shape.fill.is_opaque() {
+                    Some(rect_shape.fill)
+                } else {
+                    self.background_at(rect.center())
+                        .map(|under| under.blend(rect_shape.fill))
+                };
+                if let Some(fill) = fill {
+                    self.backgrounds.push((rect, fill));
+                }
+            }
+            Shape::Text(text_shape) => self.text(clip_rect, text_shape),
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, clip_rect: Rect, text_shape: &TextShape) {
+        let rect = text_shape.visual_bounding_rect().intersect(clip_rect);
+        if !rect.is_positive()
+            || self
+                .disabled_rects
+                .iter()
+                .any(|r| r.contains(rect.center()))
+        {
+            return;
+        }
+        let Some(background) = self.background_at(rect.center()) else {
+            return; // We don't know what is behind the text
+        };
+
+        let job = &text_shape.galley.job;
+        let mut worst: Option<(f32, f32)> = None; // (contrast, required)
+        for section in &job.sections {
+            let Some(text) = job.text.get(section.byte_range.clone()) else {
+                continue;
+            };
+            if text.trim().is_empty() {
+                continue;
+            }
+            let color = if let Some(color) = text_shape.override_text_color {
+                color
+            } else if section.format.color == Color32::PLACEHOLDER {
+                text_shape.fallback_color
+            } else {
+                section.format.color
+            };
+            let color = color.gamma_multiply(text_shape.opacity_factor);
+            if color.a() == 0 {
+                continue;
+            }
+
+            let contrast = contrast_ratio(background.blend(color), background);
+            let required = if LARGE_TEXT_SIZE <= section.format.font_id.size {
+                MIN_LARGE_TEXT_CONTRAST
+            } else {
+                MIN_TEXT_CONTRAST
+            };
+            if contrast < required && worst.is_none_or(|(worst, _)| contrast < worst) {
+                worst = Some((contrast, required));
+            }
+        }
+
+        if let Some((contrast, required)) = worst {
+            let mut snippet: String = job.text.chars().take(20).collect();
+            if snippet.len() < job.text.len() {
+                snippet += "…";
+            }
+            self.issues.push(AccessibilityIssue {
+                kind: AccessibilityIssueKind::LowContrast,
+                id: None,
+                rect,
+                message: format!(
+                    "Text '{snippet}' has a contrast of {contrast:.1}:1, but needs {required}:1"
+                ),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Context, RawInput};
+
+    fn missing_names(ctx: &Context) -> usize {
+        ctx.options_mut(|options| options.audit_accessibility = true);
+        let _ = ctx.run_ui(RawInput::default(), |ui| {
+            let label = ui.label("Name");
+            ui.text_edit_singleline(&mut String::from("Ferris"))
+                .labelled_by(label.id);
+            ui.add(crate::Button::new("")); // No name
+        });
+        ctx.accessibility_issues()
+            .iter()
+            .filter(|issue| issue.kind == AccessibilityIssueKind::MissingName)
+            .count()
+    }
+
+    #[test]
+    fn test_missing_name_needs_accesskit() {
+        // Without AccessKit we don't know that the text edit is labelled, so we don't check:
+        assert_eq!(missing_names(&Context::default()), 0);
+
+        // Only the button has no name:
+        let ctx = Context::default();
+        ctx.enable_accesskit();
+        assert_eq!(missing_names(&ctx), 1);
+    }
+
+    #[test]
+    fn test_contrast_ratio() {
+        let ratio = contrast_ratio(Color32::BLACK, Color32::WHITE);
+        assert!((ratio - 21.0).abs() < 0.01, "Got {ratio}");
+        assert_eq!(contrast_ratio(Color32::RED, Color32::RED), 1.0);
+        assert_eq!(
+            contrast_ratio(Color32::WHITE, Color32::GRAY),
+            contrast_ratio(Color32::GRAY, Color32::WHITE)
+        );
+    }
+}
@@ -576,6 +576,10 @@ impl CollapsingHeader {
         header_response.widget_info(|| {
             WidgetInfo::labeled(WidgetType::CollapsingHeader, ui.is_enabled(), galley.text())
         });
+        ui.ctx()
+            .accesskit_node_builder(header_response.id, |builder| {
+                builder.set_expanded(state.is_open());
+            });
 
         let openness = state.openness(ui.ctx());
 
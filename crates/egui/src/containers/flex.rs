@@ -0,0 +1,558 @@
+//! A flexbox-style container, see [`Flex`].
+
+use std::ops::Range;
+
+use emath::{Align, NumExt as _, Pos2, Rect, Vec2};
+
+use crate::{Context, Id, InnerResponse, Layout, Response, Sense, Ui, UiBuilder, Widget};
+
+/// How to use the space that is left over in a line of a [`Flex`] when no item grows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum FlexJustify {
+    /// Put the items at the start of the line.
+    #[default]
+    Start,
+
+    /// Put the items in the middle of the line.
+    Center,
+
+    /// Put the items at the end of the line.
+    End,
+
+    /// Put the first item at the start, the last at the end, and spread the rest evenly between them.
+    SpaceBetween,
+
+    /// Put the same space on both sides of each item,
+    /// so the space between items is twice the space at the ends.
+    SpaceAround,
+
+    /// Make all spaces the same, including the ones at the ends.
+    SpaceEvenly,
+}
+
+/// Where to put an item of a [`Flex`] in the cross direction
+/// (vertically in a horizontal flex, horizontally in a vertical one).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum FlexAlign {
+    /// Align to the top (or left) of the line.
+    Start,
+
+    /// Center in the line.
+    #[default]
+    Center,
+
+    /// Align to the bottom (or right) of the line.
+    End,
+
+    /// Make the item as tall (or wide) as the line.
+    Stretch,
+}
+
+/// How a single item of a [`Flex`] is sized and placed.
+///
+/// By default an item keeps its natural size, i.e. the size of its contents.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct FlexItem {
+    grow: f32,
+    shrink: f32,
+    basis: Option<f32>,
+    align_self: Option<FlexAlign>,
+}
+
+impl Default for FlexItem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FlexItem {
+    /// An item that keeps its natural size, aligned like [`Flex::align_items`].
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            grow: 0.0,
+            shrink: 0.0,
+            basis: None,
+            align_self: None,
+        }
+    }
+
+    /// How much of the space left over in the line this item gets, relative to the other items.
+    ///
+    /// Three items with a grow of `1.0`, `2.0` and `1.0` share the left over space 1:2:1.
+    ///
+    /// Default: `0.0` (don't grow).
+    #[inline]
+    pub fn grow(mut self, grow: f32) -> Self {
+        self.grow = grow.at_least(0.0);
+        self
+    }
+
+    /// How much this item shrinks, relative to the other items, when the line is too short.
+    ///
+    /// Larger items shrink more than smaller ones with the same factor (like in CSS).
+    /// Most widgets don't get smaller when given less space, so this is mostly useful
+    /// for content that can wrap or truncate.
+    ///
+    /// Default: `0.0` (don't shrink).
+    #[inline]
+    pub fn shrink(mut self, shrink: f32) -> Self {
+        self.shrink = shrink.at_least(0.0);
+        self
+    }
+
+    /// The size of the item in the main direction, before growing or shrinking.
+    ///
+    /// Default: the natural size of the contents.
+    #[inline]
+    pub fn basis(mut self, basis: f32) -> Self {
+        self.basis = Some(basis.at_least(0.0));
+        self
+    }
+
+    /// Override [`Flex::align_items`] for this item.
+    #[inline]
+    pub fn align_self(mut self, align_self: FlexAlign) -> Self {
+        self.align_self = Some(align_self);
+        self
+    }
+}
+
+/// The natural size of an item, measured in a previous pass.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ItemState {
+    item: FlexItem,
+    size: Vec2,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct State {
+    items: Vec<ItemState>,
+}
+
+impl State {
+    fn load(ctx: &Context, id: Id) -> Option<Self> {
+        ctx.data_mut(|d| d.get_temp(id))
+    }
+
+    fn store(self, ctx: &Context, id: Id) {
+        // Like `Grid`, we don't persist the measurements: they are cheap to redo.
+        ctx.data_mut(|d| d.insert_temp(id, self));
+    }
+}
+
+/// Lay out items in a row (or column) where some items keep their natural size,
+/// and others share the space that is left over, optionally wrapping onto more lines.
+///
+/// Add items with [`FlexUi::add`] and [`FlexUi::add_ui`].
+///
+/// The natural size of each item is measured the first time it is shown
+/// (using a sizing pass, see [`Ui::is_sizing_pass`]), and then remembered.
+/// Like with [`crate::Grid`], that first frame is discarded so it is never shown.
+/// Items that keep their natural size are re-measured every frame.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use egui::{Flex, FlexItem};
+/// let mut query = String::new();
+/// Flex::horizontal().show(ui, |flex| {
+///     flex.add(FlexItem::new(), egui::Button::new("⏴"));
+///     flex.add_ui(FlexItem::new().grow(1.0), |ui| {
+///         ui.add(egui::TextEdit::singleline(&mut query).desired_width(f32::INFINITY));
+///     });
+///     flex.add(FlexItem::new().grow(2.0), egui::Button::new("Search"));
+///     flex.add(FlexItem::new().grow(1.0), egui::Button::new("Clear"));
+/// });
+/// # });
+/// ```
+#[must_use = "You should call flex.show()"]
+#[derive(Clone, Copy, Debug)]
+pub struct Flex {
+    id_salt: Option<Id>,
+    horizontal: bool,
+    wrap: bool,
+    gap: Option<Vec2>,
+    justify: FlexJustify,
+    align_items: FlexAlign,
+}
+
+impl Flex {
+    fn new(horizontal: bool) -> Self {
+        Self {
+            id_salt: None,
+            horizontal,
+            wrap: false,
+            gap: None,
+            justify: FlexJustify::default(),
+            align_items: FlexAlign::default(),
+        }
+    }
+
+    /// Lay out the items left-to-right.
+    ///
+    /// With [`Self::wrap`], items that don't fit go onto new rows below.
+    #[inline]
+    pub fn horizontal() -> Self {
+        Self::new(true)
+    }
+
+    /// Lay out the items top-down.
+    ///
+    /// With [`Self::wrap`], items that don't fit go onto new columns to the right.
+    #[inline]
+    pub fn vertical() -> Self {
+        Self::new(false)
+    }
+
+    /// Used to remember the sizes of the items.
+    ///
+    /// Default: an id based on the position in the parent [`Ui`].
+    #[inline]
+    pub fn id_salt(mut self, id_salt: impl std::hash::Hash) -> Self {
+        self.id_salt = Some(Id::new(id_salt));
+        self
+    }
+
+    /// Start a new line when the next item doesn't fit.
+    ///
+    /// Default: `false`.
+    #[inline]
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Space between items (`x`) and rows (`y`) for a horizontal flex,
+    /// or between columns (`x`) and items (`y`) for a vertical flex.
+    ///
+    /// Default: [`crate::style::Spacing::item_spacing`].
+    #[inline]
+    pub fn gap(mut self, gap: impl Into<Vec2>) -> Self {
+        self.gap = Some(gap.into());
+        self
+    }
+
+    /// How to use left over space when no item in the line grows.
+    ///
+    /// Default: [`FlexJustify::Start`].
+    #[inline]
+    pub fn justify(mut self, justify: FlexJustify) -> Self {
+        self.justify = justify;
+        self
+    }
+
+    /// Where to put the items in the cross direction.
+    /// Can be overridden per item with [`FlexItem::align_self`].
+    ///
+    /// Default: [`FlexAlign::Center`].
+    #[inline]
+    pub fn align_items(mut self, align_items: FlexAlign) -> Self {
+        self.align_items = align_items;
+        self
+    }
+
+    /// Lay out the items added in `add_contents`.
+    ///
+    /// The returned [`Response`] covers all lines.
+    pub fn show<R>(
+        self,
+        ui: &mut Ui,
+        add_contents: impl FnOnce(&mut FlexUi<'_>) -> R,
+    ) -> InnerResponse<R> {
+        let id = self.id_salt.map_or_else(
+            || ui.next_auto_id(),
+            |id_salt| ui.make_persistent_id(id_salt),
+        );
+        let prev_state = State::load(ui.ctx(), id);
+        let is_first_frame = prev_state.is_none();
+        let prev_state = prev_state.unwrap_or_default();
+
+        let available = ui.available_rect_before_wrap();
+        debug_assert!(
+            available.min.is_finite(),
+            "Flex not yet available for right-to-left or bottom-up layouts"
+        );
+        let main_size = if ui.is_sizing_pass() {
+            // Make everything as small as possible:
+            f32::INFINITY
+        } else {
+            available.size()[self.main_axis()]
+        };
+        let gap = self.gap.unwrap_or_else(|| ui.spacing().item_spacing);
+
+        let plan = self.plan(&prev_state.items, main_size, gap);
+        let mut flex = FlexUi {
+            ui,
+            flex: self,
+            origin: available.min,
+            max_size: self.vec2(main_size, available.size()[self.cross_axis()]),
+            plan,
+            prev_state,
+            curr_state: State::default(),
+        };
+        let inner = add_contents(&mut flex);
+        let FlexUi {
+            ui,
+            origin,
+            prev_state,
+            curr_state,
+            ..
+        } = flex;
+
+        if curr_state.items.len() < prev_state.items.len() && ui.is_visible() {
+            // The items that are left were laid out sharing space with the removed ones:
+            ui.request_discard("Flex item removed");
+        }
+
+        let size = self.plan(&curr_state.items, main_size, gap).size;
+
+        // We need to always save state on the first frame, otherwise request_discard
+        // would be called repeatedly (see #5132)
+        if curr_state != prev_state || is_first_frame {
+            curr_state.store(ui.ctx(), id);
+            ui.ctx().request_repaint();
+        }
+
+        let response = ui.allocate_rect(Rect::from_min_size(origin, size), Sense::hover());
+        InnerResponse::new(inner, response)
+    }
+
+    /// 0 for x, 1 for y.
+    fn main_axis(&self) -> usize {
+        usize::from(!self.horizontal)
+    }
+
+    fn cross_axis(&self) -> usize {
+        usize::from(self.horizontal)
+    }
+
+    fn vec2(&self, main: f32, cross: f32) -> Vec2 {
+        if self.horizontal {
+            Vec2::new(main, cross)
+        } else {
+            Vec2::new(cross, main)
+        }
+    }
+
+    /// Where to put each item, relative to the top left corner of the flex.
+    fn plan(&self, items: &[ItemState], main_size: f32, gap: Vec2) -> Plan {
+        let (m, c) = (self.main_axis(), self.cross_axis());
+        let basis = |state: &ItemState| state.item.basis.unwrap_or(state.size[m]);
+
+        let mut lines: Vec<Range<usize>> = vec![];
+        let mut line_start = 0;
+        let mut line_length = 0.0;
+        for (i, state) in items.iter().enumerate() {
+            if i == line_start {
+                line_length = basis(state);
+            } else if self.wrap && main_size < line_length + gap[m] + basis(state) {
+                lines.push(line_start..i);
+                line_start = i;
+                line_length = basis(state);
+            } else {
+                line_length += gap[m] + basis(state);
+            }
+        }
+        if line_start < items.len() {
+            lines.push(line_start..items.len());
+        }
+
+        let mut rects = Vec::with_capacity(items.len());
+        let mut main_extent: f32 = 0.0;
+        let mut cross_offset = 0.0;
+        let mut fills_line = false;
+        for line in lines {
+            let items = &items[line];
+            let gaps = gap[m] * (items.len() - 1) as f32;
+            let mut sizes: Vec<f32> = items.iter().map(basis).collect();
+            let free = main_size - sizes.iter().sum::<f32>() - gaps;
+
+            let total_grow: f32 = items.iter().map(|state| state.item.grow).sum();
+            let total_shrink: f32 = items
+                .iter()
+                .zip(&sizes)
+                .map(|(state, size)| state.item.shrink * size)
+                .sum();
+            if free.is_finite() && 0.0 < free && 0.0 < total_grow {
+                for (size, state) in sizes.iter_mut().zip(items) {
+                    *size += free * state.item.grow / total_grow;
+                }
+                fills_line = true;
+            } else if free < 0.0 && 0.0 < total_shrink {
+                for (size, state) in sizes.iter_mut().zip(items) {
+                    *size = (*size + free * state.item.shrink * *size / total_shrink).at_least(0.0);
+                }
+            }
+
+            let used = sizes.iter().sum::<f32>() + gaps;
+            let left_over = if main_size.is_finite() {
+                (main_size - used).at_least(0.0)
+            } else {
+                0.0
+            };
+            let n = items.len() as f32;
+            let (mut pos, spacing) = match self.justify {
+                FlexJustify::Center => (left_over / 2.0, gap[m]),
+                FlexJustify::End => (left_over, gap[m]),
+                FlexJustify::SpaceBetween if 1 < items.len() => {
+                    (0.0, gap[m] + left_over / (n - 1.0))
+                }
+                FlexJustify::Start | FlexJustify::SpaceBetween => (0.0, gap[m]),
+                FlexJustify::SpaceAround => (left_over / (2.0 * n), gap[m] + left_over / n),
+                FlexJustify::SpaceEvenly => (left_over / (n + 1.0), gap[m] + left_over / (n + 1.0)),
+            };
+            if self.justify != FlexJustify::Start && main_size.is_finite() {
+                fills_line = true;
+            }
+
+            let line_cross = items.iter().map(|state| state.size[c]).fold(0.0, f32::max);
+            for (state, size) in items.iter().zip(sizes) {
+                let item_cross = state.size[c].at_most(line_cross);
+                let (cross_pos, cross_size) =
+                    match state.item.align_self.unwrap_or(self.align_items) {
+                        FlexAlign::Start => (0.0, item_cross),
+                        FlexAlign::Center => ((line_cross - item_cross) / 2.0, item_cross),
+                        FlexAlign::End => (line_cross - item_cross, item_cross),
+                        FlexAlign::Stretch => (0.0, line_cross),
+                    };
+                rects.push(Rect::from_min_size(
+                    self.vec2(pos, cross_offset + cross_pos).to_pos2(),
+                    self.vec2(size, cross_size),
+                ));
+                pos += size + spacing;
+            }
+            main_extent = main_extent.max(pos - spacing);
+            cross_offset += line_cross + gap[c];
+        }
+
+        if fills_line {
+            main_extent = main_extent.max(main_size);
+        }
+        let cross_extent = if rects.is_empty() {
+            0.0
+        } else {
+            cross_offset - gap[c]
+        };
+        Plan {
+            rects,
+            size: self.vec2(main_extent, cross_extent),
+        }
+    }
+}
+
+struct Plan {
+    /// Relative to the top left corner of the flex.
+    rects: Vec<Rect>,
+
+    /// The size of the whole flex.
+    size: Vec2,
+}
+
+/// Add items to a [`Flex`], see [`Flex::show`].
+pub struct FlexUi<'a> {
+    ui: &'a mut Ui,
+    flex: Flex,
+    origin: Pos2,
+
+    /// The space the items may use.
+    max_size: Vec2,
+
+    /// Based on the measurements of the previous pass.
+    plan: Plan,
+    prev_state: State,
+    curr_state: State,
+}
+
+impl FlexUi<'_> {
+    /// Add a widget as an item.
+    pub fn add(&mut self, item: FlexItem, widget: impl Widget) -> Response {
+        self.add_ui(item, |ui| ui.add(widget)).inner
+    }
+
+    /// Add an item with any contents.
+    ///
+    /// The contents are laid out top-down. When the item is wider than its natural size
+    /// (it grows in a horizontal flex, or is stretched in a vertical one)
+    /// the contents are also justified, so e.g. a button fills the width of the item.
+    pub fn add_ui<R>(
+        &mut self,
+        item: FlexItem,
+        add_contents: impl FnOnce(&mut Ui) -> R,
+    ) -> InnerResponse<R> {
+        let index = self.curr_state.items.len();
+        let prev = self
+            .prev_state
+            .items
+            .get(index)
+            .filter(|prev| prev.item == item)
+            .copied();
+        let planned = prev.zip(self.plan.rects.get(index).copied());
+
+        let ui_builder = UiBuilder::new().id_salt(("flex_item", index));
+        let ui_builder = if let Some((prev, rect)) = planned {
+            let rect = rect.translate(self.origin.to_vec2());
+            let resized = self.resized(&prev, rect.size());
+
+            // Let the contents grow where the item has its natural size,
+            // so we notice when that changes.
+            let mut max_rect = rect;
+            for (axis, resized) in resized.into_iter().enumerate() {
+                if !resized {
+                    max_rect.max[axis] =
+                        max_rect.max[axis].max(self.origin[axis] + self.max_size[axis]);
+                }
+            }
+            ui_builder
+                .max_rect(max_rect)
+                .layout(Layout::top_down(Align::Min).with_cross_justify(resized[0]))
+        } else {
+            // A new item: measure it this frame, and hide the glitchy result.
+            if self.ui.is_visible() {
+                self.ui.request_discard("new Flex item");
+            }
+            ui_builder
+                .max_rect(Rect::from_min_size(self.origin, self.max_size))
+                .layout(Layout::top_down(Align::Min))
+                .sizing_pass()
+                .invisible()
+        };
+
+        let mut child_ui = self.ui.new_child(ui_builder);
+        let inner = add_contents(&mut child_ui);
+        let response = child_ui.remember_min_rect();
+        let content = child_ui.min_rect().size();
+
+        let size = if let Some((prev, rect)) = planned {
+            // Where the item was resized, its contents may fill all the space they got,
+            // so we can't know the natural size unless the contents didn't fit.
+            // Small changes are from rounding to pixels, and would make the layout jitter.
+            let resized = self.resized(&prev, rect.size());
+            let mut size = prev.size;
+            for (axis, resized) in resized.into_iter().enumerate() {
+                let changed = 0.5 <= (content[axis] - prev.size[axis]).abs();
+                let overflows = rect.size()[axis] + 0.5 < content[axis];
+                if changed && (!resized || overflows) {
+                    size[axis] = content[axis];
+                }
+            }
+            size
+        } else {
+            content
+        };
+        self.curr_state.items.push(ItemState { item, size });
+
+        InnerResponse::new(inner, response)
+    }
+
+    /// For each axis: did the item get a different size than its natural one?
+    fn resized(&self, prev: &ItemState, assigned: Vec2) -> [bool; 2] {
+        let mut resized = [0, 1].map(|axis| 0.5 <= (assigned[axis] - prev.size[axis]).abs());
+        if prev.item.basis.is_some() {
+            resized[self.flex.main_axis()] = true;
+        }
+        resized
+    }
+}
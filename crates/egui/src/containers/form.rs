@@ -0,0 +1,344 @@
+//! Validate the fields of a form, see [`Form`].
+//!
+//! The functions in this module are validators for common cases,
+//! e.g. `form::required(&name).and_then(|()| form::max_length(&name, 20))`.
+
+use std::{
+    fmt::Display,
+    ops::{Deref, DerefMut, RangeInclusive},
+};
+
+use emath::Rect;
+use epaint::{Stroke, StrokeKind};
+
+use crate::{Id, InnerResponse, Key, Response, Ui, WidgetText, id::IdSet};
+
+/// Check that the user filled in `text`.
+///
+/// # Errors
+/// If `text` is empty or only whitespace.
+pub fn required(text: &str) -> Result<(), String> {
+    if text.trim().is_empty() {
+        Err("This field is required".to_owned())
+    } else {
+        Ok(())
+    }
+}
+
+/// Check that `text` isn't too short.
+///
+/// # Errors
+/// If `text` has fewer than `min` characters.
+pub fn min_length(text: &str, min: usize) -> Result<(), String> {
+    if text.chars().count() < min {
+        Err(format!("Must be at least {min} characters"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Check that `text` isn't too long.
+///
+/// # Errors
+/// If `text` has more than `max` characters.
+pub fn max_length(text: &str, max: usize) -> Result<(), String> {
+    if max < text.chars().count() {
+        Err(format!("Must be at most {max} characters"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Check a number, e.g. of a [`crate::DragValue`] without clamping.
+///
+/// # Errors
+/// If `value` is outside of `range`.
+pub fn in_range<T: PartialOrd + Display>(
+    value: &T,
+    range: RangeInclusive<T>,
+) -> Result<(), String> {
+    if range.contains(value) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Must be between {} and {}",
+            range.start(),
+            range.end()
+        ))
+    }
+}
+
+/// Check that the user picked something, e.g. in a [`crate::ComboBox`] over an `Option`.
+///
+/// # Errors
+/// If `value` is `None`.
+pub fn selected<T>(value: &Option<T>) -> Result<(), String> {
+    if value.is_some() {
+        Ok(())
+    } else {
+        Err("Please select an option".to_owned())
+    }
+}
+
+/// A field of a [`Form`] that failed validation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldError {
+    /// The id of the widget of the field.
+    pub id: Id,
+
+    /// Where the widget of the field is.
+    pub rect: Rect,
+
+    /// The label of the field.
+    pub label: String,
+
+    /// Why the field is invalid.
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Default)]
+struct State {
+    /// The fields the user has changed or left, whose errors are shown.
+    touched: IdSet,
+
+    /// Once the user has tried to submit the form, the errors of all fields are shown.
+    submit_attempted: bool,
+}
+
+/// A form with fields that are validated before it can be submitted.
+///
+/// Add fields with [`FormUi::field`], each with the result of validating its value.
+/// The error of an invalid field is shown once the user has changed or left the field,
+/// or tried to submit the form, with a frame in [`crate::Visuals::error_fg_color`] around the widget.
+///
+/// When the user submits a form with invalid fields, the first of them is focused
+/// and [`FormResponse::submitted`] stays `false`.
+///
+/// See [`crate::Stepper`] for a form spread over several steps.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use egui::{Form, form};
+/// # let mut name = String::new();
+/// # let mut age = 0;
+/// let response = Form::new().id_salt("sign_up").show(ui, |form| {
+///     form.field("Name", form::required(&name), |ui| ui.text_edit_singleline(&mut name));
+///     form.field("Age", form::in_range(&age, 18..=120), |ui| {
+///         ui.add(egui::DragValue::new(&mut age))
+///     });
+///     form.error_summary();
+///     form.submit_button("Sign up");
+/// });
+/// if response.submitted {
+///     // …
+/// }
+/// # });
+/// ```
+#[must_use = "You should call form.show()"]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Form {
+    id_salt: Option<Id>,
+}
+
+impl Form {
+    /// A form where no field has been touched yet.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Used to remember which fields the user has touched.
+    ///
+    /// Default: an id based on the position in the parent [`Ui`].
+    #[inline]
+    pub fn id_salt(mut self, id_salt: impl std::hash::Hash) -> Self {
+        self.id_salt = Some(Id::new(id_salt));
+        self
+    }
+
+    /// Show the form, with the fields added by `add_contents`.
+    pub fn show<R>(
+        self,
+        ui: &mut Ui,
+        add_contents: impl FnOnce(&mut FormUi<'_>) -> R,
+    ) -> FormResponse<R> {
+        let id = self.id_salt.map_or_else(
+            || ui.next_auto_id(),
+            |id_salt| ui.make_persistent_id(id_salt),
+        );
+        let mut state: State = ui.ctx().data_mut(|d| d.get_temp(id)).unwrap_or_default();
+
+        let InnerResponse {
+            inner: (inner, errors, submit),
+            response,
+        } = ui.scope(|ui| {
+            let mut form = FormUi {
+                ui,
+                state: &mut state,
+                errors: Vec::new(),
+                submit: false,
+            };
+            let inner = add_contents(&mut form);
+            (inner, form.errors, form.submit)
+        });
+
+        let submitted = submit && errors.is_empty();
+        if submit && let Some(first) = errors.first() {
+            ui.memory_mut(|mem| mem.request_focus(first.id));
+            ui.scroll_to_rect(first.rect, None);
+            if !state.submit_attempted {
+                state.submit_attempted = true;
+                // The fields before the submit button were shown without their errors:
+                ui.ctx().request_discard("Form submitted with errors");
+            }
+        }
+        ui.ctx().data_mut(|d| d.insert_temp(id, state));
+
+        FormResponse {
+            inner,
+            response,
+            submitted,
+            errors,
+        }
+    }
+}
+
+/// Add fields to a [`Form`], see [`Form::show`].
+///
+/// Dereferences to the [`Ui`] of the form, so you can add anything else to it.
+pub struct FormUi<'a> {
+    ui: &'a mut Ui,
+    state: &'a mut State,
+    errors: Vec<FieldError>,
+    submit: bool,
+}
+
+impl FormUi<'_> {
+    /// Add a field with a `label` above the widget added by `add_widget`.
+    ///
+    /// `validation` is the result of validating the value of the field,
+    /// e.g. with [`required`]. The widget is expected to be focusable,
+    /// like a [`crate::TextEdit`], [`crate::DragValue`] or [`crate::ComboBox`].
+    ///
+    /// Pressing enter in a single-line [`crate::TextEdit`] submits the form.
+    pub fn field(
+        &mut self,
+        label: impl Into<WidgetText>,
+        validation: Result<(), String>,
+        add_widget: impl FnOnce(&mut Ui) -> Response,
+    ) -> Response {
+        let label = label.into();
+        let label_text = label.text().to_owned();
+        let state = &mut *self.state;
+        let (response, error) = self
+            .ui
+            .vertical(|ui| {
+                let label = ui.label(label);
+                let response = add_widget(ui).labelled_by(label.id);
+                if response.changed() || response.lost_focus() {
+                    state.touched.insert(response.id);
+                }
+                let error = validation.err().map(|message| FieldError {
+                    id: response.id,
+                    rect: response.rect,
+                    label: label_text,
+                    message,
+                });
+
+                if let Some(error) = &error
+                    && (state.submit_attempted || state.touched.contains(&response.id))
+                {
+                    let color = ui.visuals().error_fg_color;
+                    ui.painter().rect_stroke(
+                        response.rect,
+                        ui.visuals().widgets.inactive.corner_radius,
+                        Stroke::new(1.0, color),
+                        StrokeKind::Outside,
+                    );
+                    ui.colored_label(color, &error.message);
+                }
+                (response, error)
+            })
+            .inner;
+
+        if response.lost_focus() && self.ui.input(|i| i.key_pressed(Key::Enter)) {
+            self.submit = true;
+        }
+        self.errors.extend(error);
+        response
+    }
+
+    /// Are all the fields added so far valid?
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// List the invalid fields added so far, once the user has tried to submit the form.
+    ///
+    /// Call this after adding the fields, e.g. right before [`Self::submit_button`].
+    pub fn error_summary(&mut self) {
+        if !self.state.submit_attempted || self.errors.is_empty() {
+            return;
+        }
+        let color = self.ui.visuals().error_fg_color;
+        for error in &self.errors {
+            self.ui
+                .colored_label(color, format!("{}: {}", error.label, error.message));
+        }
+    }
+
+    /// A button that submits the form when clicked.
+    pub fn submit_button(&mut self, text: impl Into<WidgetText>) -> Response {
+        let response = self.ui.button(text);
+        if response.clicked() {
+            self.submit = true;
+        }
+        response
+    }
+
+    /// Submit the form, e.g. from your own button or keyboard shortcut.
+    ///
+    /// The form is only submitted if all the fields are valid,
+    /// including the ones added after calling this.
+    pub fn submit(&mut self) {
+        self.submit = true;
+    }
+}
+
+impl Deref for FormUi<'_> {
+    type Target = Ui;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.ui
+    }
+}
+
+impl DerefMut for FormUi<'_> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.ui
+    }
+}
+
+/// What happened to a [`Form`], see [`Form::show`].
+pub struct FormResponse<R> {
+    /// What the contents of the form returned.
+    pub inner: R,
+
+    /// The response of the whole form.
+    pub response: Response,
+
+    /// Did the user submit the form this frame, with all its fields valid?
+    pub submitted: bool,
+
+    /// The invalid fields, in the order they were added.
+    pub errors: Vec<FieldError>,
+}
+
+impl<R> FormResponse<R> {
+    /// Are all the fields valid?
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
@@ -6,6 +6,8 @@ pub(crate) mod area;
 mod close_tag;
 pub mod collapsing_header;
 mod combo_box;
+pub mod flex;
+pub mod form;
 pub mod frame;
 pub mod menu;
 pub mod modal;
@@ -16,7 +18,11 @@ pub(crate) mod resize;
 mod scene;
 pub mod scroll_area;
 mod sides;
+mod splitter;
+mod stepper;
+mod tab_bar;
 mod tooltip;
+mod virtual_list;
 pub(crate) mod window;
 
 pub use {
@@ -24,6 +30,8 @@ pub use {
     close_tag::ClosableTag,
     collapsing_header::{CollapsingHeader, CollapsingResponse},
     combo_box::*,
+    flex::{Flex, FlexAlign, FlexItem, FlexJustify, FlexUi},
+    form::{Form, FormResponse, FormUi},
     frame::Frame,
     modal::{Modal, ModalResponse},
     old_popup::*,
@@ -33,6 +41,10 @@ pub use {
     scene::{DragPanButtons, Scene},
     scroll_area::ScrollArea,
     sides::Sides,
+    splitter::{Splitter, SplitterPane, SplitterUi},
+    stepper::{Stepper, StepperResponse},
+    tab_bar::{Tab, TabBar, TabBarResponse},
     tooltip::*,
+    virtual_list::VirtualList,
     window::Window,
 };
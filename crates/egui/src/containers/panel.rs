@@ -27,6 +27,34 @@ fn animate_expansion(ctx: &Context, id: Id, is_expanded: bool) -> f32 {
     ctx.animate_bool_responsive(id, is_expanded)
 }
 
+/// The stroke of the line along a resize handle, e.g. the edge of a panel.
+pub(crate) fn resize_line_stroke(ui: &Ui, is_resizing: bool, resize_hover: bool) -> Stroke {
+    if is_resizing {
+        ui.style().visuals.widgets.active.fg_stroke // highly visible
+    } else if resize_hover {
+        ui.style().visuals.widgets.hovered.fg_stroke // highly visible
+    } else {
+        // TODO(emilk): distinguish resizable from non-resizable
+        ui.style().visuals.widgets.noninteractive.bg_stroke // dim
+    }
+}
+
+/// The cursor to show when hovering a resize handle that moves along the x axis
+/// (if `horizontal`) or the y axis.
+///
+/// `can_move` is whether the handle can move towards smaller and larger coordinates,
+/// i.e. left/up and right/down.
+pub(crate) fn resize_cursor_icon(horizontal: bool, can_move: [bool; 2]) -> CursorIcon {
+    match (horizontal, can_move) {
+        (true, [false, true]) => CursorIcon::ResizeEast,
+        (true, [true, false]) => CursorIcon::ResizeWest,
+        (true, _) => CursorIcon::ResizeHorizontal,
+        (false, [false, true]) => CursorIcon::ResizeSouth,
+        (false, [true, false]) => CursorIcon::ResizeNorth,
+        (false, _) => CursorIcon::ResizeVertical,
+    }
+}
+
 /// State regarding panels.
 #[derive(Clone, Copy, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -724,13 +752,8 @@ impl Panel {
         PanelState { rect }.store(ui.ctx(), id);
 
         {
-            let stroke = if is_resizing {
-                ui.style().visuals.widgets.active.fg_stroke // highly visible
-            } else if resize_hover {
-                ui.style().visuals.widgets.hovered.fg_stroke // highly visible
-            } else if show_separator_line {
-                // TODO(emilk): distinguish resizable from non-resizable
-                ui.style().visuals.widgets.noninteractive.bg_stroke // dim
+            let stroke = if is_resizing || resize_hover || show_separator_line {
+                resize_line_stroke(ui, is_resizing, resize_hover)
             } else {
                 Stroke::NONE
             };
@@ -848,33 +871,17 @@ impl Panel {
     }
 
     fn cursor_icon(&self, panel_sizer: &PanelSizer<'_>) -> CursorIcon {
-        if panel_sizer.size <= self.size_range.min {
-            match self.side {
-                PanelSide::Vertical(side) => match side {
-                    VerticalSide::Left => CursorIcon::ResizeEast,
-                    VerticalSide::Right => CursorIcon::ResizeWest,
-                },
-                PanelSide::Horizontal(side) => match side {
-                    HorizontalSide::Top => CursorIcon::ResizeSouth,
-                    HorizontalSide::Bottom => CursorIcon::ResizeNorth,
-                },
-            }
-        } else if panel_sizer.size < self.size_range.max {
-            match self.side {
-                PanelSide::Vertical(_) => CursorIcon::ResizeHorizontal,
-                PanelSide::Horizontal(_) => CursorIcon::ResizeVertical,
-            }
-        } else {
-            match self.side {
-                PanelSide::Vertical(side) => match side {
-                    VerticalSide::Left => CursorIcon::ResizeWest,
-                    VerticalSide::Right => CursorIcon::ResizeEast,
-                },
-                PanelSide::Horizontal(side) => match side {
-                    HorizontalSide::Top => CursorIcon::ResizeNorth,
-                    HorizontalSide::Bottom => CursorIcon::ResizeSouth,
-                },
-            }
+        let can_grow = panel_sizer.size < self.size_range.max;
+        let can_shrink = self.size_range.min < panel_sizer.size;
+        match self.side {
+            PanelSide::Vertical(side) => match side {
+                VerticalSide::Left => resize_cursor_icon(true, [can_shrink, can_grow]),
+                VerticalSide::Right => resize_cursor_icon(true, [can_grow, can_shrink]),
+            },
+            PanelSide::Horizontal(side) => match side {
+                HorizontalSide::Top => resize_cursor_icon(false, [can_shrink, can_grow]),
+                HorizontalSide::Bottom => resize_cursor_icon(false, [can_grow, can_shrink]),
+            },
         }
     }
 
@@ -3,14 +3,15 @@
 #![expect(clippy::needless_range_loop)]
 
 use std::ops::{Add, AddAssign, BitOr, BitOrAssign};
+use std::sync::Arc;
 
 use emath::GuiRounding as _;
 use epaint::Margin;
 
 use crate::{
-    Context, CursorIcon, Id, NumExt as _, Pos2, Rangef, Rect, Response, Sense, Ui, UiBuilder,
-    UiKind, UiStackInfo, Vec2, Vec2b, WidgetInfo, emath, epaint, lerp, pass_state, pos2, remap,
-    remap_clamp,
+    Color32, Context, CursorIcon, Id, NumExt as _, Pos2, Rangef, Rect, Response, Sense, Ui,
+    UiBuilder, UiKind, UiStackInfo, Vec2, Vec2b, WidgetInfo, emath, epaint, layers::ShapeIdx, lerp,
+    pass_state, pos2, remap, remap_clamp,
 };
 
 #[derive(Clone, Copy, Debug)]
@@ -53,6 +54,11 @@ pub struct State {
 
     /// Area that can be dragged. This is the size of the content from the last frame.
     interact_rect: Option<Rect>,
+
+    /// The widget set with [`ScrollArea::anchor_id`], and where it was relative to the
+    /// top left of the content last frame.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    anchor: Option<(Id, Vec2)>,
 }
 
 impl Default for State {
@@ -67,6 +73,7 @@ impl Default for State {
             scroll_start_offset_from_top_left: [None; 2],
             scroll_stuck_to_end: Vec2b::TRUE,
             interact_rect: None,
+            anchor: None,
         }
     }
 }
@@ -104,6 +111,43 @@ pub struct ScrollAreaOutput<R> {
     pub inner_rect: Rect,
 }
 
+impl<R> ScrollAreaOutput<R> {
+    /// How far the viewport is from the start (left/top) of the content.
+    #[inline]
+    pub fn distance_to_start(&self) -> Vec2 {
+        self.state.offset
+    }
+
+    /// How far the viewport is from the end (right/bottom) of the content.
+    #[inline]
+    pub fn distance_to_end(&self) -> Vec2 {
+        (self.content_size - self.inner_rect.size() - self.state.offset).max(Vec2::ZERO)
+    }
+
+    /// Is the viewport within `threshold` points of the start (left/top) of the content?
+    ///
+    /// Use this to load more content when the user scrolls up, e.g. older messages in a chat log.
+    /// Together with [`ScrollArea::anchor_id`] the content you add above won't move the viewport.
+    ///
+    /// This is also `true` if the content fits without scrolling.
+    #[inline]
+    pub fn is_near_start(&self, threshold: f32) -> Vec2b {
+        let distance = self.distance_to_start();
+        Vec2b::new(distance.x <= threshold, distance.y <= threshold)
+    }
+
+    /// Is the viewport within `threshold` points of the end (right/bottom) of the content?
+    ///
+    /// Use this to load more content when the user scrolls down, e.g. for an infinite feed.
+    ///
+    /// This is also `true` if the content fits without scrolling.
+    #[inline]
+    pub fn is_near_end(&self, threshold: f32) -> Vec2b {
+        let distance = self.distance_to_end();
+        Vec2b::new(distance.x <= threshold, distance.y <= threshold)
+    }
+}
+
 /// Indicate whether the horizontal and vertical scroll bars must be always visible, hidden or visible when needed.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -141,6 +185,297 @@ impl ScrollBarVisibility {
     ];
 }
 
+/// Show a minimap instead of the vertical scroll bar of a [`ScrollArea`].
+///
+/// The minimap shows a scaled-down preview of the content, and [`MinimapMarker`]s for things
+/// like search hits and errors. The part of the content that is currently visible is highlighted.
+/// Click anywhere on the minimap to jump there, or drag to scroll.
+///
+/// The preview is made from what the content painted, with each row of text shown as a block
+/// in the text color.
+/// It is only rebuilt when the content changes.
+/// Most widgets skip painting when they are outside the visible area, so for them only the visible
+/// part will show up in the preview, while e.g. a large [`crate::TextEdit`] will show up in full.
+/// Turn off the preview with [`Self::preview`] and use markers if that is a problem.
+///
+/// The minimap follows [`crate::style::ScrollStyle::floating`]:
+/// when floating it is painted on top of the content, otherwise it allocates space next to it.
+///
+/// See [`ScrollArea::minimap`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Minimap {
+    /// The width of the minimap.
+    pub width: f32,
+
+    /// The largest scale the content is shown at.
+    ///
+    /// The content is scaled down to fit the height of the [`ScrollArea`],
+    /// but short content is not scaled up beyond this.
+    pub max_scale: f32,
+
+    /// Show a scaled-down copy of the content?
+    pub preview: bool,
+
+    /// Markers to show along the minimap.
+    pub markers: Vec<MinimapMarker>,
+}
+
+impl Default for Minimap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Minimap {
+    /// A minimap with a preview of the content, and no markers.
+    pub fn new() -> Self {
+        Self {
+            width: 64.0,
+            max_scale: 0.15,
+            preview: true,
+            markers: Vec::new(),
+        }
+    }
+
+    /// The width of the minimap.
+    ///
+    /// Default: `64.0`.
+    #[inline]
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// The largest scale the content is shown at.
+    ///
+    /// Default: `0.15`.
+    #[inline]
+    pub fn max_scale(mut self, max_scale: f32) -> Self {
+        self.max_scale = max_scale;
+        self
+    }
+
+    /// Show a scaled-down copy of the content?
+    ///
+    /// Default: `true`.
+    #[inline]
+    pub fn preview(mut self, preview: bool) -> Self {
+        self.preview = preview;
+        self
+    }
+
+    /// Add a marker.
+    #[inline]
+    pub fn marker(mut self, marker: MinimapMarker) -> Self {
+        self.markers.push(marker);
+        self
+    }
+
+    /// Add several markers.
+    #[inline]
+    pub fn markers(mut self, markers: impl IntoIterator<Item = MinimapMarker>) -> Self {
+        self.markers.extend(markers);
+        self
+    }
+
+    /// How much to scale the content by to show it in a minimap of the given height.
+    fn scale(&self, track_height: f32, content_height: f32) -> f32 {
+        if content_height <= 0.0 {
+            self.max_scale
+        } else {
+            (track_height / content_height).at_most(self.max_scale)
+        }
+    }
+}
+
+/// Something to highlight on a [`Minimap`], like a search hit or an error.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MinimapMarker {
+    /// Where in the content the marker is, as a fraction of the content height,
+    /// where `0.0` is the top and `1.0` is the bottom.
+    ///
+    /// For a list of lines, this is `line / num_lines..=(line + 1) / num_lines`.
+    pub range: Rangef,
+
+    /// The color of the marker, painted on top of the preview.
+    pub color: Color32,
+}
+
+impl MinimapMarker {
+    /// A marker covering part of the content, given as fractions of the content height.
+    pub fn new(range: impl Into<Rangef>, color: Color32) -> Self {
+        Self {
+            range: range.into(),
+            color,
+        }
+    }
+
+    /// A marker at a single position in the content, given as a fraction of the content height.
+    pub fn at(t: f32, color: Color32) -> Self {
+        Self::new(t..=t, color)
+    }
+}
+
+/// The scaled-down copy of the content shown in a [`Minimap`].
+///
+/// This is only rebuilt when the content or the scale changes,
+/// not when the content is scrolled.
+#[derive(Clone, Default)]
+struct MinimapPreview {
+    /// The shapes the content painted.
+    source: Vec<epaint::ClippedShape>,
+
+    /// Where the top left of the content was when [`Self::source`] was painted.
+    source_origin: Pos2,
+
+    /// From content coordinates (relative to the top left of the content) to the minimap.
+    transform: emath::TSTransform,
+
+    /// What to paint in the minimap.
+    shapes: Arc<[epaint::Shape]>,
+}
+
+impl MinimapPreview {
+    /// The preview of the shapes in `range` of the layer,
+    /// which were painted with the top left of the content at `content_origin`.
+    fn update(
+        ctx: &Context,
+        id: Id,
+        layer_id: crate::LayerId,
+        range: std::ops::Range<usize>,
+        content_origin: Pos2,
+        transform: emath::TSTransform,
+    ) -> Arc<[epaint::Shape]> {
+        let mut preview: Self = ctx.data_mut(|d| std::mem::take(d.get_temp_mut_or_default(id)));
+
+        ctx.graphics(|graphics| {
+            let Some(paint_list) = graphics.get(layer_id) else {
+                preview = Self::default();
+                return;
+            };
+            let source = || paint_list.all_entries().skip(range.start).take(range.len());
+
+            // The clip rect doesn't matter for the preview, and doesn't move with the content:
+            let scrolled = content_origin - preview.source_origin;
+            let unchanged = preview.transform == transform
+                && preview.source.len() == source().len()
+                && preview
+                    .source
+                    .iter()
+                    .zip(source())
+                    .all(|(a, b)| same_preview_source(&a.shape, &b.shape, scrolled));
+            if !unchanged {
+                let screen_transform =
+                    transform * emath::TSTransform::from_translation(-content_origin.to_vec2());
+                let mut shapes = vec![];
+                for clipped in source() {
+                    add_preview_shape(&mut shapes, &clipped.shape, screen_transform);
+                }
+                preview = Self {
+                    source: source().cloned().collect(),
+                    source_origin: content_origin,
+                    transform,
+                    shapes: shapes.into(),
+                };
+            }
+        });
+
+        let shapes = Arc::clone(&preview.shapes);
+        ctx.data_mut(|d| d.insert_temp(id, preview));
+        shapes
+    }
+}
+
+/// Would the two shapes give the same preview, if `a` is moved by `delta`?
+fn same_preview_source(a: &epaint::Shape, b: &epaint::Shape, delta: Vec2) -> bool {
+    use epaint::Shape;
+
+    match (a, b) {
+        (Shape::Vec(a), Shape::Vec(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b)
+                    .all(|(a, b)| same_preview_source(a, b, delta))
+        }
+        (Shape::Text(a), Shape::Text(b)) => {
+            // The galley is shared while the text stays the same, and is slow to compare:
+            Arc::ptr_eq(&a.galley, &b.galley)
+                && a.pos + delta == b.pos
+                && a.override_text_color == b.override_text_color
+                && a.fallback_color == b.fallback_color
+                && a.opacity_factor == b.opacity_factor
+        }
+        (Shape::Mesh(a), Shape::Mesh(b)) => {
+            (Arc::ptr_eq(a, b) && delta == Vec2::ZERO)
+                || (a.texture_id == b.texture_id
+                    && a.indices == b.indices
+                    && a.vertices.len() == b.vertices.len()
+                    && a.vertices
+                        .iter()
+                        .zip(&b.vertices)
+                        .all(|(a, b)| a.pos + delta == b.pos && a.uv == b.uv && a.color == b.color))
+        }
+        (Shape::Path(a), Shape::Path(b)) => {
+            a.closed == b.closed
+                && a.fill == b.fill
+                && a.stroke == b.stroke
+                && a.points.len() == b.points.len()
+                && a.points
+                    .iter()
+                    .zip(&b.points)
+                    .all(|(a, b)| *a + delta == *b)
+        }
+        _ if delta == Vec2::ZERO => a == b,
+        _ => {
+            // The remaining shapes are small:
+            let mut a = a.clone();
+            a.translate(delta);
+            a == *b
+        }
+    }
+}
+
+/// Scale down the shape for the preview.
+///
+/// Text is too small to read in the minimap anyway, so each row is shown as a block
+/// in the text color. That is also a lot cheaper than scaling the galley.
+fn add_preview_shape(
+    shapes: &mut Vec<epaint::Shape>,
+    shape: &epaint::Shape,
+    transform: emath::TSTransform,
+) {
+    use epaint::Shape;
+
+    match shape {
+        Shape::Noop | Shape::Callback(_) => {}
+        Shape::Vec(vec) => {
+            for shape in vec {
+                add_preview_shape(shapes, shape, transform);
+            }
+        }
+        Shape::Text(text) => {
+            let color = text
+                .override_text_color
+                .unwrap_or(text.fallback_color)
+                .gamma_multiply(text.opacity_factor);
+            for row in &text.galley.rows {
+                if row.glyphs.is_empty() {
+                    continue;
+                }
+                let rect = row
+                    .rect_without_leading_space()
+                    .translate(text.pos.to_vec2());
+                shapes.push(Shape::rect_filled(transform * rect, 0.0, color));
+            }
+        }
+        _ => {
+            let mut shape = shape.clone();
+            shape.transform(transform);
+            shapes.push(shape);
+        }
+    }
+}
+
 /// What is the source of scrolling for a [`ScrollArea`].
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -307,6 +642,11 @@ pub struct ScrollArea {
 
     /// If false, `scroll_to_*` functions will not be animated
     animated: bool,
+
+    /// Keep this widget at the same position in the viewport.
+    anchor_id: Option<Id>,
+
+    minimap: Option<Minimap>,
 }
 
 impl ScrollArea {
@@ -355,6 +695,8 @@ impl ScrollArea {
             content_margin: None,
             stick_to_end: Vec2b::FALSE,
             animated: true,
+            anchor_id: None,
+            minimap: None,
         }
     }
 
@@ -635,6 +977,73 @@ impl ScrollArea {
         self.stick_to_end[1] = stick;
         self
     }
+
+    /// Keep the widget with this [`Id`] at the same position in the viewport, even when
+    /// content is added, removed or resized above (or to the left of) it.
+    ///
+    /// This is useful when loading older messages into a chat log or log viewer:
+    /// without an anchor the scroll offset stays the same, so the visible content jumps.
+    ///
+    /// The widget must be added to the [`ScrollArea`] each frame.
+    /// Usually you want to anchor to one of the widgets that is currently visible,
+    /// e.g. the first or last visible row.
+    ///
+    /// If the anchor moves, the pass is discarded (see [`Context::request_discard`])
+    /// so the user never sees the content jump.
+    /// [`Self::stick_to_bottom`] and [`Self::stick_to_right`] take precedence over this.
+    ///
+    /// ```
+    /// # egui::__run_test_ui(|ui| {
+    /// # let messages: Vec<(u64, String)> = vec![];
+    /// let first_id = messages.first().map(|(id, _)| egui::Id::new(id));
+    /// let mut scroll_area = egui::ScrollArea::vertical();
+    /// if let Some(first_id) = first_id {
+    ///     scroll_area = scroll_area.anchor_id(first_id);
+    /// }
+    /// let output = scroll_area.show(ui, |ui| {
+    ///     for (id, message) in &messages {
+    ///         ui.scope_builder(egui::UiBuilder::new().id(id), |ui| ui.label(message));
+    ///     }
+    /// });
+    /// if output.is_near_start(100.0).y {
+    ///     // Load older messages…
+    /// }
+    /// # });
+    /// ```
+    #[inline]
+    pub fn anchor_id(mut self, anchor_id: impl Into<Option<Id>>) -> Self {
+        self.anchor_id = anchor_id.into();
+        self
+    }
+
+    /// Show a [`Minimap`] instead of the vertical scroll bar.
+    ///
+    /// The preview in the minimap only shows what the content painted. Most widgets skip painting
+    /// when they are outside the visible area, so usually only the visible part of the content
+    /// shows up in the preview. Use [`MinimapMarker`]s for things that should always be shown.
+    ///
+    /// ```
+    /// # egui::__run_test_ui(|ui| {
+    /// # let lines: Vec<String> = vec![];
+    /// use egui::scroll_area::{Minimap, MinimapMarker};
+    ///
+    /// let num_lines = lines.len().max(1) as f32;
+    /// let errors = lines.iter().enumerate().filter(|(_, line)| line.contains("ERROR"));
+    /// let minimap = Minimap::new().markers(errors.map(|(i, _)| {
+    ///     MinimapMarker::new(i as f32 / num_lines..=(i + 1) as f32 / num_lines, egui::Color32::RED)
+    /// }));
+    /// egui::ScrollArea::vertical().minimap(minimap).show(ui, |ui| {
+    ///     for line in &lines {
+    ///         ui.monospace(line);
+    ///     }
+    /// });
+    /// # });
+    /// ```
+    #[inline]
+    pub fn minimap(mut self, minimap: impl Into<Option<Minimap>>) -> Self {
+        self.minimap = minimap.into();
+        self
+    }
 }
 
 struct Prepared {
@@ -684,6 +1093,16 @@ struct Prepared {
     background_drag_response: Option<Response>,
 
     animated: bool,
+
+    anchor_id: Option<Id>,
+
+    /// Did the user set the scroll offset with [`ScrollArea::scroll_offset`] this frame?
+    offset_was_set: Vec2b,
+
+    minimap: Option<Minimap>,
+
+    /// Where the shapes painted by the content start, for the [`Minimap`] preview.
+    content_shapes_start: ShapeIdx,
 }
 
 impl ScrollArea {
@@ -705,6 +1124,8 @@ impl ScrollArea {
             content_margin: _, // Used elsewhere
             stick_to_end,
             animated,
+            anchor_id,
+            minimap,
         } = self;
 
         let ctx = ui.ctx().clone();
@@ -732,7 +1153,16 @@ impl ScrollArea {
             ctx.animate_bool_responsive(id.with("v"), show_bars[1]),
         );
 
-        let current_bar_use = show_bars_factor.yx() * ui.spacing().scroll.allocated_width();
+        let mut bar_allocated_width = Vec2::splat(ui.spacing().scroll.allocated_width());
+        if let Some(minimap) = &minimap {
+            let scroll_style = ui.spacing().scroll;
+            if !scroll_style.floating {
+                // The vertical bar uses up horizontal space:
+                bar_allocated_width.x =
+                    scroll_style.bar_inner_margin + minimap.width + scroll_style.bar_outer_margin;
+            }
+        }
+        let current_bar_use = show_bars_factor.yx() * bar_allocated_width;
 
         let available_outer = ui.available_rect_before_wrap();
 
@@ -781,6 +1211,8 @@ impl ScrollArea {
                 .ui_stack_info(UiStackInfo::new(UiKind::ScrollArea))
                 .max_rect(content_max_rect),
         );
+        let content_shapes_start =
+            ctx.graphics_mut(|graphics| graphics.entry(content_ui.layer_id()).next_idx());
 
         {
             // Clip the content, but only when we really need to:
@@ -923,6 +1355,10 @@ impl ScrollArea {
             saved_scroll_target,
             background_drag_response,
             animated,
+            anchor_id,
+            offset_was_set: Vec2b::new(offset_x.is_some(), offset_y.is_some()),
+            minimap,
+            content_shapes_start,
         }
     }
 
@@ -1050,10 +1486,44 @@ impl Prepared {
             saved_scroll_target,
             background_drag_response,
             animated,
+            anchor_id,
+            offset_was_set,
+            minimap,
+            content_shapes_start,
         } = self;
 
         let content_size = content_ui.min_size();
 
+        // The minimap may be painted on the same layer as the content, so remember where it ended:
+        let content_shapes = content_shapes_start.0
+            ..ui.ctx()
+                .graphics_mut(|graphics| graphics.entry(content_ui.layer_id()).next_idx().0);
+
+        // Where the top left of the content is, at the scroll offset we laid it out with:
+        let content_origin = content_ui.max_rect().min;
+        let anchor_pos = anchor_id.and_then(|anchor_id| {
+            let rect = ui
+                .ctx()
+                .viewport(|viewport| viewport.this_pass.widgets.get(anchor_id).map(|w| w.rect))?;
+            Some((anchor_id, rect.min - content_origin))
+        });
+        if let (Some((anchor_id, anchor_pos)), Some((last_id, last_anchor_pos))) =
+            (anchor_pos, state.anchor)
+            && anchor_id == last_id
+        {
+            // Keep the anchor where it was in the viewport, even if the content before it changed:
+            for d in 0..2 {
+                let delta = anchor_pos[d] - last_anchor_pos[d];
+                if direction_enabled[d] && !offset_was_set[d] && 0.5 < delta.abs() {
+                    state.offset[d] += delta;
+                    if let Some(animation) = &mut state.offset_target[d] {
+                        animation.target_offset += delta;
+                    }
+                    ui.ctx().request_discard("ScrollArea anchor moved");
+                }
+            }
+        }
+
         let scroll_delta = content_ui
             .ctx()
             .pass_state_mut(|state| std::mem::take(&mut state.scroll_delta));
@@ -1265,6 +1735,105 @@ impl Prepared {
                 max_cross = ui.clip_rect().max[1 - d] - outer_margin;
             }
 
+            if d == 1
+                && let Some(minimap) = &minimap
+            {
+                let min_cross = if scroll_style.floating {
+                    max_cross - show_factor * minimap.width
+                } else {
+                    inner_rect.max.x + inner_margin
+                };
+                let minimap_rect =
+                    Rect::from_x_y_ranges(min_cross..=max_cross, scroll_bar_rect.y_range());
+                let scale = minimap.scale(minimap_rect.height(), content_size.y);
+                let track_top = minimap_rect.top();
+
+                let sense = if scroll_source.scroll_bar && ui.is_enabled() {
+                    Sense::CLICK | Sense::DRAG
+                } else {
+                    Sense::hover()
+                };
+                let response = ui.interact(minimap_rect, interact_id, sense);
+                response.widget_info(|| WidgetInfo::new(crate::WidgetType::ScrollBar));
+                state.scroll_bar_interaction[d] = response.hovered() || response.dragged();
+
+                if let Some(pointer_pos) = response.interact_pointer_pos() {
+                    // Center the viewport on where the user clicked:
+                    let content_y = (pointer_pos.y - track_top) / scale;
+                    state.offset.y = (content_y - inner_rect.height() / 2.0)
+                        .clamp(0.0, max_offset.y.at_least(0.0));
+                    state.scroll_stuck_to_end[d] = false;
+                    state.offset_target[d] = None;
+                    state.vel[d] = 0.0;
+                }
+
+                if ui.is_rect_visible(minimap_rect) {
+                    let painter = ui
+                        .painter()
+                        .with_clip_rect(minimap_rect.intersect(ui.clip_rect()));
+                    let background_opacity =
+                        if !scroll_style.floating || response.hovered() || response.dragged() {
+                            1.0
+                        } else {
+                            scroll_style.active_background_opacity
+                        };
+                    painter.rect_filled(
+                        minimap_rect,
+                        0.0,
+                        ui.visuals()
+                            .extreme_bg_color
+                            .gamma_multiply(background_opacity),
+                    );
+
+                    if minimap.preview {
+                        // Map content coordinates (relative to the top left of the content) to the minimap:
+                        let transform = emath::TSTransform::new(minimap_rect.min.to_vec2(), scale);
+                        let preview = MinimapPreview::update(
+                            ui.ctx(),
+                            id.with("__minimap_preview"),
+                            content_ui.layer_id(),
+                            content_shapes.clone(),
+                            content_origin,
+                            transform,
+                        );
+                        painter.extend(preview.iter().cloned());
+                    }
+
+                    let scaled_content_height = content_size.y * scale;
+                    for marker in &minimap.markers {
+                        let y_range = Rangef::new(
+                            track_top + marker.range.min * scaled_content_height,
+                            track_top + marker.range.max * scaled_content_height,
+                        );
+                        let rect = Rect::from_x_y_ranges(minimap_rect.x_range(), y_range)
+                            .expand2(Vec2::new(0.0, (1.0 - y_range.span() / 2.0).at_least(0.0)));
+                        painter.rect_filled(rect, 0.0, marker.color);
+                    }
+
+                    // Highlight what is visible in the viewport:
+                    let visuals = if response.is_pointer_button_down_on() {
+                        &ui.visuals().widgets.active
+                    } else if response.hovered() {
+                        &ui.visuals().widgets.hovered
+                    } else {
+                        &ui.visuals().widgets.inactive
+                    };
+                    let viewport_rect = Rect::from_x_y_ranges(
+                        minimap_rect.x_range(),
+                        track_top + state.offset.y * scale
+                            ..=track_top + (state.offset.y + inner_rect.height()) * scale,
+                    );
+                    painter.rect(
+                        viewport_rect,
+                        0.0,
+                        visuals.weak_bg_fill.gamma_multiply(0.3),
+                        visuals.bg_stroke,
+                        epaint::StrokeKind::Inside,
+                    );
+                }
+                continue;
+            }
+
             let full_width = scroll_style.bar_width;
 
             // The bounding rect of a fully visible bar.
@@ -1499,9 +2068,46 @@ impl Prepared {
         state.show_scroll = show_scroll_this_frame;
         state.content_is_too_large = content_is_too_large;
         state.interact_rect = Some(inner_rect);
+        state.anchor = anchor_pos;
 
         state.store(ui.ctx(), id);
 
         (content_size, state)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use epaint::{Color32, Shape, Stroke, pos2, vec2};
+
+    use super::same_preview_source;
+
+    #[test]
+    fn test_scrolled_preview_source() {
+        let shapes = [
+            Shape::rect_filled(
+                crate::Rect::from_min_size(pos2(10.0, 20.0), vec2(30.0, 5.0)),
+                0.0,
+                Color32::RED,
+            ),
+            Shape::line(
+                vec![pos2(0.0, 0.0), pos2(10.0, 10.0), pos2(20.0, 0.0)],
+                Stroke::new(1.0, Color32::WHITE),
+            ),
+            Shape::Vec(vec![Shape::circle_filled(
+                pos2(5.0, 5.0),
+                2.0,
+                Color32::BLUE,
+            )]),
+        ];
+        let scrolled = vec2(0.0, -42.0);
+        for shape in shapes {
+            assert!(same_preview_source(&shape, &shape, vec2(0.0, 0.0)));
+            let mut moved = shape.clone();
+            moved.translate(scrolled);
+            assert!(same_preview_source(&shape, &moved, scrolled));
+            assert!(!same_preview_source(&shape, &moved, vec2(0.0, 0.0)));
+            assert!(!same_preview_source(&shape, &shape, scrolled));
+        }
+    }
+}
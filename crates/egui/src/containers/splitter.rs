@@ -0,0 +1,486 @@
+//! Divide a [`Ui`] into resizable panes, see [`Splitter`].
+
+use emath::{Align, NumExt as _, Rangef, Rect, Vec2};
+
+use crate::{
+    Context, Id, InnerResponse, Layout, Sense, Stroke, Ui, UiBuilder,
+    containers::panel::{resize_cursor_icon, resize_line_stroke},
+};
+
+/// How a single pane of a [`Splitter`] is sized.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct SplitterPane {
+    weight: f32,
+    min_size: f32,
+    collapsible: bool,
+}
+
+impl Default for SplitterPane {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SplitterPane {
+    /// A pane with a weight of `1.0` that can't be collapsed.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            weight: 1.0,
+            min_size: 0.0,
+            collapsible: false,
+        }
+    }
+
+    /// How much of the space this pane gets at first, relative to the other panes.
+    ///
+    /// Three panes with a weight of `1.0`, `2.0` and `1.0` start out sized 1:2:1.
+    /// After that, the sizes are set by the user dragging the handles between the panes.
+    ///
+    /// Default: `1.0`.
+    #[inline]
+    pub fn weight(mut self, weight: f32) -> Self {
+        self.weight = weight.at_least(0.0);
+        self
+    }
+
+    /// The user can't make the pane smaller than this.
+    ///
+    /// Default: `0.0`.
+    #[inline]
+    pub fn min_size(mut self, min_size: f32) -> Self {
+        self.min_size = min_size.at_least(0.0);
+        self
+    }
+
+    /// Can the user collapse this pane by double-clicking a handle next to it?
+    ///
+    /// Double-click again to bring it back at the size it had.
+    ///
+    /// Default: `false`.
+    #[inline]
+    pub fn collapsible(mut self, collapsible: bool) -> Self {
+        self.collapsible = collapsible;
+        self
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+struct State {
+    /// The share of the space each pane gets, relative to the other panes that aren't collapsed.
+    ///
+    /// A collapsed pane keeps its ratio, so we can bring it back at the same size.
+    ratios: Vec<f32>,
+
+    collapsed: Vec<bool>,
+}
+
+impl State {
+    fn load(ctx: &Context, id: Id, persist: bool) -> Option<Self> {
+        if persist {
+            ctx.data_mut(|d| d.get_persisted(id))
+        } else {
+            ctx.data_mut(|d| d.get_temp(id))
+        }
+    }
+
+    fn store(self, ctx: &Context, id: Id, persist: bool) {
+        if persist {
+            ctx.data_mut(|d| d.insert_persisted(id, self));
+        } else {
+            ctx.data_mut(|d| d.insert_temp(id, self));
+        }
+    }
+
+    fn new(panes: &[SplitterPane]) -> Self {
+        let total_weight: f32 = panes.iter().map(|pane| pane.weight).sum();
+        let ratios = panes
+            .iter()
+            .map(|pane| {
+                if 0.0 < total_weight {
+                    pane.weight / total_weight
+                } else {
+                    1.0 / panes.len() as f32
+                }
+            })
+            .collect();
+        let collapsed = vec![false; panes.len()];
+        Self { ratios, collapsed }
+    }
+
+    /// The size of each pane when sharing `space`, respecting the min sizes.
+    fn sizes(&self, panes: &[SplitterPane], space: f32) -> Vec<f32> {
+        let mut sizes = vec![0.0; panes.len()];
+        let mut at_min = self.collapsed.clone();
+        loop {
+            let used_by_min: f32 = (0..sizes.len())
+                .filter(|&i| at_min[i] && !self.collapsed[i])
+                .map(|i| panes[i].min_size)
+                .sum();
+            let left = (space - used_by_min).at_least(0.0);
+            let total_ratio: f32 = (0..sizes.len())
+                .filter(|&i| !at_min[i])
+                .map(|i| self.ratios[i])
+                .sum();
+
+            let mut done = true;
+            for i in 0..sizes.len() {
+                if self.collapsed[i] {
+                    sizes[i] = 0.0;
+                } else if at_min[i] {
+                    sizes[i] = panes[i].min_size;
+                } else {
+                    sizes[i] = if 0.0 < total_ratio {
+                        left * self.ratios[i] / total_ratio
+                    } else {
+                        0.0
+                    };
+                    if sizes[i] < panes[i].min_size {
+                        at_min[i] = true;
+                        done = false;
+                    }
+                }
+            }
+            if done {
+                return sizes;
+            }
+        }
+    }
+
+    /// Remember the sizes of the panes that aren't collapsed as ratios.
+    fn set_sizes(&mut self, sizes: &[f32], space: f32) {
+        if space <= 0.0 {
+            return;
+        }
+        for (i, size) in sizes.iter().enumerate() {
+            if !self.collapsed[i] {
+                self.ratios[i] = size / space;
+            }
+        }
+    }
+
+    /// Collapse (or bring back) one of the two panes next to a handle,
+    /// when the user double-clicks it.
+    fn toggle_collapsed(&mut self, panes: &[SplitterPane], handle: usize) {
+        let Some(pane) = [handle, handle + 1]
+            .into_iter()
+            .find(|&i| self.collapsed[i])
+            .or_else(|| {
+                [handle, handle + 1]
+                    .into_iter()
+                    .find(|&i| panes[i].collapsible)
+            })
+        else {
+            return;
+        };
+
+        let num_visible = self.collapsed.iter().filter(|c| !**c).count();
+        if self.collapsed[pane] || 1 < num_visible {
+            self.collapsed[pane] = !self.collapsed[pane];
+        }
+    }
+}
+
+/// Divide the available space of a [`Ui`] into panes, side by side or on top of each other,
+/// with handles between them that the user can drag to resize the panes.
+///
+/// Configure the panes with [`Self::pane`], and fill them in with [`SplitterUi::pane`].
+/// Each pane is given its own [`Ui`], which you can split further by putting another
+/// [`Splitter`] in it.
+///
+/// The sizes of the panes are remembered as ratios of the available space.
+/// With an [`Self::id_salt`] they are persisted with the rest of the egui memory.
+///
+/// When the number of panes changes, the sizes are reset based on [`SplitterPane::weight`].
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use egui::{Splitter, SplitterPane};
+/// Splitter::horizontal()
+///     .id_salt("editor")
+///     .pane(SplitterPane::new().min_size(100.0).collapsible(true))
+///     .pane(SplitterPane::new().weight(3.0))
+///     .show(ui, |splitter| {
+///         splitter.pane(|ui| {
+///             ui.label("Files");
+///         });
+///         splitter.pane(|ui| {
+///             Splitter::vertical()
+///                 .id_salt("editor_terminal")
+///                 .pane(SplitterPane::new().weight(2.0))
+///                 .pane(SplitterPane::new().collapsible(true))
+///                 .show(ui, |splitter| {
+///                     splitter.pane(|ui| ui.label("Code"));
+///                     splitter.pane(|ui| ui.label("Terminal"));
+///                 });
+///         });
+///     });
+/// # });
+/// ```
+#[must_use = "You should call splitter.show()"]
+#[derive(Clone, Debug)]
+pub struct Splitter {
+    id_salt: Option<Id>,
+    horizontal: bool,
+    gap: Option<f32>,
+    show_separator_line: bool,
+    panes: Vec<SplitterPane>,
+}
+
+impl Splitter {
+    fn new(horizontal: bool) -> Self {
+        Self {
+            id_salt: None,
+            horizontal,
+            gap: None,
+            show_separator_line: true,
+            panes: Vec::new(),
+        }
+    }
+
+    /// Put the panes side by side, left to right.
+    #[inline]
+    pub fn horizontal() -> Self {
+        Self::new(true)
+    }
+
+    /// Put the panes on top of each other, top to bottom.
+    #[inline]
+    pub fn vertical() -> Self {
+        Self::new(false)
+    }
+
+    /// Used to remember the sizes of the panes.
+    ///
+    /// With an id salt the sizes are persisted (with the `persistence` feature),
+    /// without one they are only remembered until the app is closed.
+    ///
+    /// Default: an id based on the position in the parent [`Ui`].
+    #[inline]
+    pub fn id_salt(mut self, id_salt: impl std::hash::Hash) -> Self {
+        self.id_salt = Some(Id::new(id_salt));
+        self
+    }
+
+    /// The space between two panes, where the handle is.
+    ///
+    /// Default: [`crate::style::Spacing::item_spacing`].
+    #[inline]
+    pub fn gap(mut self, gap: f32) -> Self {
+        self.gap = Some(gap.at_least(0.0));
+        self
+    }
+
+    /// Show a line along the handles, even when they are not hovered.
+    ///
+    /// Default: `true`.
+    #[inline]
+    pub fn show_separator_line(mut self, show_separator_line: bool) -> Self {
+        self.show_separator_line = show_separator_line;
+        self
+    }
+
+    /// Add a pane, after the ones already added.
+    ///
+    /// The contents are added with [`SplitterUi::pane`], in the same order.
+    #[inline]
+    pub fn pane(mut self, pane: SplitterPane) -> Self {
+        self.panes.push(pane);
+        self
+    }
+
+    /// Add several panes, after the ones already added.
+    #[inline]
+    pub fn panes(mut self, panes: impl IntoIterator<Item = SplitterPane>) -> Self {
+        self.panes.extend(panes);
+        self
+    }
+
+    /// Fill the available space of `ui` with the panes, whose contents are added by `add_contents`.
+    pub fn show<R>(
+        self,
+        ui: &mut Ui,
+        add_contents: impl FnOnce(&mut SplitterUi<'_>) -> R,
+    ) -> InnerResponse<R> {
+        let persist = self.id_salt.is_some();
+        let id = self.id_salt.map_or_else(
+            || ui.next_auto_id(),
+            |id_salt| ui.make_persistent_id(id_salt),
+        );
+        let rect = ui.available_rect_before_wrap();
+        let axis = self.main_axis();
+        let gap = self.gap.unwrap_or_else(|| ui.spacing().item_spacing[axis]);
+        let panes = &self.panes;
+
+        let mut state = State::load(ui.ctx(), id, persist)
+            .filter(|state| state.ratios.len() == panes.len())
+            .unwrap_or_else(|| State::new(panes));
+        let num_handles = panes.len().saturating_sub(1);
+        let space = (rect.size()[axis] - num_handles as f32 * gap).at_least(0.0);
+        let mut sizes = state.sizes(panes, space);
+
+        // Resize before laying out the panes, to avoid frame latency:
+        for handle in 0..num_handles {
+            let Some(response) = ui.ctx().read_response(id.with(("handle", handle))) else {
+                continue;
+            };
+            if let (true, Some(pointer)) = (response.dragged(), response.interact_pointer_pos()) {
+                let start =
+                    rect.min[axis] + sizes[..handle].iter().sum::<f32>() + handle as f32 * gap;
+                let both = sizes[handle] + sizes[handle + 1];
+                let [first, second] = [handle, handle + 1].map(|i| panes[i].min_size);
+                let size = (pointer[axis] - start - 0.5 * gap).clamp(0.0, both);
+
+                // Dragging a collapsed pane open brings it back:
+                let size = if state.collapsed[handle] && first <= size {
+                    state.collapsed[handle] = false;
+                    size
+                } else if state.collapsed[handle + 1] && second <= both - size {
+                    state.collapsed[handle + 1] = false;
+                    size
+                } else if state.collapsed[handle] || state.collapsed[handle + 1] {
+                    sizes[handle]
+                } else {
+                    size.clamp(first, (both - second).at_least(first))
+                };
+                sizes[handle] = size;
+                sizes[handle + 1] = both - size;
+                state.set_sizes(&sizes, space);
+            }
+        }
+
+        let mut splitter = SplitterUi {
+            ui,
+            splitter: &self,
+            id,
+            rect,
+            gap,
+            sizes,
+            collapsed: state.collapsed.clone(),
+            next_pane: 0,
+        };
+        let inner = add_contents(&mut splitter);
+        let SplitterUi { ui, sizes, .. } = splitter;
+
+        // The handles are interacted with after the panes, on top of them,
+        // otherwise their input could be eaten by the contents, e.g. a `ScrollArea`.
+        let mut pos = rect.min[axis];
+        for handle in 0..num_handles {
+            pos += sizes[handle];
+            let line_pos = pos + 0.5 * gap;
+            pos += gap;
+
+            let handle_rect = self
+                .rect(Rangef::point(line_pos), self.cross_range(rect))
+                .expand2(self.vec2(ui.style().interaction.resize_grab_radius_side, 0.0));
+            let response = ui.interact(
+                handle_rect,
+                id.with(("handle", handle)),
+                Sense::click_and_drag(),
+            );
+            if response.double_clicked() {
+                state.toggle_collapsed(panes, handle);
+                ui.ctx().request_repaint();
+            }
+
+            let is_resizing = response.dragged();
+            let resize_hover = response.hovered();
+            if resize_hover || is_resizing {
+                let [first, second] = [handle, handle + 1].map(|i| panes[i].min_size);
+                let can_move = [first < sizes[handle], second < sizes[handle + 1]];
+                ui.set_cursor_icon(resize_cursor_icon(self.horizontal, can_move));
+            }
+
+            let stroke = if is_resizing || resize_hover || self.show_separator_line {
+                resize_line_stroke(ui, is_resizing, resize_hover)
+            } else {
+                Stroke::NONE
+            };
+            if self.horizontal {
+                ui.painter().vline(line_pos, rect.y_range(), stroke);
+            } else {
+                ui.painter().hline(rect.x_range(), line_pos, stroke);
+            }
+        }
+        state.store(ui.ctx(), id, persist);
+
+        let response = ui.allocate_rect(rect, Sense::hover());
+        InnerResponse::new(inner, response)
+    }
+
+    fn main_axis(&self) -> usize {
+        usize::from(!self.horizontal)
+    }
+
+    fn vec2(&self, main: f32, cross: f32) -> Vec2 {
+        if self.horizontal {
+            Vec2::new(main, cross)
+        } else {
+            Vec2::new(cross, main)
+        }
+    }
+
+    fn cross_range(&self, rect: Rect) -> Rangef {
+        if self.horizontal {
+            rect.y_range()
+        } else {
+            rect.x_range()
+        }
+    }
+
+    fn rect(&self, main: Rangef, cross: Rangef) -> Rect {
+        if self.horizontal {
+            Rect::from_x_y_ranges(main, cross)
+        } else {
+            Rect::from_x_y_ranges(cross, main)
+        }
+    }
+}
+
+/// Add the contents of the panes of a [`Splitter`], see [`Splitter::show`].
+pub struct SplitterUi<'a> {
+    ui: &'a mut Ui,
+    splitter: &'a Splitter,
+    id: Id,
+    rect: Rect,
+    gap: f32,
+    sizes: Vec<f32>,
+    collapsed: Vec<bool>,
+
+    /// The index of the next pane to add.
+    next_pane: usize,
+}
+
+impl SplitterUi<'_> {
+    /// Add the contents of the next pane.
+    ///
+    /// Returns `None` if the pane is collapsed, in which case `add_contents` is not called.
+    /// Panes beyond the ones added with [`Splitter::pane`] are not shown either.
+    pub fn pane<R>(&mut self, add_contents: impl FnOnce(&mut Ui) -> R) -> Option<InnerResponse<R>> {
+        let index = self.next_pane;
+        self.next_pane += 1;
+        if self.sizes.len() <= index || self.collapsed[index] {
+            return None;
+        }
+
+        let axis = self.splitter.main_axis();
+        let start =
+            self.rect.min[axis] + self.sizes[..index].iter().sum::<f32>() + index as f32 * self.gap;
+        let rect = self.splitter.rect(
+            Rangef::new(start, start + self.sizes[index]),
+            self.splitter.cross_range(self.rect),
+        );
+
+        let mut child_ui = self.ui.new_child(
+            UiBuilder::new()
+                .id_salt((self.id, "pane", index))
+                .layout(Layout::top_down(Align::Min))
+                .max_rect(rect),
+        );
+        child_ui.shrink_clip_rect(rect);
+        let inner = add_contents(&mut child_ui);
+        Some(InnerResponse::new(inner, child_ui.response()))
+    }
+}
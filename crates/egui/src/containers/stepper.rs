@@ -0,0 +1,162 @@
+//! A form spread over several steps, see [`Stepper`].
+
+use emath::Align;
+
+use crate::{
+    Button, Form, FormUi, Id, InnerResponse, Layout, Response, Ui, WidgetText, form::FieldError,
+};
+
+/// A form spread over several steps, like in a setup wizard.
+///
+/// Each step is a [`Form`], and the user can't go to the next step while
+/// the current one has invalid fields. "Back" and the titles of the steps before
+/// the current one go back without validating.
+///
+/// The current step is your own `usize`, so you can start over by setting it to `0`.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use egui::{Stepper, form};
+/// # let mut step = 0;
+/// # let mut name = String::new();
+/// # let mut email = String::new();
+/// let response = Stepper::new(["Name", "Email"]).show(ui, &mut step, |form, step| match step {
+///     0 => {
+///         form.field("Name", form::required(&name), |ui| ui.text_edit_singleline(&mut name));
+///     }
+///     _ => {
+///         form.field("Email", form::required(&email), |ui| ui.text_edit_singleline(&mut email));
+///     }
+/// });
+/// if response.finished {
+///     // …
+/// }
+/// # });
+/// ```
+#[must_use = "You should call stepper.show()"]
+#[derive(Clone, Debug)]
+pub struct Stepper {
+    id_salt: Option<Id>,
+    titles: Vec<WidgetText>,
+    finish_text: WidgetText,
+}
+
+impl Stepper {
+    /// One step for each title.
+    pub fn new(titles: impl IntoIterator<Item = impl Into<WidgetText>>) -> Self {
+        Self {
+            id_salt: None,
+            titles: titles.into_iter().map(Into::into).collect(),
+            finish_text: "Finish".into(),
+        }
+    }
+
+    /// Used to remember which fields of each step the user has touched.
+    ///
+    /// Default: an id based on the position in the parent [`Ui`].
+    #[inline]
+    pub fn id_salt(mut self, id_salt: impl std::hash::Hash) -> Self {
+        self.id_salt = Some(Id::new(id_salt));
+        self
+    }
+
+    /// The text of the button on the last step.
+    ///
+    /// Default: `"Finish"`.
+    #[inline]
+    pub fn finish_text(mut self, finish_text: impl Into<WidgetText>) -> Self {
+        self.finish_text = finish_text.into();
+        self
+    }
+
+    /// Show the titles of the steps, the fields of the current `step` added by `add_contents`,
+    /// and the buttons to go back and forth.
+    pub fn show<R>(
+        self,
+        ui: &mut Ui,
+        step: &mut usize,
+        add_contents: impl FnOnce(&mut FormUi<'_>, usize) -> R,
+    ) -> StepperResponse<R> {
+        let id = self.id_salt.map_or_else(
+            || ui.next_auto_id(),
+            |id_salt| ui.make_persistent_id(id_salt),
+        );
+        let last = self.titles.len().saturating_sub(1);
+        *step = (*step).min(last);
+        let current = *step;
+
+        let InnerResponse {
+            inner: form,
+            response,
+        } = ui.vertical(|ui| {
+            ui.horizontal_wrapped(|ui| {
+                for (i, title) in self.titles.iter().enumerate() {
+                    if 0 < i {
+                        ui.weak("›");
+                    }
+                    let button = Button::selectable(i == current, title.clone());
+                    if ui.add_enabled(i <= current, button).clicked() {
+                        *step = i;
+                    }
+                }
+            });
+            ui.separator();
+
+            Form::new().id_salt(id.with(current)).show(ui, |form| {
+                let inner = add_contents(form, current);
+                form.error_summary();
+                form.separator();
+                let next = form
+                    .horizontal(|ui| {
+                        if ui.add_enabled(0 < current, Button::new("Back")).clicked() {
+                            *step = current - 1;
+                        }
+                        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                            let text = if current == last {
+                                self.finish_text.clone()
+                            } else {
+                                "Next".into()
+                            };
+                            ui.button(text).clicked()
+                        })
+                        .inner
+                    })
+                    .inner;
+                if next {
+                    form.submit();
+                }
+                inner
+            })
+        });
+
+        let finished = form.submitted && current == last;
+        if form.submitted && current < last {
+            *step = current + 1;
+        }
+        if *step != current {
+            ui.ctx().request_repaint();
+        }
+
+        StepperResponse {
+            inner: form.inner,
+            response,
+            finished,
+            errors: form.errors,
+        }
+    }
+}
+
+/// What happened to a [`Stepper`], see [`Stepper::show`].
+pub struct StepperResponse<R> {
+    /// What the contents of the current step returned.
+    pub inner: R,
+
+    /// The response of the whole stepper.
+    pub response: Response,
+
+    /// Did the user finish the last step this frame, with all its fields valid?
+    pub finished: bool,
+
+    /// The invalid fields of the current step.
+    pub errors: Vec<FieldError>,
+}
@@ -0,0 +1,475 @@
+//! A row of tabs the user can switch between, reorder and close, see [`TabBar`].
+
+use std::{hash::Hash, sync::Arc};
+
+use emath::{Rect, Vec2, pos2, vec2};
+use epaint::{CornerRadius, Galley};
+
+use crate::{
+    Button, Color32, DragAndDrop, Id, Key, Modifiers, Response, Sense, TextStyle, TextWrapMode, Ui,
+    UiBuilder, WidgetInfo, WidgetText, WidgetType, containers::menu::MenuButton,
+};
+
+/// A single tab of a [`TabBar`].
+#[derive(Clone, Debug)]
+pub struct Tab {
+    id: Id,
+    title: WidgetText,
+    pinned: bool,
+    closable: bool,
+}
+
+impl Tab {
+    /// `id_salt` must be unique among the tabs of the bar,
+    /// and should stay the same when the tab is moved, e.g. the path of the document in the tab.
+    pub fn new(id_salt: impl Hash, title: impl Into<WidgetText>) -> Self {
+        Self {
+            id: Id::new(id_salt),
+            title: title.into(),
+            pinned: false,
+            closable: true,
+        }
+    }
+
+    /// Pinned tabs are kept in front of the other tabs, can't be closed,
+    /// and are never moved into the overflow menu.
+    ///
+    /// Default: `false`.
+    #[inline]
+    pub fn pinned(mut self, pinned: bool) -> Self {
+        self.pinned = pinned;
+        self
+    }
+
+    /// Show a close button on the tab, and close it when it is middle-clicked.
+    ///
+    /// Default: `true`.
+    #[inline]
+    pub fn closable(mut self, closable: bool) -> Self {
+        self.closable = closable;
+        self
+    }
+
+    fn can_close(&self) -> bool {
+        self.closable && !self.pinned
+    }
+}
+
+/// The payload while the user drags a tab to move it.
+struct DraggedTab {
+    bar: Id,
+    index: usize,
+}
+
+/// What happened to a [`TabBar`], see [`TabBar::show`].
+pub struct TabBarResponse<T> {
+    /// The response of the whole bar.
+    pub response: Response,
+
+    /// The index of the active tab, or `None` if there are no tabs.
+    pub active: Option<usize>,
+
+    /// Did the active tab change since the last frame?
+    ///
+    /// This is `false` the first time the bar is shown.
+    pub changed: bool,
+
+    /// The tab the user closed this frame. It has already been removed from the tabs.
+    pub closed: Option<T>,
+}
+
+/// A row of tabs, like in a web browser or a code editor.
+///
+/// The tabs are your own values, one [`Tab`] describing each of them.
+/// [`TabBar`] only shows the tabs; show the contents of [`TabBarResponse::active`] below it.
+///
+/// * Click a tab to make it active. With a [`Self::id_salt`], which tab is active is persisted with the rest of the egui memory.
+/// * Drag a tab to move it. [`Tab::pinned`] tabs stay in front of the others.
+/// * Click the close button or middle-click a tab to close it; it is removed and returned in [`TabBarResponse::closed`].
+/// * Switch tabs with Ctrl+Tab and Ctrl+Shift+Tab, or Ctrl+PageDown and Ctrl+PageUp.
+/// * Tabs that don't fit go into a menu at the end of the bar.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use egui::{Tab, TabBar};
+/// # let mut documents = vec![("Notes".to_owned(), String::new())];
+/// let tabs = TabBar::new()
+///     .id_salt("documents")
+///     .show(ui, &mut documents, |(title, _)| Tab::new(title, title));
+/// if let Some(active) = tabs.active {
+///     ui.text_edit_multiline(&mut documents[active].1);
+/// }
+/// # });
+/// ```
+#[must_use = "You should call tab_bar.show()"]
+#[derive(Clone, Copy, Debug)]
+pub struct TabBar {
+    id_salt: Option<Id>,
+    select: Option<Id>,
+    keyboard_navigation: bool,
+}
+
+impl Default for TabBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TabBar {
+    /// A tab bar where the first tab is active until the user picks another.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            id_salt: None,
+            select: None,
+            keyboard_navigation: true,
+        }
+    }
+
+    /// Used to remember the active tab.
+    ///
+    /// With an id salt the active tab is persisted (with the `persistence` feature),
+    /// without one it is only remembered until the app is closed.
+    ///
+    /// Default: an id based on the position in the parent [`Ui`].
+    #[inline]
+    pub fn id_salt(mut self, id_salt: impl Hash) -> Self {
+        self.id_salt = Some(Id::new(id_salt));
+        self
+    }
+
+    /// Make the tab with this `id_salt` (see [`Tab::new`]) active,
+    /// e.g. right after the user opened a new document.
+    #[inline]
+    pub fn select(mut self, id_salt: impl Hash) -> Self {
+        self.select = Some(Id::new(id_salt));
+        self
+    }
+
+    /// Switch tabs with Ctrl+Tab and Ctrl+Shift+Tab, or Ctrl+PageDown and Ctrl+PageUp.
+    ///
+    /// The keys are consumed, so with several tab bars only the first one shown gets them.
+    ///
+    /// Default: `true`.
+    #[inline]
+    pub fn keyboard_navigation(mut self, keyboard_navigation: bool) -> Self {
+        self.keyboard_navigation = keyboard_navigation;
+        self
+    }
+
+    /// Show one tab for each of the `tabs`, described by `tab`.
+    ///
+    /// The `tabs` are reordered when the user moves a tab, or a pinned tab comes after one that isn't,
+    /// and a tab is removed from them when the user closes it.
+    pub fn show<T>(
+        self,
+        ui: &mut Ui,
+        tabs: &mut Vec<T>,
+        tab: impl Fn(&T) -> Tab,
+    ) -> TabBarResponse<T> {
+        let persist = self.id_salt.is_some();
+        let id = self.id_salt.map_or_else(
+            || ui.next_auto_id(),
+            |id_salt| ui.make_persistent_id(id_salt),
+        );
+
+        let mut infos: Vec<Tab> = tabs.iter().map(&tab).collect();
+        if infos.windows(2).any(|w| !w[0].pinned && w[1].pinned) {
+            let mut both: Vec<(Tab, T)> = infos.into_iter().zip(tabs.drain(..)).collect();
+            both.sort_by_key(|(tab, _)| !tab.pinned);
+            (infos, *tabs) = both.into_iter().unzip();
+        }
+        let n = infos.len();
+        let num_pinned = infos.iter().filter(|tab| tab.pinned).count();
+
+        let previous: Option<Id> = ui.ctx().data_mut(|d| {
+            if persist {
+                d.get_persisted(id)
+            } else {
+                d.get_temp(id)
+            }
+        });
+        let mut active = self
+            .select
+            .or(previous)
+            .and_then(|active| infos.iter().position(|tab| tab.id == active))
+            .or_else(|| (0 < n).then_some(0));
+
+        if self.keyboard_navigation
+            && let Some(current) = active
+        {
+            let step = ui.input_mut(|i| {
+                if i.consume_key(Modifiers::CTRL | Modifiers::SHIFT, Key::Tab)
+                    || i.consume_key(Modifiers::CTRL, Key::PageUp)
+                {
+                    Some(n - 1)
+                } else if i.consume_key(Modifiers::CTRL, Key::Tab)
+                    || i.consume_key(Modifiers::CTRL, Key::PageDown)
+                {
+                    Some(1)
+                } else {
+                    None
+                }
+            });
+            if let Some(step) = step {
+                active = Some((current + step) % n);
+            }
+        }
+
+        let padding = ui.spacing().button_padding;
+        let gap = ui.spacing().item_spacing.x;
+        let close_size = ui.spacing().icon_width;
+        let galleys: Vec<Arc<Galley>> = infos
+            .iter()
+            .map(|tab| {
+                tab.title.clone().into_galley(
+                    ui,
+                    Some(TextWrapMode::Extend),
+                    f32::INFINITY,
+                    TextStyle::Button,
+                )
+            })
+            .collect();
+        let widths: Vec<f32> = infos
+            .iter()
+            .zip(&galleys)
+            .map(|(tab, galley)| {
+                let close_width = if tab.can_close() {
+                    gap + close_size
+                } else {
+                    0.0
+                };
+                2.0 * padding.x + galley.size().x + close_width
+            })
+            .collect();
+        let height = galleys
+            .iter()
+            .map(|galley| galley.size().y + 2.0 * padding.y)
+            .fold(ui.spacing().interact_size.y, f32::max);
+
+        let (bar_rect, response) =
+            ui.allocate_exact_size(vec2(ui.available_width(), height), Sense::hover());
+
+        // Tabs that don't fit go into the overflow menu, except for the pinned and active tabs:
+        let total_width = widths.iter().sum::<f32>() + gap * n.saturating_sub(1) as f32;
+        let overflow = bar_rect.width() < total_width;
+        let mut visible = vec![true; n];
+        if overflow {
+            let space = bar_rect.width() - height - gap;
+            let mut used: f32 = widths[..num_pinned].iter().map(|w| w + gap).sum();
+            let mut fits = true;
+            for i in num_pinned..n {
+                fits &= used + widths[i] <= space;
+                visible[i] = fits;
+                if fits {
+                    used += widths[i] + gap;
+                }
+            }
+            if let Some(active) = active
+                && !visible[active]
+            {
+                for i in (num_pinned..n).rev() {
+                    if used + widths[active] <= space {
+                        break;
+                    }
+                    if visible[i] {
+                        visible[i] = false;
+                        used -= widths[i] + gap;
+                    }
+                }
+                visible[active] = true;
+            }
+        }
+
+        // Interact with everything before painting, so the tabs are painted in their new state:
+        let mut rects = vec![None; n];
+        let mut responses = Vec::with_capacity(n);
+        let mut close = None;
+        let mut x = bar_rect.left();
+        for i in (0..n).filter(|&i| visible[i]) {
+            let rect = Rect::from_min_size(pos2(x, bar_rect.top()), vec2(widths[i], height));
+            x += widths[i] + gap;
+            rects[i] = Some(rect);
+
+            let tab_id = id.with(infos[i].id);
+            let tab_response = ui.interact(rect, tab_id, Sense::click_and_drag());
+            if tab_response.clicked() || tab_response.drag_started() {
+                active = Some(i);
+            }
+            tab_response.dnd_set_drag_payload(DraggedTab { bar: id, index: i });
+
+            let close_response = infos[i].can_close().then(|| {
+                let close_rect = Rect::from_center_size(
+                    pos2(rect.right() - padding.x - 0.5 * close_size, rect.center().y),
+                    Vec2::splat(close_size),
+                );
+                let close_response = ui.interact(close_rect, tab_id.with("close"), Sense::click());
+                close_response.widget_info(|| {
+                    WidgetInfo::labeled(WidgetType::Button, ui.is_enabled(), "Close tab")
+                });
+                if close_response.clicked() || tab_response.middle_clicked() {
+                    close = Some(infos[i].id);
+                }
+                close_response
+            });
+            responses.push((i, rect, tab_response, close_response));
+        }
+
+        if overflow {
+            let button_rect = Rect::from_min_max(
+                pos2(bar_rect.right() - height, bar_rect.top()),
+                bar_rect.max,
+            );
+            ui.scope_builder(UiBuilder::new().max_rect(button_rect), |ui| {
+                let button = Button::new("⏷").min_size(Vec2::splat(height));
+                MenuButton::from_button(button).ui(ui, |ui| {
+                    for i in (0..n).filter(|&i| !visible[i]) {
+                        if ui.button(infos[i].title.clone()).clicked() {
+                            active = Some(i);
+                        }
+                    }
+                });
+            });
+        }
+
+        let painter = ui.painter();
+        painter.hline(
+            bar_rect.x_range(),
+            bar_rect.bottom(),
+            ui.visuals().widgets.noninteractive.bg_stroke,
+        );
+        for (i, rect, tab_response, close_response) in responses {
+            let selected = active == Some(i);
+            tab_response.widget_info(|| {
+                WidgetInfo::selected(
+                    WidgetType::SelectableLabel,
+                    ui.is_enabled(),
+                    selected,
+                    galleys[i].text(),
+                )
+            });
+
+            let visuals = ui.style().interact_selectable(&tab_response, selected);
+            let fill = if selected || tab_response.hovered() || tab_response.dragged() {
+                visuals.weak_bg_fill
+            } else {
+                Color32::TRANSPARENT
+            };
+            let radius = visuals.corner_radius.nw;
+            let corner_radius = CornerRadius {
+                nw: radius,
+                ne: radius,
+                sw: 0,
+                se: 0,
+            };
+            painter.rect_filled(rect, corner_radius, fill);
+            let text_pos = pos2(
+                rect.left() + padding.x,
+                rect.center().y - 0.5 * galleys[i].size().y,
+            );
+            painter.galley(text_pos, Arc::clone(&galleys[i]), visuals.text_color());
+
+            if let Some(close_response) = close_response {
+                let close_visuals = ui.style().interact(&close_response);
+                let close_rect = close_response
+                    .rect
+                    .shrink(2.0)
+                    .expand(close_visuals.expansion);
+                let stroke = if close_response.hovered() {
+                    close_visuals.fg_stroke
+                } else {
+                    visuals.fg_stroke
+                };
+                painter.line_segment([close_rect.left_top(), close_rect.right_bottom()], stroke);
+                painter.line_segment([close_rect.right_top(), close_rect.left_bottom()], stroke);
+            }
+        }
+
+        // Moving a tab:
+        let mut moved = None;
+        if let Some(dragged) = DragAndDrop::payload::<DraggedTab>(ui.ctx())
+            && dragged.bar == id
+            && dragged.index < n
+            && let Some(pointer) = ui.ctx().pointer_hover_pos()
+            && ui.rect_contains_pointer(bar_rect)
+        {
+            let from = dragged.index;
+            let (min, max) = if from < num_pinned {
+                (0, num_pinned)
+            } else {
+                (num_pinned, n)
+            };
+            let to = (0..n)
+                .find(|&i| rects[i].is_some_and(|rect| pointer.x < rect.center().x))
+                .unwrap_or(n)
+                .clamp(min, max);
+
+            let line_x = rects[to..]
+                .iter()
+                .flatten()
+                .next()
+                .map(|rect| rect.left() - 0.5 * gap)
+                .or_else(|| {
+                    rects[..to]
+                        .iter()
+                        .flatten()
+                        .last()
+                        .map(|rect| rect.right() + 0.5 * gap)
+                });
+            if let Some(line_x) = line_x {
+                painter.vline(line_x, bar_rect.y_range(), ui.visuals().selection.stroke);
+            }
+
+            if ui.input(|i| i.pointer.any_released()) {
+                DragAndDrop::clear_payload(ui.ctx());
+                moved = Some((from, if from < to { to - 1 } else { to }));
+            }
+        }
+
+        let mut active_id = active.map(|active| infos[active].id);
+        if let Some((from, to)) = moved
+            && from != to
+        {
+            let item = tabs.remove(from);
+            tabs.insert(to, item);
+            let info = infos.remove(from);
+            infos.insert(to, info);
+        }
+
+        let mut closed = None;
+        if let Some(i) = close.and_then(|close| infos.iter().position(|tab| tab.id == close)) {
+            if active_id == Some(infos[i].id) {
+                // The tab after the closed one becomes active, or the one before it if it was the last:
+                active_id = infos
+                    .get(i + 1)
+                    .or_else(|| infos.get(i.checked_sub(1)?))
+                    .map(|tab| tab.id);
+            }
+            infos.remove(i);
+            closed = Some(tabs.remove(i));
+        }
+        if moved.is_some() || closed.is_some() {
+            ui.ctx().request_repaint();
+        }
+
+        let active =
+            active_id.and_then(|active_id| infos.iter().position(|tab| tab.id == active_id));
+        if let Some(active_id) = active_id {
+            ui.ctx().data_mut(|d| {
+                if persist {
+                    d.insert_persisted(id, active_id);
+                } else {
+                    d.insert_temp(id, active_id);
+                }
+            });
+        }
+
+        TabBarResponse {
+            response,
+            active,
+            // With no previous frame to compare to, nothing changed:
+            changed: previous.is_some() && active_id != previous,
+            closed,
+        }
+    }
+}
@@ -0,0 +1,461 @@
+//! A scrollable list that only lays out the visible rows, see [`VirtualList`].
+
+use std::ops::Range;
+
+use emath::{Align, NumExt as _, Rect};
+
+use crate::{Id, ScrollArea, Ui, UiBuilder, scroll_area::ScrollAreaOutput};
+
+/// Remembered between frames.
+#[derive(Clone, Debug, Default)]
+struct State {
+    heights: RowHeights,
+
+    /// The row at the top of the viewport last frame, and how far into it we were scrolled.
+    anchor: Option<(usize, f32)>,
+
+    /// The scroll offset at the end of last frame.
+    offset: f32,
+
+    viewport_height: f32,
+
+    /// Were we scrolled all the way to the bottom last frame?
+    at_bottom: bool,
+}
+
+/// The measured height of each row, with prefix sums so that finding the top of a row,
+/// or the row at some height, doesn't need to go through all rows.
+///
+/// The prefix sums are kept in a Fenwick tree of the measured heights and the number of
+/// measured rows, so that rows that haven't been measured can use the latest estimate.
+#[derive(Clone, Debug, Default)]
+struct RowHeights {
+    /// The measured height of each row, if it has been shown.
+    heights: Vec<Option<f32>>,
+
+    /// `tree[i - 1]` is the sum of the measured heights, and the number of measured rows,
+    /// of the `i & i.wrapping_neg()` rows ending with row `i - 1`.
+    tree: Vec<(f32, usize)>,
+
+    /// The sum of all measured heights, and the number of measured rows.
+    total: (f32, usize),
+}
+
+impl RowHeights {
+    fn len(&self) -> usize {
+        self.heights.len()
+    }
+
+    fn resize(&mut self, len: usize) {
+        if len < self.len() {
+            self.heights.truncate(len);
+            self.tree.truncate(len);
+            self.total = self.prefix(len);
+        }
+        while self.len() < len {
+            // The new row isn't measured, so the node only covers rows that are already there:
+            let i = self.len() + 1;
+            let (sum, count) = self.prefix(i - 1);
+            let (start_sum, start_count) = self.prefix(i - lowest_bit(i));
+            self.heights.push(None);
+            self.tree.push((sum - start_sum, count - start_count));
+        }
+    }
+
+    /// Insert `count` rows that haven't been measured before the first row.
+    fn insert_at_start(&mut self, count: usize) {
+        self.heights.splice(0..0, std::iter::repeat_n(None, count));
+
+        // Rebuild the tree:
+        let len = self.len();
+        self.tree.clear();
+        self.tree.extend(
+            self.heights
+                .iter()
+                .map(|height| height.map_or((0.0, 0), |height| (height, 1))),
+        );
+        for i in 1..=len {
+            let parent = i + lowest_bit(i);
+            if parent <= len {
+                let (sum, count) = self.tree[i - 1];
+                self.tree[parent - 1].0 += sum;
+                self.tree[parent - 1].1 += count;
+            }
+        }
+    }
+
+    fn set(&mut self, index: usize, height: f32) {
+        let old = self.heights[index];
+        if old == Some(height) {
+            return;
+        }
+        self.heights[index] = Some(height);
+        let sum = height - old.unwrap_or(0.0);
+        let count = usize::from(old.is_none());
+        self.total.0 += sum;
+        self.total.1 += count;
+        let mut i = index + 1;
+        while i <= self.len() {
+            self.tree[i - 1].0 += sum;
+            self.tree[i - 1].1 += count;
+            i += lowest_bit(i);
+        }
+    }
+
+    /// The sum of the measured heights, and the number of measured rows, of the first `len` rows.
+    fn prefix(&self, mut len: usize) -> (f32, usize) {
+        let (mut sum, mut count) = (0.0, 0);
+        while 0 < len {
+            sum += self.tree[len - 1].0;
+            count += self.tree[len - 1].1;
+            len -= lowest_bit(len);
+        }
+        (sum, count)
+    }
+
+    /// The mean height of the rows we have measured.
+    fn estimate(&self, default_height: f32) -> f32 {
+        let (sum, count) = self.total;
+        if count == 0 {
+            default_height
+        } else {
+            sum / count as f32
+        }
+    }
+
+    fn height(&self, index: usize, estimate: f32) -> f32 {
+        self.heights[index].unwrap_or(estimate)
+    }
+
+    /// The top of the row relative to the top of the list.
+    ///
+    /// With `index == len` this is the total height, plus spacing.
+    fn top(&self, index: usize, estimate: f32, spacing: f32) -> f32 {
+        let (sum, count) = self.prefix(index);
+        sum + (index - count) as f32 * estimate + index as f32 * spacing
+    }
+
+    /// The number of rows that end (including spacing) at or above `y`.
+    fn rows_above(&self, y: f32, estimate: f32, spacing: f32) -> usize {
+        let len = self.len();
+        let mut index = 0;
+        let mut top = 0.0;
+        let mut step = if len == 0 { 0 } else { 1 << len.ilog2() };
+        while 0 < step {
+            if index + step <= len {
+                let (sum, count) = self.tree[index + step - 1];
+                let next_top = top + sum + (step - count) as f32 * estimate + step as f32 * spacing;
+                if next_top <= y {
+                    index += step;
+                    top = next_top;
+                }
+            }
+            step /= 2;
+        }
+        index
+    }
+}
+
+/// The number of rows covered by node `i` of a Fenwick tree.
+fn lowest_bit(i: usize) -> usize {
+    i & i.wrapping_neg()
+}
+
+/// A vertically scrolling list of rows of different heights, where only the visible rows
+/// are laid out.
+///
+/// Unlike [`ScrollArea::show_rows`], the rows don't need to have the same height,
+/// and unlike `egui_extras::TableBody::heterogeneous_rows` you don't need to know the heights
+/// up front. Each row is measured when it is shown, and its height remembered.
+/// The heights of rows that have never been shown are estimated from the ones that have.
+///
+/// When the heights of rows above the viewport change (e.g. when they are measured for the first
+/// time) the list keeps the row at the top of the viewport in place, so the contents don't jump.
+///
+/// The heights are remembered by row index. If you insert rows before the first one
+/// (e.g. when loading older messages), tell the list with [`Self::rows_inserted_at_start`],
+/// otherwise the rows get the heights of the rows that used to be at their index.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// let messages = vec!["Hello!".to_owned(); 10_000];
+/// egui::VirtualList::new()
+///     .stick_to_bottom(true)
+///     .show(ui, messages.len(), |ui, index| {
+///         ui.label(format!("#{index}"));
+///         ui.label(&messages[index]);
+///     });
+/// # });
+/// ```
+#[must_use = "You should call .show()"]
+#[derive(Clone, Debug)]
+pub struct VirtualList {
+    id_salt: Id,
+    scroll_area: ScrollArea,
+    estimated_row_height: Option<f32>,
+    stick_to_bottom: bool,
+    scroll_to_item: Option<(usize, Align)>,
+    rows_inserted_at_start: usize,
+}
+
+impl Default for VirtualList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VirtualList {
+    /// A list with a vertical [`ScrollArea`]. The rows are added in [`Self::show`].
+    pub fn new() -> Self {
+        Self {
+            id_salt: Id::new("virtual_list"),
+            scroll_area: ScrollArea::vertical(),
+            estimated_row_height: None,
+            stick_to_bottom: false,
+            scroll_to_item: None,
+            rows_inserted_at_start: 0,
+        }
+    }
+
+    /// A source for the unique [`Id`], e.g. `.id_salt("chat_log")` or `.id_salt(channel_id)`.
+    #[inline]
+    pub fn id_salt(mut self, id_salt: impl std::hash::Hash) -> Self {
+        self.id_salt = Id::new(id_salt);
+        self
+    }
+
+    /// Use this [`ScrollArea`], e.g. to set a max height or turn off auto-shrinking.
+    ///
+    /// The id, scroll offset and stickiness of it will be set by the list.
+    #[inline]
+    pub fn scroll_area(mut self, scroll_area: ScrollArea) -> Self {
+        self.scroll_area = scroll_area;
+        self
+    }
+
+    /// The height to assume for rows that haven't been shown yet, before any row has been measured.
+    ///
+    /// After that, the average height of the measured rows is used.
+    ///
+    /// Default: [`crate::style::Spacing::interact_size`]`.y`.
+    #[inline]
+    pub fn estimated_row_height(mut self, estimated_row_height: f32) -> Self {
+        self.estimated_row_height = Some(estimated_row_height);
+        self
+    }
+
+    /// Keep showing the last row while new rows are added, until the user scrolls up.
+    ///
+    /// See [`ScrollArea::stick_to_bottom`].
+    #[inline]
+    pub fn stick_to_bottom(mut self, stick_to_bottom: bool) -> Self {
+        self.stick_to_bottom = stick_to_bottom;
+        self
+    }
+
+    /// Scroll so that the row with this index is visible, placed according to `align`:
+    /// [`Align::TOP`] puts it at the top of the viewport, [`Align::Center`] in the middle, etc.
+    ///
+    /// Set this only in the frame you want to scroll.
+    #[inline]
+    pub fn scroll_to_item(mut self, index: usize, align: Align) -> Self {
+        self.scroll_to_item = Some((index, align));
+        self
+    }
+
+    /// `count` rows were inserted before the first row since last frame.
+    ///
+    /// The rows that were already there keep their measured heights,
+    /// and the row at the top of the viewport stays in place.
+    ///
+    /// Set this only in the frame the rows were inserted.
+    #[inline]
+    pub fn rows_inserted_at_start(mut self, count: usize) -> Self {
+        self.rows_inserted_at_start = count;
+        self
+    }
+
+    /// Show the list with `total_rows` rows, calling `add_row` for each visible row.
+    ///
+    /// The returned [`ScrollAreaOutput::inner`] is the range of rows that were shown.
+    pub fn show(
+        self,
+        ui: &mut Ui,
+        total_rows: usize,
+        mut add_row: impl FnMut(&mut Ui, usize),
+    ) -> ScrollAreaOutput<Range<usize>> {
+        let Self {
+            id_salt,
+            scroll_area,
+            estimated_row_height,
+            stick_to_bottom,
+            scroll_to_item,
+            rows_inserted_at_start,
+        } = self;
+
+        let id = ui.make_persistent_id(id_salt);
+        let mut state: State = ui
+            .ctx()
+            .data_mut(|d| std::mem::take(d.get_temp_mut_or_default(id)));
+        if 0 < rows_inserted_at_start {
+            state.heights.insert_at_start(rows_inserted_at_start);
+            if let Some((index, _)) = &mut state.anchor {
+                *index += rows_inserted_at_start;
+            }
+        }
+        state.heights.resize(total_rows);
+
+        let spacing = ui.spacing().item_spacing.y;
+        let default_height = estimated_row_height.unwrap_or_else(|| ui.spacing().interact_size.y);
+        let estimate = state.heights.estimate(default_height);
+        let top = |state: &State, index: usize| state.heights.top(index, estimate, spacing);
+
+        let mut scroll_area = scroll_area
+            .id_salt(id_salt)
+            .stick_to_bottom(stick_to_bottom);
+        if let Some((index, align)) = scroll_to_item.filter(|(index, _)| *index < total_rows) {
+            let free_space = state.viewport_height - state.heights.height(index, estimate);
+            let offset = top(&state, index) - align.to_factor() * free_space;
+            scroll_area = scroll_area.vertical_scroll_offset(offset.at_least(0.0));
+        } else if stick_to_bottom && state.at_bottom {
+            // Lay out the last rows right away, even if rows were added since last frame:
+            let bottom = top(&state, total_rows) - spacing - state.viewport_height;
+            scroll_area = scroll_area.vertical_scroll_offset(bottom.at_least(0.0));
+        } else if let Some((index, within)) = state.anchor
+            && index < total_rows
+        {
+            // Keep the same row at the top of the viewport, even if the rows above it changed height:
+            let offset = top(&state, index) + within;
+            if 0.5 < (offset - state.offset).abs() {
+                scroll_area = scroll_area.vertical_scroll_offset(offset);
+            }
+        }
+
+        let output = scroll_area.show_viewport(ui, |ui, viewport| {
+            let total_height = (top(&state, total_rows) - spacing).at_least(0.0);
+            ui.set_height(total_height);
+
+            let first = state.heights.rows_above(viewport.min.y, estimate, spacing);
+            let list_top = ui.max_rect().top();
+            let first_top = top(&state, first);
+            let mut y = first_top;
+            let mut index = first;
+            while index < total_rows && y < viewport.max.y {
+                let max_rect =
+                    Rect::from_x_y_ranges(ui.max_rect().x_range(), list_top + y..=f32::INFINITY);
+                let rect = ui
+                    .scope_builder(
+                        UiBuilder::new()
+                            .id_salt((id, index))
+                            .global_scope(true)
+                            .max_rect(max_rect),
+                        |ui| add_row(ui, index),
+                    )
+                    .response
+                    .rect;
+                state.heights.set(index, rect.height());
+                y += rect.height() + spacing;
+                index += 1;
+            }
+            let top = if first == 0 {
+                f32::NEG_INFINITY
+            } else {
+                first_top
+            };
+            let bottom = if index == total_rows {
+                f32::INFINITY
+            } else {
+                y
+            };
+            (first..index, top..=bottom)
+        });
+        let (shown, laid_out) = output.inner;
+
+        let offset = output.state.offset.y;
+        let viewport_height = output.inner_rect.height();
+        if !laid_out.contains(&offset) || !laid_out.contains(&(offset + viewport_height)) {
+            // E.g. the rows changed height and the scroll area was clamped: show the rows we scrolled to.
+            ui.ctx().request_repaint();
+        }
+        // Find the row at the top with the estimate the rows were laid out with,
+        // so that it stays in place next frame even if the estimate changes:
+        let row = state
+            .heights
+            .rows_above(offset, estimate, spacing)
+            .at_most(total_rows.saturating_sub(1));
+        state.anchor =
+            (0 < total_rows).then(|| (row, offset - state.heights.top(row, estimate, spacing)));
+        state.offset = offset;
+        state.viewport_height = viewport_height;
+        state.at_bottom = output.content_size.y <= offset + output.inner_rect.height() + 1.0;
+
+        ui.ctx().data_mut(|d| d.insert_temp(id, state));
+
+        let ScrollAreaOutput {
+            inner: _,
+            id,
+            state,
+            content_size,
+            inner_rect,
+        } = output;
+        ScrollAreaOutput {
+            inner: shown,
+            id,
+            state,
+            content_size,
+            inner_rect,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RowHeights;
+
+    /// Check the prefix sums against adding up all rows.
+    fn check(rows: &RowHeights, estimate: f32, spacing: f32) {
+        let mut top = 0.0;
+        for index in 0..=rows.len() {
+            assert_eq!(
+                rows.top(index, estimate, spacing),
+                top,
+                "top of row {index}"
+            );
+            if index < rows.len() {
+                let next_top = top + rows.height(index, estimate) + spacing;
+                assert_eq!(rows.rows_above(next_top - 0.5, estimate, spacing), index);
+                assert_eq!(rows.rows_above(next_top, estimate, spacing), index + 1);
+                top = next_top;
+            }
+        }
+    }
+
+    #[test]
+    fn test_row_heights() {
+        let mut rows = RowHeights::default();
+        assert_eq!(rows.rows_above(100.0, 10.0, 2.0), 0);
+
+        rows.resize(13);
+        check(&rows, 10.0, 2.0);
+        for index in [3, 7, 8, 12] {
+            rows.set(index, index as f32 + 1.0);
+        }
+        rows.set(7, 20.0);
+        assert_eq!(rows.estimate(10.0), (4.0 + 20.0 + 9.0 + 13.0) / 4.0);
+        check(&rows, rows.estimate(10.0), 2.0);
+
+        rows.resize(9);
+        assert_eq!(rows.estimate(10.0), (4.0 + 20.0 + 9.0) / 3.0);
+        check(&rows, 10.0, 2.0);
+
+        rows.resize(30);
+        rows.set(25, 1.0);
+        check(&rows, 10.0, 2.0);
+
+        rows.insert_at_start(5);
+        assert_eq!(rows.len(), 35);
+        assert_eq!(rows.heights[12], Some(20.0));
+        assert_eq!(rows.estimate(10.0), (4.0 + 20.0 + 9.0 + 1.0) / 4.0);
+        check(&rows, 10.0, 0.0);
+    }
+}
@@ -21,6 +21,7 @@ use crate::{
     SafeAreaInsets, ScrollArea, Sense, Style, TextStyle, TextureHandle, TextureOptions, Ui,
     UiBuilder, ViewportBuilder, ViewportCommand, ViewportId, ViewportIdMap, ViewportIdPair,
     ViewportIdSet, ViewportOutput, Visuals, Widget as _, WidgetRect, WidgetText,
+    accessibility_audit::AccessibilityIssue,
     animation_manager::AnimationManager,
     containers::{self, area::AreaState},
     data::output::PlatformOutput,
@@ -40,7 +41,7 @@ use crate::{
     viewport::ViewportClass,
 };
 
-use crate::IdMap;
+use crate::{IdMap, id::IdSet};
 
 /// Information given to the backend about when it is time to repaint the ui.
 ///
@@ -240,6 +241,9 @@ pub struct ViewportState {
     pub output: PlatformOutput,
     pub commands: Vec<ViewportCommand>,
 
+    /// Found at the end of the last pass, if [`crate::Options::audit_accessibility`] is on.
+    pub accessibility_issues: Vec<AccessibilityIssue>,
+
     // ----------------------
     // Cross-frame statistics:
     pub num_multipass_in_row: usize,
@@ -367,6 +371,7 @@ impl ViewportRepaintInfo {
 struct ContextImpl {
     fonts: Option<Fonts>,
     font_definitions: FontDefinitions,
+    font_provider: Option<Arc<dyn epaint::text::FontProvider>>,
 
     memory: Memory,
     animation_manager: AnimationManager,
@@ -578,6 +583,14 @@ impl ContextImpl {
             Fonts::new(text_options, self.font_definitions.clone())
         });
 
+        let font_provider_changed = match (fonts.font_provider(), &self.font_provider) {
+            (Some(old), Some(new)) => !Arc::ptr_eq(old, new),
+            (old, new) => old.is_some() != new.is_some(),
+        };
+        if font_provider_changed {
+            fonts.set_font_provider(self.font_provider.clone());
+        }
+
         {
             profiling::scope!("Fonts::begin_pass");
             fonts.begin_pass(text_options);
@@ -1144,10 +1157,6 @@ impl Context {
     pub fn check_for_id_clash(&self, id: Id, new_rect: Rect, what: &str) {
         let prev_rect = self.pass_state_mut(move |state| state.used_ids.insert(id, new_rect));
 
-        if !self.options(|opt| opt.warn_on_id_clash) {
-            return;
-        }
-
         let Some(prev_rect) = prev_rect else { return };
 
         // It is ok to reuse the same ID for e.g. a frame around a widget,
@@ -1158,6 +1167,15 @@ impl Context {
             return;
         }
 
+        let (warn_on_id_clash, audit_accessibility) =
+            self.options(|opt| (opt.warn_on_id_clash, opt.is_auditing_accessibility()));
+        if audit_accessibility {
+            self.pass_state_mut(|state| state.id_clashes.push((id, new_rect)));
+        }
+        if !warn_on_id_clash {
+            return;
+        }
+
         let show_error = |widget_rect: Rect, text: String| {
             let content_rect = self.content_rect();
 
@@ -1502,22 +1520,31 @@ impl Context {
 
     /// This is called by [`Response::widget_info`], but can also be called directly.
     ///
-    /// With some debug flags it will store the widget info in [`crate::WidgetRects`] for later display.
+    /// With some debug flags, or with [`crate::Options::audit_accessibility`],
+    /// it will store the widget info in [`crate::WidgetRects`] for later display.
     #[inline]
     pub fn register_widget_info(&self, id: Id, make_info: impl Fn() -> crate::WidgetInfo) {
-        #[cfg(debug_assertions)]
-        self.write(|ctx| {
-            if ctx.memory.options.style().debug.show_interactive_widgets {
-                ctx.viewport().this_pass.widgets.set_info(id, make_info());
+        let store_info = self.options(|options| {
+            #[cfg(debug_assertions)]
+            if options.style().debug.show_interactive_widgets {
+                return true;
             }
+            options.is_auditing_accessibility()
         });
 
-        #[cfg(not(debug_assertions))]
-        {
-            _ = (self, id, make_info);
+        if store_info {
+            self.write(|ctx| ctx.viewport().this_pass.widgets.set_info(id, make_info()));
         }
     }
 
+    /// Accessibility problems found at the end of the last pass.
+    ///
+    /// Always empty unless [`crate::Options::audit_accessibility`] is turned on.
+    /// See [`crate::accessibility_audit`] for what is checked.
+    pub fn accessibility_issues(&self) -> Vec<AccessibilityIssue> {
+        self.viewport(|viewport| viewport.accessibility_issues.clone())
+    }
+
     /// Get a full-screen painter for a new or existing layer
     pub fn layer_painter(&self, layer_id: LayerId) -> Painter {
         let content_rect = self.content_rect();
@@ -2064,6 +2091,23 @@ impl Context {
         }
     }
 
+    /// Look for fonts with the glyphs that are missing from the fonts set with [`Self::set_fonts`],
+    /// e.g. CJK or Thai characters.
+    ///
+    /// Use [`epaint::text::SystemFontProvider`] to look among the fonts installed on the system:
+    ///
+    /// ```
+    /// # let ctx = egui::Context::default();
+    /// ctx.set_font_provider(Some(std::sync::Arc::new(
+    ///     egui::epaint::text::SystemFontProvider::new(),
+    /// )));
+    /// ```
+    ///
+    /// The provider will become active at the start of the next pass.
+    pub fn set_font_provider(&self, font_provider: Option<Arc<dyn epaint::text::FontProvider>>) {
+        self.write(|ctx| ctx.font_provider = font_provider);
+    }
+
     /// Does the OS use dark or light mode?
     /// This is used when the theme preference is set to [`crate::ThemePreference::System`].
     pub fn system_theme(&self) -> Option<Theme> {
@@ -2548,6 +2592,22 @@ impl Context {
             paint_widget_id(focused_id, "focused", Color32::PURPLE);
         }
 
+        if self.global_style().debug.show_accessibility_issues {
+            use crate::accessibility_audit::AccessibilityIssueKind;
+
+            let painter = self.debug_painter();
+            for issue in self.accessibility_issues() {
+                let color = match issue.kind {
+                    AccessibilityIssueKind::LowContrast => Color32::ORANGE,
+                    AccessibilityIssueKind::SmallHitTarget => Color32::YELLOW,
+                    AccessibilityIssueKind::MissingName
+                    | AccessibilityIssueKind::DuplicateId
+                    | AccessibilityIssueKind::NotFocusable => Color32::RED,
+                };
+                painter.debug_rect(issue.rect, color, issue.message);
+            }
+        }
+
         if let Some(debug_rect) = self.pass_state_mut(|fs| fs.debug_rect.take()) {
             debug_rect.paint(&self.debug_painter());
         }
@@ -2597,6 +2657,20 @@ impl ContextImpl {
 
         let mut platform_output: PlatformOutput = std::mem::take(&mut viewport.output);
 
+        // Only AccessKit knows which widgets are labelled by other widgets:
+        let labelled_ids: Option<IdSet> = if self.memory.options.is_auditing_accessibility() {
+            viewport.this_pass.accesskit_state.as_ref().map(|state| {
+                state
+                    .nodes
+                    .iter()
+                    .filter(|(_, node)| !node.labelled_by().is_empty())
+                    .map(|(id, _)| *id)
+                    .collect()
+            })
+        } else {
+            None
+        };
+
         {
             profiling::scope!("accesskit");
             let state = viewport.this_pass.accesskit_state.take();
@@ -2625,6 +2699,19 @@ impl ContextImpl {
             .graphics
             .drain(self.memory.areas().order(), &self.memory.to_global);
 
+        viewport.accessibility_issues = if self.memory.options.is_auditing_accessibility() {
+            crate::accessibility_audit::audit(&crate::accessibility_audit::AuditInput {
+                widgets: &viewport.this_pass.widgets,
+                labelled: labelled_ids.as_ref(),
+                id_clashes: &viewport.this_pass.id_clashes,
+                shapes: &shapes,
+                style: self.memory.options.style(),
+                to_global: &self.memory.to_global,
+            })
+        } else {
+            Vec::new()
+        };
+
         let mut repaint_needed = false;
 
         if self.memory.options.repaint_on_widget_change {
@@ -3959,6 +4046,13 @@ impl Context {
         });
     }
 
+    /// Remove the callback set with [`Self::set_immediate_viewport_renderer`] on the current thread.
+    ///
+    /// This lets e.g. a test harness install its own callback, and put back the previous one when done.
+    pub fn take_immediate_viewport_renderer() -> Option<Box<ImmediateViewportRendererCallback>> {
+        IMMEDIATE_VIEWPORT_RENDERER.with(|render_sync| render_sync.take())
+    }
+
     /// If `true`, [`Self::show_viewport_deferred`] and [`Self::show_viewport_immediate`] will
     /// embed the new viewports inside the existing one, instead of spawning a new native window.
     ///
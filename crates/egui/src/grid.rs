@@ -1,10 +1,10 @@
-use std::sync::Arc;
+use std::{collections::BTreeMap, ops::Range, sync::Arc};
 
 use emath::GuiRounding as _;
 
 use crate::{
-    Align2, Color32, Context, Id, InnerResponse, NumExt as _, Painter, Rect, Region, Style, Ui,
-    UiBuilder, Vec2, vec2,
+    Align, Align2, Color32, Context, Id, InnerResponse, NumExt as _, Painter, Rect, Region, Style,
+    Ui, UiBuilder, Vec2, vec2,
 };
 
 #[cfg(debug_assertions)]
@@ -14,6 +14,10 @@ use crate::Stroke;
 pub(crate) struct State {
     col_widths: Vec<f32>,
     row_heights: Vec<f32>,
+
+    /// Size of the contents of each [`Ui::grid_cell`], by `(row, col)`.
+    /// Used to align the contents within the cell.
+    cell_sizes: BTreeMap<(usize, usize), Vec2>,
 }
 
 impl State {
@@ -48,6 +52,20 @@ impl State {
         self.row_heights.get(row).copied()
     }
 
+    /// Make the columns (or rows) of a span wide enough for its contents,
+    /// by widening the last of them.
+    fn fit_span(sizes: &mut Vec<f32>, range: Range<usize>, size: f32, spacing: f32) {
+        if range.is_empty() {
+            return;
+        }
+        sizes.resize(sizes.len().max(range.end), 0.0);
+        let available =
+            sizes[range.clone()].iter().sum::<f32>() + (range.len() - 1) as f32 * spacing;
+        if available < size {
+            sizes[range.end - 1] += size - available;
+        }
+    }
+
     fn full_width(&self, x_spacing: f32) -> f32 {
         self.col_widths.iter().sum::<f32>()
             + (self.col_widths.len().at_least(1) - 1) as f32 * x_spacing
@@ -56,6 +74,142 @@ impl State {
 
 // ----------------------------------------------------------------------------
 
+/// Where to put a cell of a [`Grid`], and how large it is. Used with [`Ui::grid_cell`].
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use egui::{Align2, GridCell};
+/// egui::Grid::new("settings").num_columns(3).show(ui, |ui| {
+///     ui.grid_cell(GridCell::new().col_span(3), |ui| ui.heading("Profile"));
+///     ui.end_row();
+///
+///     ui.label("Name");
+///     ui.text_edit_singleline(&mut String::new());
+///     ui.grid_cell(GridCell::new().row_span(2).align(Align2::CENTER_TOP), |ui| {
+///         ui.label("🐱");
+///     });
+///     ui.end_row();
+///
+///     ui.label("Email");
+///     ui.text_edit_singleline(&mut String::new());
+///     ui.end_row();
+/// });
+/// # });
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GridCell {
+    col: Option<usize>,
+    col_span: usize,
+    row_span: usize,
+    align: Option<Align2>,
+}
+
+impl Default for GridCell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GridCell {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            col: None,
+            col_span: 1,
+            row_span: 1,
+            align: None,
+        }
+    }
+
+    /// Put the cell in this column of the current row, leaving the cells before it empty.
+    ///
+    /// Columns can only be skipped forward: if the row is already past this column,
+    /// the cell is put in the next free column instead.
+    #[inline]
+    pub fn col(mut self, col: usize) -> Self {
+        self.col = Some(col);
+        self
+    }
+
+    /// How many columns the cell covers.
+    ///
+    /// Default: 1.
+    #[inline]
+    pub fn col_span(mut self, col_span: usize) -> Self {
+        self.col_span = col_span.at_least(1);
+        self
+    }
+
+    /// How many rows the cell covers.
+    ///
+    /// The following rows will skip the columns covered by this cell.
+    ///
+    /// Default: 1.
+    #[inline]
+    pub fn row_span(mut self, row_span: usize) -> Self {
+        self.row_span = row_span.at_least(1);
+        self
+    }
+
+    /// How to align the contents within the cell.
+    ///
+    /// Default: the alignment of the column (see [`GridColumn::align`]), vertically centered.
+    #[inline]
+    pub fn align(mut self, align: Align2) -> Self {
+        self.align = Some(align);
+        self
+    }
+}
+
+/// Settings for one column of a [`Grid`], see [`Grid::column`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GridColumn {
+    align: Option<Align>,
+    min_width: Option<f32>,
+    max_width: Option<f32>,
+}
+
+impl GridColumn {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How to align the cells of this column horizontally, e.g. [`Align::RIGHT`] for numbers.
+    ///
+    /// Default: [`Align::LEFT`].
+    #[inline]
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = Some(align);
+        self
+    }
+
+    /// The minimum width of the column.
+    ///
+    /// Default: [`Grid::min_col_width`].
+    #[inline]
+    pub fn min_width(mut self, min_width: f32) -> Self {
+        self.min_width = Some(min_width);
+        self
+    }
+
+    /// The soft maximum (wrapping) width of the column.
+    ///
+    /// Default: [`Grid::max_col_width`].
+    #[inline]
+    pub fn max_width(mut self, max_width: f32) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+}
+
+/// A cell covering more than one column or row.
+struct Span {
+    rows: Range<usize>,
+    cols: Range<usize>,
+    size: Vec2,
+}
+
 // type alias for boxed function to determine row color during grid generation
 type ColorPickerFn = Box<dyn Send + Sync + Fn(usize, &Style) -> Option<Color32>>;
 
@@ -81,6 +235,13 @@ pub(crate) struct GridLayout {
     min_cell_size: Vec2,
     max_cell_size: Vec2,
     color_picker: Option<ColorPickerFn>,
+    columns: Vec<GridColumn>,
+
+    /// Set by [`Ui::grid_cell`] for the next cell.
+    next_cell: Option<GridCell>,
+
+    /// The cells that cover more than one column or row, in this frame.
+    spans: Vec<Span>,
 
     // Cursor:
     col: usize,
@@ -116,6 +277,9 @@ impl GridLayout {
             min_cell_size: ui.spacing().interact_size,
             max_cell_size: Vec2::INFINITY,
             color_picker: None,
+            columns: Vec::new(),
+            next_cell: None,
+            spans: Vec::new(),
 
             col: 0,
             row: 0,
@@ -127,9 +291,85 @@ impl GridLayout {
     fn prev_col_width(&self, col: usize) -> f32 {
         self.prev_state
             .col_width(col)
+            .unwrap_or_else(|| self.min_col_width(col))
+    }
+
+    fn min_col_width(&self, col: usize) -> f32 {
+        self.columns
+            .get(col)
+            .and_then(|column| column.min_width)
             .unwrap_or(self.min_cell_size.x)
     }
 
+    fn max_col_width(&self, col: usize) -> f32 {
+        self.columns
+            .get(col)
+            .and_then(|column| column.max_width)
+            .unwrap_or(self.max_cell_size.x)
+    }
+
+    fn col_span(&self) -> usize {
+        self.next_cell.map_or(1, |cell| cell.col_span)
+    }
+
+    fn row_span(&self) -> usize {
+        self.next_cell.map_or(1, |cell| cell.row_span)
+    }
+
+    /// The width of the next cell, based on the previous frame.
+    fn prev_span_width(&self) -> f32 {
+        let cols = self.col..self.col + self.col_span();
+        cols.clone()
+            .map(|col| self.prev_col_width(col))
+            .sum::<f32>()
+            + (cols.len() - 1) as f32 * self.spacing.x
+    }
+
+    /// The height of the next cell, based on the previous frame.
+    fn prev_span_height(&self) -> f32 {
+        let rows = self.row..self.row + self.row_span();
+        rows.clone()
+            .map(|row| self.prev_row_height(row))
+            .sum::<f32>()
+            + (rows.len() - 1) as f32 * self.spacing.y
+    }
+
+    /// Is this cell covered by a [`GridCell::row_span`] from a row above?
+    fn is_covered(&self, row: usize, col: usize) -> bool {
+        self.spans.iter().any(|span| {
+            span.rows.start < row && span.rows.contains(&row) && span.cols.contains(&col)
+        })
+    }
+
+    /// Move to the next column that isn't covered by a cell from a row above.
+    fn skip_covered(&mut self, cursor: &mut Rect) {
+        while self.is_covered(self.row, self.col) {
+            cursor.min.x += self.prev_col_width(self.col) + self.spacing.x;
+            self.col += 1;
+        }
+    }
+
+    /// Prepare for a [`Ui::grid_cell`].
+    pub(crate) fn set_next_cell(&mut self, cell: GridCell, cursor: &mut Rect) {
+        if let Some(col) = cell.col {
+            while self.col < col {
+                cursor.min.x += self.prev_col_width(self.col) + self.spacing.x;
+                self.col += 1;
+                self.skip_covered(cursor);
+            }
+        }
+        self.next_cell = Some(cell);
+    }
+
+    /// The size of the contents of the next cell in the previous frame.
+    pub(crate) fn prev_cell_size(&self) -> Vec2 {
+        self.prev_state
+            .cell_sizes
+            .get(&(self.row, self.col))
+            .copied()
+            .unwrap_or(Vec2::ZERO)
+    }
+
     fn prev_row_height(&self, row: usize) -> f32 {
         self.prev_state
             .row_height(row)
@@ -137,11 +377,12 @@ impl GridLayout {
     }
 
     pub(crate) fn wrap_text(&self) -> bool {
-        self.max_cell_size.x.is_finite()
+        self.max_col_width(self.col).is_finite()
     }
 
     pub(crate) fn available_rect(&self, region: &Region) -> Rect {
-        let is_last_column = Some(self.col + 1) == self.num_columns;
+        let is_last_column = Some(self.col + self.col_span()) == self.num_columns;
+        let max_col_width = self.max_col_width(self.col);
 
         let width = if is_last_column {
             // The first frame we don't really know the widths of the previous columns,
@@ -149,21 +390,22 @@ impl GridLayout {
             if self.is_first_frame {
                 self.curr_state
                     .col_width(self.col)
-                    .unwrap_or(self.min_cell_size.x)
+                    .unwrap_or_else(|| self.min_col_width(self.col))
             } else {
-                (self.initial_available.right() - region.cursor.left())
-                    .at_most(self.max_cell_size.x)
+                (self.initial_available.right() - region.cursor.left()).at_most(max_col_width)
             }
-        } else if self.max_cell_size.x.is_finite() {
+        } else if 1 < self.col_span() {
+            self.prev_span_width()
+        } else if max_col_width.is_finite() {
             // TODO(emilk): should probably heed `prev_state` here too
-            self.max_cell_size.x
+            max_col_width
         } else {
             // If we want to allow width-filling widgets like [`Separator`] in one of the first cells
             // then we need to make sure they don't spill out of the first cell:
             self.prev_state
                 .col_width(self.col)
                 .or_else(|| self.curr_state.col_width(self.col))
-                .unwrap_or(self.min_cell_size.x)
+                .unwrap_or_else(|| self.min_col_width(self.col))
         };
 
         // If something above was wider, we can be wider:
@@ -180,20 +422,32 @@ impl GridLayout {
     }
 
     pub(crate) fn next_cell(&self, cursor: Rect, child_size: Vec2) -> Rect {
-        let width = self.prev_state.col_width(self.col).unwrap_or(0.0);
-        let height = self.prev_row_height(self.row);
+        let width = if self.next_cell.is_some() {
+            self.prev_span_width()
+        } else {
+            self.prev_state.col_width(self.col).unwrap_or(0.0)
+        };
+        let height = self.prev_span_height();
         let size = child_size.max(vec2(width, height));
         Rect::from_min_size(cursor.min, size).round_ui()
     }
 
-    #[expect(clippy::unused_self)]
     pub(crate) fn align_size_within_rect(&self, size: Vec2, frame: Rect) -> Rect {
-        // TODO(emilk): allow this alignment to be customized
-        Align2::LEFT_CENTER
+        self.cell_align()
             .align_size_within_rect(size, frame)
             .round_ui()
     }
 
+    /// How to align the contents of the next cell.
+    pub(crate) fn cell_align(&self) -> Align2 {
+        self.next_cell
+            .and_then(|cell| cell.align)
+            .unwrap_or_else(|| {
+                let align = self.columns.get(self.col).and_then(|column| column.align);
+                Align2([align.unwrap_or(Align::LEFT), Align::Center])
+            })
+    }
+
     pub(crate) fn justify_and_align(&self, frame: Rect, size: Vec2) -> Rect {
         self.align_size_within_rect(size, frame)
     }
@@ -229,13 +483,46 @@ impl GridLayout {
             }
         }
 
+        let next_cell = self.next_cell.take();
+        let cell = next_cell.unwrap_or_default();
+        let cols = self.col..self.col + cell.col_span;
+        let rows = self.row..self.row + cell.row_span;
+
+        for col in cols.clone() {
+            let width = if cols.len() == 1 {
+                widget_rect.width()
+            } else {
+                0.0 // See `Span`
+            };
+            self.curr_state
+                .set_min_col_width(col, width.max(self.min_col_width(col)));
+        }
+        let height = if rows.len() == 1 {
+            widget_rect.height()
+        } else {
+            0.0 // See `Span`
+        };
         self.curr_state
-            .set_min_col_width(self.col, widget_rect.width().max(self.min_cell_size.x));
-        self.curr_state
-            .set_min_row_height(self.row, widget_rect.height().max(self.min_cell_size.y));
+            .set_min_row_height(self.row, height.max(self.min_cell_size.y));
 
-        cursor.min.x += self.prev_col_width(self.col) + self.spacing.x;
-        self.col += 1;
+        if next_cell.is_some() {
+            self.curr_state
+                .cell_sizes
+                .insert((self.row, self.col), widget_rect.size());
+        }
+        if 1 < cols.len() || 1 < rows.len() {
+            self.spans.push(Span {
+                rows,
+                cols: cols.clone(),
+                size: widget_rect.size(),
+            });
+        }
+
+        for col in cols {
+            cursor.min.x += self.prev_col_width(col) + self.spacing.x;
+        }
+        self.col += cell.col_span;
+        self.skip_covered(cursor);
     }
 
     fn paint_row(&self, cursor: &Rect, painter: &Painter) {
@@ -259,6 +546,18 @@ impl GridLayout {
     }
 
     pub(crate) fn end_row(&mut self, cursor: &mut Rect, painter: &Painter) {
+        // Cells spanning down to this row decide how tall it needs to be:
+        for span in &self.spans {
+            if span.rows.end == self.row + 1 {
+                State::fit_span(
+                    &mut self.curr_state.row_heights,
+                    span.rows.clone(),
+                    span.size.y,
+                    self.spacing.y,
+                );
+            }
+        }
+
         cursor.min.x = self.initial_available.min.x;
         cursor.min.y += self.spacing.y;
         cursor.min.y += self
@@ -268,11 +567,28 @@ impl GridLayout {
 
         self.col = 0;
         self.row += 1;
+        self.next_cell = None;
 
         self.paint_row(cursor, painter);
+        self.skip_covered(cursor);
     }
 
-    pub(crate) fn save(&self) {
+    pub(crate) fn save(&mut self) {
+        for Span { rows, cols, size } in &self.spans {
+            State::fit_span(
+                &mut self.curr_state.col_widths,
+                cols.clone(),
+                size.x,
+                self.spacing.x,
+            );
+            State::fit_span(
+                &mut self.curr_state.row_heights,
+                rows.clone(),
+                size.y,
+                self.spacing.y,
+            );
+        }
+
         // We need to always save state on the first frame, otherwise request_discard
         // would be called repeatedly (see #5132)
         if self.curr_state != self.prev_state || self.is_first_frame {
@@ -287,10 +603,12 @@ impl GridLayout {
 /// A simple grid layout.
 ///
 /// The cells are always laid out left to right, top-down.
-/// The contents of each cell will be aligned to the left and center.
+/// The contents of each cell will be aligned to the left and center,
+/// unless you change that with [`Self::column`] or [`GridCell::align`].
 ///
 /// If you want to add multiple widgets to a cell you need to group them with
-/// [`Ui::horizontal`], [`Ui::vertical`] etc.
+/// [`Ui::horizontal`], [`Ui::vertical`] etc, or use [`Ui::grid_cell`].
+/// [`Ui::grid_cell`] can also make a cell span several columns or rows.
 ///
 /// ```
 /// # egui::__run_test_ui(|ui| {
@@ -320,6 +638,7 @@ pub struct Grid {
     spacing: Option<Vec2>,
     start_row: usize,
     color_picker: Option<ColorPickerFn>,
+    columns: Vec<GridColumn>,
 }
 
 impl Grid {
@@ -334,6 +653,7 @@ impl Grid {
             spacing: None,
             start_row: 0,
             color_picker: None,
+            columns: Vec::new(),
         }
     }
 
@@ -400,6 +720,33 @@ impl Grid {
         self
     }
 
+    /// Set the alignment and width limits of one column.
+    ///
+    /// ```
+    /// # egui::__run_test_ui(|ui| {
+    /// use egui::{Align, GridColumn};
+    /// egui::Grid::new("prices")
+    ///     .column(1, GridColumn::new().align(Align::RIGHT).min_width(80.0))
+    ///     .show(ui, |ui| {
+    ///         ui.label("Apples");
+    ///         ui.label("1.50");
+    ///         ui.end_row();
+    ///
+    ///         ui.label("Melons");
+    ///         ui.label("12.00");
+    ///         ui.end_row();
+    ///     });
+    /// # });
+    /// ```
+    #[inline]
+    pub fn column(mut self, col: usize, column: GridColumn) -> Self {
+        if self.columns.len() <= col {
+            self.columns.resize(col + 1, GridColumn::default());
+        }
+        self.columns[col] = column;
+        self
+    }
+
     /// Change which row number the grid starts on.
     /// This can be useful when you have a large [`crate::Grid`] inside of [`crate::ScrollArea::show_rows`].
     #[inline]
@@ -428,6 +775,7 @@ impl Grid {
             spacing,
             start_row,
             mut color_picker,
+            columns,
         } = self;
         let min_col_width = min_col_width.unwrap_or_else(|| ui.spacing().interact_size.x);
         let min_row_height = min_row_height.unwrap_or_else(|| ui.spacing().interact_size.y);
@@ -467,6 +815,7 @@ impl Grid {
                     min_cell_size: vec2(min_col_width, min_row_height),
                     max_cell_size,
                     spacing,
+                    columns,
                     row: start_row,
                     ..GridLayout::new(ui, id, prev_state)
                 };
@@ -388,6 +388,7 @@
 #![expect(clippy::float_cmp)]
 #![expect(clippy::manual_range_contains)]
 
+pub mod accessibility_audit;
 mod animation_manager;
 mod atomics;
 pub mod cache;
@@ -414,6 +415,7 @@ mod painter;
 mod pass_state;
 pub(crate) mod placer;
 pub mod plugin;
+pub mod recording;
 pub mod response;
 mod sense;
 pub mod style;
@@ -458,8 +460,8 @@ pub use epaint::{
 pub mod text {
     pub use crate::text_selection::CCursorRange;
     pub use epaint::text::{
-        FontData, FontDefinitions, FontFamily, Fonts, Galley, LayoutJob, LayoutSection, TAB_SIZE,
-        TextFormat, TextWrapping, cursor::CCursor,
+        FontData, FontDefinitions, FontFamily, Fonts, Galley, LayoutJob, LayoutSection,
+        Placeholder, PlaceholderAlign, TAB_SIZE, TextFormat, TextWrapping, cursor::CCursor,
     };
 }
 
@@ -477,7 +479,7 @@ pub use self::{
     },
     drag_and_drop::DragAndDrop,
     epaint::text::TextWrapMode,
-    grid::Grid,
+    grid::{Grid, GridCell, GridColumn},
     id::{Id, IdMap},
     input_state::{InputOptions, InputState, MultiTouchInfo, PointerState, SurrenderFocusOn},
     layers::{LayerId, Order},
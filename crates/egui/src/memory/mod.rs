@@ -277,6 +277,15 @@ pub struct Options {
     /// By default this is `true` in debug builds.
     pub warn_on_id_clash: bool,
 
+    /// Check the ui for common accessibility problems at the end of each pass,
+    /// such as buttons without a label or text with too little contrast.
+    ///
+    /// Read the result with [`crate::Context::accessibility_issues`].
+    /// See [`crate::accessibility_audit`] for the list of checks.
+    ///
+    /// This has a small performance cost, so it is `false` by default.
+    pub audit_accessibility: bool,
+
     /// Options related to input state handling.
     pub input_options: crate::input_state::InputOptions,
 
@@ -311,6 +320,7 @@ impl Default for Options {
             max_passes: NonZeroUsize::new(2).unwrap(),
             screen_reader: false,
             warn_on_id_clash: cfg!(debug_assertions),
+            audit_accessibility: false,
 
             // Input:
             input_options: Default::default(),
@@ -351,6 +361,16 @@ impl Options {
 }
 
 impl Options {
+    /// Is the accessibility audit on, either with [`Self::audit_accessibility`]
+    /// or [`crate::style::DebugOptions::show_accessibility_issues`]?
+    pub(crate) fn is_auditing_accessibility(&self) -> bool {
+        #[cfg(debug_assertions)]
+        if self.style().debug.show_accessibility_issues {
+            return true;
+        }
+        self.audit_accessibility
+    }
+
     /// Show the options in the ui.
     pub fn ui(&mut self, ui: &mut crate::Ui) {
         let theme = self.theme();
@@ -368,6 +388,7 @@ impl Options {
             max_passes,
             screen_reader: _, // needs to come from the integration
             warn_on_id_clash,
+            audit_accessibility,
             input_options,
             reduce_texture_memory,
         } = self;
@@ -400,6 +421,8 @@ impl Options {
 
                 ui.checkbox(warn_on_id_clash, "Warn if two widgets have the same Id");
 
+                ui.checkbox(audit_accessibility, "Audit accessibility");
+
                 ui.checkbox(reduce_texture_memory, "Reduce texture memory");
             });
 
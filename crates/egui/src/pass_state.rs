@@ -189,6 +189,11 @@ pub struct PassState {
     /// All [`Id`]s that were used this pass.
     pub used_ids: IdMap<Rect>,
 
+    /// [`Id`]s that were used by more than one widget this pass, with the rect of the later one.
+    ///
+    /// Only collected when [`crate::Options::audit_accessibility`] is on.
+    pub id_clashes: Vec<(Id, Rect)>,
+
     /// All widgets produced this pass.
     pub widgets: WidgetRects,
 
@@ -237,6 +242,7 @@ impl Default for PassState {
     fn default() -> Self {
         Self {
             used_ids: Default::default(),
+            id_clashes: Default::default(),
             widgets: Default::default(),
             layers: Default::default(),
             tooltips: Default::default(),
@@ -259,6 +265,7 @@ impl PassState {
         profiling::function_scope!();
         let Self {
             used_ids,
+            id_clashes,
             widgets,
             tooltips,
             layers,
@@ -275,6 +282,7 @@ impl PassState {
         } = self;
 
         used_ids.clear();
+        id_clashes.clear();
         widgets.clear();
         tooltips.clear();
         layers.clear();
@@ -0,0 +1,385 @@
+//! Recording and replaying of [`RawInput`], e.g. to reproduce bugs.
+//!
+//! Register an [`InputRecorder`] to record all input to a [`Context`]:
+//!
+//! ```
+//! # let ctx = egui::Context::default();
+//! use egui::recording::InputRecorder;
+//!
+//! ctx.add_plugin(InputRecorder::default());
+//!
+//! // … run the app for a while …
+//! # let _ = ctx.run_ui(Default::default(), |_ui| {});
+//!
+//! let recording = ctx.plugin::<InputRecorder>().lock().take_recording();
+//! assert_eq!(recording.len(), 1);
+//! ```
+//!
+//! With the `persistence` feature you can also stream the input to a file
+//! with [`InputRecorder::to_file`], and load it back with [`InputRecording::load`].
+//!
+//! The recorded input can then be fed back into a [`Context`] frame by frame,
+//! including the time, either directly with [`InputRecording::replay`],
+//! or by registering an [`InputReplayer`], which replaces the input from the integration (e.g. `eframe`).
+//! `egui_kittest` has `Harness::replay` to turn a recording into a regression test.
+
+use std::collections::VecDeque;
+
+use crate::{Context, FullOutput, Plugin, RawInput, Ui, ViewportIdSet};
+
+/// A sequence of [`RawInput`]s, one for each frame.
+///
+/// Created by [`InputRecorder`].
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct InputRecording {
+    /// The input of each frame, in order.
+    pub frames: Vec<RawInput>,
+}
+
+impl InputRecording {
+    /// Number of recorded frames.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Were no frames recorded?
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Run one frame for each recorded input, and return the output of each frame.
+    ///
+    /// This is deterministic, as long as `run_ui` only depends on the input (including [`RawInput::time`]).
+    pub fn replay(&self, ctx: &Context, mut run_ui: impl FnMut(&mut Ui)) -> Vec<FullOutput> {
+        profiling::function_scope!();
+        self.frames
+            .iter()
+            .map(|input| ctx.run_ui(input.clone(), &mut run_ui))
+            .collect()
+    }
+
+    /// Parse a recording with one [RON](https://docs.rs/ron)-encoded [`RawInput`] per line,
+    /// as written by [`Self::to_ron_lines`] and [`InputRecorder::to_file`].
+    ///
+    /// Empty lines are ignored.
+    ///
+    /// # Errors
+    /// If a line can't be parsed. The error includes the line number.
+    #[cfg(feature = "persistence")]
+    pub fn from_ron_lines(text: &str) -> Result<Self, String> {
+        let frames = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| ron::from_str(line).map_err(|err| format!("line {}: {err}", i + 1)))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { frames })
+    }
+
+    /// One [RON](https://docs.rs/ron)-encoded [`RawInput`] per line.
+    ///
+    /// # Errors
+    /// If a frame can't be serialized. The error includes the frame number.
+    #[cfg(feature = "persistence")]
+    pub fn to_ron_lines(&self) -> Result<String, String> {
+        let mut text = String::new();
+        for (i, input) in self.frames.iter().enumerate() {
+            text += &encode_frame(input).map_err(|err| format!("frame {i}: {err}"))?;
+            text.push('\n');
+        }
+        Ok(text)
+    }
+
+    /// Load a recording written by [`InputRecorder::to_file`] or [`Self::save`].
+    ///
+    /// # Errors
+    /// If the file can't be read or parsed.
+    #[cfg(feature = "persistence")]
+    pub fn load(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Self::from_ron_lines(&text)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+    }
+
+    /// Save the recording, in the same format as [`InputRecorder::to_file`].
+    ///
+    /// # Errors
+    /// If a frame can't be serialized, or the file can't be written.
+    #[cfg(feature = "persistence")]
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        let text = self
+            .to_ron_lines()
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        std::fs::write(path, text)
+    }
+}
+
+/// The compact RON format never contains newlines.
+#[cfg(feature = "persistence")]
+fn encode_frame(input: &RawInput) -> ron::Result<String> {
+    ron::to_string(input)
+}
+
+/// A [`Plugin`] that records all [`RawInput`] given to the [`Context`].
+///
+/// [`Plugin::input_hook`] is called for every pass of a frame,
+/// but only the input of the first pass is recorded,
+/// so that replaying it results in the same passes (see [`Context::request_discard`]).
+///
+/// See [the module docs](crate::recording) for an example.
+#[derive(Default)]
+pub struct InputRecorder {
+    recording: InputRecording,
+    paused: bool,
+
+    /// The input of the pass that is about to start.
+    pending: Option<RawInput>,
+
+    #[cfg(feature = "persistence")]
+    file: Option<std::io::BufWriter<std::fs::File>>,
+}
+
+impl InputRecorder {
+    /// Also write each frame to this file as soon as it is recorded,
+    /// so that the recording survives a crash.
+    ///
+    /// The file contains one [RON](https://docs.rs/ron)-encoded [`RawInput`] per line,
+    /// and can be loaded with [`InputRecording::load`].
+    ///
+    /// # Errors
+    /// If the file can't be created.
+    #[cfg(feature = "persistence")]
+    pub fn to_file(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let file = std::fs::File::create(path)?;
+        Ok(Self {
+            file: Some(std::io::BufWriter::new(file)),
+            ..Default::default()
+        })
+    }
+
+    /// Stop recording until [`Self::resume`] is called.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Continue recording after [`Self::pause`].
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Has recording been paused with [`Self::pause`]?
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// The frames recorded so far.
+    pub fn recording(&self) -> &InputRecording {
+        &self.recording
+    }
+
+    /// Take the frames recorded so far, and start a new recording.
+    ///
+    /// If writing to a file, the file still contains all frames.
+    pub fn take_recording(&mut self) -> InputRecording {
+        std::mem::take(&mut self.recording)
+    }
+
+    #[cfg(feature = "persistence")]
+    fn write_to_file(&mut self, input: &RawInput) {
+        use std::io::Write as _;
+
+        if let Some(file) = &mut self.file {
+            let result = encode_frame(input)
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+                .and_then(|line| writeln!(file, "{line}"))
+                .and_then(|()| file.flush());
+            if let Err(err) = result {
+                log::warn!("Failed to write input recording: {err}. Stopping writing to file.");
+                self.file = None;
+            }
+        }
+    }
+}
+
+impl Plugin for InputRecorder {
+    fn debug_name(&self) -> &'static str {
+        "InputRecorder"
+    }
+
+    fn input_hook(&mut self, input: &mut RawInput) {
+        if !self.paused {
+            self.pending = Some(input.clone());
+        }
+    }
+
+    fn on_begin_pass(&mut self, ui: &mut Ui) {
+        if let Some(input) = self.pending.take()
+            && ui.ctx().current_pass_index() == 0
+        {
+            #[cfg(feature = "persistence")]
+            self.write_to_file(&input);
+
+            self.recording.frames.push(input);
+        }
+    }
+}
+
+/// A [`Plugin`] that replaces the [`RawInput`] of each frame with a recorded one.
+///
+/// This lets you replay a recording in any integration, e.g. in `eframe`:
+/// the input from the window is ignored until all recorded frames have been used.
+/// A repaint is requested until then, so the replay doesn't stall.
+///
+/// Frames of viewports other than the one that is next in the recording are left untouched.
+///
+/// ```
+/// # let ctx = egui::Context::default();
+/// # let recording = egui::recording::InputRecording::default();
+/// use egui::recording::InputReplayer;
+///
+/// ctx.add_plugin(InputReplayer::new(recording));
+/// ```
+pub struct InputReplayer {
+    frames: VecDeque<RawInput>,
+
+    /// What is left of the frame being replayed, for the extra passes of that frame.
+    current: Option<RawInput>,
+
+    /// Viewports for which the next pass will be an extra pass of the same frame.
+    discarding: ViewportIdSet,
+}
+
+impl InputReplayer {
+    /// Replay the frames of the recording, starting with the next frame.
+    pub fn new(recording: InputRecording) -> Self {
+        Self {
+            frames: recording.frames.into(),
+            current: None,
+            discarding: Default::default(),
+        }
+    }
+
+    /// Number of recorded frames not yet replayed.
+    pub fn remaining(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Have all recorded frames been replayed?
+    pub fn is_finished(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+impl Plugin for InputReplayer {
+    fn debug_name(&self) -> &'static str {
+        "InputReplayer"
+    }
+
+    fn input_hook(&mut self, input: &mut RawInput) {
+        let first_pass = !self.discarding.remove(&input.viewport_id);
+        if first_pass {
+            self.current = None;
+            if self
+                .frames
+                .front()
+                .is_some_and(|next| next.viewport_id == input.viewport_id)
+            {
+                self.current = self.frames.pop_front();
+            }
+        }
+
+        // Each pass gets what is left of the input, just like `Context::run` does it:
+        if let Some(current) = &mut self.current
+            && current.viewport_id == input.viewport_id
+        {
+            *input = current.take();
+        }
+    }
+
+    fn on_end_pass(&mut self, ui: &mut Ui) {
+        if ui.ctx().will_discard() {
+            self.discarding.insert(ui.ctx().viewport_id());
+        }
+        if !self.frames.is_empty() {
+            ui.ctx().request_repaint();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Event, Key, Modifiers};
+
+    fn input(time: f64, events: Vec<Event>) -> RawInput {
+        RawInput {
+            time: Some(time),
+            events,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let key_press = Event::Key {
+            key: Key::A,
+            physical_key: None,
+            pressed: true,
+            repeat: false,
+            modifiers: Modifiers::NONE,
+        };
+        let inputs = vec![
+            input(0.0, vec![]),
+            input(0.5, vec![key_press]),
+            input(1.25, vec![Event::Text("hello".to_owned())]),
+        ];
+
+        let ctx = Context::default();
+        ctx.add_plugin(InputRecorder::default());
+        let mut seen = vec![];
+        for input in &inputs {
+            let _ = ctx.run_ui(input.clone(), |ui| {
+                ui.ctx().request_discard("test multi-pass");
+                seen.push(ui.input(|i| (i.time, i.events.len())));
+            });
+        }
+        let recording = ctx.plugin::<InputRecorder>().lock().take_recording();
+        assert_eq!(
+            recording.len(),
+            3,
+            "Only the first pass of each frame is recorded"
+        );
+        assert_eq!(recording.frames[1].events, inputs[1].events);
+
+        // Replaying into a new context gives the same input:
+        let replay_ctx = Context::default();
+        let mut replayed = vec![];
+        recording.replay(&replay_ctx, |ui| {
+            ui.ctx().request_discard("test multi-pass");
+            replayed.push(ui.input(|i| (i.time, i.events.len())));
+        });
+        assert_eq!(seen, replayed);
+
+        // The replayer plugin ignores the input from the integration:
+        let replay_ctx = Context::default();
+        replay_ctx.add_plugin(InputReplayer::new(recording.clone()));
+        let mut replayed = vec![];
+        for _ in 0..3 {
+            let _ = replay_ctx.run_ui(input(100.0, vec![]), |ui| {
+                ui.ctx().request_discard("test multi-pass");
+                replayed.push(ui.input(|i| (i.time, i.events.len())));
+            });
+        }
+        assert_eq!(seen, replayed);
+        assert!(replay_ctx.plugin::<InputReplayer>().lock().is_finished());
+
+        #[cfg(feature = "persistence")]
+        assert_eq!(
+            InputRecording::from_ron_lines(&recording.to_ron_lines().unwrap()),
+            Ok(recording)
+        );
+    }
+}
@@ -1310,6 +1310,12 @@ pub struct DebugOptions {
     /// `Sense::click()` when it should be using `Sense::CLICK`) and you need to find which one it
     /// is.
     pub show_focused_widget: bool,
+
+    /// Outline widgets and text with accessibility problems, and explain the problem.
+    ///
+    /// This turns on the audit even if [`crate::Options::audit_accessibility`] is off.
+    /// See [`crate::accessibility_audit`].
+    pub show_accessibility_issues: bool,
 }
 
 #[cfg(debug_assertions)]
@@ -1327,6 +1333,7 @@ impl Default for DebugOptions {
             show_widget_hits: false,
             show_unaligned: cfg!(debug_assertions),
             show_focused_widget: false,
+            show_accessibility_issues: false,
         }
     }
 }
@@ -2489,6 +2496,7 @@ impl DebugOptions {
             show_widget_hits,
             show_unaligned,
             show_focused_widget,
+            show_accessibility_issues,
         } = self;
 
         {
@@ -2528,6 +2536,11 @@ impl DebugOptions {
             "Highlight which widget has keyboard focus",
         );
 
+        ui.checkbox(
+            show_accessibility_issues,
+            "Show accessibility issues (missing labels, low contrast, …)",
+        );
+
         ui.vertical_centered(|ui| reset_button(ui, self, "Reset debug options"));
     }
 }
@@ -2847,6 +2860,7 @@ impl Widget for &mut FontTweak {
                     y_offset_factor,
                     y_offset,
                     hinting_override,
+                    prefer_color_glyphs,
                 } = self;
 
                 ui.label("Scale");
@@ -2874,6 +2888,11 @@ impl Widget for &mut FontTweak {
                         ui.selectable_value(hinting_override, Some(true), "Enable");
                         ui.selectable_value(hinting_override, Some(false), "Disable");
                     });
+                ui.end_row();
+
+                ui.label("prefer_color_glyphs");
+                ui.checkbox(prefer_color_glyphs, "");
+                ui.end_row();
 
                 if ui.button("Reset").clicked() {
                     *self = Default::default();
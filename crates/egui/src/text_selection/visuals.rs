@@ -59,24 +59,23 @@ pub fn paint_text_selection(
             // Change color of the selected text:
             let first_glyph_index = if ri == min.row { min.column } else { 0 };
             let last_glyph_index = if ri == max.row {
-                max.column
+                max.column.min(row.glyphs.len())
             } else {
                 row.glyphs.len() - 1
             };
 
-            let first_vertex_index = row
+            // Color glyphs (emojis) keep their colors:
+            for glyph in row
                 .glyphs
-                .get(first_glyph_index)
-                .map_or(row.visuals.glyph_vertex_range.start, |g| {
-                    g.first_vertex as _
-                });
-            let last_vertex_index = row
-                .glyphs
-                .get(last_glyph_index)
-                .map_or(row.visuals.glyph_vertex_range.end, |g| g.first_vertex as _);
-
-            for vi in first_vertex_index..last_vertex_index {
-                mesh.vertices[vi].color = text_color;
+                .get(first_glyph_index..last_glyph_index)
+                .unwrap_or_default()
+            {
+                if !glyph.uv_rect.is_nothing() && !glyph.uv_rect.is_color {
+                    let first_vertex = glyph.first_vertex as usize;
+                    for vertex in &mut mesh.vertices[first_vertex..first_vertex + 4] {
+                        vertex.color = text_color;
+                    }
+                }
             }
         }
 
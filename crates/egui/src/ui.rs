@@ -1100,7 +1100,7 @@ impl Ui {
 
     /// Update the [`WidgetRect`] created in [`Ui::new`] or [`Ui::new_child`] with the current
     /// [`Ui::min_rect`].
-    fn remember_min_rect(&mut self) -> Response {
+    pub(crate) fn remember_min_rect(&mut self) -> Response {
         self.min_rect_already_remembered = true;
         // We remove the id from used_ids to prevent a duplicate id warning from showing
         // when the ui was created with `UiBuilder::sense`.
@@ -2739,6 +2739,42 @@ impl Ui {
             .end_row(self.spacing().item_spacing, &self.painter().clone());
     }
 
+    /// Add a cell to a [`crate::Grid`] that can span several columns or rows,
+    /// be put in a specific column, or be aligned differently.
+    ///
+    /// The contents are laid out top-down.
+    /// Outside of a grid this is the same as [`Self::scope`].
+    ///
+    /// See [`crate::GridCell`] for an example.
+    pub fn grid_cell<R>(
+        &mut self,
+        cell: crate::GridCell,
+        add_contents: impl FnOnce(&mut Ui) -> R,
+    ) -> InnerResponse<R> {
+        let mut cursor = self.placer.cursor();
+        let Some(grid) = self.placer.grid_mut() else {
+            return self.scope(add_contents);
+        };
+        grid.set_next_cell(cell, &mut cursor);
+        let prev_size = grid.prev_cell_size();
+        let align = grid.cell_align();
+        self.placer.set_cursor(cursor);
+
+        // Align the contents based on their size last frame,
+        // but give them the whole width of the cell so that they can fill or wrap it.
+        let frame = self
+            .placer
+            .next_space(prev_size, self.spacing().item_spacing);
+        let aligned = self.placer.justify_and_align(frame, prev_size);
+        let max_rect = Rect::from_x_y_ranges(frame.x_range(), aligned.top()..=frame.bottom());
+        self.scope_builder(
+            UiBuilder::new()
+                .max_rect(max_rect)
+                .layout(Layout::top_down(align.x())),
+            add_contents,
+        )
+    }
+
     /// Set row height in horizontal wrapping layout.
     pub fn set_row_height(&mut self, height: f32) {
         self.placer.set_row_height(height);
@@ -5,7 +5,7 @@ use std::{borrow::Cow, sync::Arc};
 
 use crate::{
     Align, Color32, FontFamily, FontSelection, Galley, Style, TextStyle, TextWrapMode, Ui, Visuals,
-    text::{LayoutJob, TextWrapping},
+    text::{LayoutJob, Placeholder, TextWrapping},
 };
 
 /// Text and optional style choices for it.
@@ -363,6 +363,43 @@ impl RichText {
         layout_job.append(&text, 0.0, format);
     }
 
+    /// Append an inline [`Placeholder`] to an existing [`LayoutJob`],
+    /// e.g. to leave room for an image or a widget in the middle of the text.
+    ///
+    /// Only the formatting of the [`RichText`] is used (for the background, underline and row height),
+    /// not its text.
+    /// After showing the text, e.g. with [`crate::Label::show`],
+    /// look up where the placeholder ended up and paint there.
+    ///
+    /// ### Example
+    /// ```
+    /// use egui::{Align, FontSelection, RichText, Style, text::{LayoutJob, Placeholder}, vec2};
+    ///
+    /// let style = Style::default();
+    /// let mut layout_job = LayoutJob::default();
+    /// RichText::new("Press ").append_to(&mut layout_job, &style, FontSelection::Default, Align::Center);
+    /// RichText::new("").append_placeholder_to(
+    ///     &mut layout_job,
+    ///     Placeholder::new(vec2(16.0, 16.0)),
+    ///     &style,
+    ///     FontSelection::Default,
+    ///     Align::Center,
+    /// );
+    /// RichText::new(" to continue").append_to(&mut layout_job, &style, FontSelection::Default, Align::Center);
+    /// ```
+    pub fn append_placeholder_to(
+        self,
+        layout_job: &mut LayoutJob,
+        placeholder: Placeholder,
+        style: &Style,
+        fallback_font: FontSelection,
+        default_valign: Align,
+    ) {
+        let (_, format) = self.into_text_and_format(style, fallback_font, default_valign);
+
+        layout_job.append_placeholder(placeholder, format);
+    }
+
     fn into_layout_job(
         self,
         style: &Style,
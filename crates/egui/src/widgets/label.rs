@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use crate::{
-    Align, Direction, FontSelection, Galley, Pos2, Response, Sense, Stroke, TextWrapMode, Ui,
+    Align, Direction, FontSelection, Galley, Pos2, Rect, Response, Sense, Stroke, TextWrapMode, Ui,
     Widget, WidgetInfo, WidgetText, WidgetType, epaint, pos2, text_selection::LabelSelectionState,
 };
 
@@ -267,8 +267,35 @@ impl Label {
     }
 }
 
-impl Widget for Label {
-    fn ui(self, ui: &mut Ui) -> Response {
+impl Label {
+    /// Show the label and return where its text ended up.
+    ///
+    /// Use this instead of [`Ui::add`] if the text is a [`crate::text::LayoutJob`] with
+    /// [`crate::text::Placeholder`]s, to find out where to paint images or widgets.
+    ///
+    /// ```
+    /// # egui::__run_test_ui(|ui| {
+    /// use egui::{Align, FontSelection, RichText, text::{LayoutJob, Placeholder}, vec2};
+    ///
+    /// let mut job = LayoutJob::default();
+    /// let style = ui.style();
+    /// RichText::new("Press ").append_to(&mut job, style, FontSelection::Default, Align::Center);
+    /// RichText::new("").append_placeholder_to(
+    ///     &mut job,
+    ///     Placeholder::new(vec2(40.0, 18.0)),
+    ///     style,
+    ///     FontSelection::Default,
+    ///     Align::Center,
+    /// );
+    /// RichText::new(" to continue").append_to(&mut job, style, FontSelection::Default, Align::Center);
+    ///
+    /// let output = egui::Label::new(job).show(ui);
+    /// if let Some(rect) = output.placeholder_rects().next() {
+    ///     ui.put(rect, egui::Button::new("OK").small());
+    /// }
+    /// # });
+    /// ```
+    pub fn show(self, ui: &mut Ui) -> LabelOutput {
         // Interactive = the uses asked to sense interaction.
         // We DON'T want to have the color respond just because the text is selectable;
         // the cursor is enough to communicate that.
@@ -311,18 +338,51 @@ impl Widget for Label {
                     ui,
                     &response,
                     galley_pos,
-                    galley,
+                    Arc::clone(&galley),
                     response_color,
                     underline,
                 );
             } else {
                 ui.painter().add(
-                    epaint::TextShape::new(galley_pos, galley, response_color)
+                    epaint::TextShape::new(galley_pos, Arc::clone(&galley), response_color)
                         .with_underline(underline),
                 );
             }
         }
 
-        response
+        LabelOutput {
+            response,
+            galley,
+            galley_pos,
+        }
+    }
+}
+
+impl Widget for Label {
+    fn ui(self, ui: &mut Ui) -> Response {
+        self.show(ui).response
+    }
+}
+
+/// The output from [`Label::show`].
+pub struct LabelOutput {
+    /// The interaction response.
+    pub response: Response,
+
+    /// How the text was displayed.
+    pub galley: Arc<Galley>,
+
+    /// Where the text in [`Self::galley`] ended up on the screen.
+    pub galley_pos: Pos2,
+}
+
+impl LabelOutput {
+    /// Where each [`crate::text::Placeholder`] in the text ended up on the screen, in text order.
+    ///
+    /// If the text was elided, the last placeholders may be missing.
+    pub fn placeholder_rects(&self) -> impl Iterator<Item = Rect> + '_ {
+        self.galley
+            .placeholder_rects()
+            .map(|rect| rect.translate(self.galley_pos.to_vec2()))
     }
 }
@@ -0,0 +1,414 @@
+use epaint::{
+    Color32, Stroke,
+    text::{LayoutJob, TextFormat, cursor::CCursor},
+};
+
+use crate::{
+    Align, Button, EventFilter, Frame, Id, Key, Layout, Modifiers, Popup, Response, TextEdit,
+    TextStyle, Ui, UiBuilder, Widget, WidgetText, text::CCursorRange,
+};
+
+use super::TextEditState;
+
+/// The indices of the characters of `candidate` that match `query`, ignoring case,
+/// or `None` if it doesn't match.
+///
+/// The characters of `query` must come in order, but may be spread out.
+/// If they can be found next to each other, those are the ones returned.
+fn matched_chars(query: &str, candidate: &str) -> Option<Vec<usize>> {
+    let eq = |a: char, b: char| a.to_lowercase().eq(b.to_lowercase());
+    let query: Vec<char> = query.chars().collect();
+    let candidate: Vec<char> = candidate.chars().collect();
+    if query.is_empty() {
+        return Some(Vec::new());
+    }
+
+    let substring = candidate
+        .windows(query.len())
+        .position(|window| window.iter().zip(&query).all(|(&a, &b)| eq(a, b)));
+    if let Some(start) = substring {
+        return Some((start..start + query.len()).collect());
+    }
+
+    let mut indices = Vec::with_capacity(query.len());
+    let mut query = query.iter().peekable();
+    for (i, &c) in candidate.iter().enumerate() {
+        if query.next_if(|&&q| eq(c, q)).is_some() {
+            indices.push(i);
+        }
+    }
+    query.peek().is_none().then_some(indices)
+}
+
+/// The `candidates` that match `query`, best matches first.
+///
+/// Candidates containing `query` come first, sorted by where it starts,
+/// then the ones containing the characters of `query` spread out.
+fn filter_candidates(query: &str, candidates: &[impl AsRef<str>]) -> Vec<String> {
+    let mut matches: Vec<(bool, usize, &str)> = candidates
+        .iter()
+        .filter_map(|candidate| {
+            let candidate = candidate.as_ref();
+            let indices = matched_chars(query, candidate)?;
+            let first = indices.first().copied().unwrap_or(0);
+            let spread = indices.windows(2).any(|w| w[0] + 1 != w[1]);
+            Some((spread, first, candidate))
+        })
+        .collect();
+    matches.sort_by_key(|&(spread, first, _)| (spread, first));
+    matches
+        .into_iter()
+        .map(|(_, _, candidate)| candidate.to_owned())
+        .collect()
+}
+
+/// `text` with the characters that match `query` highlighted.
+fn highlighted(ui: &Ui, query: &str, text: &str) -> LayoutJob {
+    let matched = matched_chars(query, text).unwrap_or_default();
+    let font_id = TextStyle::Button.resolve(ui.style());
+    let normal = TextFormat::simple(font_id.clone(), Color32::PLACEHOLDER);
+    let strong_color = ui.visuals().strong_text_color();
+    let strong = TextFormat {
+        underline: Stroke::new(1.0, strong_color),
+        ..TextFormat::simple(font_id, strong_color)
+    };
+
+    let mut job = LayoutJob::default();
+    let mut buf = [0; 4];
+    for (i, c) in text.chars().enumerate() {
+        let format = if matched.contains(&i) {
+            strong.clone()
+        } else {
+            normal.clone()
+        };
+        // Merge runs of characters with the same format into one section:
+        match job.sections.last_mut() {
+            Some(section) if section.format == format => {
+                job.text.push(c);
+                section.byte_range.end = job.text.len();
+            }
+            _ => job.append(c.encode_utf8(&mut buf), 0.0, format),
+        }
+    }
+    job
+}
+
+#[derive(Clone, Debug, Default)]
+struct State {
+    /// The suggestions shown last frame.
+    suggestions: Vec<String>,
+
+    /// The suggestion selected with the arrow keys.
+    highlighted: Option<usize>,
+
+    /// The suggestions are hidden after accepting one, until the text changes.
+    dismissed: bool,
+}
+
+/// A single-line text input that suggests how to complete what the user is typing.
+///
+/// While the input has focus, the suggestions that match the text are shown below it,
+/// with the matching characters highlighted.
+/// Select one with the arrow keys and accept it with enter, or click it.
+/// Tab accepts the selected suggestion, or the first one if none is selected,
+/// and escape hides the suggestions.
+///
+/// In [`Self::tokens`] mode, the accepted values are collected as tags in front of the input,
+/// which the user can remove again.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// # let mut query = String::new();
+/// let fruits = ["Apple", "Banana", "Cherry"];
+/// ui.add(egui::Autocomplete::new(&mut query, &fruits).hint_text("Fruit"));
+/// # });
+/// ```
+#[must_use = "You should put this widget in a ui with `ui.add(widget);`"]
+pub struct Autocomplete<'a> {
+    text: &'a mut String,
+    suggestions: Box<dyn FnOnce(&str) -> Vec<String> + 'a>,
+    tokens: Option<&'a mut Vec<String>>,
+    allow_custom_tokens: bool,
+    id_salt: Option<Id>,
+    hint_text: WidgetText,
+    desired_width: Option<f32>,
+    max_suggestions: usize,
+}
+
+impl<'a> Autocomplete<'a> {
+    /// Suggest the `candidates` that match the text.
+    pub fn new<S: AsRef<str>>(text: &'a mut String, candidates: &'a [S]) -> Self {
+        Self::from_fn(text, |query| filter_candidates(query, candidates))
+    }
+
+    /// Get the suggestions for the text from `suggestions`.
+    ///
+    /// It is only called while the suggestions are shown, so it can do some work,
+    /// e.g. look up a database, or return what a background search has found so far.
+    pub fn from_fn(
+        text: &'a mut String,
+        suggestions: impl FnOnce(&str) -> Vec<String> + 'a,
+    ) -> Self {
+        Self {
+            text,
+            suggestions: Box::new(suggestions),
+            tokens: None,
+            allow_custom_tokens: true,
+            id_salt: None,
+            hint_text: WidgetText::default(),
+            desired_width: None,
+            max_suggestions: 10,
+        }
+    }
+
+    /// Collect the accepted values in `tokens`, shown as removable tags in front of the input.
+    ///
+    /// Values that are already in `tokens` are not suggested again.
+    /// Backspace in an empty input removes the last token.
+    #[inline]
+    pub fn tokens(mut self, tokens: &'a mut Vec<String>) -> Self {
+        self.tokens = Some(tokens);
+        self
+    }
+
+    /// In [`Self::tokens`] mode, can the user add a token that isn't suggested,
+    /// by pressing enter?
+    ///
+    /// Default: `true`.
+    #[inline]
+    pub fn allow_custom_tokens(mut self, allow_custom_tokens: bool) -> Self {
+        self.allow_custom_tokens = allow_custom_tokens;
+        self
+    }
+
+    /// Used to remember the state of the input.
+    ///
+    /// Default: an id based on the position in the parent [`Ui`].
+    #[inline]
+    pub fn id_salt(mut self, id_salt: impl std::hash::Hash) -> Self {
+        self.id_salt = Some(Id::new(id_salt));
+        self
+    }
+
+    /// Show a faint hint text when the input is empty.
+    #[inline]
+    pub fn hint_text(mut self, hint_text: impl Into<WidgetText>) -> Self {
+        self.hint_text = hint_text.into();
+        self
+    }
+
+    /// The width of the input, see [`TextEdit::desired_width`].
+    #[inline]
+    pub fn desired_width(mut self, desired_width: f32) -> Self {
+        self.desired_width = Some(desired_width);
+        self
+    }
+
+    /// Show at most this many suggestions.
+    ///
+    /// Default: `10`.
+    #[inline]
+    pub fn max_suggestions(mut self, max_suggestions: usize) -> Self {
+        self.max_suggestions = max_suggestions;
+        self
+    }
+}
+
+impl Widget for Autocomplete<'_> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let Self {
+            text,
+            suggestions,
+            mut tokens,
+            allow_custom_tokens,
+            id_salt,
+            hint_text,
+            desired_width,
+            max_suggestions,
+        } = self;
+
+        let id = id_salt.map_or_else(
+            || ui.next_auto_id(),
+            |id_salt| ui.make_persistent_id(id_salt),
+        );
+        let mut state: State = ui.ctx().data_mut(|d| d.get_temp(id)).unwrap_or_default();
+        let had_focus = ui.memory(|mem| mem.has_focus(id));
+        let was_open = had_focus && !state.dismissed && !state.suggestions.is_empty();
+        let mut changed = false;
+
+        // Handle the keys before the `TextEdit` gets them:
+        let mut accept = None;
+        if was_open {
+            let num = state.suggestions.len();
+            ui.input_mut(|i| {
+                if i.consume_key(Modifiers::NONE, Key::ArrowDown) {
+                    state.highlighted = Some(state.highlighted.map_or(0, |h| (h + 1) % num));
+                }
+                if i.consume_key(Modifiers::NONE, Key::ArrowUp) {
+                    state.highlighted =
+                        Some(state.highlighted.map_or(num - 1, |h| (h + num - 1) % num));
+                }
+                if state.highlighted.is_some() && i.consume_key(Modifiers::NONE, Key::Enter) {
+                    accept = state.highlighted;
+                }
+                if i.consume_key(Modifiers::NONE, Key::Tab) {
+                    accept = Some(state.highlighted.unwrap_or(0));
+                }
+                if i.consume_key(Modifiers::NONE, Key::Escape) {
+                    state.dismissed = true;
+                }
+            });
+        }
+        if had_focus && let Some(tokens) = &mut tokens {
+            if accept.is_none()
+                && allow_custom_tokens
+                && !text.trim().is_empty()
+                && ui.input_mut(|i| i.consume_key(Modifiers::NONE, Key::Enter))
+            {
+                tokens.push(text.trim().to_owned());
+                text.clear();
+                changed = true;
+            }
+            if text.is_empty() && ui.input_mut(|i| i.consume_key(Modifiers::NONE, Key::Backspace)) {
+                changed |= tokens.pop().is_some();
+            }
+        }
+        if let Some(accepted) = accept.and_then(|i| state.suggestions.get(i)) {
+            if let Some(tokens) = &mut tokens {
+                tokens.push(accepted.clone());
+                text.clear();
+            } else {
+                text.clone_from(accepted);
+            }
+            move_cursor_to_end(ui, id, text);
+            state.dismissed = true;
+            state.highlighted = None;
+            changed = true;
+        }
+
+        let add_text_edit = |ui: &mut Ui, text: &mut String| {
+            let mut text_edit = TextEdit::singleline(text).id(id).hint_text(hint_text);
+            if let Some(desired_width) = desired_width {
+                text_edit = text_edit.desired_width(desired_width);
+            }
+            ui.add(text_edit)
+        };
+        let mut response = if let Some(tokens) = &mut tokens {
+            ui.horizontal_wrapped(|ui| {
+                let mut remove = None;
+                for (i, token) in tokens.iter().enumerate() {
+                    if token_ui(ui, token).should_close() {
+                        remove = Some(i);
+                    }
+                }
+                if let Some(i) = remove {
+                    tokens.remove(i);
+                    changed = true;
+                }
+                add_text_edit(ui, text)
+            })
+            .inner
+        } else {
+            add_text_edit(ui, text)
+        };
+
+        if response.changed() {
+            state.dismissed = false;
+            state.highlighted = None;
+        }
+
+        let open = (had_focus || response.has_focus()) && !state.dismissed;
+        state.suggestions = if open {
+            let mut suggestions = suggestions(text);
+            if let Some(tokens) = &tokens {
+                suggestions.retain(|suggestion| !tokens.contains(suggestion));
+            }
+            suggestions.truncate(max_suggestions);
+            suggestions
+        } else {
+            Vec::new()
+        };
+        state.highlighted = state.highlighted.filter(|&i| i < state.suggestions.len());
+
+        let mut clicked = None;
+        Popup::from_response(&response)
+            .id(id.with("suggestions"))
+            .open(open && !state.suggestions.is_empty())
+            .width(response.rect.width())
+            .layout(Layout::top_down_justified(Align::LEFT))
+            .show(|ui| {
+                for (i, suggestion) in state.suggestions.iter().enumerate() {
+                    let job = highlighted(ui, text, suggestion);
+                    let item = ui.add(Button::selectable(state.highlighted == Some(i), job));
+                    // Accept on press, since the input loses focus when the pointer is pressed elsewhere:
+                    if item.clicked() || item.is_pointer_button_down_on() {
+                        clicked = Some(i);
+                    }
+                }
+            });
+        if let Some(accepted) = clicked.and_then(|i| state.suggestions.get(i)) {
+            if let Some(tokens) = &mut tokens {
+                tokens.push(accepted.clone());
+                text.clear();
+            } else {
+                text.clone_from(accepted);
+            }
+            move_cursor_to_end(ui, id, text);
+            ui.memory_mut(|mem| mem.request_focus(id));
+            state.dismissed = true;
+            state.highlighted = None;
+            state.suggestions.clear();
+            changed = true;
+        }
+
+        if response.has_focus() {
+            // While the suggestions are shown, tab and escape are for them,
+            // instead of moving focus away from the input:
+            let is_open = !state.dismissed && !state.suggestions.is_empty();
+            let event_filter = EventFilter {
+                tab: is_open,
+                horizontal_arrows: true,
+                vertical_arrows: true,
+                escape: is_open,
+            };
+            ui.memory_mut(|mem| mem.set_focus_lock_filter(id, event_filter));
+        }
+
+        ui.ctx().data_mut(|d| d.insert_temp(id, state));
+        if changed {
+            response.mark_changed();
+        }
+        response
+    }
+}
+
+fn move_cursor_to_end(ui: &Ui, id: Id, text: &str) {
+    let mut state = TextEditState::load(ui.ctx(), id).unwrap_or_default();
+    let end = CCursor::new(text.chars().count());
+    state.cursor.set_char_range(Some(CCursorRange::one(end)));
+    state.store(ui.ctx(), id);
+}
+
+/// A tag for one of the [`Autocomplete::tokens`], with a button to remove it.
+///
+/// Uses [`UiBuilder::closable`], so the returned [`Response::should_close`] is `true`
+/// when the user removes it.
+fn token_ui(ui: &mut Ui, token: &str) -> Response {
+    ui.scope_builder(UiBuilder::new().closable(), |ui| {
+        Frame::new()
+            .fill(ui.visuals().widgets.inactive.weak_bg_fill)
+            .corner_radius(ui.visuals().widgets.inactive.corner_radius)
+            .inner_margin(2)
+            .show(ui, |ui| {
+                ui.spacing_mut().item_spacing.x = 2.0;
+                ui.label(token);
+                let remove = ui
+                    .add(Button::new("🗙").small().frame(false))
+                    .on_hover_text(format!("Remove {token}"));
+                if remove.clicked() {
+                    ui.close();
+                }
+            });
+    })
+    .response
+}
@@ -1,9 +1,10 @@
+mod autocomplete;
 mod builder;
 mod output;
 mod state;
 mod text_buffer;
 
 pub use {
-    crate::text_selection::TextCursorState, builder::TextEdit, output::TextEditOutput,
-    state::TextEditState, text_buffer::TextBuffer,
+    crate::text_selection::TextCursorState, autocomplete::Autocomplete, builder::TextEdit,
+    output::TextEditOutput, state::TextEditState, text_buffer::TextBuffer,
 };
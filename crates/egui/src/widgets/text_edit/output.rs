@@ -23,4 +23,17 @@ pub struct TextEditOutput {
     pub cursor_range: Option<CCursorRange>,
 }
 
+impl TextEditOutput {
+    /// Where each [`crate::text::Placeholder`] in the text ended up on the screen, in text order.
+    ///
+    /// Placeholders come from a [`crate::TextEdit::layouter`] that lays out some characters
+    /// (usually [`crate::text::Placeholder::CHAR`]) as placeholders.
+    /// The rects may be outside of [`Self::text_clip_rect`] when the text is scrolled.
+    pub fn placeholder_rects(&self) -> impl Iterator<Item = crate::Rect> + '_ {
+        self.galley
+            .placeholder_rects()
+            .map(|rect| rect.translate(self.galley_pos.to_vec2()))
+    }
+}
+
 // TODO(emilk): add `output.paint` and `output.store` and split out that code from `TextEdit::show`.
@@ -1,6 +1,6 @@
-use super::popup::DatePickerPopup;
-use chrono::NaiveDate;
-use egui::{Area, Button, Frame, InnerResponse, Key, Order, RichText, Ui, Widget};
+use super::{load_state, picker_area, picker_should_close, popup::DatePickerPopup, store_state};
+use chrono::{NaiveDate, Weekday};
+use egui::{Button, InnerResponse, RichText, Ui, Widget};
 use std::ops::RangeInclusive;
 
 #[derive(Default, Clone)]
@@ -20,6 +20,7 @@ pub struct DatePickerButton<'a> {
     show_icon: bool,
     format: String,
     highlight_weekends: bool,
+    week_start: Weekday,
     start_end_years: Option<RangeInclusive<i32>>,
 }
 
@@ -35,6 +36,7 @@ impl<'a> DatePickerButton<'a> {
             show_icon: true,
             format: "%Y-%m-%d".to_owned(),
             highlight_weekends: true,
+            week_start: Weekday::Mon,
             start_end_years: None,
         }
     }
@@ -105,6 +107,13 @@ impl<'a> DatePickerButton<'a> {
         self
     }
 
+    /// The first day of each week in the calendar. (Default: Monday)
+    #[inline]
+    pub fn week_start(mut self, week_start: Weekday) -> Self {
+        self.week_start = week_start;
+        self
+    }
+
     /// Set the start and end years for the date picker. (Default: today's year - 100 to today's year + 10)
     /// This will limit the years you can choose from in the dropdown to the specified range.
     ///
@@ -121,7 +130,7 @@ impl Widget for DatePickerButton<'_> {
     fn ui(self, ui: &mut Ui) -> egui::Response {
         let id = ui.make_persistent_id(self.id_salt);
         let mut button_state = ui
-            .data_mut(|data| data.get_persisted::<DatePickerButtonState>(id))
+            .data_mut(|data| load_state::<DatePickerButtonState>(data, id))
             .unwrap_or_default();
 
         let mut text = if self.show_icon {
@@ -140,67 +149,41 @@ impl Widget for DatePickerButton<'_> {
         let mut button_response = ui.add(button);
         if button_response.clicked() {
             button_state.picker_visible = true;
-            ui.data_mut(|data| data.insert_persisted(id, button_state.clone()));
+            ui.data_mut(|data| store_state(data, id, button_state.clone()));
         }
 
         if button_state.picker_visible {
-            let width = 333.0;
-            let mut pos = button_response.rect.left_bottom();
-            let width_with_padding = width
-                + ui.style().spacing.item_spacing.x
-                + ui.style().spacing.window_margin.leftf()
-                + ui.style().spacing.window_margin.rightf();
-            if pos.x + width_with_padding > ui.clip_rect().right() {
-                pos.x = button_response.rect.right() - width_with_padding;
-            }
-
-            // Check to make sure the calendar never is displayed out of window
-            pos.x = pos.x.max(ui.style().spacing.window_margin.leftf());
-
-            //TODO(elwerene): Better positioning
-
             let InnerResponse {
                 inner: saved,
                 response: area_response,
-            } = Area::new(ui.make_persistent_id(self.id_salt))
-                .kind(egui::UiKind::Picker)
-                .order(Order::Foreground)
-                .fixed_pos(pos)
-                .show(ui.ctx(), |ui| {
-                    let frame = Frame::popup(ui.style());
-                    frame
-                        .show(ui, |ui| {
-                            ui.set_min_width(width);
-                            ui.set_max_width(width);
-
-                            DatePickerPopup {
-                                selection: self.selection,
-                                button_id: id,
-                                combo_boxes: self.combo_boxes,
-                                arrows: self.arrows,
-                                calendar: self.calendar,
-                                calendar_week: self.calendar_week,
-                                highlight_weekends: self.highlight_weekends,
-                                start_end_years: self.start_end_years,
-                            }
-                            .draw(ui)
-                        })
-                        .inner
-                });
+            } = picker_area(
+                ui,
+                &button_response,
+                ui.make_persistent_id(self.id_salt),
+                333.0,
+                |ui| {
+                    DatePickerPopup {
+                        selection: self.selection,
+                        button_id: id,
+                        combo_boxes: self.combo_boxes,
+                        arrows: self.arrows,
+                        calendar: self.calendar,
+                        calendar_week: self.calendar_week,
+                        highlight_weekends: self.highlight_weekends,
+                        week_start: self.week_start,
+                        start_end_years: self.start_end_years,
+                    }
+                    .draw(ui)
+                },
+            );
 
             if saved {
                 button_response.mark_changed();
             }
 
-            // We don't want to close our popup if any other popup is open, since other popups would
-            // most likely be the combo boxes in the date picker.
-            let any_popup_open = egui::Popup::is_any_open(ui.ctx());
-            if !button_response.clicked()
-                && !any_popup_open
-                && (ui.input(|i| i.key_pressed(Key::Escape)) || area_response.clicked_elsewhere())
-            {
+            if picker_should_close(ui, &button_response, &area_response) {
                 button_state.picker_visible = false;
-                ui.data_mut(|data| data.insert_persisted(id, button_state));
+                ui.data_mut(|data| store_state(data, id, button_state));
             }
         }
 
@@ -0,0 +1,140 @@
+use chrono::{Datelike as _, NaiveDate, Weekday};
+use egui::{Align, Button, Grid, Layout, RichText, Ui, Vec2};
+
+use super::{
+    DateConstraints, is_weekend, month_data, month_name, weekday_name, weekdays, weekend_fill,
+};
+
+/// How a day in the [`Calendar`] is selected.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DaySelection {
+    None,
+
+    /// Between the start and end of a selected range.
+    InRange,
+
+    /// The selected date, or the start or end of a selected range.
+    Selected,
+}
+
+/// A grid with the days of a month, shared by the pickers that show more than a [`NaiveDate`].
+pub(crate) struct Calendar<'c> {
+    pub calendar_week: bool,
+    pub highlight_weekends: bool,
+    pub week_start: Weekday,
+    pub constraints: &'c DateConstraints<'c>,
+}
+
+impl Calendar<'_> {
+    /// Show the days of `month`, and return the day the user clicked, if any.
+    ///
+    /// The days of the months before and after it are left empty,
+    /// so two months can be shown next to each other.
+    pub fn month_ui(
+        &self,
+        ui: &mut Ui,
+        year: i32,
+        month: u32,
+        selection: impl Fn(NaiveDate) -> DaySelection,
+    ) -> Option<NaiveDate> {
+        let today = chrono::offset::Utc::now().date_naive();
+        let day_size = Vec2::new(24.0, 20.0);
+        let spacing = Vec2::new(1.0, 2.0);
+        let columns = if self.calendar_week { 8.0 } else { 7.0 };
+        let mut clicked = None;
+
+        ui.vertical(|ui| {
+            // The centered month name would otherwise take all the width of a horizontal layout:
+            ui.set_width(columns * day_size.x + (columns - 1.0) * spacing.x);
+            ui.with_layout(Layout::top_down(Align::Center), |ui| {
+                ui.strong(format!("{} {year}", month_name(month)));
+            });
+
+            Grid::new(("calendar", year, month))
+                .spacing(spacing)
+                .min_col_width(day_size.x)
+                .show(ui, |ui| {
+                    if self.calendar_week {
+                        ui.weak("Wk");
+                    }
+                    for day in weekdays(self.week_start) {
+                        ui.label(weekday_name(day));
+                    }
+                    ui.end_row();
+
+                    for week in month_data(year, month, self.week_start) {
+                        if self.calendar_week {
+                            ui.weak(week.number.to_string());
+                        }
+                        for day in week.days {
+                            if day.month() != month {
+                                ui.label("");
+                                continue;
+                            }
+
+                            let fill = match selection(day) {
+                                DaySelection::Selected => ui.visuals().selection.bg_fill,
+                                DaySelection::InRange => {
+                                    ui.visuals().selection.bg_fill.gamma_multiply(0.4)
+                                }
+                                DaySelection::None
+                                    if self.highlight_weekends && is_weekend(day) =>
+                                {
+                                    weekend_fill(ui)
+                                }
+                                DaySelection::None => ui.visuals().extreme_bg_color,
+                            };
+                            let button = Button::new(RichText::new(day.day().to_string()))
+                                .fill(fill)
+                                .min_size(day_size);
+                            let response = ui.add_enabled(self.constraints.allows(day), button);
+
+                            if day == today {
+                                // Encircle today's date
+                                let stroke = ui.visuals().widgets.inactive.fg_stroke;
+                                ui.painter()
+                                    .circle_stroke(response.rect.center(), 8.0, stroke);
+                            }
+
+                            if response.clicked() {
+                                clicked = Some(day);
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+
+        clicked
+    }
+}
+
+/// Buttons to go to the previous or next month.
+pub(crate) fn month_arrows(ui: &mut Ui, year: &mut i32, month: &mut u32) {
+    ui.horizontal(|ui| {
+        if ui.button("<").on_hover_text("previous month").clicked() {
+            (*year, *month) = previous_month(*year, *month);
+        }
+        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+            if ui.button(">").on_hover_text("next month").clicked() {
+                (*year, *month) = next_month(*year, *month);
+            }
+        });
+    });
+}
+
+pub(crate) fn previous_month(year: i32, month: u32) -> (i32, u32) {
+    if month == 1 {
+        (year - 1, 12)
+    } else {
+        (year, month - 1)
+    }
+}
+
+pub(crate) fn next_month(year: i32, month: u32) -> (i32, u32) {
+    if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    }
+}
@@ -0,0 +1,297 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Datelike as _, NaiveDateTime, NaiveTime, TimeZone, Timelike as _, Weekday};
+use egui::{Align, Button, InnerResponse, Layout, RichText, Ui, Widget};
+
+use super::{
+    DateConstraints, TimePicker,
+    calendar::{Calendar, DaySelection, month_arrows},
+    from_days, load_state, picker_area, picker_should_close, store_state, to_days,
+};
+
+#[derive(Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+struct DateTimePickerState {
+    picker_visible: bool,
+
+    /// The month shown.
+    year: i32,
+    month: u32,
+
+    /// The date being picked, see [`to_days`].
+    date: i32,
+
+    /// The time being picked, in seconds since midnight.
+    seconds: u32,
+}
+
+/// Shows a date and time, and will open a picker with a calendar and a [`TimePicker`] when clicked.
+///
+/// The date and time are picked in the time zone of the selection, which is shown next to them.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// # let mut date_time = chrono::Utc::now();
+/// ui.add(egui_extras::DateTimePickerButton::new(&mut date_time).seconds(true));
+/// # });
+/// ```
+pub struct DateTimePickerButton<'a, Tz: TimeZone> {
+    selection: &'a mut DateTime<Tz>,
+    id_salt: Option<&'a str>,
+    calendar_week: bool,
+    show_icon: bool,
+    show_time_zone: bool,
+    format: Option<String>,
+    seconds: bool,
+    twelve_hour: bool,
+    highlight_weekends: bool,
+    week_start: Weekday,
+    constraints: DateConstraints<'a>,
+}
+
+impl<'a, Tz: TimeZone> DateTimePickerButton<'a, Tz> {
+    /// Show the given date and time, which is replaced when the user saves a new one.
+    pub fn new(selection: &'a mut DateTime<Tz>) -> Self {
+        Self {
+            selection,
+            id_salt: None,
+            calendar_week: true,
+            show_icon: true,
+            show_time_zone: true,
+            format: None,
+            seconds: false,
+            twelve_hour: false,
+            highlight_weekends: true,
+            week_start: Weekday::Mon,
+            constraints: DateConstraints::default(),
+        }
+    }
+
+    /// Add id source.
+    /// Must be set if multiple date time picker buttons are in the same Ui.
+    #[inline]
+    pub fn id_salt(mut self, id_salt: &'a str) -> Self {
+        self.id_salt = Some(id_salt);
+        self
+    }
+
+    /// Show calendar week in date picker popup. (Default: true)
+    #[inline]
+    pub fn calendar_week(mut self, week: bool) -> Self {
+        self.calendar_week = week;
+        self
+    }
+
+    /// Show the calendar icon on the button. (Default: true)
+    #[inline]
+    pub fn show_icon(mut self, show_icon: bool) -> Self {
+        self.show_icon = show_icon;
+        self
+    }
+
+    /// Show the offset of the time zone on the button and in the popup. (Default: true)
+    #[inline]
+    pub fn show_time_zone(mut self, show_time_zone: bool) -> Self {
+        self.show_time_zone = show_time_zone;
+        self
+    }
+
+    /// Change the format shown on the button.
+    /// (Default: %Y-%m-%d followed by the time as shown by the [`TimePicker`])
+    /// See [`chrono::format::strftime`] for valid formats.
+    #[inline]
+    pub fn format(mut self, format: impl Into<String>) -> Self {
+        self.format = Some(format.into());
+        self
+    }
+
+    /// Pick the seconds too. (Default: false)
+    #[inline]
+    pub fn seconds(mut self, seconds: bool) -> Self {
+        self.seconds = seconds;
+        self
+    }
+
+    /// Show the hours from 1 to 12, with AM and PM. (Default: false)
+    #[inline]
+    pub fn twelve_hour(mut self, twelve_hour: bool) -> Self {
+        self.twelve_hour = twelve_hour;
+        self
+    }
+
+    /// Highlight weekend days. (Default: true)
+    #[inline]
+    pub fn highlight_weekends(mut self, highlight_weekends: bool) -> Self {
+        self.highlight_weekends = highlight_weekends;
+        self
+    }
+
+    /// The first day of each week in the calendar. (Default: Monday)
+    #[inline]
+    pub fn week_start(mut self, week_start: Weekday) -> Self {
+        self.week_start = week_start;
+        self
+    }
+
+    /// The first date that can be picked.
+    #[inline]
+    pub fn min_date(mut self, min_date: chrono::NaiveDate) -> Self {
+        self.constraints.min = Some(min_date);
+        self
+    }
+
+    /// The last date that can be picked.
+    #[inline]
+    pub fn max_date(mut self, max_date: chrono::NaiveDate) -> Self {
+        self.constraints.max = Some(max_date);
+        self
+    }
+
+    /// Dates for which this returns `true` can't be picked, e.g. holidays.
+    #[inline]
+    pub fn disabled_dates(mut self, disabled: impl Fn(chrono::NaiveDate) -> bool + 'a) -> Self {
+        self.constraints.disabled = Some(Box::new(disabled));
+        self
+    }
+
+    fn button_format(&self) -> String {
+        self.format.clone().unwrap_or_else(|| {
+            let time = match (self.twelve_hour, self.seconds) {
+                (false, false) => "%H:%M",
+                (false, true) => "%H:%M:%S",
+                (true, false) => "%I:%M %p",
+                (true, true) => "%I:%M:%S %p",
+            };
+            format!("%Y-%m-%d {time}")
+        })
+    }
+}
+
+impl<Tz: TimeZone> Widget for DateTimePickerButton<'_, Tz>
+where
+    Tz::Offset: Display,
+{
+    fn ui(self, ui: &mut Ui) -> egui::Response {
+        let id = ui.make_persistent_id(("date_time_picker", self.id_salt));
+        let previous_state = ui
+            .data_mut(|data| load_state::<DateTimePickerState>(data, id))
+            .unwrap_or_default();
+        let mut state = previous_state.clone();
+
+        let offset = self.selection.offset().to_string();
+        let mut text = self.selection.format(&self.button_format()).to_string();
+        if self.show_time_zone {
+            text = format!("{text} {offset}");
+        }
+        if self.show_icon {
+            text += " 📆";
+        }
+        let mut text = RichText::new(text);
+        let visuals = ui.visuals().widgets.open;
+        if state.picker_visible {
+            text = text.color(visuals.text_color());
+        }
+        let mut button = Button::new(text);
+        if state.picker_visible {
+            button = button.fill(visuals.weak_bg_fill).stroke(visuals.bg_stroke);
+        }
+        let mut button_response = ui.add(button);
+        if button_response.clicked() {
+            let local = self.selection.naive_local();
+            state = DateTimePickerState {
+                picker_visible: true,
+                year: local.year(),
+                month: local.month(),
+                date: to_days(local.date()),
+                seconds: local.time().num_seconds_from_midnight(),
+            };
+        }
+
+        if state.picker_visible {
+            let calendar = Calendar {
+                calendar_week: self.calendar_week,
+                highlight_weekends: self.highlight_weekends,
+                week_start: self.week_start,
+                constraints: &self.constraints,
+            };
+            let width = if self.calendar_week { 210.0 } else { 185.0 };
+
+            let InnerResponse {
+                inner: (close, saved),
+                response: area_response,
+            } = picker_area(ui, &button_response, id.with("popup"), width, |ui| {
+                month_arrows(ui, &mut state.year, &mut state.month);
+
+                let date = from_days(state.date).unwrap_or_default();
+                let selection = |day| {
+                    if day == date {
+                        DaySelection::Selected
+                    } else {
+                        DaySelection::None
+                    }
+                };
+                if let Some(day) = calendar.month_ui(ui, state.year, state.month, selection) {
+                    state.date = to_days(day);
+                }
+
+                ui.separator();
+                let mut time = NaiveTime::from_num_seconds_from_midnight_opt(state.seconds, 0)
+                    .unwrap_or_default();
+                ui.horizontal(|ui| {
+                    ui.add(
+                        TimePicker::new(&mut time)
+                            .seconds(self.seconds)
+                            .twelve_hour(self.twelve_hour),
+                    );
+                    if self.show_time_zone {
+                        ui.weak(&offset).on_hover_text("time zone");
+                    }
+                });
+                state.seconds = time.num_seconds_from_midnight();
+
+                // The time may not exist on that day in this time zone,
+                // e.g. when the clocks are set forward:
+                let date = from_days(state.date).unwrap_or_default();
+                let picked = self
+                    .selection
+                    .timezone()
+                    .from_local_datetime(&NaiveDateTime::new(date, time))
+                    .earliest();
+                if picked.is_none() {
+                    ui.colored_label(ui.visuals().error_fg_color, "This time doesn't exist");
+                }
+                // The picker starts at the selection, which may be a date that can't be picked:
+                let picked = picked.filter(|_| self.constraints.allows(date));
+
+                let (mut close, mut saved) = (false, false);
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    if ui
+                        .add_enabled(picked.is_some(), Button::new("Save"))
+                        .clicked()
+                        && let Some(picked) = picked
+                    {
+                        *self.selection = picked;
+                        saved = true;
+                        close = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        close = true;
+                    }
+                });
+                (close, saved)
+            });
+
+            if saved {
+                button_response.mark_changed();
+            }
+            if close || picker_should_close(ui, &button_response, &area_response) {
+                state.picker_visible = false;
+            }
+        }
+        if state != previous_state {
+            ui.data_mut(|data| store_state(data, id, state));
+        }
+
+        button_response
+    }
+}
@@ -1,10 +1,22 @@
 #![expect(clippy::unwrap_used)] // TODO(emilk): avoid unwraps
 
 mod button;
+mod calendar;
+mod datetime;
 mod popup;
+mod range;
+mod time;
 
 pub use button::DatePickerButton;
+pub use datetime::DateTimePickerButton;
+pub use range::DateRangePickerButton;
+pub use time::TimePicker;
+
 use chrono::{Datelike as _, Duration, NaiveDate, Weekday};
+use egui::{Area, Color32, Frame, Id, InnerResponse, Key, Order, Response, Ui, util::IdTypeMap};
+
+#[cfg(feature = "serde")]
+use egui::util::id_type_map::SerializableAny;
 
 #[derive(Debug)]
 struct Week {
@@ -12,20 +24,28 @@ struct Week {
     days: Vec<NaiveDate>,
 }
 
-fn month_data(year: i32, month: u32) -> Vec<Week> {
+/// The weeks of a month, each starting on `week_start`,
+/// including the days of the months before and after that share a week with it.
+fn month_data(year: i32, month: u32, week_start: Weekday) -> Vec<Week> {
     let first = NaiveDate::from_ymd_opt(year, month, 1).expect("Could not create NaiveDate");
+    let week_end = week_start.pred();
     let mut start = first;
-    while start.weekday() != Weekday::Mon {
+    while start.weekday() != week_start {
         start = start.checked_sub_signed(Duration::days(1)).unwrap();
     }
     let mut weeks = vec![];
     let mut week = vec![];
-    while start < first || start.month() == first.month() || start.weekday() != Weekday::Mon {
+    while start < first || start.month() == first.month() || start.weekday() != week_start {
         week.push(start);
 
-        if start.weekday() == Weekday::Sun {
+        if start.weekday() == week_end {
+            // The ISO week is the one its Thursday is in:
+            let thursday = week
+                .iter()
+                .find(|day| day.weekday() == Weekday::Thu)
+                .unwrap();
             weeks.push(Week {
-                number: start.iso_week().week() as u8,
+                number: thursday.iso_week().week() as u8,
                 days: std::mem::take(&mut week),
             });
         }
@@ -34,3 +54,203 @@ fn month_data(year: i32, month: u32) -> Vec<Week> {
 
     weeks
 }
+
+/// The seven days of the week, starting at `week_start`.
+fn weekdays(week_start: Weekday) -> impl Iterator<Item = Weekday> {
+    std::iter::successors(Some(week_start), |day| Some(day.succ())).take(7)
+}
+
+fn weekday_name(day: Weekday) -> &'static str {
+    //TODO(elwerene): Locale
+    match day {
+        Weekday::Mon => "Mo",
+        Weekday::Tue => "Tu",
+        Weekday::Wed => "We",
+        Weekday::Thu => "Th",
+        Weekday::Fri => "Fr",
+        Weekday::Sat => "Sa",
+        Weekday::Sun => "Su",
+    }
+}
+
+fn month_name(i: u32) -> &'static str {
+    match i {
+        1 => "January",
+        2 => "February",
+        3 => "March",
+        4 => "April",
+        5 => "May",
+        6 => "June",
+        7 => "July",
+        8 => "August",
+        9 => "September",
+        10 => "October",
+        11 => "November",
+        12 => "December",
+        _ => panic!("Unknown month: {i}"),
+    }
+}
+
+fn is_weekend(day: NaiveDate) -> bool {
+    matches!(day.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+/// The background of weekend days, when they are highlighted.
+fn weekend_fill(ui: &Ui) -> Color32 {
+    if ui.visuals().dark_mode {
+        Color32::DARK_RED
+    } else {
+        Color32::LIGHT_RED
+    }
+}
+
+/// Dates are persisted as the number of days since the start of the common era,
+/// since [`NaiveDate`] is only serializable with the `serde` feature of `chrono`.
+fn to_days(date: NaiveDate) -> i32 {
+    date.num_days_from_ce()
+}
+
+fn from_days(days: i32) -> Option<NaiveDate> {
+    NaiveDate::from_num_days_from_ce_opt(days)
+}
+
+/// Load the state of a picker.
+///
+/// The state is only persisted with the `serde` feature, since it must be serializable
+/// when `egui/persistence` is enabled. Otherwise it is kept until the app is closed.
+#[cfg(feature = "serde")]
+fn load_state<T: SerializableAny>(data: &mut IdTypeMap, id: Id) -> Option<T> {
+    data.get_persisted(id)
+}
+
+/// Load the state of a picker.
+///
+/// The state is only persisted with the `serde` feature, since it must be serializable
+/// when `egui/persistence` is enabled. Otherwise it is kept until the app is closed.
+#[cfg(not(feature = "serde"))]
+fn load_state<T: 'static + Clone>(data: &mut IdTypeMap, id: Id) -> Option<T> {
+    data.get_temp(id)
+}
+
+/// Store the state of a picker, see [`load_state`].
+#[cfg(feature = "serde")]
+fn store_state<T: SerializableAny>(data: &mut IdTypeMap, id: Id, state: T) {
+    data.insert_persisted(id, state);
+}
+
+/// Store the state of a picker, see [`load_state`].
+#[cfg(not(feature = "serde"))]
+fn store_state<T: 'static + Clone + Send + Sync>(data: &mut IdTypeMap, id: Id, state: T) {
+    data.insert_temp(id, state);
+}
+
+/// Which dates the user may pick.
+#[derive(Default)]
+struct DateConstraints<'a> {
+    min: Option<NaiveDate>,
+    max: Option<NaiveDate>,
+    disabled: Option<Box<dyn Fn(NaiveDate) -> bool + 'a>>,
+}
+
+impl DateConstraints<'_> {
+    fn allows(&self, date: NaiveDate) -> bool {
+        self.min.is_none_or(|min| min <= date)
+            && self.max.is_none_or(|max| date <= max)
+            && !self
+                .disabled
+                .as_ref()
+                .is_some_and(|disabled| disabled(date))
+    }
+}
+
+/// Show the popup of a picker below its button.
+fn picker_area<R>(
+    ui: &Ui,
+    button_response: &Response,
+    id: Id,
+    width: f32,
+    add_contents: impl FnOnce(&mut Ui) -> R,
+) -> InnerResponse<R> {
+    let mut pos = button_response.rect.left_bottom();
+    let width_with_padding = width
+        + ui.style().spacing.item_spacing.x
+        + ui.style().spacing.window_margin.leftf()
+        + ui.style().spacing.window_margin.rightf();
+    if pos.x + width_with_padding > ui.clip_rect().right() {
+        pos.x = button_response.rect.right() - width_with_padding;
+    }
+
+    // Check to make sure the calendar never is displayed out of window
+    pos.x = pos.x.max(ui.style().spacing.window_margin.leftf());
+
+    //TODO(elwerene): Better positioning
+
+    Area::new(id)
+        .kind(egui::UiKind::Picker)
+        .order(Order::Foreground)
+        .fixed_pos(pos)
+        .show(ui.ctx(), |ui| {
+            let frame = Frame::popup(ui.style());
+            frame
+                .show(ui, |ui| {
+                    ui.set_min_width(width);
+                    ui.set_max_width(width);
+                    add_contents(ui)
+                })
+                .inner
+        })
+}
+
+/// Should the popup of a picker close, because the user pressed escape or clicked elsewhere?
+fn picker_should_close(ui: &Ui, button_response: &Response, area_response: &Response) -> bool {
+    // We don't want to close our popup if any other popup is open, since other popups would
+    // most likely be the combo boxes in the date picker.
+    let any_popup_open = egui::Popup::is_any_open(ui.ctx());
+    !button_response.clicked()
+        && !any_popup_open
+        && (ui.input(|i| i.key_pressed(Key::Escape)) || area_response.clicked_elsewhere())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn month_data_week_start() {
+        // March 2024 starts on a Friday and ends on a Sunday.
+        let weeks = month_data(2024, 3, Weekday::Mon);
+        assert_eq!(weeks.len(), 5);
+        assert_eq!(
+            weeks[0].days[0],
+            NaiveDate::from_ymd_opt(2024, 2, 26).unwrap()
+        );
+        assert_eq!(weeks[0].number, 9);
+
+        let weeks = month_data(2024, 3, Weekday::Sun);
+        assert_eq!(weeks.len(), 6);
+        assert_eq!(
+            weeks[0].days[0],
+            NaiveDate::from_ymd_opt(2024, 2, 25).unwrap()
+        );
+        assert_eq!(
+            weeks[5].days[0],
+            NaiveDate::from_ymd_opt(2024, 3, 31).unwrap()
+        );
+        assert!(weeks.iter().all(|week| week.days.len() == 7));
+    }
+
+    #[test]
+    fn date_constraints() {
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let constraints = DateConstraints {
+            min: Some(day(5)),
+            max: Some(day(20)),
+            disabled: Some(Box::new(is_weekend)),
+        };
+        assert!(!constraints.allows(day(4)));
+        assert!(constraints.allows(day(5)));
+        assert!(!constraints.allows(day(9))); // Saturday
+        assert!(constraints.allows(day(20)));
+        assert!(!constraints.allows(day(21)));
+    }
+}
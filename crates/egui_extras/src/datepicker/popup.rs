@@ -1,8 +1,11 @@
 use chrono::{Datelike as _, NaiveDate, Weekday};
 
-use egui::{Align, Button, Color32, ComboBox, Direction, Id, Layout, RichText, Ui, Vec2};
+use egui::{Align, Button, ComboBox, Direction, Id, Layout, RichText, Ui, Vec2};
 
-use super::{button::DatePickerButtonState, month_data};
+use super::{
+    button::DatePickerButtonState, is_weekend, load_state, month_data, month_name, store_state,
+    weekday_name, weekdays, weekend_fill,
+};
 
 use crate::{Column, Size, StripBuilder, TableBuilder};
 
@@ -35,6 +38,7 @@ pub(crate) struct DatePickerPopup<'a> {
     pub calendar: bool,
     pub calendar_week: bool,
     pub highlight_weekends: bool,
+    pub week_start: Weekday,
     pub start_end_years: Option<std::ops::RangeInclusive<i32>>,
 }
 
@@ -44,17 +48,17 @@ impl DatePickerPopup<'_> {
         let id = ui.make_persistent_id("date_picker");
         let today = chrono::offset::Utc::now().date_naive();
         let mut popup_state = ui
-            .data_mut(|data| data.get_persisted::<DatePickerPopupState>(id))
+            .data_mut(|data| load_state::<DatePickerPopupState>(data, id))
             .unwrap_or_default();
         if !popup_state.setup {
             popup_state.year = self.selection.year();
             popup_state.month = self.selection.month();
             popup_state.day = self.selection.day();
             popup_state.setup = true;
-            ui.data_mut(|data| data.insert_persisted(id, popup_state.clone()));
+            ui.data_mut(|data| store_state(data, id, popup_state.clone()));
         }
 
-        let weeks = month_data(popup_state.year, popup_state.month);
+        let weeks = month_data(popup_state.year, popup_state.month, self.week_start);
         let (mut close, mut saved) = (false, false);
         let height = 20.0;
         let spacing = 2.0;
@@ -102,8 +106,11 @@ impl DatePickerPopup<'_> {
                                                     .day
                                                     .min(popup_state.last_day_of_month());
                                                 ui.memory_mut(|mem| {
-                                                    mem.data
-                                                        .insert_persisted(id, popup_state.clone());
+                                                    store_state(
+                                                        &mut mem.data,
+                                                        id,
+                                                        popup_state.clone(),
+                                                    );
                                                 });
                                             }
                                         }
@@ -126,8 +133,11 @@ impl DatePickerPopup<'_> {
                                                     .day
                                                     .min(popup_state.last_day_of_month());
                                                 ui.memory_mut(|mem| {
-                                                    mem.data
-                                                        .insert_persisted(id, popup_state.clone());
+                                                    store_state(
+                                                        &mut mem.data,
+                                                        id,
+                                                        popup_state.clone(),
+                                                    );
                                                 });
                                             }
                                         }
@@ -147,8 +157,11 @@ impl DatePickerPopup<'_> {
                                                 .changed()
                                             {
                                                 ui.memory_mut(|mem| {
-                                                    mem.data
-                                                        .insert_persisted(id, popup_state.clone());
+                                                    store_state(
+                                                        &mut mem.data,
+                                                        id,
+                                                        popup_state.clone(),
+                                                    );
                                                 });
                                             }
                                         }
@@ -172,7 +185,7 @@ impl DatePickerPopup<'_> {
                                         popup_state.day =
                                             popup_state.day.min(popup_state.last_day_of_month());
                                         ui.data_mut(|data| {
-                                            data.insert_persisted(id, popup_state.clone());
+                                            store_state(data, id, popup_state.clone());
                                         });
                                     }
                                 });
@@ -192,7 +205,7 @@ impl DatePickerPopup<'_> {
                                         popup_state.day =
                                             popup_state.day.min(popup_state.last_day_of_month());
                                         ui.data_mut(|data| {
-                                            data.insert_persisted(id, popup_state.clone());
+                                            store_state(data, id, popup_state.clone());
                                         });
                                     }
                                 });
@@ -210,7 +223,7 @@ impl DatePickerPopup<'_> {
                                             popup_state.day = popup_state.last_day_of_month();
                                         }
                                         ui.data_mut(|data| {
-                                            data.insert_persisted(id, popup_state.clone());
+                                            store_state(data, id, popup_state.clone());
                                         });
                                     }
                                 });
@@ -228,7 +241,7 @@ impl DatePickerPopup<'_> {
                                             }
                                         }
                                         ui.data_mut(|data| {
-                                            data.insert_persisted(id, popup_state.clone());
+                                            store_state(data, id, popup_state.clone());
                                         });
                                     }
                                 });
@@ -244,7 +257,7 @@ impl DatePickerPopup<'_> {
                                         popup_state.day =
                                             popup_state.day.min(popup_state.last_day_of_month());
                                         ui.data_mut(|data| {
-                                            data.insert_persisted(id, popup_state.clone());
+                                            store_state(data, id, popup_state.clone());
                                         });
                                     }
                                 });
@@ -256,7 +269,7 @@ impl DatePickerPopup<'_> {
                                         popup_state.day =
                                             popup_state.day.min(popup_state.last_day_of_month());
                                         ui.data_mut(|data| {
-                                            data.insert_persisted(id, popup_state.clone());
+                                            store_state(data, id, popup_state.clone());
                                         });
                                     }
                                 });
@@ -283,13 +296,12 @@ impl DatePickerPopup<'_> {
                                     });
                                 }
 
-                                //TODO(elwerene): Locale
-                                for name in ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"] {
+                                for day in weekdays(self.week_start) {
                                     header.col(|ui| {
                                         ui.with_layout(
                                             Layout::centered_and_justified(Direction::TopDown),
                                             |ui| {
-                                                ui.label(name);
+                                                ui.label(weekday_name(day));
                                             },
                                         );
                                     });
@@ -314,15 +326,10 @@ impl DatePickerPopup<'_> {
                                                             && popup_state.day == day.day()
                                                         {
                                                             ui.visuals().selection.bg_fill
-                                                        } else if (day.weekday() == Weekday::Sat
-                                                            || day.weekday() == Weekday::Sun)
+                                                        } else if is_weekend(day)
                                                             && self.highlight_weekends
                                                         {
-                                                            if ui.visuals().dark_mode {
-                                                                Color32::DARK_RED
-                                                            } else {
-                                                                Color32::LIGHT_RED
-                                                            }
+                                                            weekend_fill(ui)
                                                         } else {
                                                             ui.visuals().extreme_bg_color
                                                         };
@@ -367,7 +374,8 @@ impl DatePickerPopup<'_> {
                                                             popup_state.month = day.month();
                                                             popup_state.day = day.day();
                                                             ui.data_mut(|data| {
-                                                                data.insert_persisted(
+                                                                store_state(
+                                                                    data,
                                                                     id,
                                                                     popup_state.clone(),
                                                                 );
@@ -414,30 +422,14 @@ impl DatePickerPopup<'_> {
         if close {
             popup_state.setup = false;
             ui.data_mut(|data| {
-                data.insert_persisted(id, popup_state);
-                data.get_persisted_mut_or_default::<DatePickerButtonState>(self.button_id)
-                    .picker_visible = false;
+                store_state(data, id, popup_state);
+                let mut button_state =
+                    load_state::<DatePickerButtonState>(data, self.button_id).unwrap_or_default();
+                button_state.picker_visible = false;
+                store_state(data, self.button_id, button_state);
             });
         }
 
         saved && close
     }
 }
-
-fn month_name(i: u32) -> &'static str {
-    match i {
-        1 => "January",
-        2 => "February",
-        3 => "March",
-        4 => "April",
-        5 => "May",
-        6 => "June",
-        7 => "July",
-        8 => "August",
-        9 => "September",
-        10 => "October",
-        11 => "November",
-        12 => "December",
-        _ => panic!("Unknown month: {i}"),
-    }
-}
@@ -0,0 +1,266 @@
+use std::ops::RangeInclusive;
+
+use chrono::{Datelike as _, NaiveDate, Weekday};
+use egui::{Align, Button, InnerResponse, Layout, RichText, Sense, Ui, Vec2, Widget};
+
+use super::{
+    DateConstraints,
+    calendar::{Calendar, DaySelection, month_arrows, next_month},
+    from_days, load_state, picker_area, picker_should_close, store_state, to_days,
+};
+
+#[derive(Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+struct DateRangePickerState {
+    picker_visible: bool,
+
+    /// The first of the two months shown.
+    year: i32,
+    month: u32,
+
+    /// The range being picked, see [`to_days`].
+    start: Option<i32>,
+    end: Option<i32>,
+}
+
+/// Shows a range of dates, and will open a picker with two months when clicked.
+///
+/// Click the first and last day of the range, then `Save`.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// # let today = chrono::NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+/// # let mut range = today..=today;
+/// ui.add(egui_extras::DateRangePickerButton::new(&mut range).max_date(today));
+/// # });
+/// ```
+pub struct DateRangePickerButton<'a> {
+    selection: &'a mut RangeInclusive<NaiveDate>,
+    id_salt: Option<&'a str>,
+    calendar_week: bool,
+    show_icon: bool,
+    format: String,
+    highlight_weekends: bool,
+    week_start: Weekday,
+    constraints: DateConstraints<'a>,
+}
+
+impl<'a> DateRangePickerButton<'a> {
+    /// Show the given range of dates, which is replaced when the user saves a new one.
+    pub fn new(selection: &'a mut RangeInclusive<NaiveDate>) -> Self {
+        Self {
+            selection,
+            id_salt: None,
+            calendar_week: true,
+            show_icon: true,
+            format: "%Y-%m-%d".to_owned(),
+            highlight_weekends: true,
+            week_start: Weekday::Mon,
+            constraints: DateConstraints::default(),
+        }
+    }
+
+    /// Add id source.
+    /// Must be set if multiple date range picker buttons are in the same Ui.
+    #[inline]
+    pub fn id_salt(mut self, id_salt: &'a str) -> Self {
+        self.id_salt = Some(id_salt);
+        self
+    }
+
+    /// Show calendar week in date picker popup. (Default: true)
+    #[inline]
+    pub fn calendar_week(mut self, week: bool) -> Self {
+        self.calendar_week = week;
+        self
+    }
+
+    /// Show the calendar icon on the button. (Default: true)
+    #[inline]
+    pub fn show_icon(mut self, show_icon: bool) -> Self {
+        self.show_icon = show_icon;
+        self
+    }
+
+    /// Change the format of the dates shown on the button. (Default: %Y-%m-%d)
+    /// See [`chrono::format::strftime`] for valid formats.
+    #[inline]
+    pub fn format(mut self, format: impl Into<String>) -> Self {
+        self.format = format.into();
+        self
+    }
+
+    /// Highlight weekend days. (Default: true)
+    #[inline]
+    pub fn highlight_weekends(mut self, highlight_weekends: bool) -> Self {
+        self.highlight_weekends = highlight_weekends;
+        self
+    }
+
+    /// The first day of each week in the calendar. (Default: Monday)
+    #[inline]
+    pub fn week_start(mut self, week_start: Weekday) -> Self {
+        self.week_start = week_start;
+        self
+    }
+
+    /// The first date that can be picked.
+    #[inline]
+    pub fn min_date(mut self, min_date: NaiveDate) -> Self {
+        self.constraints.min = Some(min_date);
+        self
+    }
+
+    /// The last date that can be picked.
+    #[inline]
+    pub fn max_date(mut self, max_date: NaiveDate) -> Self {
+        self.constraints.max = Some(max_date);
+        self
+    }
+
+    /// Dates for which this returns `true` can't be picked as the start or end of the range,
+    /// e.g. holidays.
+    #[inline]
+    pub fn disabled_dates(mut self, disabled: impl Fn(NaiveDate) -> bool + 'a) -> Self {
+        self.constraints.disabled = Some(Box::new(disabled));
+        self
+    }
+}
+
+impl Widget for DateRangePickerButton<'_> {
+    fn ui(self, ui: &mut Ui) -> egui::Response {
+        let id = ui.make_persistent_id(("date_range_picker", self.id_salt));
+        let previous_state = ui
+            .data_mut(|data| load_state::<DateRangePickerState>(data, id))
+            .unwrap_or_default();
+        let mut state = previous_state.clone();
+
+        let (start, end) = (*self.selection.start(), *self.selection.end());
+        let mut text = format!(
+            "{} – {}",
+            start.format(&self.format),
+            end.format(&self.format)
+        );
+        if self.show_icon {
+            text += " 📆";
+        }
+        let mut text = RichText::new(text);
+        let visuals = ui.visuals().widgets.open;
+        if state.picker_visible {
+            text = text.color(visuals.text_color());
+        }
+        let mut button = Button::new(text);
+        if state.picker_visible {
+            button = button.fill(visuals.weak_bg_fill).stroke(visuals.bg_stroke);
+        }
+        let mut button_response = ui.add(button);
+        if button_response.clicked() {
+            state = DateRangePickerState {
+                picker_visible: true,
+                year: start.year(),
+                month: start.month(),
+                start: Some(to_days(start)),
+                end: Some(to_days(end)),
+            };
+        }
+
+        if state.picker_visible {
+            let calendar = Calendar {
+                calendar_week: self.calendar_week,
+                highlight_weekends: self.highlight_weekends,
+                week_start: self.week_start,
+                constraints: &self.constraints,
+            };
+            let width = if self.calendar_week { 420.0 } else { 370.0 };
+
+            let InnerResponse {
+                inner: (close, saved),
+                response: area_response,
+            } = picker_area(ui, &button_response, id.with("popup"), width, |ui| {
+                month_arrows(ui, &mut state.year, &mut state.month);
+
+                let pending_start = state.start.and_then(from_days);
+                let pending_end = state.end.and_then(from_days);
+                let selection = |day: NaiveDate| match (pending_start, pending_end) {
+                    (Some(start), _) if day == start => DaySelection::Selected,
+                    (_, Some(end)) if day == end => DaySelection::Selected,
+                    (Some(start), Some(end)) if start < day && day < end => DaySelection::InRange,
+                    _ => DaySelection::None,
+                };
+
+                let (next_year, next_month) = next_month(state.year, state.month);
+                let clicked = ui
+                    .horizontal_top(|ui| {
+                        let first = calendar.month_ui(ui, state.year, state.month, selection);
+                        // A separator would take all the height of the popup,
+                        // so draw the line between the months once they are shown:
+                        let (gap, _) = ui.allocate_exact_size(
+                            Vec2::new(ui.spacing().item_spacing.x, 0.0),
+                            Sense::hover(),
+                        );
+                        let second = calendar.month_ui(ui, next_year, next_month, selection);
+                        ui.painter().vline(
+                            gap.center().x,
+                            ui.min_rect().y_range(),
+                            ui.visuals().widgets.noninteractive.bg_stroke,
+                        );
+                        first.or(second)
+                    })
+                    .inner;
+
+                if let Some(day) = clicked {
+                    match (pending_start, pending_end) {
+                        (Some(start), None) => {
+                            let (start, end) = if day < start {
+                                (day, start)
+                            } else {
+                                (start, day)
+                            };
+                            state.start = Some(to_days(start));
+                            state.end = Some(to_days(end));
+                        }
+                        _ => {
+                            state.start = Some(to_days(day));
+                            state.end = None;
+                        }
+                    }
+                }
+
+                let (mut close, mut saved) = (false, false);
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    // The picker starts at the selection, whose days may not be allowed:
+                    let range = pending_start.zip(pending_end).filter(|&(start, end)| {
+                        clicked.is_none()
+                            && self.constraints.allows(start)
+                            && self.constraints.allows(end)
+                    });
+                    if ui
+                        .add_enabled(range.is_some(), Button::new("Save"))
+                        .clicked()
+                        && let Some((start, end)) = range
+                    {
+                        *self.selection = start..=end;
+                        saved = true;
+                        close = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        close = true;
+                    }
+                });
+                (close, saved)
+            });
+
+            if saved {
+                button_response.mark_changed();
+            }
+            if close || picker_should_close(ui, &button_response, &area_response) {
+                state.picker_visible = false;
+            }
+        }
+        if state != previous_state {
+            ui.data_mut(|data| store_state(data, id, state));
+        }
+
+        button_response
+    }
+}
@@ -0,0 +1,112 @@
+use chrono::{NaiveTime, Timelike as _};
+use egui::{DragValue, Response, Ui, Widget};
+
+/// Edit a time of day, with a field each for the hours, minutes and (optionally) seconds.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// # let mut time = chrono::NaiveTime::from_hms_opt(13, 37, 0).unwrap();
+/// ui.add(egui_extras::TimePicker::new(&mut time).twelve_hour(true));
+/// # });
+/// ```
+pub struct TimePicker<'a> {
+    time: &'a mut NaiveTime,
+    seconds: bool,
+    twelve_hour: bool,
+}
+
+impl<'a> TimePicker<'a> {
+    /// Edit the given time, in hours and minutes of a 24 hour clock.
+    pub fn new(time: &'a mut NaiveTime) -> Self {
+        Self {
+            time,
+            seconds: false,
+            twelve_hour: false,
+        }
+    }
+
+    /// Show a field for the seconds. (Default: false)
+    ///
+    /// Without it, the seconds of the time are left as they are.
+    #[inline]
+    pub fn seconds(mut self, seconds: bool) -> Self {
+        self.seconds = seconds;
+        self
+    }
+
+    /// Show the hours from 1 to 12, with a toggle for AM and PM. (Default: false)
+    #[inline]
+    pub fn twelve_hour(mut self, twelve_hour: bool) -> Self {
+        self.twelve_hour = twelve_hour;
+        self
+    }
+}
+
+impl Widget for TimePicker<'_> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let (mut hour, mut minute, mut second) =
+            (self.time.hour(), self.time.minute(), self.time.second());
+        let mut pm = 12 <= hour;
+        let two_digits = |n: f64, _| format!("{n:02}");
+
+        let mut response = ui
+            .horizontal(|ui| {
+                ui.spacing_mut().item_spacing.x = 2.0;
+                if self.twelve_hour {
+                    let mut hour12 = if hour % 12 == 0 { 12 } else { hour % 12 };
+                    ui.add(DragValue::new(&mut hour12).range(1..=12).speed(0.1))
+                        .on_hover_text("hour");
+                    hour = hour12 % 12 + if pm { 12 } else { 0 };
+                } else {
+                    ui.add(
+                        DragValue::new(&mut hour)
+                            .range(0..=23)
+                            .speed(0.1)
+                            .custom_formatter(two_digits),
+                    )
+                    .on_hover_text("hour");
+                }
+                ui.label(":");
+                ui.add(
+                    DragValue::new(&mut minute)
+                        .range(0..=59)
+                        .speed(0.1)
+                        .custom_formatter(two_digits),
+                )
+                .on_hover_text("minute");
+                if self.seconds {
+                    ui.label(":");
+                    ui.add(
+                        DragValue::new(&mut second)
+                            .range(0..=59)
+                            .speed(0.1)
+                            .custom_formatter(two_digits),
+                    )
+                    .on_hover_text("second");
+                }
+                if self.twelve_hour {
+                    ui.add_space(4.0);
+                    let am = ui.selectable_label(!pm, "AM").clicked();
+                    let toggled_pm = ui.selectable_label(pm, "PM").clicked();
+                    if (am && pm) || (toggled_pm && !pm) {
+                        pm = !pm;
+                        hour = (hour + 12) % 24;
+                    }
+                }
+            })
+            .response;
+
+        // Keep the fraction of a second:
+        let time = self
+            .time
+            .with_hour(hour)
+            .and_then(|time| time.with_minute(minute));
+        if let Some(time) = time.and_then(|time| time.with_second(second))
+            && time != *self.time
+        {
+            *self.time = time;
+            response.mark_changed();
+        }
+        response
+    }
+}
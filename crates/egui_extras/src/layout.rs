@@ -167,7 +167,7 @@ impl<'l> StripLayout<'l> {
         } else if flags.fixed_columns_width > 0.0 {
             // Non-fixed columns: clip to avoid drawing over fixed columns
             let mut clip = self.ui.clip_rect();
-            clip.min.x = clip.min.x + flags.fixed_columns_width;
+            clip.min.x += flags.fixed_columns_width;
             (gapless_rect, self.ui.painter().with_clip_rect(clip))
         } else {
             (gapless_rect, self.ui.painter().clone())
@@ -296,7 +296,7 @@ impl<'l> StripLayout<'l> {
         } else if flags.fixed_columns_width > 0.0 {
             // Non-fixed columns: clip to exclude fixed column region
             let mut clip = self.ui.clip_rect();
-            clip.min.x = clip.min.x + flags.fixed_columns_width;
+            clip.min.x += flags.fixed_columns_width;
             child_ui.shrink_clip_rect(clip);
         }
 
@@ -11,6 +11,9 @@
 #[cfg(feature = "chrono")]
 mod datepicker;
 
+#[cfg(feature = "markdown")]
+mod markdown;
+
 pub mod syntax_highlighting;
 
 #[doc(hidden)]
@@ -23,7 +26,12 @@ mod table;
 pub mod row_resize;
 
 #[cfg(feature = "chrono")]
-pub use crate::datepicker::DatePickerButton;
+pub use crate::datepicker::{
+    DatePickerButton, DateRangePickerButton, DateTimePickerButton, TimePicker,
+};
+
+#[cfg(feature = "markdown")]
+pub use crate::markdown::Markdown;
 
 pub(crate) use crate::layout::StripLayout;
 pub use crate::sizing::Size;
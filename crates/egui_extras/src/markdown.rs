@@ -0,0 +1,641 @@
+use std::sync::Arc;
+
+use egui::{
+    Align, Checkbox, FontId, Hyperlink, Id, Image, Label, Layout, Response, Stroke, TextFormat,
+    TextStyle, TextWrapMode, Ui, Widget, text::LayoutJob,
+};
+use pulldown_cmark::{Alignment, CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+
+use crate::syntax_highlighting::{self, CodeTheme};
+use crate::{Column, TableBuilder};
+
+/// Shows [CommonMark](https://commonmark.org/) Markdown.
+///
+/// Tables, strikethrough and task lists are supported as well.
+///
+/// Code blocks are highlighted with [`syntax_highlighting::highlight`],
+/// tables are laid out with [`TableBuilder`],
+/// and images are shown with [`egui::Image`], so remember to install image loaders,
+/// e.g. with [`crate::install_image_loaders`].
+///
+/// All text can be selected, also across paragraphs.
+///
+/// The parsed document is cached, so you can call this every frame.
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// ui.add(egui_extras::Markdown::new(
+///     "# Release notes\n\
+///      * Added **bold** features\n\
+///      * Fixed `bugs`",
+/// ));
+/// # });
+/// ```
+pub struct Markdown<'a> {
+    source: &'a str,
+    id_salt: Option<Id>,
+    selectable: bool,
+    code_theme: Option<CodeTheme>,
+}
+
+impl<'a> Markdown<'a> {
+    /// Render the given CommonMark source.
+    pub fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            id_salt: None,
+            selectable: true,
+            code_theme: None,
+        }
+    }
+
+    /// Used to give the tables of the document unique ids.
+    ///
+    /// Must be set if several documents with tables are shown in the same [`Ui`].
+    #[inline]
+    pub fn id_salt(mut self, id_salt: impl std::hash::Hash) -> Self {
+        self.id_salt = Some(Id::new(id_salt));
+        self
+    }
+
+    /// Can the text be selected? (Default: true)
+    #[inline]
+    pub fn selectable(mut self, selectable: bool) -> Self {
+        self.selectable = selectable;
+        self
+    }
+
+    /// The theme used for code blocks.
+    ///
+    /// By default [`CodeTheme::from_memory`] is used.
+    #[inline]
+    pub fn code_theme(mut self, code_theme: CodeTheme) -> Self {
+        self.code_theme = Some(code_theme);
+        self
+    }
+}
+
+impl Widget for Markdown<'_> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let Self {
+            source,
+            id_salt,
+            selectable,
+            code_theme,
+        } = self;
+
+        let events = ui
+            .ctx()
+            .memory_mut(|mem| mem.caches.cache::<ParseCache>().get(source).clone());
+
+        let code_theme = code_theme.unwrap_or_else(|| CodeTheme::from_memory(ui.ctx(), ui.style()));
+
+        let id = id_salt.map_or_else(|| ui.next_auto_id(), |salt| ui.make_persistent_id(salt));
+
+        let mut renderer = Renderer {
+            id,
+            selectable,
+            code_theme: &code_theme,
+            num_blocks: 0,
+        };
+
+        ui.vertical(|ui| renderer.blocks(ui, &mut events.iter()))
+            .response
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[derive(Default)]
+struct MarkdownParser;
+
+impl egui::cache::ComputerMut<&str, Arc<[Event<'static>]>> for MarkdownParser {
+    fn compute(&mut self, source: &str) -> Arc<[Event<'static>]> {
+        profiling::function_scope!();
+        let options = Options::ENABLE_TABLES
+            | Options::ENABLE_STRIKETHROUGH
+            | Options::ENABLE_TASKLISTS
+            | Options::ENABLE_SMART_PUNCTUATION;
+        Parser::new_ext(source, options)
+            .map(Event::into_static)
+            .collect()
+    }
+}
+
+type ParseCache = egui::cache::FrameCache<Arc<[Event<'static>]>, MarkdownParser>;
+
+type Events<'e> = std::slice::Iter<'e, Event<'static>>;
+
+/// Is this event part of the text of a paragraph, heading, table cell, …?
+fn is_inline(event: &Event<'_>) -> bool {
+    match event {
+        Event::Text(_)
+        | Event::Code(_)
+        | Event::InlineMath(_)
+        | Event::DisplayMath(_)
+        | Event::InlineHtml(_)
+        | Event::FootnoteReference(_)
+        | Event::SoftBreak
+        | Event::HardBreak
+        | Event::TaskListMarker(_) => true,
+
+        Event::Start(
+            Tag::Emphasis
+            | Tag::Strong
+            | Tag::Strikethrough
+            | Tag::Superscript
+            | Tag::Subscript
+            | Tag::Link { .. }
+            | Tag::Image { .. },
+        )
+        | Event::End(
+            TagEnd::Emphasis
+            | TagEnd::Strong
+            | TagEnd::Strikethrough
+            | TagEnd::Superscript
+            | TagEnd::Subscript
+            | TagEnd::Link
+            | TagEnd::Image,
+        ) => true,
+
+        Event::Start(_) | Event::End(_) | Event::Html(_) | Event::Rule => false,
+    }
+}
+
+/// The nesting of inline styles at some point in the text.
+#[derive(Clone, Copy, Default)]
+struct InlineStyle {
+    heading: Option<HeadingLevel>,
+    strong: u32,
+    emphasis: u32,
+    strikethrough: u32,
+    small: u32,
+    code: bool,
+    link: bool,
+}
+
+struct Renderer<'t> {
+    id: Id,
+    selectable: bool,
+    code_theme: &'t CodeTheme,
+
+    /// Used to give each table a unique id.
+    num_blocks: usize,
+}
+
+impl Renderer<'_> {
+    fn next_id(&mut self) -> Id {
+        self.num_blocks += 1;
+        self.id.with(self.num_blocks)
+    }
+
+    /// Show blocks until the end of the enclosing block (or document).
+    fn blocks(&mut self, ui: &mut Ui, events: &mut Events<'_>) {
+        while let Some(event) = events.as_slice().first() {
+            if is_inline(event) {
+                // e.g. the text of a tight list item, which has no paragraph
+                self.inline_block(ui, events, InlineStyle::default());
+                continue;
+            }
+
+            events.next();
+            match event {
+                Event::End(_) => return,
+                Event::Start(tag) => self.block(ui, tag, events),
+                Event::Rule => {
+                    ui.separator();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Show the block started by `tag`, up to and including its end.
+    fn block(&mut self, ui: &mut Ui, tag: &Tag<'_>, events: &mut Events<'_>) {
+        match tag {
+            Tag::Paragraph => {
+                self.inline_block(ui, events, InlineStyle::default());
+                events.next(); // TagEnd::Paragraph
+                self.block_spacing(ui);
+            }
+            Tag::Heading { level, .. } => {
+                let style = InlineStyle {
+                    heading: Some(*level),
+                    ..Default::default()
+                };
+                self.inline_block(ui, events, style);
+                events.next(); // TagEnd::Heading
+                if matches!(level, HeadingLevel::H1 | HeadingLevel::H2) {
+                    ui.separator();
+                }
+                self.block_spacing(ui);
+            }
+            Tag::BlockQuote(_) => {
+                let id = self.next_id();
+                let rect = ui.indent(id, |ui| self.blocks(ui, events)).response.rect;
+                let x = rect.left() - 0.5 * ui.spacing().indent;
+                ui.painter().vline(
+                    x,
+                    rect.y_range(),
+                    Stroke::new(2.0, ui.visuals().weak_text_color()),
+                );
+            }
+            Tag::CodeBlock(kind) => {
+                self.code_block(ui, kind, events);
+                self.block_spacing(ui);
+            }
+            Tag::HtmlBlock => {
+                let mut html = String::new();
+                for event in events.by_ref() {
+                    match event {
+                        Event::Html(text) | Event::Text(text) => html.push_str(text),
+                        Event::End(_) => break,
+                        _ => {}
+                    }
+                }
+                let text = egui::RichText::new(html.trim_end()).monospace().weak();
+                ui.add(Label::new(text).selectable(self.selectable));
+            }
+            Tag::List(first_number) => {
+                self.list(ui, *first_number, events);
+                self.block_spacing(ui);
+            }
+            Tag::Table(alignments) => {
+                self.table(ui, alignments, events);
+                self.block_spacing(ui);
+            }
+            _ => {
+                // Footnote definitions and the like: just show what's inside.
+                self.blocks(ui, events);
+            }
+        }
+    }
+
+    fn block_spacing(&self, ui: &mut Ui) {
+        ui.add_space(0.5 * ui.text_style_height(&TextStyle::Body));
+    }
+
+    /// A wrapping paragraph of inline content.
+    fn inline_block(&self, ui: &mut Ui, events: &mut Events<'_>, style: InlineStyle) {
+        ui.horizontal_wrapped(|ui| {
+            ui.spacing_mut().item_spacing.x = 0.0;
+            self.inlines(ui, events, style);
+        });
+    }
+
+    /// Show inline content until the next non-inline event.
+    ///
+    /// Consecutive text is laid out together as one [`LayoutJob`],
+    /// so that it wraps nicely and can be selected as a whole.
+    fn inlines(&self, ui: &mut Ui, events: &mut Events<'_>, mut style: InlineStyle) {
+        let mut job = LayoutJob::default();
+        let mut link_url: Option<&str> = None;
+
+        while let Some(event) = events.as_slice().first() {
+            if !is_inline(event) {
+                break;
+            }
+            events.next();
+
+            match event {
+                Event::Text(text) => job.append(text, 0.0, self.text_format(ui, style)),
+                Event::Code(text)
+                | Event::InlineMath(text)
+                | Event::DisplayMath(text)
+                | Event::InlineHtml(text) => {
+                    let code_style = InlineStyle {
+                        code: true,
+                        ..style
+                    };
+                    job.append(text, 0.0, self.text_format(ui, code_style));
+                }
+                Event::FootnoteReference(label) => {
+                    let small = InlineStyle {
+                        small: style.small + 1,
+                        ..style
+                    };
+                    job.append(&format!("[{label}]"), 0.0, self.text_format(ui, small));
+                }
+                Event::SoftBreak => job.append(" ", 0.0, self.text_format(ui, style)),
+                Event::HardBreak => job.append("\n", 0.0, self.text_format(ui, style)),
+                Event::TaskListMarker(checked) => {
+                    self.flush_text(ui, &mut job);
+                    let mut checked = *checked;
+                    ui.add_enabled(false, Checkbox::without_text(&mut checked));
+                    ui.add_space(ui.spacing().item_spacing.y);
+                }
+
+                Event::Start(Tag::Emphasis) => style.emphasis += 1,
+                Event::End(TagEnd::Emphasis) => style.emphasis = style.emphasis.saturating_sub(1),
+                Event::Start(Tag::Strong) => style.strong += 1,
+                Event::End(TagEnd::Strong) => style.strong = style.strong.saturating_sub(1),
+                Event::Start(Tag::Strikethrough) => style.strikethrough += 1,
+                Event::End(TagEnd::Strikethrough) => {
+                    style.strikethrough = style.strikethrough.saturating_sub(1);
+                }
+                Event::Start(Tag::Superscript | Tag::Subscript) => style.small += 1,
+                Event::End(TagEnd::Superscript | TagEnd::Subscript) => {
+                    style.small = style.small.saturating_sub(1);
+                }
+
+                Event::Start(Tag::Link { dest_url, .. }) => {
+                    self.flush_text(ui, &mut job);
+                    link_url = Some(dest_url);
+                    style.link = true;
+                }
+                Event::End(TagEnd::Link) => {
+                    if let Some(url) = link_url.take() {
+                        let text = std::mem::take(&mut job);
+                        ui.add(Hyperlink::from_label_and_url(text, url));
+                    }
+                    style.link = false;
+                }
+
+                Event::Start(Tag::Image {
+                    dest_url, title, ..
+                }) => {
+                    self.flush_text(ui, &mut job);
+
+                    let mut alt_text = String::new();
+                    for event in events.by_ref() {
+                        match event {
+                            Event::Text(text) | Event::Code(text) => alt_text.push_str(text),
+                            Event::End(TagEnd::Image) => break,
+                            _ => {}
+                        }
+                    }
+
+                    let image = Image::new(dest_url.to_string())
+                        .max_width(ui.available_width())
+                        .alt_text(alt_text);
+                    let response = ui.add(image);
+                    if !title.is_empty() {
+                        response.on_hover_text(title.to_string());
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        if let Some(url) = link_url {
+            // Unterminated link
+            ui.add(Hyperlink::from_label_and_url(job, url));
+        } else {
+            self.flush_text(ui, &mut job);
+        }
+    }
+
+    fn flush_text(&self, ui: &mut Ui, job: &mut LayoutJob) {
+        if !job.is_empty() {
+            let job = std::mem::take(job);
+            ui.add(Label::new(job).selectable(self.selectable));
+        }
+    }
+
+    fn text_format(&self, ui: &Ui, style: InlineStyle) -> TextFormat {
+        let visuals = ui.visuals();
+        let body = TextStyle::Body.resolve(ui.style());
+
+        let mut font_id = if style.code {
+            TextStyle::Monospace.resolve(ui.style())
+        } else if let Some(level) = style.heading {
+            let heading = TextStyle::Heading.resolve(ui.style());
+            let size = match level {
+                HeadingLevel::H1 => 1.3 * heading.size,
+                HeadingLevel::H2 => heading.size,
+                HeadingLevel::H3 => egui::lerp(body.size..=heading.size, 0.5),
+                HeadingLevel::H4 => 1.1 * body.size,
+                HeadingLevel::H5 | HeadingLevel::H6 => body.size,
+            };
+            FontId::new(size, heading.family)
+        } else {
+            body
+        };
+        font_id.size *= 0.75_f32.powi(style.small as i32);
+
+        let color = if style.link {
+            visuals.hyperlink_color
+        } else if style.heading == Some(HeadingLevel::H6) {
+            visuals.weak_text_color()
+        } else if 0 < style.strong || style.heading.is_some() {
+            visuals.strong_text_color()
+        } else {
+            visuals.text_color()
+        };
+
+        TextFormat {
+            font_id,
+            color,
+            background: if style.code {
+                visuals.code_bg_color
+            } else {
+                Default::default()
+            },
+            italics: 0 < style.emphasis,
+            strikethrough: if 0 < style.strikethrough {
+                Stroke::new(1.0, color)
+            } else {
+                Stroke::NONE
+            },
+            valign: if 0 < style.small {
+                Align::TOP
+            } else {
+                Align::BOTTOM
+            },
+            ..Default::default()
+        }
+    }
+
+    fn code_block(&self, ui: &mut Ui, kind: &CodeBlockKind<'_>, events: &mut Events<'_>) {
+        let mut code = String::new();
+        for event in events.by_ref() {
+            match event {
+                Event::Text(text) => code.push_str(text),
+                Event::End(_) => break,
+                _ => {}
+            }
+        }
+        if code.ends_with('\n') {
+            code.pop();
+        }
+
+        let language = match kind {
+            CodeBlockKind::Fenced(info) => info.split_whitespace().next().unwrap_or_default(),
+            CodeBlockKind::Indented => "",
+        };
+
+        let job =
+            syntax_highlighting::highlight(ui.ctx(), ui.style(), self.code_theme, &code, language);
+
+        egui::Frame::NONE
+            .fill(ui.visuals().code_bg_color)
+            .corner_radius(ui.visuals().widgets.noninteractive.corner_radius)
+            .inner_margin(ui.spacing().button_padding)
+            .show(ui, |ui| {
+                ui.set_min_width(ui.available_width());
+                ui.add(Label::new(job).selectable(self.selectable));
+            });
+    }
+
+    fn list(&mut self, ui: &mut Ui, first_number: Option<u64>, events: &mut Events<'_>) {
+        let mut number = first_number;
+
+        while let Some(event) = events.next() {
+            match event {
+                Event::Start(Tag::Item) => {
+                    let marker = match &mut number {
+                        Some(number) => {
+                            *number += 1;
+                            format!("{}.", *number - 1)
+                        }
+                        None => "•".to_owned(),
+                    };
+
+                    ui.horizontal_top(|ui| {
+                        ui.label(marker);
+                        ui.vertical(|ui| self.blocks(ui, events));
+                    });
+                }
+                Event::End(_) => break,
+                _ => {}
+            }
+        }
+    }
+
+    fn table(&mut self, ui: &mut Ui, alignments: &[Alignment], events: &mut Events<'_>) {
+        // TableBuilder wants the header and body as separate closures, so collect the cells first:
+        let mut header: Vec<&[Event<'static>]> = vec![];
+        let mut rows: Vec<Vec<&[Event<'static>]>> = vec![];
+        let mut in_header = false;
+
+        while let Some(event) = events.next() {
+            match event {
+                Event::Start(Tag::TableHead) => in_header = true,
+                Event::End(TagEnd::TableHead) => in_header = false,
+                Event::Start(Tag::TableRow) => rows.push(vec![]),
+                Event::Start(Tag::TableCell) => {
+                    let cell = take_table_cell(events);
+                    if in_header {
+                        header.push(cell);
+                    } else if let Some(row) = rows.last_mut() {
+                        row.push(cell);
+                    }
+                }
+                Event::End(TagEnd::Table) => break,
+                _ => {}
+            }
+        }
+
+        let row_height = ui.text_style_height(&TextStyle::Body) + 2.0 * ui.spacing().item_spacing.y;
+        let id = self.next_id();
+
+        let cell_ui = |ui: &mut Ui, column: usize, cell: &[Event<'static>], header: bool| {
+            let layout = match alignments.get(column) {
+                Some(Alignment::Center) => Layout::top_down(Align::Center),
+                Some(Alignment::Right) => Layout::top_down(Align::Max),
+                _ => Layout::top_down(Align::Min),
+            };
+            ui.with_layout(layout, |ui| {
+                ui.style_mut().wrap_mode = Some(TextWrapMode::Extend);
+                let style = InlineStyle {
+                    strong: u32::from(header),
+                    ..Default::default()
+                };
+                self.inlines(ui, &mut cell.iter(), style);
+            });
+        };
+
+        ui.push_id(id, |ui| {
+            TableBuilder::new(ui)
+                .id_salt(id)
+                .vscroll(false)
+                .striped(true)
+                .columns(Column::auto(), alignments.len().max(1))
+                .header(row_height, |mut row| {
+                    for (column, cell) in header.iter().enumerate() {
+                        row.col(|ui| cell_ui(ui, column, cell, true));
+                    }
+                })
+                .body(|mut body| {
+                    for cells in &rows {
+                        body.row(row_height, |mut row| {
+                            for (column, cell) in cells.iter().enumerate() {
+                                row.col(|ui| cell_ui(ui, column, cell, false));
+                            }
+                        });
+                    }
+                });
+        });
+    }
+}
+
+/// The events of a table cell, after its [`Tag::TableCell`] has been consumed.
+fn take_table_cell<'e>(events: &mut Events<'e>) -> &'e [Event<'static>] {
+    let rest = events.as_slice();
+    let len = rest
+        .iter()
+        .position(|event| matches!(event, Event::End(TagEnd::TableCell)))
+        .unwrap_or(rest.len());
+    *events = rest[(len + 1).min(rest.len())..].iter();
+    &rest[..len]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCUMENT: &str = "# Title
+
+Some *emphasis*, **strong**, ~~strikethrough~~, `code` and a [link](https://example.com).
+
+> A quote
+> - with a list
+
+1. First
+2. Second
+   * Nested
+
+- [x] Done
+- [ ] Todo
+
+| Name | Value |
+|:-----|------:|
+| a    | 1     |
+| b    | 2     |
+
+```rs
+fn main() {}
+```
+
+---
+![alt text](file://image.png \"title\")
+";
+
+    #[test]
+    fn table_cells() {
+        let events: Vec<_> = Parser::new_ext("| a | b |\n|---|---|\n| 1 | 2 |", Options::all())
+            .map(Event::into_static)
+            .collect();
+        let mut iter = events.iter();
+        let start = iter
+            .position(|event| matches!(event, Event::Start(Tag::TableCell)))
+            .expect("table should have cells");
+        assert_eq!(start, 2);
+
+        let cell = take_table_cell(&mut iter);
+        assert!(matches!(cell, [Event::Text(text)] if &**text == "a"));
+        assert!(matches!(iter.next(), Some(Event::Start(Tag::TableCell))));
+    }
+
+    #[test]
+    fn show_document() {
+        let ctx = egui::Context::default();
+        for _ in 0..2 {
+            let _ = ctx.run_ui(Default::default(), |ui| {
+                let response = ui.add(Markdown::new(DOCUMENT));
+                assert!(0.0 < response.rect.height(), "Document should not be empty");
+            });
+        }
+    }
+}
@@ -75,18 +75,13 @@ impl RowResizeState {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum RowResizeMode {
+    #[default]
     Live,
     Deferred,
 }
 
-impl Default for RowResizeMode {
-    fn default() -> Self {
-        RowResizeMode::Live
-    }
-}
-
 /// Configuration for row resizing behavior.
 #[derive(Clone, Copy, Debug)]
 pub struct RowResizeConfig {
@@ -173,6 +168,7 @@ struct RowResizePreviewState {
 /// Call this for each row after rendering it, passing the row's bottom Y position.
 /// Set `is_header` to true when calling from the row header column.
 /// Returns the new height if the row was resized.
+#[expect(clippy::too_many_arguments)]
 pub fn handle_row_resize(
     ui: &Ui,
     state: &mut RowResizeState,
@@ -207,7 +203,7 @@ pub fn handle_row_resize(
     
     // Check if pointer is in the resize rect (in screen coordinates)
     let pointer_pos = ui.ctx().input(|i| i.pointer.hover_pos());
-    let pointer_in_rect = pointer_pos.map_or(false, |pos| interact_rect.contains(pos));
+    let pointer_in_rect = pointer_pos.is_some_and(|pos| interact_rect.contains(pos));
     
     // Track drag state
     let drag_key = resize_id.with("row_drag");
@@ -255,25 +251,28 @@ pub fn handle_row_resize(
         preview = RowResizePreviewState::default();
     } else {
         // Deferred updates with guide line preview
-        if primary_pressed && pointer_in_rect {
-            if let Some(pos) = pointer_pos {
-                preview.active = true;
-                preview.row = Some(row_index);
-                preview.start_height = current_height;
-                preview.start_pointer_y = pos.y;
-                preview.pending_height = current_height;
-                preview.preview_y = row_bottom_y;
-            }
+        if primary_pressed
+            && pointer_in_rect
+            && let Some(pos) = pointer_pos
+        {
+            preview.active = true;
+            preview.row = Some(row_index);
+            preview.start_height = current_height;
+            preview.start_pointer_y = pos.y;
+            preview.pending_height = current_height;
+            preview.preview_y = row_bottom_y;
         }
 
-        if is_dragging && preview.active && preview.row == Some(row_index) {
-            if let Some(pos) = pointer_pos {
-                let delta = pos.y - preview.start_pointer_y;
-                let mut updated_height = preview.start_height + delta;
-                updated_height = config.height_range.clamp(updated_height);
-                preview.pending_height = updated_height;
-                preview.preview_y = row_bottom_y + (updated_height - current_height);
-            }
+        if is_dragging
+            && preview.active
+            && preview.row == Some(row_index)
+            && let Some(pos) = pointer_pos
+        {
+            let delta = pos.y - preview.start_pointer_y;
+            let mut updated_height = preview.start_height + delta;
+            updated_height = config.height_range.clamp(updated_height);
+            preview.pending_height = updated_height;
+            preview.preview_y = row_bottom_y + (updated_height - current_height);
         }
 
         if was_dragging && !primary_down && preview.row == Some(row_index) {
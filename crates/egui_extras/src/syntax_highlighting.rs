@@ -1,7 +1,16 @@
 //! Syntax highlighting for code.
 //!
 //! Turn on the `syntect` feature for great syntax highlighting of any language.
-//! Otherwise, a very simple fallback will be used, that works okish for C, C++, Rust, and Python.
+//! Otherwise, a very simple fallback will be used, that works okish for C, C++, Rust, Python and a few others.
+//! You can teach the fallback new languages with [`register_language`].
+
+#[cfg(not(feature = "syntect"))]
+mod language;
+
+#[cfg(not(feature = "syntect"))]
+pub use language::{
+    HighlightedLine, Language, Languages, LineState, NumberFormat, StringDelimiter, TokenType,
+};
 
 use egui::TextStyle;
 use egui::text::LayoutJob;
@@ -55,6 +64,68 @@ pub fn highlight_with(
     )
 }
 
+/// Add syntax highlighting to a code string, with a custom set of [`Languages`].
+///
+/// The results are memoized, so you can call this every frame without performance penalty.
+///
+/// Prefer [`register_language`] if you just want to add a few languages.
+#[cfg(not(feature = "syntect"))]
+pub fn highlight_with(
+    ctx: &egui::Context,
+    style: &egui::Style,
+    theme: &CodeTheme,
+    code: &str,
+    language: &str,
+    languages: &Languages,
+) -> LayoutJob {
+    highlight_inner(
+        ctx,
+        style,
+        theme,
+        code,
+        language,
+        Some(HighlightSettings(languages)),
+    )
+}
+
+/// Register a language for the simple built-in syntax highlighter.
+///
+/// It can then be used by passing any of its [`Language::names`] to [`highlight`] or [`code_view_ui`].
+/// A language registered later takes precedence over earlier ones and the built-in ones with the same name.
+///
+/// ### Example
+///
+/// ```
+/// # egui::__run_test_ui(|ui| {
+/// use egui_extras::syntax_highlighting::{Language, StringDelimiter, register_language};
+///
+/// register_language(
+///     ui.ctx(),
+///     Language::new("ini")
+///         .line_comment(";")
+///         .string(StringDelimiter::new("\"")),
+/// );
+/// # });
+/// ```
+#[cfg(not(feature = "syntect"))]
+pub fn register_language(ctx: &egui::Context, language: Language) {
+    ctx.data_mut(|data| {
+        let settings = data.get_temp_mut_or_default::<PrivateSettings>(egui::Id::NULL);
+        let mut languages = (*settings.0).clone();
+        languages.add(language);
+        settings.0 = std::sync::Arc::new(languages);
+    });
+}
+
+// Private type, so that users can't interfere with it in the `IdTypeMap`
+#[cfg(feature = "syntect")]
+#[derive(Clone, Default)]
+struct PrivateSettings(std::sync::Arc<SyntectSettings>);
+
+#[cfg(not(feature = "syntect"))]
+#[derive(Clone, Default)]
+struct PrivateSettings(std::sync::Arc<Languages>);
+
 fn highlight_inner(
     ctx: &egui::Context,
     style: &egui::Style,
@@ -67,44 +138,11 @@ fn highlight_inner(
     // performing it at a separate thread (ctx, ctx.global_style()) can be used and when ui is available
     // (ui.ctx(), ui.style()) can be used
 
-    #[expect(non_local_definitions)]
-    impl
-        egui::cache::ComputerMut<
-            (&egui::FontId, &CodeTheme, &str, &str, HighlightSettings<'_>),
-            LayoutJob,
-        > for Highlighter
-    {
-        fn compute(
-            &mut self,
-            (font_id, theme, code, lang, settings): (
-                &egui::FontId,
-                &CodeTheme,
-                &str,
-                &str,
-                HighlightSettings<'_>,
-            ),
-        ) -> LayoutJob {
-            Self::highlight(font_id.clone(), theme, code, lang, settings)
-        }
-    }
-
-    type HighlightCache = egui::cache::FrameCache<LayoutJob, Highlighter>;
-
     let font_id = style
         .override_font_id
         .clone()
         .unwrap_or_else(|| TextStyle::Monospace.resolve(style));
 
-    // Private type, so that users can't interfere with it in the `IdTypeMap`
-    #[cfg(feature = "syntect")]
-    #[derive(Clone, Default)]
-    struct PrivateSettings(std::sync::Arc<SyntectSettings>);
-
-    // Dummy private settings, to minimize code changes without `syntect`
-    #[cfg(not(feature = "syntect"))]
-    #[derive(Clone, Default)]
-    struct PrivateSettings(std::sync::Arc<()>);
-
     ctx.memory_mut(|mem| {
         let settings = settings.unwrap_or_else(|| {
             HighlightSettings(
@@ -116,28 +154,58 @@ fn highlight_inner(
         mem.caches
             .cache::<HighlightCache>()
             .get((&font_id, theme, code, language, settings))
-            .clone()
     })
 }
 
+/// Like [`egui::cache::FrameCache`], but also evicts the highlighted lines once per frame,
+/// so that several documents that are edited at the same time don't evict each other's lines.
+#[derive(Default)]
+struct HighlightCache {
+    generation: u32,
+    jobs: ahash::HashMap<u64, (u32, LayoutJob)>,
+    highlighter: Highlighter,
+}
+
+impl HighlightCache {
+    fn get(
+        &mut self,
+        key: (&egui::FontId, &CodeTheme, &str, &str, HighlightSettings<'_>),
+    ) -> LayoutJob {
+        let (font_id, theme, code, language, settings) = key;
+        let generation = self.generation;
+        let (used, job) = self.jobs.entry(egui::util::hash(key)).or_insert_with(|| {
+            let job = self
+                .highlighter
+                .highlight(font_id.clone(), theme, code, language, settings);
+            (generation, job)
+        });
+        *used = generation;
+        job.clone()
+    }
+}
+
+impl egui::cache::CacheTrait for HighlightCache {
+    fn update(&mut self) {
+        let generation = self.generation;
+        self.jobs.retain(|_, (used, _)| *used == generation);
+        self.generation = generation.wrapping_add(1);
+
+        // Only keep the lines of the documents we highlighted this frame:
+        #[cfg(not(feature = "syntect"))]
+        self.highlighter.lines.evict_cache();
+    }
+
+    fn len(&self) -> usize {
+        self.jobs.len()
+    }
+}
+
 fn monospace_font_size(style: &egui::Style) -> f32 {
     TextStyle::Monospace.resolve(style).size
 }
 
 // ----------------------------------------------------------------------------
 
-#[cfg(not(feature = "syntect"))]
-#[derive(Clone, Copy, PartialEq, enum_map::Enum)]
-#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
-enum TokenType {
-    Comment,
-    Keyword,
-    Literal,
-    StringLiteral,
-    Punctuation,
-    Whitespace,
-}
-
 #[cfg(feature = "syntect")]
 #[derive(Clone, Copy, Hash, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -219,9 +287,115 @@ pub struct CodeTheme {
     font_id: egui::FontId,
 
     #[cfg(not(feature = "syntect"))]
+    #[cfg_attr(feature = "serde", serde(with = "formats_serde"))]
     formats: enum_map::EnumMap<TokenType, egui::TextFormat>,
 }
 
+/// Themes stored before [`TokenType::Number`] was added lack a format for it,
+/// so numbers fall back to the format of literals, which they used to be highlighted as.
+///
+/// Human-readable formats store the formats as a map, so missing token types are easy to spot.
+/// Binary formats used to store them as a tuple in the order of [`TokenType`],
+/// so they now start with a marker format (with a NaN font size),
+/// followed by a map from token type to format.
+#[cfg(all(feature = "serde", not(feature = "syntect")))]
+mod formats_serde {
+    use egui::TextFormat;
+    use enum_map::EnumMap;
+    use serde::{
+        Deserialize as _, Deserializer, Serialize as _, Serializer, ser::SerializeTuple as _,
+    };
+
+    use super::{CodeTheme, TokenType};
+
+    type Formats = EnumMap<TokenType, TextFormat>;
+
+    /// The order of the token types in binary themes stored without the marker.
+    const OLD_TOKEN_TYPES: [TokenType; 6] = [
+        TokenType::Comment,
+        TokenType::Keyword,
+        TokenType::Literal,
+        TokenType::StringLiteral,
+        TokenType::Punctuation,
+        TokenType::Whitespace,
+    ];
+
+    fn marker() -> TextFormat {
+        TextFormat {
+            font_id: egui::FontId::monospace(f32::NAN),
+            ..Default::default()
+        }
+    }
+
+    /// Serializes a map, no matter if the format is human-readable.
+    struct AsMap<'a>(&'a Formats);
+
+    impl serde::Serialize for AsMap<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_map(self.0)
+        }
+    }
+
+    pub fn serialize<S: Serializer>(formats: &Formats, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            return AsMap(formats).serialize(serializer);
+        }
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&marker())?;
+        tuple.serialize_element(&AsMap(formats))?;
+        tuple.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Formats, D::Error> {
+        if deserializer.is_human_readable() {
+            let stored = ahash::HashMap::<TokenType, TextFormat>::deserialize(deserializer)?;
+            return Ok(from_stored(stored));
+        }
+        deserializer.deserialize_tuple(OLD_TOKEN_TYPES.len(), BinaryVisitor)
+    }
+
+    fn from_stored(mut stored: ahash::HashMap<TokenType, TextFormat>) -> Formats {
+        let mut formats = CodeTheme::default().formats;
+        if let Some(literal) = stored.get(&TokenType::Literal) {
+            formats[TokenType::Number] = literal.clone();
+        }
+        for (token_type, format) in stored.drain() {
+            formats[token_type] = format;
+        }
+        formats
+    }
+
+    struct BinaryVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for BinaryVisitor {
+        type Value = Formats;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            formatter.write_str("a code theme marker followed by a map, or a tuple of formats")
+        }
+
+        fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Formats, A::Error> {
+            let missing = |index| <A::Error as serde::de::Error>::invalid_length(index, &self);
+            let first: TextFormat = seq.next_element()?.ok_or_else(|| missing(0))?;
+            if first.font_id.size.is_nan() {
+                let stored: ahash::HashMap<TokenType, TextFormat> =
+                    seq.next_element()?.ok_or_else(|| missing(1))?;
+                return Ok(from_stored(stored));
+            }
+
+            let mut stored = ahash::HashMap::default();
+            stored.insert(OLD_TOKEN_TYPES[0], first);
+            for (index, token_type) in OLD_TOKEN_TYPES.into_iter().enumerate().skip(1) {
+                stored.insert(
+                    token_type,
+                    seq.next_element()?.ok_or_else(|| missing(index))?,
+                );
+            }
+            Ok(from_stored(stored))
+        }
+    }
+}
+
 impl Default for CodeTheme {
     fn default() -> Self {
         Self::dark(12.0)
@@ -304,7 +478,7 @@ impl CodeTheme {
     ///
     /// There is one dark and one light theme stored at any one time.
     pub fn store_in_memory(self, ctx: &egui::Context) {
-        let id = if ctx.style().visuals.dark_mode {
+        let id = if ctx.global_style().visuals.dark_mode {
             egui::Id::new("dark")
         } else {
             egui::Id::new("light")
@@ -384,6 +558,7 @@ impl CodeTheme {
                 TokenType::Comment => TextFormat::simple(font_id.clone(), Color32::from_gray(120)),
                 TokenType::Keyword => TextFormat::simple(font_id.clone(), Color32::from_rgb(255, 100, 100)),
                 TokenType::Literal => TextFormat::simple(font_id.clone(), Color32::from_rgb(87, 165, 171)),
+                TokenType::Number => TextFormat::simple(font_id.clone(), Color32::from_rgb(87, 165, 171)),
                 TokenType::StringLiteral => TextFormat::simple(font_id.clone(), Color32::from_rgb(109, 147, 226)),
                 TokenType::Punctuation => TextFormat::simple(font_id.clone(), Color32::LIGHT_GRAY),
                 TokenType::Whitespace => TextFormat::simple(font_id.clone(), Color32::TRANSPARENT),
@@ -403,6 +578,7 @@ impl CodeTheme {
                 TokenType::Comment => TextFormat::simple(font_id.clone(), Color32::GRAY),
                 TokenType::Keyword => TextFormat::simple(font_id.clone(), Color32::from_rgb(235, 0, 0)),
                 TokenType::Literal => TextFormat::simple(font_id.clone(), Color32::from_rgb(153, 134, 255)),
+                TokenType::Number => TextFormat::simple(font_id.clone(), Color32::from_rgb(153, 134, 255)),
                 TokenType::StringLiteral => TextFormat::simple(font_id.clone(), Color32::from_rgb(37, 203, 105)),
                 TokenType::Punctuation => TextFormat::simple(font_id.clone(), Color32::DARK_GRAY),
                 TokenType::Whitespace => TextFormat::simple(font_id.clone(), Color32::TRANSPARENT),
@@ -437,6 +613,7 @@ impl CodeTheme {
                         (TokenType::Comment, "// comment"),
                         (TokenType::Keyword, "keyword"),
                         (TokenType::Literal, "literal"),
+                        (TokenType::Number, "number 42"),
                         (TokenType::StringLiteral, "\"string literal\""),
                         (TokenType::Punctuation, "punctuation ;"),
                         // (TokenType::Whitespace, "whitespace"),
@@ -510,44 +687,60 @@ impl Default for SyntectSettings {
 #[derive(Copy, Clone)]
 struct HighlightSettings<'a>(&'a SyntectSettings);
 
+#[cfg(feature = "syntect")]
+impl std::hash::Hash for HighlightSettings<'_> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::ptr::hash(self.0, state);
+    }
+}
+
+/// Highlight settings are memoized by the generation of the [`Languages`], rather than value
 #[cfg(not(feature = "syntect"))]
 #[derive(Copy, Clone)]
-struct HighlightSettings<'a>(&'a ());
+struct HighlightSettings<'a>(&'a Languages);
 
+#[cfg(not(feature = "syntect"))]
 impl std::hash::Hash for HighlightSettings<'_> {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        std::ptr::hash(self.0, state);
+        std::hash::Hash::hash(&self.0.generation(), state);
     }
 }
 
 #[derive(Default)]
-struct Highlighter;
+struct Highlighter {
+    /// Highlighted lines, so that editing a large document only re-highlights the lines that changed.
+    #[cfg(not(feature = "syntect"))]
+    lines: egui::cache::FrameCache<HighlightedLine, LineHighlighter>,
+}
 
 impl Highlighter {
     fn highlight(
+        &mut self,
         font_id: egui::FontId,
         theme: &CodeTheme,
         code: &str,
         lang: &str,
         settings: HighlightSettings<'_>,
     ) -> LayoutJob {
-        Self::highlight_impl(theme, code, lang, settings).unwrap_or_else(|| {
-            // Fallback:
-            LayoutJob::simple(
-                code.into(),
-                font_id,
-                if theme.dark_mode {
-                    egui::Color32::LIGHT_GRAY
-                } else {
-                    egui::Color32::DARK_GRAY
-                },
-                f32::INFINITY,
-            )
-        })
+        self.highlight_impl(theme, code, lang, settings)
+            .unwrap_or_else(|| {
+                // Fallback:
+                LayoutJob::simple(
+                    code.into(),
+                    font_id,
+                    if theme.dark_mode {
+                        egui::Color32::LIGHT_GRAY
+                    } else {
+                        egui::Color32::DARK_GRAY
+                    },
+                    f32::INFINITY,
+                )
+            })
     }
 
     #[cfg(feature = "syntect")]
     fn highlight_impl(
+        &mut self,
         theme: &CodeTheme,
         text: &str,
         language: &str,
@@ -595,6 +788,7 @@ impl Highlighter {
                         underline,
                         ..Default::default()
                     },
+                    placeholder: None,
                 });
             }
         }
@@ -625,249 +819,114 @@ fn as_byte_range(whole: &str, range: &str) -> std::ops::Range<usize> {
 #[cfg(not(feature = "syntect"))]
 impl Highlighter {
     fn highlight_impl(
+        &mut self,
         theme: &CodeTheme,
-        mut text: &str,
+        text: &str,
         language: &str,
-        _settings: HighlightSettings<'_>,
+        settings: HighlightSettings<'_>,
     ) -> Option<LayoutJob> {
         profiling::function_scope!();
 
-        let language = Language::new(language)?;
-
         // Extremely simple syntax highlighter for when we compile without syntect
 
+        settings.0.find(language)?;
+
         let mut job = LayoutJob::default();
+        let mut state = LineState::default();
 
-        while !text.is_empty() {
-            if language.double_slash_comments && text.starts_with("//")
-                || language.hash_comments && text.starts_with('#')
-            {
-                let end = text.find('\n').unwrap_or(text.len());
-                job.append(&text[..end], 0.0, theme.formats[TokenType::Comment].clone());
-                text = &text[end..];
-            } else if text.starts_with('"') {
-                let end = text[1..]
-                    .find('"')
-                    .map(|i| i + 2)
-                    .or_else(|| text.find('\n'))
-                    .unwrap_or(text.len());
+        for line in text.split_inclusive('\n') {
+            let highlighted = self.lines.get((settings, language, line, state));
+            for (range, token_type) in &highlighted.tokens {
                 job.append(
-                    &text[..end],
+                    &line[range.clone()],
                     0.0,
-                    theme.formats[TokenType::StringLiteral].clone(),
+                    theme.formats[*token_type].clone(),
                 );
-                text = &text[end..];
-            } else if text.starts_with(|c: char| c.is_ascii_alphanumeric()) {
-                let end = text[1..]
-                    .find(|c: char| !c.is_ascii_alphanumeric())
-                    .map_or_else(|| text.len(), |i| i + 1);
-                let word = &text[..end];
-                let tt = if language.is_keyword(word) {
-                    TokenType::Keyword
-                } else {
-                    TokenType::Literal
-                };
-                job.append(word, 0.0, theme.formats[tt].clone());
-                text = &text[end..];
-            } else if text.starts_with(|c: char| c.is_ascii_whitespace()) {
-                let end = text[1..]
-                    .find(|c: char| !c.is_ascii_whitespace())
-                    .map_or_else(|| text.len(), |i| i + 1);
-                job.append(
-                    &text[..end],
-                    0.0,
-                    theme.formats[TokenType::Whitespace].clone(),
-                );
-                text = &text[end..];
-            } else {
-                let mut it = text.char_indices();
-                it.next();
-                let end = it.next().map_or(text.len(), |(idx, _chr)| idx);
-                job.append(
-                    &text[..end],
-                    0.0,
-                    theme.formats[TokenType::Punctuation].clone(),
-                );
-                text = &text[end..];
             }
+            state = highlighted.end_state;
         }
 
         Some(job)
     }
 }
 
+/// Highlights single lines with [`Language::highlight_line`].
 #[cfg(not(feature = "syntect"))]
-struct Language {
-    /// `// comment`
-    double_slash_comments: bool,
-
-    /// `# comment`
-    hash_comments: bool,
-
-    keywords: std::collections::BTreeSet<&'static str>,
-}
+#[derive(Default)]
+struct LineHighlighter;
 
 #[cfg(not(feature = "syntect"))]
-impl Language {
-    fn new(language: &str) -> Option<Self> {
-        match language.to_lowercase().as_str() {
-            "c" | "h" | "hpp" | "cpp" | "c++" => Some(Self::cpp()),
-            "py" | "python" => Some(Self::python()),
-            "rs" | "rust" => Some(Self::rust()),
-            "toml" => Some(Self::toml()),
-            _ => {
-                None // unsupported language
-            }
-        }
+impl egui::cache::ComputerMut<(HighlightSettings<'_>, &str, &str, LineState), HighlightedLine>
+    for LineHighlighter
+{
+    fn compute(
+        &mut self,
+        (settings, language, line, state): (HighlightSettings<'_>, &str, &str, LineState),
+    ) -> HighlightedLine {
+        settings.0.find(language).map_or_else(
+            || HighlightedLine {
+                tokens: vec![(0..line.len(), TokenType::Literal)],
+                end_state: LineState::Normal,
+            },
+            |language| language.highlight_line(line, state),
+        )
     }
+}
 
-    fn is_keyword(&self, word: &str) -> bool {
-        self.keywords.contains(word)
-    }
+#[cfg(all(test, feature = "serde", not(feature = "syntect")))]
+mod tests {
+    use egui::{Color32, FontId, TextFormat};
 
-    fn cpp() -> Self {
-        Self {
-            double_slash_comments: true,
-            hash_comments: false,
-            keywords: [
-                "alignas",
-                "alignof",
-                "and_eq",
-                "and",
-                "asm",
-                "atomic_cancel",
-                "atomic_commit",
-                "atomic_noexcept",
-                "auto",
-                "bitand",
-                "bitor",
-                "bool",
-                "break",
-                "case",
-                "catch",
-                "char",
-                "char16_t",
-                "char32_t",
-                "char8_t",
-                "class",
-                "co_await",
-                "co_return",
-                "co_yield",
-                "compl",
-                "concept",
-                "const_cast",
-                "const",
-                "consteval",
-                "constexpr",
-                "constinit",
-                "continue",
-                "decltype",
-                "default",
-                "delete",
-                "do",
-                "double",
-                "dynamic_cast",
-                "else",
-                "enum",
-                "explicit",
-                "export",
-                "extern",
-                "false",
-                "float",
-                "for",
-                "friend",
-                "goto",
-                "if",
-                "inline",
-                "int",
-                "long",
-                "mutable",
-                "namespace",
-                "new",
-                "noexcept",
-                "not_eq",
-                "not",
-                "nullptr",
-                "operator",
-                "or_eq",
-                "or",
-                "private",
-                "protected",
-                "public",
-                "reflexpr",
-                "register",
-                "reinterpret_cast",
-                "requires",
-                "return",
-                "short",
-                "signed",
-                "sizeof",
-                "static_assert",
-                "static_cast",
-                "static",
-                "struct",
-                "switch",
-                "synchronized",
-                "template",
-                "this",
-                "thread_local",
-                "throw",
-                "true",
-                "try",
-                "typedef",
-                "typeid",
-                "typename",
-                "union",
-                "unsigned",
-                "using",
-                "virtual",
-                "void",
-                "volatile",
-                "wchar_t",
-                "while",
-                "xor_eq",
-                "xor",
-            ]
-            .into_iter()
-            .collect(),
-        }
-    }
+    use super::{CodeTheme, TokenType};
 
-    fn python() -> Self {
-        Self {
-            double_slash_comments: false,
-            hash_comments: true,
-            keywords: [
-                "and", "as", "assert", "break", "class", "continue", "def", "del", "elif", "else",
-                "except", "False", "finally", "for", "from", "global", "if", "import", "in", "is",
-                "lambda", "None", "nonlocal", "not", "or", "pass", "raise", "return", "True",
-                "try", "while", "with", "yield",
-            ]
-            .into_iter()
-            .collect(),
-        }
+    /// How [`CodeTheme`] was stored before [`TokenType::Number`] was added.
+    #[derive(serde::Serialize)]
+    struct OldCodeTheme {
+        dark_mode: bool,
+        formats: [TextFormat; 6],
     }
 
-    fn rust() -> Self {
-        Self {
-            double_slash_comments: true,
-            hash_comments: false,
-            keywords: [
-                "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else",
-                "enum", "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match",
-                "mod", "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct",
-                "super", "trait", "true", "type", "unsafe", "use", "where", "while",
-            ]
-            .into_iter()
-            .collect(),
-        }
+    #[test]
+    fn test_binary_theme_round_trip() {
+        let mut theme = CodeTheme::light(14.0);
+        theme.formats[TokenType::Number].color = Color32::RED;
+        let bytes = bincode::serialize(&theme).unwrap();
+        let loaded: CodeTheme = bincode::deserialize(&bytes).unwrap();
+        assert!(loaded == theme);
     }
 
-    fn toml() -> Self {
-        Self {
-            double_slash_comments: false,
-            hash_comments: true,
-            keywords: Default::default(),
-        }
+    #[test]
+    fn test_old_binary_theme() {
+        let format = |color| TextFormat::simple(FontId::monospace(10.0), color);
+        let old = OldCodeTheme {
+            dark_mode: false,
+            formats: [
+                format(Color32::GRAY),
+                format(Color32::RED),
+                format(Color32::GREEN),
+                format(Color32::BLUE),
+                format(Color32::YELLOW),
+                format(Color32::TRANSPARENT),
+            ],
+        };
+        let bytes = bincode::serialize(&old).unwrap();
+        let loaded: CodeTheme = bincode::deserialize(&bytes).unwrap();
+        assert!(!loaded.is_dark());
+        assert_eq!(loaded.formats[TokenType::Comment].color, Color32::GRAY);
+        assert_eq!(loaded.formats[TokenType::Keyword].color, Color32::RED);
+        assert_eq!(loaded.formats[TokenType::Literal].color, Color32::GREEN);
+        assert_eq!(loaded.formats[TokenType::Number].color, Color32::GREEN);
+        assert_eq!(
+            loaded.formats[TokenType::StringLiteral].color,
+            Color32::BLUE
+        );
+        assert_eq!(
+            loaded.formats[TokenType::Punctuation].color,
+            Color32::YELLOW
+        );
+        assert_eq!(
+            loaded.formats[TokenType::Whitespace].color,
+            Color32::TRANSPARENT
+        );
     }
 }
@@ -0,0 +1,1074 @@
+//! Language definitions for the simple built-in syntax highlighter.
+//!
+//! These are only used when the `syntect` feature is turned off.
+
+use std::collections::BTreeSet;
+use std::ops::Range;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The kind of a highlighted piece of code.
+///
+/// Each kind gets its own [`egui::TextFormat`] in a [`super::CodeTheme`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, enum_map::Enum)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum TokenType {
+    Comment,
+    Keyword,
+    Literal,
+    Number,
+    StringLiteral,
+    Punctuation,
+    Whitespace,
+}
+
+// ----------------------------------------------------------------------------
+
+/// How a string literal starts and ends, e.g. `"…"` or `[[…]]`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StringDelimiter {
+    /// Starts the string.
+    pub open: String,
+
+    /// Ends the string.
+    pub close: String,
+
+    /// The character that escapes the next character, e.g. `\` in `"a \" b"`.
+    pub escape: Option<char>,
+
+    /// Can the string span multiple lines?
+    ///
+    /// If not, an unterminated string ends at the end of the line.
+    pub multiline: bool,
+}
+
+impl StringDelimiter {
+    /// A string that starts and ends with the same delimiter, and uses `\` for escaping.
+    pub fn new(delimiter: impl Into<String>) -> Self {
+        let delimiter = delimiter.into();
+        Self {
+            open: delimiter.clone(),
+            close: delimiter,
+            escape: Some('\\'),
+            multiline: false,
+        }
+    }
+
+    /// A string with different start and end delimiters, without escaping.
+    pub fn pair(open: impl Into<String>, close: impl Into<String>) -> Self {
+        Self {
+            open: open.into(),
+            close: close.into(),
+            escape: None,
+            multiline: false,
+        }
+    }
+
+    /// The character that escapes the next character, or `None` to disable escaping.
+    #[inline]
+    pub fn escape(mut self, escape: Option<char>) -> Self {
+        self.escape = escape;
+        self
+    }
+
+    /// Can the string span multiple lines?
+    #[inline]
+    pub fn multiline(mut self, multiline: bool) -> Self {
+        self.multiline = multiline;
+        self
+    }
+
+    /// Returns the byte offset just after the closing delimiter, if it is in `text`.
+    fn find_close(&self, text: &str) -> Option<usize> {
+        let mut chars = text.char_indices();
+        while let Some((i, c)) = chars.next() {
+            if text[i..].starts_with(self.close.as_str()) {
+                return Some(i + self.close.len());
+            } else if Some(c) == self.escape {
+                chars.next();
+            }
+        }
+        None
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Which number literals a language supports.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NumberFormat {
+    /// `0x1F`
+    pub hex: bool,
+
+    /// `0b1010`
+    pub binary: bool,
+
+    /// `0o17`
+    pub octal: bool,
+
+    /// `3.14`
+    pub fraction: bool,
+
+    /// `1e-3`
+    pub exponent: bool,
+
+    /// A character that can be used to group digits, e.g. `_` in `1_000_000`.
+    pub digit_separator: Option<char>,
+
+    /// A type suffix directly after the number, e.g. `u32` in `42u32` or `f` in `1.0f`.
+    pub suffix: bool,
+}
+
+impl Default for NumberFormat {
+    /// Decimal numbers with fraction and exponent.
+    fn default() -> Self {
+        Self {
+            hex: false,
+            binary: false,
+            octal: false,
+            fraction: true,
+            exponent: true,
+            digit_separator: None,
+            suffix: false,
+        }
+    }
+}
+
+impl NumberFormat {
+    /// C-like numbers: decimal, hexadecimal and type suffixes.
+    pub fn c_like() -> Self {
+        Self {
+            hex: true,
+            suffix: true,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the length of the number at the start of `text`, if any.
+    fn len(&self, text: &str) -> Option<usize> {
+        let bytes = text.as_bytes();
+        if !bytes.first()?.is_ascii_digit() {
+            return None;
+        }
+
+        let separator = self.digit_separator.filter(char::is_ascii).map(|c| c as u8);
+        let digits_len = |start: usize, radix: u32| {
+            bytes[start..]
+                .iter()
+                .take_while(|&&b| (b as char).is_digit(radix) || Some(b) == separator)
+                .count()
+        };
+
+        let radix = match bytes.get(1).map(u8::to_ascii_lowercase) {
+            Some(b'x') if self.hex && bytes[0] == b'0' => Some(16),
+            Some(b'b') if self.binary && bytes[0] == b'0' => Some(2),
+            Some(b'o') if self.octal && bytes[0] == b'0' => Some(8),
+            _ => None,
+        };
+
+        let mut len = match radix.map(|radix| digits_len(2, radix)) {
+            Some(prefixed_len) if 0 < prefixed_len => 2 + prefixed_len,
+            _ => {
+                let mut len = digits_len(0, 10);
+
+                if self.fraction
+                    && bytes.get(len) == Some(&b'.')
+                    && bytes.get(len + 1).is_some_and(u8::is_ascii_digit)
+                {
+                    len += 1 + digits_len(len + 1, 10);
+                }
+
+                if self.exponent && matches!(bytes.get(len), Some(b'e' | b'E')) {
+                    let sign_len = usize::from(matches!(bytes.get(len + 1), Some(b'+' | b'-')));
+                    if bytes
+                        .get(len + 1 + sign_len)
+                        .is_some_and(u8::is_ascii_digit)
+                    {
+                        len += 1 + sign_len + digits_len(len + 1 + sign_len, 10);
+                    }
+                }
+
+                len
+            }
+        };
+
+        if self.suffix {
+            len += bytes[len..]
+                .iter()
+                .take_while(|&&b| is_identifier_char(b as char))
+                .count();
+        }
+
+        Some(len)
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Describes the syntax of a language for the simple built-in syntax highlighter.
+///
+/// Register your own with [`super::register_language`].
+///
+/// ### Example
+///
+/// ```
+/// use egui_extras::syntax_highlighting::{Language, NumberFormat, StringDelimiter};
+///
+/// let ini = Language::new("ini")
+///     .alias("cfg")
+///     .line_comment(";")
+///     .string(StringDelimiter::new("\""))
+///     .keywords(["true", "false"])
+///     .numbers(NumberFormat::default());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Language {
+    /// Names and file extensions that select this language, e.g. `["rs", "rust"]`.
+    ///
+    /// These are matched case-insensitively.
+    pub names: Vec<String>,
+
+    /// Tokens that start a comment that runs to the end of the line, e.g. `//` or `#`.
+    pub line_comments: Vec<String>,
+
+    /// Start and end tokens of block comments, e.g. `("/*", "*/")`.
+    pub block_comments: Vec<(String, String)>,
+
+    /// String literals, tried in order.
+    ///
+    /// Put longer delimiters first, e.g. `"""` before `"`.
+    pub strings: Vec<StringDelimiter>,
+
+    /// Words that are highlighted as [`TokenType::Keyword`].
+    pub keywords: BTreeSet<String>,
+
+    /// Match keywords case-insensitively, like in SQL.
+    ///
+    /// If set, [`Self::keywords`] should be lowercase.
+    pub case_insensitive: bool,
+
+    /// Which number literals are recognized.
+    pub numbers: NumberFormat,
+}
+
+impl Language {
+    /// A language without any comments, strings or keywords.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            names: vec![name.into()],
+            ..Default::default()
+        }
+    }
+
+    /// Another name or file extension for this language.
+    #[inline]
+    pub fn alias(mut self, name: impl Into<String>) -> Self {
+        self.names.push(name.into());
+        self
+    }
+
+    /// Add a token that starts a comment that runs to the end of the line.
+    #[inline]
+    pub fn line_comment(mut self, start: impl Into<String>) -> Self {
+        self.line_comments.push(start.into());
+        self
+    }
+
+    /// Add a comment that runs from `open` to `close`, possibly over several lines.
+    #[inline]
+    pub fn block_comment(mut self, open: impl Into<String>, close: impl Into<String>) -> Self {
+        self.block_comments.push((open.into(), close.into()));
+        self
+    }
+
+    /// Add a kind of string literal.
+    #[inline]
+    pub fn string(mut self, delimiter: StringDelimiter) -> Self {
+        self.strings.push(delimiter);
+        self
+    }
+
+    /// Add keywords.
+    #[inline]
+    pub fn keywords(mut self, keywords: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.keywords.extend(keywords.into_iter().map(Into::into));
+        self
+    }
+
+    /// Match keywords case-insensitively.
+    #[inline]
+    pub fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Which number literals are recognized.
+    #[inline]
+    pub fn numbers(mut self, numbers: NumberFormat) -> Self {
+        self.numbers = numbers;
+        self
+    }
+
+    /// Is this language known by the given name or file extension?
+    pub fn has_name(&self, name: &str) -> bool {
+        self.names.iter().any(|n| n.eq_ignore_ascii_case(name))
+    }
+
+    /// Is `word` one of the [`Self::keywords`]?
+    pub fn is_keyword(&self, word: &str) -> bool {
+        if self.case_insensitive {
+            self.keywords.contains(&word.to_lowercase())
+        } else {
+            self.keywords.contains(word)
+        }
+    }
+
+    /// Highlight a single line of code.
+    ///
+    /// `state` is the [`HighlightedLine::end_state`] of the previous line,
+    /// or [`LineState::default`] for the first line.
+    /// The line may include its trailing newline.
+    ///
+    /// The result only depends on the arguments,
+    /// so it can be cached per line to avoid re-highlighting a whole document on every change.
+    pub fn highlight_line(&self, line: &str, mut state: LineState) -> HighlightedLine {
+        let mut tokens: Vec<(Range<usize>, TokenType)> = Vec::new();
+        let mut push = |range: Range<usize>, token_type: TokenType| {
+            if let Some((last_range, last_type)) = tokens.last_mut()
+                && *last_type == token_type
+                && last_range.end == range.start
+            {
+                last_range.end = range.end;
+            } else {
+                tokens.push((range, token_type));
+            }
+        };
+
+        let line_end = line.trim_end_matches(['\n', '\r']).len();
+        let mut pos = 0;
+
+        while pos < line.len() {
+            let rest = &line[pos..];
+
+            match state {
+                LineState::BlockComment(index) => {
+                    let close = self.block_comments.get(index).map(|(_, close)| close);
+                    let end =
+                        close.and_then(|close| rest.find(close.as_str()).map(|i| i + close.len()));
+                    if end.is_some() || close.is_none() {
+                        state = LineState::Normal;
+                    }
+                    let end = pos + end.unwrap_or(rest.len());
+                    push(pos..end, TokenType::Comment);
+                    pos = end;
+                }
+
+                LineState::String(index) => {
+                    let delimiter = self.strings.get(index);
+                    let end = delimiter.and_then(|delimiter| delimiter.find_close(rest));
+                    if end.is_some() || !delimiter.is_some_and(|d| d.multiline) {
+                        state = LineState::Normal;
+                    }
+                    let end = pos + end.unwrap_or(rest.len());
+                    push(pos..end, TokenType::StringLiteral);
+                    pos = end;
+                }
+
+                LineState::Normal => {
+                    if let Some(index) = self
+                        .block_comments
+                        .iter()
+                        .position(|(open, _)| rest.starts_with(open.as_str()))
+                    {
+                        let end = pos + self.block_comments[index].0.len();
+                        push(pos..end, TokenType::Comment);
+                        state = LineState::BlockComment(index);
+                        pos = end;
+                    } else if self
+                        .line_comments
+                        .iter()
+                        .any(|start| rest.starts_with(start.as_str()))
+                    {
+                        let end = line_end.max(pos);
+                        push(pos..end, TokenType::Comment);
+                        pos = end;
+                        if pos < line.len() {
+                            push(pos..line.len(), TokenType::Whitespace);
+                            pos = line.len();
+                        }
+                    } else if let Some(index) = self
+                        .strings
+                        .iter()
+                        .position(|delimiter| rest.starts_with(delimiter.open.as_str()))
+                    {
+                        let end = pos + self.strings[index].open.len();
+                        push(pos..end, TokenType::StringLiteral);
+                        state = LineState::String(index);
+                        pos = end;
+                    } else if let Some(len) = self.numbers.len(rest) {
+                        push(pos..pos + len, TokenType::Number);
+                        pos += len;
+                    } else if rest.starts_with(is_identifier_char) {
+                        let len = rest
+                            .find(|c: char| !is_identifier_char(c))
+                            .unwrap_or(rest.len());
+                        let token_type = if self.is_keyword(&rest[..len]) {
+                            TokenType::Keyword
+                        } else {
+                            TokenType::Literal
+                        };
+                        push(pos..pos + len, token_type);
+                        pos += len;
+                    } else if rest.starts_with(|c: char| c.is_whitespace()) {
+                        let len = rest
+                            .find(|c: char| !c.is_whitespace())
+                            .unwrap_or(rest.len());
+                        push(pos..pos + len, TokenType::Whitespace);
+                        pos += len;
+                    } else {
+                        let len = rest.chars().next().map_or(rest.len(), char::len_utf8);
+                        push(pos..pos + len, TokenType::Punctuation);
+                        pos += len;
+                    }
+                }
+            }
+        }
+
+        HighlightedLine {
+            tokens,
+            end_state: state,
+        }
+    }
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// The highlighting state at a line boundary.
+///
+/// This is what carries over from one line to the next,
+/// e.g. that we are inside a block comment or a multi-line string.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LineState {
+    #[default]
+    Normal,
+
+    /// Inside the block comment with this index in [`Language::block_comments`].
+    BlockComment(usize),
+
+    /// Inside the string with this index in [`Language::strings`].
+    String(usize),
+}
+
+/// The output of [`Language::highlight_line`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HighlightedLine {
+    /// Byte ranges into the line, in order, covering the whole line.
+    pub tokens: Vec<(Range<usize>, TokenType)>,
+
+    /// The state at the end of the line, to pass on to the next line.
+    pub end_state: LineState,
+}
+
+// ----------------------------------------------------------------------------
+
+/// A set of [`Language`]s that the simple built-in syntax highlighter can use.
+///
+/// The [`Default`] contains definitions for C/C++, Python, Rust, TOML, JSON, YAML, SQL, Lua, GLSL and WGSL.
+#[derive(Clone, Debug)]
+pub struct Languages {
+    languages: Vec<Language>,
+
+    /// Changes every time the set is modified, so that highlighting results can be cached.
+    generation: u64,
+}
+
+impl Default for Languages {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl Languages {
+    /// No languages at all.
+    pub fn empty() -> Self {
+        Self {
+            languages: Vec::new(),
+            generation: next_generation(),
+        }
+    }
+
+    /// All the built-in languages.
+    pub fn builtin() -> Self {
+        let mut languages = Self::empty();
+        for language in [
+            Language::cpp(),
+            Language::python(),
+            Language::rust(),
+            Language::toml(),
+            Language::json(),
+            Language::yaml(),
+            Language::sql(),
+            Language::lua(),
+            Language::glsl(),
+            Language::wgsl(),
+        ] {
+            languages.add(language);
+        }
+        languages
+    }
+
+    /// Add a language.
+    ///
+    /// If a name is used by several languages, the one added last wins.
+    pub fn add(&mut self, language: Language) {
+        self.languages.push(language);
+        self.generation = next_generation();
+    }
+
+    /// Find the language with the given name or file extension.
+    pub fn find(&self, name: &str) -> Option<&Language> {
+        self.languages.iter().rev().find(|l| l.has_name(name))
+    }
+
+    /// All languages, in the order they were added.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &Language> {
+        self.languages.iter()
+    }
+
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+fn next_generation() -> u64 {
+    static GENERATION: AtomicU64 = AtomicU64::new(0);
+    GENERATION.fetch_add(1, Ordering::Relaxed)
+}
+
+// ----------------------------------------------------------------------------
+// Built-in languages:
+
+impl Language {
+    /// C and C++, also known as `c`, `h`, `hpp` and `c++`.
+    pub fn cpp() -> Self {
+        Self::new("cpp")
+            .alias("c")
+            .alias("h")
+            .alias("hpp")
+            .alias("c++")
+            .line_comment("//")
+            .block_comment("/*", "*/")
+            .string(StringDelimiter::new("\""))
+            .string(StringDelimiter::new("'"))
+            .numbers(NumberFormat {
+                binary: true,
+                digit_separator: Some('\''),
+                ..NumberFormat::c_like()
+            })
+            .keywords([
+                "alignas",
+                "alignof",
+                "and_eq",
+                "and",
+                "asm",
+                "atomic_cancel",
+                "atomic_commit",
+                "atomic_noexcept",
+                "auto",
+                "bitand",
+                "bitor",
+                "bool",
+                "break",
+                "case",
+                "catch",
+                "char",
+                "char16_t",
+                "char32_t",
+                "char8_t",
+                "class",
+                "co_await",
+                "co_return",
+                "co_yield",
+                "compl",
+                "concept",
+                "const_cast",
+                "const",
+                "consteval",
+                "constexpr",
+                "constinit",
+                "continue",
+                "decltype",
+                "default",
+                "delete",
+                "do",
+                "double",
+                "dynamic_cast",
+                "else",
+                "enum",
+                "explicit",
+                "export",
+                "extern",
+                "false",
+                "float",
+                "for",
+                "friend",
+                "goto",
+                "if",
+                "inline",
+                "int",
+                "long",
+                "mutable",
+                "namespace",
+                "new",
+                "noexcept",
+                "not_eq",
+                "not",
+                "nullptr",
+                "operator",
+                "or_eq",
+                "or",
+                "private",
+                "protected",
+                "public",
+                "reflexpr",
+                "register",
+                "reinterpret_cast",
+                "requires",
+                "return",
+                "short",
+                "signed",
+                "sizeof",
+                "static_assert",
+                "static_cast",
+                "static",
+                "struct",
+                "switch",
+                "synchronized",
+                "template",
+                "this",
+                "thread_local",
+                "throw",
+                "true",
+                "try",
+                "typedef",
+                "typeid",
+                "typename",
+                "union",
+                "unsigned",
+                "using",
+                "virtual",
+                "void",
+                "volatile",
+                "wchar_t",
+                "while",
+                "xor_eq",
+                "xor",
+            ])
+    }
+
+    /// Python, also known as `py`.
+    pub fn python() -> Self {
+        Self::new("python")
+            .alias("py")
+            .line_comment("#")
+            .string(StringDelimiter::new("\"\"\"").multiline(true))
+            .string(StringDelimiter::new("'''").multiline(true))
+            .string(StringDelimiter::new("\""))
+            .string(StringDelimiter::new("'"))
+            .numbers(NumberFormat {
+                hex: true,
+                binary: true,
+                octal: true,
+                digit_separator: Some('_'),
+                ..Default::default()
+            })
+            .keywords([
+                "and", "as", "assert", "break", "class", "continue", "def", "del", "elif", "else",
+                "except", "False", "finally", "for", "from", "global", "if", "import", "in", "is",
+                "lambda", "None", "nonlocal", "not", "or", "pass", "raise", "return", "True",
+                "try", "while", "with", "yield",
+            ])
+    }
+
+    /// Rust, also known as `rs`.
+    pub fn rust() -> Self {
+        Self::new("rust")
+            .alias("rs")
+            .line_comment("//")
+            .block_comment("/*", "*/")
+            .string(StringDelimiter::new("\"").multiline(true))
+            .numbers(NumberFormat {
+                hex: true,
+                binary: true,
+                octal: true,
+                digit_separator: Some('_'),
+                suffix: true,
+                ..Default::default()
+            })
+            .keywords([
+                "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else",
+                "enum", "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match",
+                "mod", "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct",
+                "super", "trait", "true", "type", "unsafe", "use", "where", "while",
+            ])
+    }
+
+    /// TOML.
+    pub fn toml() -> Self {
+        Self::new("toml")
+            .line_comment("#")
+            .string(StringDelimiter::new("\"\"\"").multiline(true))
+            .string(StringDelimiter::pair("'''", "'''").multiline(true))
+            .string(StringDelimiter::new("\""))
+            .string(StringDelimiter::pair("'", "'"))
+            .numbers(NumberFormat {
+                hex: true,
+                binary: true,
+                octal: true,
+                digit_separator: Some('_'),
+                ..Default::default()
+            })
+            .keywords(["true", "false", "inf", "nan"])
+    }
+
+    /// JSON.
+    pub fn json() -> Self {
+        Self::new("json")
+            .string(StringDelimiter::new("\""))
+            .keywords(["true", "false", "null"])
+    }
+
+    /// YAML, also known as `yml`.
+    pub fn yaml() -> Self {
+        Self::new("yaml")
+            .alias("yml")
+            .line_comment("#")
+            .string(StringDelimiter::new("\""))
+            .string(StringDelimiter::pair("'", "'"))
+            .keywords([
+                "true", "false", "True", "False", "TRUE", "FALSE", "null", "Null", "NULL", "yes",
+                "no", "on", "off",
+            ])
+    }
+
+    /// SQL, with case-insensitive keywords.
+    pub fn sql() -> Self {
+        Self::new("sql")
+            .line_comment("--")
+            .block_comment("/*", "*/")
+            .string(StringDelimiter::pair("'", "'"))
+            .string(StringDelimiter::pair("\"", "\""))
+            .case_insensitive(true)
+            .keywords([
+                "add",
+                "all",
+                "alter",
+                "and",
+                "as",
+                "asc",
+                "between",
+                "by",
+                "case",
+                "check",
+                "column",
+                "constraint",
+                "create",
+                "cross",
+                "default",
+                "delete",
+                "desc",
+                "distinct",
+                "drop",
+                "else",
+                "end",
+                "exists",
+                "false",
+                "foreign",
+                "from",
+                "full",
+                "group",
+                "having",
+                "in",
+                "index",
+                "inner",
+                "insert",
+                "into",
+                "is",
+                "join",
+                "key",
+                "left",
+                "like",
+                "limit",
+                "not",
+                "null",
+                "offset",
+                "on",
+                "or",
+                "order",
+                "outer",
+                "primary",
+                "references",
+                "right",
+                "select",
+                "set",
+                "table",
+                "then",
+                "true",
+                "union",
+                "unique",
+                "update",
+                "values",
+                "view",
+                "when",
+                "where",
+                "with",
+            ])
+    }
+
+    /// Lua.
+    pub fn lua() -> Self {
+        Self::new("lua")
+            .block_comment("--[[", "]]")
+            .line_comment("--")
+            .string(StringDelimiter::pair("[[", "]]").multiline(true))
+            .string(StringDelimiter::new("\""))
+            .string(StringDelimiter::new("'"))
+            .numbers(NumberFormat {
+                hex: true,
+                ..Default::default()
+            })
+            .keywords([
+                "and", "break", "do", "else", "elseif", "end", "false", "for", "function", "goto",
+                "if", "in", "local", "nil", "not", "or", "repeat", "return", "then", "true",
+                "until", "while",
+            ])
+    }
+
+    /// The OpenGL Shading Language, also known as `vert` and `frag`.
+    pub fn glsl() -> Self {
+        Self::new("glsl")
+            .alias("vert")
+            .alias("frag")
+            .line_comment("//")
+            .block_comment("/*", "*/")
+            .numbers(NumberFormat::c_like())
+            .keywords([
+                "attribute",
+                "bool",
+                "break",
+                "bvec2",
+                "bvec3",
+                "bvec4",
+                "case",
+                "centroid",
+                "const",
+                "continue",
+                "default",
+                "discard",
+                "do",
+                "else",
+                "false",
+                "flat",
+                "float",
+                "for",
+                "highp",
+                "if",
+                "in",
+                "inout",
+                "int",
+                "invariant",
+                "ivec2",
+                "ivec3",
+                "ivec4",
+                "layout",
+                "lowp",
+                "mat2",
+                "mat3",
+                "mat4",
+                "mediump",
+                "noperspective",
+                "out",
+                "precision",
+                "return",
+                "sampler2D",
+                "sampler3D",
+                "samplerCube",
+                "smooth",
+                "struct",
+                "switch",
+                "true",
+                "uint",
+                "uniform",
+                "uvec2",
+                "uvec3",
+                "uvec4",
+                "varying",
+                "vec2",
+                "vec3",
+                "vec4",
+                "void",
+                "while",
+            ])
+    }
+
+    /// The WebGPU Shading Language.
+    pub fn wgsl() -> Self {
+        Self::new("wgsl")
+            .line_comment("//")
+            .block_comment("/*", "*/")
+            .numbers(NumberFormat::c_like())
+            .keywords([
+                "alias",
+                "array",
+                "atomic",
+                "bitcast",
+                "bool",
+                "break",
+                "case",
+                "const",
+                "const_assert",
+                "continue",
+                "continuing",
+                "default",
+                "diagnostic",
+                "discard",
+                "else",
+                "enable",
+                "f16",
+                "f32",
+                "false",
+                "fn",
+                "for",
+                "i32",
+                "if",
+                "let",
+                "loop",
+                "mat2x2",
+                "mat3x3",
+                "mat4x4",
+                "override",
+                "ptr",
+                "requires",
+                "return",
+                "sampler",
+                "struct",
+                "switch",
+                "texture_2d",
+                "true",
+                "u32",
+                "var",
+                "vec2",
+                "vec3",
+                "vec4",
+                "while",
+            ])
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens<'a>(line: &'a str, highlighted: &HighlightedLine) -> Vec<(&'a str, TokenType)> {
+        highlighted
+            .tokens
+            .iter()
+            .map(|(range, token_type)| (&line[range.clone()], *token_type))
+            .collect()
+    }
+
+    #[test]
+    fn numbers() {
+        let rust = NumberFormat {
+            hex: true,
+            digit_separator: Some('_'),
+            suffix: true,
+            ..Default::default()
+        };
+        assert_eq!(rust.len("0xFF_FF;"), Some(7));
+        assert_eq!(rust.len("1_000u32 "), Some(8));
+        assert_eq!(rust.len("1.5e-3)"), Some(6));
+        assert_eq!(rust.len("1..2"), Some(1));
+        assert_eq!(rust.len("x1"), None);
+
+        let plain = NumberFormat::default();
+        assert_eq!(plain.len("0x10"), Some(1));
+        assert_eq!(plain.len("2e"), Some(1));
+    }
+
+    #[test]
+    fn block_comment_spans_lines() {
+        let rust = Language::rust();
+
+        let first = rust.highlight_line("let x = 1; /* start\n", LineState::Normal);
+        assert_eq!(first.end_state, LineState::BlockComment(0));
+
+        let line = "end */ fn";
+        let second = rust.highlight_line(line, first.end_state);
+        assert_eq!(second.end_state, LineState::Normal);
+        assert_eq!(
+            tokens(line, &second),
+            vec![
+                ("end */", TokenType::Comment),
+                (" ", TokenType::Whitespace),
+                ("fn", TokenType::Keyword),
+            ]
+        );
+    }
+
+    #[test]
+    fn strings_and_escapes() {
+        let python = Language::python();
+        let line = r#"x = "a \" b" # c"#;
+        let highlighted = python.highlight_line(line, LineState::Normal);
+        assert_eq!(highlighted.end_state, LineState::Normal);
+        assert_eq!(
+            tokens(line, &highlighted),
+            vec![
+                ("x", TokenType::Literal),
+                (" ", TokenType::Whitespace),
+                ("=", TokenType::Punctuation),
+                (" ", TokenType::Whitespace),
+                (r#""a \" b""#, TokenType::StringLiteral),
+                (" ", TokenType::Whitespace),
+                ("# c", TokenType::Comment),
+            ]
+        );
+
+        // Single-line strings end at the end of the line:
+        let unterminated = python.highlight_line("'abc\n", LineState::Normal);
+        assert_eq!(unterminated.end_state, LineState::Normal);
+
+        // Multi-line strings don't:
+        let multiline = python.highlight_line("'''abc\n", LineState::Normal);
+        assert_eq!(multiline.end_state, LineState::String(1));
+    }
+
+    #[test]
+    fn case_insensitive_keywords() {
+        let sql = Language::sql();
+        assert!(sql.is_keyword("SELECT"));
+        assert!(sql.is_keyword("select"));
+        assert!(!sql.is_keyword("users"));
+    }
+
+    #[test]
+    fn custom_languages_take_precedence() {
+        let mut languages = Languages::builtin();
+        assert!(languages.find("RS").is_some());
+        assert!(languages.find("ini").is_none());
+
+        let generation = languages.generation();
+        languages.add(Language::new("ini").alias("rs").line_comment(";"));
+        assert_ne!(languages.generation(), generation);
+
+        assert_eq!(
+            languages.find("rs").map(|l| l.names[0].as_str()),
+            Some("ini")
+        );
+        assert!(languages.find("ini").is_some());
+    }
+}
@@ -5,7 +5,7 @@
 
 use egui::{
     Align, Id, NumExt as _, Rangef, Rect, Response, ScrollArea, Ui, Vec2, Vec2b,
-    scroll_area::{ScrollAreaOutput, ScrollBarVisibility, ScrollSource},
+    scroll_area::{Minimap, ScrollAreaOutput, ScrollBarVisibility, ScrollSource},
 };
 
 use crate::{StripLayout, layout::CellDirection};
@@ -153,18 +153,13 @@ impl TableStyle {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum ColumnResizeMode {
+    #[default]
     Live,
     Deferred,
 }
 
-impl Default for ColumnResizeMode {
-    fn default() -> Self {
-        ColumnResizeMode::Live
-    }
-}
-
 #[derive(Clone, Copy, Debug, Default)]
 pub struct TableResizeInfo {
     pub active: bool,
@@ -369,6 +364,7 @@ struct TableScrollOptions {
     auto_shrink: Vec2b,
     scroll_bar_visibility: ScrollBarVisibility,
     animated: bool,
+    minimap: Option<Minimap>,
 }
 
 impl Default for TableScrollOptions {
@@ -385,6 +381,7 @@ impl Default for TableScrollOptions {
             auto_shrink: Vec2b::TRUE,
             scroll_bar_visibility: ScrollBarVisibility::VisibleWhenNeeded,
             animated: true,
+            minimap: None,
         }
     }
 }
@@ -437,9 +434,12 @@ pub struct TableBuilder<'a> {
     scroll_options: TableScrollOptions,
     sense: egui::Sense,
     style: TableStyle,
-    scroll_bar_companion: Option<Box<dyn FnOnce(&mut Ui, f32) -> f32 + 'a>>,
+    scroll_bar_companion: Option<ScrollBarCompanion<'a>>,
 }
 
+/// Shown next to the horizontal scroll bar, see [`TableBuilder::scroll_bar_companion`].
+type ScrollBarCompanion<'a> = Box<dyn FnOnce(&mut Ui, f32) -> f32 + 'a>;
+
 impl<'a> TableBuilder<'a> {
     pub fn new(ui: &'a mut Ui) -> Self {
         let cell_layout = *ui.layout();
@@ -633,6 +633,20 @@ impl<'a> TableBuilder<'a> {
         self
     }
 
+    /// Show a [`Minimap`] of the body instead of the vertical scroll bar.
+    ///
+    /// Only the rows that are laid out show up in the preview, so for large tables
+    /// you probably want to turn it off with [`Minimap::preview`] and use markers instead.
+    ///
+    /// Default: `None`.
+    ///
+    /// See [`ScrollArea::minimap`] for more.
+    #[inline]
+    pub fn minimap(mut self, minimap: impl Into<Option<Minimap>>) -> Self {
+        self.scroll_options.minimap = minimap.into();
+        self
+    }
+
     /// What layout should we use for the individual cells?
     #[inline]
     pub fn cell_layout(mut self, cell_layout: egui::Layout) -> Self {
@@ -831,16 +845,16 @@ impl<'a> TableBuilder<'a> {
                                     state.column_widths[i] = new_width;
                                 }
                             } else {
-                                if resize_response.drag_started() {
-                                    if let Some(pos) = pointer_pos {
-                                        resize_preview.active = true;
-                                        resize_preview.column = Some(i);
-                                        resize_preview.start_width = header_widths[i];
-                                        resize_preview.start_pointer_x = pos.x;
-                                        resize_preview.start_handle_x = resize_x;
-                                        resize_preview.pending_width = header_widths[i];
-                                        resize_preview.preview_x = resize_x;
-                                    }
+                                if resize_response.drag_started()
+                                    && let Some(pos) = pointer_pos
+                                {
+                                    resize_preview.active = true;
+                                    resize_preview.column = Some(i);
+                                    resize_preview.start_width = header_widths[i];
+                                    resize_preview.start_pointer_x = pos.x;
+                                    resize_preview.start_handle_x = resize_x;
+                                    resize_preview.pending_width = header_widths[i];
+                                    resize_preview.preview_x = resize_x;
                                 }
 
                                 if resize_preview.active && resize_preview.column == Some(i) {
@@ -944,7 +958,6 @@ impl<'a> TableBuilder<'a> {
             header_bottom,
             state_id,
             columns,
-            available_width,
             state,
             max_used_widths,
             is_sizing_pass,
@@ -992,7 +1005,7 @@ impl<'a> TableBuilder<'a> {
             available_width
         };
 
-        let (is_sizing_pass, mut state) = TableState::load(
+        let (is_sizing_pass, state) = TableState::load(
             ui,
             state_id,
             resizable,
@@ -1009,7 +1022,6 @@ impl<'a> TableBuilder<'a> {
             header_bottom: None,
             state_id,
             columns,
-            available_width,
             state,
             max_used_widths,
             is_sizing_pass,
@@ -1148,7 +1160,6 @@ pub struct Table<'a> {
     header_bottom: Option<f32>,
     state_id: egui::Id,
     columns: Vec<Column>,
-    available_width: f32,
     state: TableState,
 
     /// Accumulated maximum used widths for each column.
@@ -1168,7 +1179,7 @@ pub struct Table<'a> {
 
     /// Custom styling options.
     style: TableStyle,
-    scroll_bar_companion: Option<Box<dyn FnOnce(&mut Ui, f32) -> f32 + 'a>>,
+    scroll_bar_companion: Option<ScrollBarCompanion<'a>>,
 }
 
 impl Table<'_> {
@@ -1193,7 +1204,6 @@ impl Table<'_> {
             resizable,
             resizable_body,
             resize_mode,
-            mut available_width,
             mut state,
             mut max_used_widths,
             is_sizing_pass,
@@ -1217,10 +1227,9 @@ impl Table<'_> {
             auto_shrink,
             scroll_bar_visibility,
             animated,
+            minimap,
         } = scroll_options;
 
-        let cursor_position = ui.cursor().min;
-
         // Calculate fixed columns width for horizontal scrollbar offset
         let spacing_x = 0.0; // Force zero spacing
         let mut fixed_columns_width_for_scrollbar = 0.0;
@@ -1232,23 +1241,24 @@ impl Table<'_> {
 
         let mut companion_width = 0.0;
 
-        if hscroll {
-            if let Some(companion) = scroll_bar_companion {
-                let available_rect = ui.available_rect_before_wrap();
-                let sb_allocated_width = ui.spacing().scroll.allocated_width();
-                let bottom = available_rect.bottom();
-                let left = available_rect.left();
-
-                let rect = egui::Rect::from_min_size(
-                    egui::pos2(left, bottom - sb_allocated_width),
-                    egui::vec2(available_rect.width(), sb_allocated_width),
-                );
-
-                let mut child_ui =
-                    ui.child_ui(rect, egui::Layout::left_to_right(egui::Align::Center), None);
-                companion_width = companion(&mut child_ui, available_rect.width());
-                println!("[Table::body] companion_width={}", companion_width);
-            }
+        if hscroll && let Some(companion) = scroll_bar_companion {
+            let available_rect = ui.available_rect_before_wrap();
+            let sb_allocated_width = ui.spacing().scroll.allocated_width();
+            let bottom = available_rect.bottom();
+            let left = available_rect.left();
+
+            let rect = egui::Rect::from_min_size(
+                egui::pos2(left, bottom - sb_allocated_width),
+                egui::vec2(available_rect.width(), sb_allocated_width),
+            );
+
+            let mut child_ui = ui.new_child(
+                egui::UiBuilder::new()
+                    .max_rect(rect)
+                    .layout(egui::Layout::left_to_right(egui::Align::Center)),
+            );
+            companion_width = companion(&mut child_ui, available_rect.width());
+            println!("[Table::body] companion_width={}", companion_width);
         }
 
         let mut scroll_area = ScrollArea::new([hscroll, vscroll])
@@ -1262,7 +1272,8 @@ impl Table<'_> {
             .max_height(max_scroll_height)
             .auto_shrink(auto_shrink)
             .scroll_bar_visibility(scroll_bar_visibility)
-            .animated(animated);
+            .animated(animated)
+            .minimap(minimap);
 
         if let Some(scroll_offset_y) = scroll_offset_y {
             scroll_area = scroll_area.vertical_scroll_offset(scroll_offset_y);
@@ -1453,16 +1464,16 @@ impl Table<'_> {
                                     }
                                 }
                             } else {
-                                if resize_response.drag_started() {
-                                    if let Some(pos) = pointer_pos {
-                                        resize_preview.active = true;
-                                        resize_preview.column = Some(i);
-                                        resize_preview.start_width = *column_width;
-                                        resize_preview.start_pointer_x = pos.x;
-                                        resize_preview.start_handle_x = resize_x;
-                                        resize_preview.pending_width = *column_width;
-                                        resize_preview.preview_x = resize_x;
-                                    }
+                                if resize_response.drag_started()
+                                    && let Some(pos) = pointer_pos
+                                {
+                                    resize_preview.active = true;
+                                    resize_preview.column = Some(i);
+                                    resize_preview.start_width = *column_width;
+                                    resize_preview.start_pointer_x = pos.x;
+                                    resize_preview.start_handle_x = resize_x;
+                                    resize_preview.pending_width = *column_width;
+                                    resize_preview.preview_x = resize_x;
                                 }
 
                                 if resize_preview.active && resize_preview.column == Some(i) {
@@ -1575,7 +1586,7 @@ impl Table<'_> {
                                 // Check if pointer is in the header resize rect (in screen coordinates)
                                 let pointer_pos = ui.ctx().input(|i| i.pointer.hover_pos());
                                 let pointer_in_rect =
-                                    pointer_pos.map_or(false, |pos| interact_rect.contains(pos));
+                                    pointer_pos.is_some_and(|pos| interact_rect.contains(pos));
 
                                 // Track drag state per column in frame-persistent data
                                 let drag_key = column_resize_id.with("header_drag");
@@ -1587,13 +1598,8 @@ impl Table<'_> {
                                     ui.ctx().input(|i| i.pointer.primary_pressed());
 
                                 // Start drag on press in rect
-                                let is_dragging = if primary_pressed && pointer_in_rect {
-                                    true
-                                } else if was_dragging && primary_down {
-                                    true
-                                } else {
-                                    false
-                                };
+                                let is_dragging = (primary_pressed && pointer_in_rect)
+                                    || (was_dragging && primary_down);
 
                                 ui.data_mut(|d| d.insert_temp(drag_key, is_dragging));
 
@@ -1627,35 +1633,35 @@ impl Table<'_> {
                                         }
                                     }
                                 } else {
-                                    if primary_pressed && pointer_in_rect {
-                                        if let Some(pos) = pointer_pos {
-                                            resize_preview.active = true;
-                                            resize_preview.column = Some(i);
-                                            resize_preview.start_width = *column_width;
-                                            resize_preview.start_pointer_x = pos.x;
-                                            resize_preview.start_handle_x = resize_x;
-                                            resize_preview.pending_width = *column_width;
-                                            resize_preview.preview_x = resize_x;
-                                        }
+                                    if primary_pressed
+                                        && pointer_in_rect
+                                        && let Some(pos) = pointer_pos
+                                    {
+                                        resize_preview.active = true;
+                                        resize_preview.column = Some(i);
+                                        resize_preview.start_width = *column_width;
+                                        resize_preview.start_pointer_x = pos.x;
+                                        resize_preview.start_handle_x = resize_x;
+                                        resize_preview.pending_width = *column_width;
+                                        resize_preview.preview_x = resize_x;
                                     }
 
                                     if is_dragging
                                         && resize_preview.active
                                         && resize_preview.column == Some(i)
+                                        && let Some(pos) = pointer_pos
                                     {
-                                        if let Some(pos) = pointer_pos {
-                                            let delta = pos.x - resize_preview.start_pointer_x;
-                                            let mut new_width =
-                                                resize_preview.start_width + delta;
-                                            if !column.clip {
-                                                new_width =
-                                                    new_width.at_least(max_used_widths_ref[i]);
-                                            }
-                                            resize_preview.pending_width =
-                                                width_range.clamp(new_width);
-                                            resize_preview.preview_x =
-                                                resize_preview.start_handle_x + delta;
+                                        let delta = pos.x - resize_preview.start_pointer_x;
+                                        let mut new_width =
+                                            resize_preview.start_width + delta;
+                                        if !column.clip {
+                                            new_width =
+                                                new_width.at_least(max_used_widths_ref[i]);
                                         }
+                                        resize_preview.pending_width =
+                                            width_range.clamp(new_width);
+                                        resize_preview.preview_x =
+                                            resize_preview.start_handle_x + delta;
                                     }
 
                                     if was_dragging
@@ -1716,8 +1722,6 @@ impl Table<'_> {
                         // ui.painter().with_clip_rect(clip_rect).line_segment([p0, p1], stroke);
                     }
                 }
-
-                available_width -= *column_width + spacing_x;
             }
 
             ui.data_mut(|d| {
@@ -2192,8 +2196,6 @@ impl TableRow<'_, '_> {
             selection_stroke: self.style.selection_stroke,
         };
 
-        let scroll_offset_x = self.scroll_offset_x;
-        let fixed_columns_width = self.fixed_columns_width;
 
         let (used_rect, response) = self.layout.add(
             flags,
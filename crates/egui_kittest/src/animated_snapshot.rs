@@ -0,0 +1,362 @@
+//! Snapshot tests of animations: a sequence of frames, stored as an animated PNG (APNG).
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use image::{
+    AnimationDecoder as _, DynamicImage, ImageError, ImageFormat, RgbaImage,
+    codecs::png::PngDecoder,
+    error::{EncodingError, ImageFormatHint},
+};
+
+use crate::snapshot::Mode;
+use crate::{Harness, SnapshotError, SnapshotOptions, SnapshotResult};
+
+/// Animated snapshot test with custom options.
+///
+/// The frames are compared one by one, with the same [`SnapshotOptions::threshold`] and
+/// [`SnapshotOptions::failed_pixel_count_threshold`] for each frame.
+///
+/// The snapshot will be saved as an animated PNG under `{output_path}/{name}.png`,
+/// with `step_dt` seconds between frames.
+/// The new frames from the most recent test run will be saved under `{output_path}/{name}.new.png`.
+/// If the frames didn't match the snapshot, the diff image of the first differing frame
+/// will be saved under `{output_path}/{name}.diff.png`.
+///
+/// If the env-var `UPDATE_SNAPSHOTS` is set, then the old animation will backed up under `{output_path}/{name}.old.png`.
+/// and then new animation will be written to `{output_path}/{name}.png`
+///
+/// # Errors
+/// Returns a [`SnapshotError`] if the frames do not match the snapshot or if there was an error
+/// reading or writing the snapshot.
+pub fn try_animated_image_snapshot_options(
+    frames: &[RgbaImage],
+    step_dt: f32,
+    name: impl Into<String>,
+    options: &SnapshotOptions,
+) -> SnapshotResult {
+    try_animated_image_snapshot_options_impl(frames, step_dt, name.into(), options)
+}
+
+fn try_animated_image_snapshot_options_impl(
+    frames: &[RgbaImage],
+    step_dt: f32,
+    name: String,
+    options: &SnapshotOptions,
+) -> SnapshotResult {
+    #![expect(clippy::print_stdout)]
+
+    let mode = Mode::from_env();
+
+    let SnapshotOptions {
+        threshold,
+        output_path,
+        failed_pixel_count_threshold,
+    } = options;
+
+    let parent_path = if let Some(parent) = PathBuf::from(&name).parent() {
+        output_path.join(parent)
+    } else {
+        output_path.clone()
+    };
+    std::fs::create_dir_all(parent_path).ok();
+
+    // The one that is checked in to git
+    let snapshot_path = output_path.join(format!("{name}.png"));
+
+    // These should be in .gitignore:
+    let diff_path = output_path.join(format!("{name}.diff.png"));
+    let old_backup_path = output_path.join(format!("{name}.old.png"));
+    let new_path = output_path.join(format!("{name}.new.png"));
+
+    // Delete old temporary files if they exist:
+    std::fs::remove_file(&diff_path).ok();
+    std::fs::remove_file(&old_backup_path).ok();
+    std::fs::remove_file(&new_path).ok();
+
+    let update_snapshot = || {
+        // Keep the old version so the user can compare it:
+        std::fs::rename(&snapshot_path, &old_backup_path).ok();
+
+        // Write the new file to the checked in path:
+        save_apng(frames, step_dt, &snapshot_path).map_err(|err| SnapshotError::WriteSnapshot {
+            err,
+            path: snapshot_path.clone(),
+        })?;
+
+        // No need for an explicit `.new` file:
+        std::fs::remove_file(&new_path).ok();
+
+        println!("Updated snapshot: {}", snapshot_path.display());
+
+        Ok(())
+    };
+
+    let write_new_png = || {
+        save_apng(frames, step_dt, &new_path).map_err(|err| SnapshotError::WriteSnapshot {
+            err,
+            path: new_path.clone(),
+        })
+    };
+
+    let previous = match load_frames(&snapshot_path) {
+        Ok(previous) => previous,
+        Err(err) => {
+            // No previous snapshot - probably a new test.
+            if mode.is_update() {
+                return update_snapshot();
+            } else {
+                write_new_png()?;
+
+                return Err(SnapshotError::OpenSnapshot {
+                    path: snapshot_path.clone(),
+                    err,
+                });
+            }
+        }
+    };
+
+    if previous.len() != frames.len() {
+        if mode.is_update() {
+            return update_snapshot();
+        } else {
+            write_new_png()?;
+
+            return Err(SnapshotError::FrameCountMismatch {
+                name,
+                expected: previous.len(),
+                actual: frames.len(),
+            });
+        }
+    }
+
+    if let (Some(previous), Some(new)) = (previous.first(), frames.first())
+        && previous.dimensions() != new.dimensions()
+    {
+        if mode.is_update() {
+            return update_snapshot();
+        } else {
+            write_new_png()?;
+
+            return Err(SnapshotError::SizeMismatch {
+                name,
+                expected: previous.dimensions(),
+                actual: new.dimensions(),
+            });
+        }
+    }
+
+    // Compare each frame:
+    let threshold = if mode == Mode::UpdateAll {
+        0.0 // Produce diff for any error, however small
+    } else {
+        *threshold
+    };
+
+    let mut first_diff = None;
+    let mut differing_frames = 0;
+    for (frame, (previous, new)) in previous.into_iter().zip(frames).enumerate() {
+        let result =
+            dify::diff::get_results(previous, new.clone(), threshold, true, None, &None, &None);
+        if let Some((num_wrong_pixels, diff_image)) = result
+            && *failed_pixel_count_threshold < num_wrong_pixels.max(0) as usize
+        {
+            differing_frames += 1;
+            if first_diff.is_none() {
+                first_diff = Some((frame, num_wrong_pixels, diff_image));
+            }
+        }
+    }
+
+    let Some((frame, diff, diff_image)) = first_diff else {
+        return Ok(()); // All frames below threshold
+    };
+
+    diff_image
+        .save(diff_path.clone())
+        .map_err(|err| SnapshotError::WriteSnapshot {
+            path: diff_path.clone(),
+            err,
+        })?;
+
+    match mode {
+        Mode::Test => {
+            write_new_png()?;
+
+            Err(SnapshotError::FrameDiff {
+                name,
+                frame,
+                diff,
+                differing_frames,
+                frame_count: frames.len(),
+                diff_path,
+            })
+        }
+        Mode::UpdateFailing | Mode::UpdateAll => update_snapshot(),
+    }
+}
+
+/// Animated snapshot test.
+///
+/// This uses the default [`SnapshotOptions`].
+/// See [`try_animated_image_snapshot_options`] for details.
+///
+/// # Errors
+/// Returns a [`SnapshotError`] if the frames do not match the snapshot or if there was an error
+/// reading or writing the snapshot.
+pub fn try_animated_image_snapshot(
+    frames: &[RgbaImage],
+    step_dt: f32,
+    name: impl Into<String>,
+) -> SnapshotResult {
+    try_animated_image_snapshot_options(frames, step_dt, name, &SnapshotOptions::default())
+}
+
+/// Read all frames of a (possibly animated) PNG.
+fn load_frames(path: &Path) -> Result<Vec<RgbaImage>, ImageError> {
+    let decoder = PngDecoder::new(BufReader::new(File::open(path)?))?;
+    if decoder.is_apng()? {
+        let frames = decoder.apng()?.into_frames().collect_frames()?;
+        Ok(frames
+            .into_iter()
+            .map(|frame| frame.into_buffer())
+            .collect())
+    } else {
+        Ok(vec![DynamicImage::from_decoder(decoder)?.to_rgba8()])
+    }
+}
+
+/// Write the frames as an animated PNG, looping forever.
+fn save_apng(frames: &[RgbaImage], step_dt: f32, path: &Path) -> Result<(), ImageError> {
+    let Some(first) = frames.first() else {
+        return Err(encoding_error("an animation needs at least one frame"));
+    };
+    let (width, height) = first.dimensions();
+
+    let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .set_animated(frames.len() as u32, 0)
+        .map_err(encoding_error)?;
+    let delay_ms = (step_dt * 1000.0).round().clamp(1.0, u16::MAX as f32) as u16;
+    encoder
+        .set_frame_delay(delay_ms, 1000)
+        .map_err(encoding_error)?;
+
+    let mut writer = encoder.write_header().map_err(encoding_error)?;
+    for frame in frames {
+        if frame.dimensions() != (width, height) {
+            return Err(encoding_error("all frames must have the same size"));
+        }
+        writer
+            .write_image_data(frame.as_raw())
+            .map_err(encoding_error)?;
+    }
+    writer.finish().map_err(encoding_error)
+}
+
+fn encoding_error(
+    err: impl Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+) -> ImageError {
+    ImageError::Encoding(EncodingError::new(
+        ImageFormatHint::Exact(ImageFormat::Png),
+        err,
+    ))
+}
+
+impl<State> Harness<'_, State> {
+    /// Render `count` frames, calling [`Harness::step`] between them.
+    ///
+    /// The first frame is the current state, so no step is run before it.
+    /// Each step advances the time by [`crate::HarnessBuilder::with_step_dt`],
+    /// so animations progress at a fixed rate.
+    ///
+    /// # Errors
+    /// Returns an error if the rendering fails.
+    pub fn render_frames(&mut self, count: usize) -> Result<Vec<RgbaImage>, String> {
+        let mut frames = Vec::with_capacity(count);
+        for i in 0..count {
+            if i > 0 {
+                self.step();
+            }
+            frames.push(self.render()?);
+        }
+        Ok(frames)
+    }
+
+    /// Render `count` frames with [`Harness::render_frames`], and compare them to the
+    /// animated snapshot with custom options.
+    ///
+    /// These options will override the ones set by [`crate::HarnessBuilder::with_options`].
+    ///
+    /// The snapshot will be saved as an animated PNG under `{output_path}/{name}.png`.
+    /// The new frames from the most recent test run will be saved under `{output_path}/{name}.new.png`.
+    /// If the frames didn't match the snapshot, the diff image of the first differing frame
+    /// will be saved under `{output_path}/{name}.diff.png`.
+    ///
+    /// # Errors
+    /// Returns a [`SnapshotError`] if the frames do not match the snapshot, if there was an
+    /// error reading or writing the snapshot, if the rendering fails or if no default renderer is available.
+    pub fn try_animated_snapshot_options(
+        &mut self,
+        name: impl Into<String>,
+        count: usize,
+        options: &SnapshotOptions,
+    ) -> SnapshotResult {
+        let frames = self
+            .render_frames(count)
+            .map_err(|err| SnapshotError::RenderError { err })?;
+        try_animated_image_snapshot_options(&frames, self.step_dt, name, options)
+    }
+
+    /// Render `count` frames and compare them to the animated snapshot.
+    ///
+    /// This is like [`Self::try_animated_snapshot_options`] but will use the options set by [`crate::HarnessBuilder::with_options`].
+    ///
+    /// # Errors
+    /// Returns a [`SnapshotError`] if the frames do not match the snapshot, if there was an
+    /// error reading or writing the snapshot, if the rendering fails or if no default renderer is available.
+    pub fn try_animated_snapshot(
+        &mut self,
+        name: impl Into<String>,
+        count: usize,
+    ) -> SnapshotResult {
+        let options = self.default_snapshot_options.clone();
+        self.try_animated_snapshot_options(name, count, &options)
+    }
+
+    /// Render `count` frames and compare them to the animated snapshot with custom options.
+    ///
+    /// See [`Self::try_animated_snapshot_options`].
+    ///
+    /// # Panics
+    /// The result is added to the [`Harness`]'s internal [`crate::SnapshotResults`].
+    ///
+    /// The harness will panic when dropped if there were any snapshot errors.
+    #[track_caller]
+    pub fn animated_snapshot_options(
+        &mut self,
+        name: impl Into<String>,
+        count: usize,
+        options: &SnapshotOptions,
+    ) {
+        let result = self.try_animated_snapshot_options(name, count, options);
+        self.snapshot_results.add(result);
+    }
+
+    /// Render `count` frames and compare them to the animated snapshot.
+    ///
+    /// This is like [`Self::animated_snapshot_options`] but will use the options set by [`crate::HarnessBuilder::with_options`].
+    ///
+    /// # Panics
+    /// The result is added to the [`Harness`]'s internal [`crate::SnapshotResults`].
+    ///
+    /// The harness will panic when dropped if there were any snapshot errors.
+    #[track_caller]
+    pub fn animated_snapshot(&mut self, name: impl Into<String>, count: usize) {
+        let result = self.try_animated_snapshot(name, count);
+        self.snapshot_results.add(result);
+    }
+}
@@ -15,6 +15,7 @@ pub struct HarnessBuilder<State = ()> {
     pub(crate) state: PhantomData<State>,
     pub(crate) renderer: Box<dyn TestRenderer>,
     pub(crate) wait_for_pending_images: bool,
+    pub(crate) multiple_viewports: bool,
 
     #[cfg(feature = "snapshot")]
     pub(crate) default_snapshot_options: crate::SnapshotOptions,
@@ -31,6 +32,7 @@ impl<State> Default for HarnessBuilder<State> {
             max_steps: 4,
             step_dt: 1.0 / 4.0,
             wait_for_pending_images: true,
+            multiple_viewports: false,
             os: egui::os::OperatingSystem::Nix,
 
             #[cfg(feature = "snapshot")]
@@ -119,6 +121,25 @@ impl<State> HarnessBuilder<State> {
         self
     }
 
+    /// Show viewports as separate viewports, like a native backend would.
+    ///
+    /// By default, [`egui::Context::show_viewport_immediate`] and [`egui::Context::show_viewport_deferred`]
+    /// show their viewport embedded as a window in the root viewport.
+    ///
+    /// With this, immediate viewports run within the pass of their parent, and
+    /// deferred viewports run after the root viewport in each step.
+    /// Each viewport then has its own input, output, AccessKit tree and rendered image.
+    /// See e.g. [`Harness::viewport_root`] and [`Harness::render_viewport`].
+    ///
+    /// New viewports get the size of [`egui::ViewportBuilder::inner_size`], or else the size of the harness.
+    ///
+    /// Default: `false`
+    #[inline]
+    pub fn with_multiple_viewports(mut self, multiple_viewports: bool) -> Self {
+        self.multiple_viewports = multiple_viewports;
+        self
+    }
+
     /// Set the [`TestRenderer`] to use for rendering.
     ///
     /// By default, a [`LazyRenderer`] is used.
@@ -128,6 +149,14 @@ impl<State> HarnessBuilder<State> {
         self
     }
 
+    /// Render on the CPU with a [`crate::SoftwareTestRenderer`].
+    ///
+    /// This needs no GPU and gives the same images on every machine.
+    #[cfg(any(feature = "wgpu", feature = "snapshot"))]
+    pub fn software(self) -> Self {
+        self.renderer(crate::SoftwareTestRenderer::new())
+    }
+
     /// Enable wgpu rendering with a default setup suitable for testing.
     ///
     /// This sets up a [`crate::wgpu::WgpuTestRenderer`] with the default setup.
@@ -0,0 +1,424 @@
+//! Measure how much work each frame does, and assert that it stays within a budget.
+
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+
+use egui::Shape;
+use egui::epaint::{ImageDelta, stats::PaintStats};
+
+use crate::Harness;
+
+/// How much work a single frame did, see [`Harness::profile_frames`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FrameStats {
+    /// Time spent in [`egui::Context::run_ui`], i.e. running the ui code, including layout and text layout.
+    ///
+    /// If the frame needed several passes, this is the sum of all of them.
+    /// Handling the output (e.g. uploading textures to the renderer) is not included.
+    pub layout_time: Duration,
+
+    /// Time spent turning the shapes into triangles.
+    pub tessellation_time: Duration,
+
+    /// Number of shapes painted, including those nested in [`egui::Shape::Vec`].
+    pub num_shapes: usize,
+
+    /// Number of text shapes, including those nested in [`egui::Shape::Vec`].
+    pub num_text_shapes: usize,
+
+    /// Number of meshes (one per clip rectangle and texture) after tessellation.
+    pub num_primitives: usize,
+
+    /// Number of vertices after tessellation.
+    pub num_vertices: usize,
+
+    /// Number of indices after tessellation.
+    pub num_indices: usize,
+
+    /// Number of textures that were created or changed (including the font atlas).
+    pub num_texture_uploads: usize,
+
+    /// Number of bytes in the created or changed textures.
+    pub texture_upload_bytes: usize,
+
+    /// Number of texts that had to be laid out because they weren't cached from the previous frame.
+    pub galley_cache_misses: u64,
+}
+
+impl FrameStats {
+    fn cpu_time(&self) -> Duration {
+        self.layout_time + self.tessellation_time
+    }
+}
+
+/// The [`FrameStats`] of a sequence of frames, from [`Harness::profile_frames`].
+///
+/// Print it to get a table of all frames, or check it against a [`FrameBudget`]:
+/// ```
+/// # use egui_kittest::{Harness, FrameBudget};
+/// let mut harness = Harness::new_ui(|ui| {
+///     ui.label("Hello world!");
+/// });
+///
+/// harness.profile_frames(5).assert_within(
+///     &FrameBudget::default()
+///         .max_vertices(1_000)
+///         .no_texture_uploads()
+///         .no_galley_cache_misses(),
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FrameProfile {
+    /// The stats of each frame, in the order they ran.
+    pub frames: Vec<FrameStats>,
+}
+
+impl FrameProfile {
+    /// The largest value of some statistic over all frames, skipping the first `skip` frames.
+    pub fn max_of<T: Ord + Default>(&self, skip: usize, f: impl Fn(&FrameStats) -> T) -> T {
+        self.frames
+            .iter()
+            .skip(skip)
+            .map(f)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// The average CPU time (layout + tessellation) per frame.
+    pub fn mean_cpu_time(&self) -> Duration {
+        if self.frames.is_empty() {
+            return Duration::ZERO;
+        }
+        self.frames
+            .iter()
+            .map(FrameStats::cpu_time)
+            .sum::<Duration>()
+            / self.frames.len() as u32
+    }
+
+    /// Check that every frame stays within the budget.
+    ///
+    /// # Errors
+    /// Returns every broken limit.
+    pub fn check(&self, budget: &FrameBudget) -> Result<(), FrameBudgetError> {
+        let FrameBudget {
+            warm_up_frames,
+            max_shapes,
+            max_vertices,
+            max_texture_uploads,
+            max_galley_cache_misses,
+            max_cpu_time,
+        } = *budget;
+
+        let mut violations = vec![];
+        for (i, frame) in self.frames.iter().enumerate() {
+            let mut check = |what: &str, value: String, limit: String, broken: bool| {
+                if broken {
+                    violations.push(format!(
+                        "frame {i}: {what} was {value}, the limit is {limit}"
+                    ));
+                }
+            };
+
+            if let Some(max) = max_shapes {
+                check(
+                    "shapes",
+                    frame.num_shapes.to_string(),
+                    max.to_string(),
+                    max < frame.num_shapes,
+                );
+            }
+            if let Some(max) = max_vertices {
+                check(
+                    "vertices",
+                    frame.num_vertices.to_string(),
+                    max.to_string(),
+                    max < frame.num_vertices,
+                );
+            }
+            if let Some(max) = max_cpu_time {
+                check(
+                    "cpu time",
+                    format!("{:.1?}", frame.cpu_time()),
+                    format!("{max:.1?}"),
+                    max < frame.cpu_time(),
+                );
+            }
+
+            if i < warm_up_frames {
+                continue;
+            }
+            if let Some(max) = max_texture_uploads {
+                check(
+                    "texture uploads",
+                    frame.num_texture_uploads.to_string(),
+                    max.to_string(),
+                    max < frame.num_texture_uploads,
+                );
+            }
+            if let Some(max) = max_galley_cache_misses {
+                check(
+                    "galley cache misses",
+                    frame.galley_cache_misses.to_string(),
+                    max.to_string(),
+                    max < frame.galley_cache_misses,
+                );
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(FrameBudgetError {
+                violations,
+                profile: self.clone(),
+            })
+        }
+    }
+
+    /// Assert that every frame stays within the budget.
+    ///
+    /// # Panics
+    /// If any limit is broken, listing all of them followed by the stats of each frame.
+    #[track_caller]
+    pub fn assert_within(&self, budget: &FrameBudget) {
+        if let Err(err) = self.check(budget) {
+            panic!("{err}");
+        }
+    }
+}
+
+impl Display for FrameProfile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "frame    layout  tessellate  shapes  texts  vertices  indices  uploads (bytes)  galley misses"
+        )?;
+        for (i, frame) in self.frames.iter().enumerate() {
+            let FrameStats {
+                layout_time,
+                tessellation_time,
+                num_shapes,
+                num_text_shapes,
+                num_primitives: _,
+                num_vertices,
+                num_indices,
+                num_texture_uploads,
+                texture_upload_bytes,
+                galley_cache_misses,
+            } = frame;
+            writeln!(
+                f,
+                "{i:>5} {:>9} {:>11} {num_shapes:>7} {num_text_shapes:>6} {num_vertices:>9} {num_indices:>8} {:>16} {galley_cache_misses:>14}",
+                format!("{layout_time:.1?}"),
+                format!("{tessellation_time:.1?}"),
+                format!("{num_texture_uploads} ({texture_upload_bytes})"),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Limits for [`FrameProfile::check`].
+///
+/// All limits are off by default.
+/// The limits on texture uploads and galley cache misses don't apply to the first
+/// [`Self::warm_up_frames`] frames, since the first frames usually upload the font atlas
+/// and lay out all text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameBudget {
+    /// How many frames at the start may upload textures and lay out text.
+    pub warm_up_frames: usize,
+
+    /// The maximum [`FrameStats::num_shapes`] of each frame.
+    pub max_shapes: Option<usize>,
+
+    /// The maximum [`FrameStats::num_vertices`] of each frame.
+    pub max_vertices: Option<usize>,
+
+    /// The maximum [`FrameStats::num_texture_uploads`] of each frame after the warm-up.
+    pub max_texture_uploads: Option<usize>,
+
+    /// The maximum [`FrameStats::galley_cache_misses`] of each frame after the warm-up.
+    pub max_galley_cache_misses: Option<u64>,
+
+    /// The maximum CPU time (layout + tessellation) of each frame.
+    pub max_cpu_time: Option<Duration>,
+}
+
+impl Default for FrameBudget {
+    fn default() -> Self {
+        Self {
+            warm_up_frames: 1,
+            max_shapes: None,
+            max_vertices: None,
+            max_texture_uploads: None,
+            max_galley_cache_misses: None,
+            max_cpu_time: None,
+        }
+    }
+}
+
+impl FrameBudget {
+    /// How many frames at the start may upload textures and lay out text.
+    ///
+    /// Default: 1
+    #[inline]
+    pub fn warm_up_frames(mut self, warm_up_frames: usize) -> Self {
+        self.warm_up_frames = warm_up_frames;
+        self
+    }
+
+    /// At most this many shapes per frame.
+    #[inline]
+    pub fn max_shapes(mut self, max_shapes: usize) -> Self {
+        self.max_shapes = Some(max_shapes);
+        self
+    }
+
+    /// At most this many vertices per frame, after tessellation.
+    #[inline]
+    pub fn max_vertices(mut self, max_vertices: usize) -> Self {
+        self.max_vertices = Some(max_vertices);
+        self
+    }
+
+    /// At most this many texture uploads per frame after the warm-up.
+    #[inline]
+    pub fn max_texture_uploads(mut self, max_texture_uploads: usize) -> Self {
+        self.max_texture_uploads = Some(max_texture_uploads);
+        self
+    }
+
+    /// No texture uploads after the warm-up.
+    #[inline]
+    pub fn no_texture_uploads(self) -> Self {
+        self.max_texture_uploads(0)
+    }
+
+    /// At most this many text layouts per frame after the warm-up.
+    #[inline]
+    pub fn max_galley_cache_misses(mut self, max_galley_cache_misses: u64) -> Self {
+        self.max_galley_cache_misses = Some(max_galley_cache_misses);
+        self
+    }
+
+    /// No text layouts after the warm-up, i.e. all text is cached.
+    #[inline]
+    pub fn no_galley_cache_misses(self) -> Self {
+        self.max_galley_cache_misses(0)
+    }
+
+    /// At most this much CPU time (layout + tessellation) per frame.
+    ///
+    /// Timings vary a lot between machines and runs, so use a generous limit.
+    #[inline]
+    pub fn max_cpu_time(mut self, max_cpu_time: Duration) -> Self {
+        self.max_cpu_time = Some(max_cpu_time);
+        self
+    }
+}
+
+/// The limits of a [`FrameBudget`] that were broken.
+#[derive(Clone, Debug)]
+pub struct FrameBudgetError {
+    /// A description of each broken limit, e.g. `frame 2: vertices was 1200, the limit is 1000`.
+    pub violations: Vec<String>,
+
+    /// The profile that was checked.
+    pub profile: FrameProfile,
+}
+
+impl Display for FrameBudgetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Frame budget exceeded:")?;
+        for violation in &self.violations {
+            writeln!(f, "  {violation}")?;
+        }
+        write!(f, "\n{}", self.profile)
+    }
+}
+
+impl std::error::Error for FrameBudgetError {}
+
+impl<State> Harness<'_, State> {
+    /// Run `count` frames and measure each of them.
+    ///
+    /// Unlike [`Harness::step`], this runs exactly one frame per measurement and doesn't
+    /// handle queued events, so call [`Harness::run`] first if there are any.
+    ///
+    /// For each frame this measures the time to run the ui, tessellates the shapes
+    /// (which the harness otherwise only does when rendering), and counts the
+    /// shapes, vertices, texture uploads and text layouts.
+    /// Only the root viewport is measured.
+    pub fn profile_frames(&mut self, count: usize) -> FrameProfile {
+        let frames = (0..count).map(|_| self.profile_frame()).collect();
+        FrameProfile { frames }
+    }
+
+    fn profile_frame(&mut self) -> FrameStats {
+        let misses_before = self.ctx.fonts(|fonts| fonts.num_galley_cache_misses());
+
+        let layout_time = self._step(false);
+
+        let galley_cache_misses = self
+            .ctx
+            .fonts(|fonts| fonts.num_galley_cache_misses())
+            .saturating_sub(misses_before);
+
+        let shapes = self.output.shapes.clone();
+        let mut shape_counts = ShapeCounts::default();
+        for clipped_shape in &shapes {
+            shape_counts.add(&clipped_shape.shape);
+        }
+        let start = Instant::now();
+        let primitives = self.ctx.tessellate(shapes, self.output.pixels_per_point);
+        let tessellation_time = start.elapsed();
+        let paint_stats = PaintStats::default().with_clipped_primitives(&primitives);
+
+        let delta = &self.output.textures_delta;
+        FrameStats {
+            layout_time,
+            tessellation_time,
+            num_shapes: shape_counts.shapes,
+            num_text_shapes: shape_counts.texts,
+            num_primitives: paint_stats.clipped_primitives.num_elements(),
+            num_vertices: paint_stats.vertices.num_elements(),
+            num_indices: paint_stats.indices.num_elements(),
+            num_texture_uploads: delta.set.len(),
+            texture_upload_bytes: delta
+                .set
+                .iter()
+                .map(|(_, ImageDelta { image, .. })| {
+                    image.width() * image.height() * image.bytes_per_pixel()
+                })
+                .sum(),
+            galley_cache_misses,
+        }
+    }
+}
+
+/// [`PaintStats`] can't count shapes of mixed kinds, so we count them here.
+#[derive(Default)]
+struct ShapeCounts {
+    shapes: usize,
+    texts: usize,
+}
+
+impl ShapeCounts {
+    fn add(&mut self, shape: &Shape) {
+        match shape {
+            Shape::Vec(shapes) => {
+                for shape in shapes {
+                    self.add(shape);
+                }
+            }
+            Shape::Noop => {}
+            Shape::Text(_) => {
+                self.shapes += 1;
+                self.texts += 1;
+            }
+            _ => self.shapes += 1,
+        }
+    }
+}
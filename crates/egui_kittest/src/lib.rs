@@ -11,21 +11,41 @@ mod snapshot;
 #[cfg(feature = "snapshot")]
 pub use crate::snapshot::*;
 
+#[cfg(feature = "snapshot")]
+mod animated_snapshot;
+
+#[cfg(feature = "snapshot")]
+pub use crate::animated_snapshot::*;
+
+#[cfg(feature = "snapshot")]
+mod text_snapshot;
+
+#[cfg(feature = "snapshot")]
+pub use crate::text_snapshot::*;
+
 mod app_kind;
 mod config;
+mod frame_stats;
 mod node;
+mod query;
 mod renderer;
+#[cfg(any(feature = "wgpu", feature = "snapshot"))]
+mod software;
 #[cfg(feature = "wgpu")]
 mod texture_to_image;
+mod viewports;
 #[cfg(feature = "wgpu")]
 pub mod wgpu;
 
 // re-exports:
 pub use {
-    self::{builder::*, node::*, renderer::*},
+    self::{builder::*, frame_stats::*, node::*, query::*, renderer::*},
     kittest,
 };
 
+#[cfg(any(feature = "wgpu", feature = "snapshot"))]
+pub use self::software::SoftwareTestRenderer;
+
 use std::{
     fmt::{Debug, Display, Formatter},
     time::Duration,
@@ -33,12 +53,14 @@ use std::{
 
 use egui::{
     Color32, Key, Modifiers, PointerButton, Pos2, Rect, RepaintCause, Shape, Vec2, ViewportId,
+    accessibility_audit::AccessibilityIssue,
     epaint::{ClippedShape, RectShape},
     style::ScrollAnimation,
 };
 use kittest::Queryable;
 
 use crate::app_kind::AppKind;
+use crate::viewports::Viewports;
 
 #[derive(Debug, Clone)]
 pub struct ExceededMaxStepsError {
@@ -82,6 +104,7 @@ pub struct Harness<'a, State = ()> {
     step_dt: f32,
     wait_for_pending_images: bool,
     queued_events: EventQueue,
+    viewports: Option<Viewports>,
 
     #[cfg(feature = "snapshot")]
     default_snapshot_options: SnapshotOptions,
@@ -111,8 +134,9 @@ impl<'a, State> Harness<'a, State> {
             max_steps,
             step_dt,
             state: _,
-            mut renderer,
+            renderer,
             wait_for_pending_images,
+            multiple_viewports,
 
             #[cfg(feature = "snapshot")]
             default_snapshot_options,
@@ -134,6 +158,9 @@ impl<'a, State> Harness<'a, State> {
         let viewport = input.viewports.get_mut(&ViewportId::ROOT).unwrap();
         viewport.native_pixels_per_point = Some(pixels_per_point);
 
+        let viewports = multiple_viewports
+            .then(|| Viewports::new(&ctx, screen_rect.size(), pixels_per_point, step_dt));
+
         let mut response = None;
 
         // We need to run egui for a single frame so that the AccessKit state can be initialized
@@ -142,8 +169,6 @@ impl<'a, State> Harness<'a, State> {
             response = app.run(ui, &mut state, false);
         });
 
-        renderer.handle_delta(&output.textures_delta);
-
         let mut harness = Self {
             app,
             ctx,
@@ -155,10 +180,11 @@ impl<'a, State> Harness<'a, State> {
                     .take()
                     .expect("AccessKit was disabled"),
             ),
-            output,
+            output: Default::default(),
             response,
             state,
             renderer,
+            viewports,
             max_steps,
             step_dt,
             wait_for_pending_images,
@@ -170,6 +196,8 @@ impl<'a, State> Harness<'a, State> {
             #[cfg(feature = "snapshot")]
             snapshot_results: SnapshotResults::default(),
         };
+        harness.handle_output(output);
+
         // Run the harness until it is stable, ensuring that all Areas are shown and animations are done
         harness.run_ok();
         harness
@@ -268,31 +296,52 @@ impl<'a, State> Harness<'a, State> {
     /// Run a frame for each queued event (or a single frame if there are no events).
     /// This will call the app closure with each queued event and
     /// update the Harness.
+    ///
+    /// With [`HarnessBuilder::with_multiple_viewports`], the events queued for each viewport
+    /// are handled in the same frames, one event per viewport and frame.
     pub fn step(&mut self) {
-        let events = std::mem::take(&mut *self.queued_events.lock());
-        if events.is_empty() {
-            self._step(false);
-        }
-        for event in events {
+        let mut events = std::mem::take(&mut *self.queued_events.lock()).into_iter();
+        let mut stepped = false;
+        loop {
+            let event = events.next();
+            let viewport_event = self
+                .viewports
+                .as_ref()
+                .is_some_and(Viewports::apply_next_events);
+            if stepped && event.is_none() && !viewport_event {
+                break;
+            }
             match event {
-                EventType::Event(event) => {
+                Some(EventType::Event(event)) => {
                     self.input.events.push(event);
                 }
-                EventType::Modifiers(modifiers) => {
+                Some(EventType::Modifiers(modifiers)) => {
                     self.input.modifiers = modifiers;
                 }
+                None => {}
             }
             self._step(false);
+            stepped = true;
         }
     }
 
     /// Run a single step. This will not process any events.
-    fn _step(&mut self, sizing_pass: bool) {
+    ///
+    /// Returns the time spent running the ui.
+    fn _step(&mut self, sizing_pass: bool) -> std::time::Duration {
         self.input.predicted_dt = self.step_dt;
+        self.run_frame(sizing_pass)
+    }
 
+    /// Run a frame with the current [`Self::input`].
+    ///
+    /// Returns the time spent in [`egui::Context::run_ui`], i.e. without handling the output.
+    fn run_frame(&mut self, sizing_pass: bool) -> std::time::Duration {
+        let start = std::time::Instant::now();
         let mut output = self.ctx.run_ui(self.input.take(), |ui| {
             self.response = self.app.run(ui, &mut self.state, sizing_pass);
         });
+        let ui_time = start.elapsed();
         self.kittest.update(
             output
                 .platform_output
@@ -300,10 +349,73 @@ impl<'a, State> Harness<'a, State> {
                 .take()
                 .expect("AccessKit was disabled"),
         );
-        self.renderer.handle_delta(&output.textures_delta);
+
+        self.handle_output(output);
+        ui_time
+    }
+
+    /// Update the textures of the renderer and run the deferred viewports.
+    fn handle_output(&mut self, output: egui::FullOutput) {
+        if let Some(viewports) = &mut self.viewports {
+            // Immediate viewports ran during the root pass, so their textures come first:
+            let mut outputs = viewports.take_immediate_outputs();
+            for (_, output) in &outputs {
+                self.renderer.handle_delta(&output.textures_delta);
+            }
+            self.renderer.handle_delta(&output.textures_delta);
+
+            for (&id, viewport_output) in &output.viewport_output {
+                if id == ViewportId::ROOT {
+                    continue;
+                }
+                if let Some(deferred_output) =
+                    viewports.run_deferred(&self.ctx, id, viewport_output)
+                {
+                    // A deferred viewport can show immediate viewports too:
+                    for (immediate_id, immediate_output) in viewports.take_immediate_outputs() {
+                        self.renderer.handle_delta(&immediate_output.textures_delta);
+                        outputs.push((immediate_id, immediate_output));
+                    }
+                    self.renderer.handle_delta(&deferred_output.textures_delta);
+                    outputs.push((id, deferred_output));
+                }
+            }
+
+            for command in viewports.update(&output, outputs) {
+                viewports::handle_root_command(&mut self.input, &command);
+            }
+        } else {
+            self.renderer.handle_delta(&output.textures_delta);
+        }
+
         self.output = output;
     }
 
+    /// Run one frame for each frame of the recording, with the recorded input (including the time)
+    /// instead of the input of the harness.
+    ///
+    /// Use this to turn a recording of a bug (made with [`egui::recording::InputRecorder`])
+    /// into a regression test.
+    ///
+    /// ```
+    /// # use egui_kittest::{Harness, kittest::Queryable};
+    /// # let recording = egui::recording::InputRecording::default();
+    /// // let recording = egui::recording::InputRecording::load("tests/recordings/bug.ron")?;
+    /// let mut checked = false;
+    /// let mut harness = Harness::new_ui_state(|ui, checked| {
+    ///     ui.checkbox(checked, "Check me!");
+    /// }, checked);
+    ///
+    /// harness.replay(&recording);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn replay(&mut self, recording: &egui::recording::InputRecording) {
+        for input in &recording.frames {
+            self.input = input.clone();
+            self.run_frame(false);
+        }
+    }
+
     /// Calculate the rect that includes all popups and tooltips.
     fn compute_total_rect_with_popups(&self) -> Option<Rect> {
         // Start with the standard response rect
@@ -375,7 +487,7 @@ impl<'a, State> Harness<'a, State> {
             let wait_for_images = self.wait_for_pending_images && self.ctx.has_pending_images();
 
             // We only care about immediate repaints
-            if self.root_viewport_output().repaint_delay != Duration::ZERO && !wait_for_images {
+            if !self.needs_immediate_repaint() && !wait_for_images {
                 break;
             } else if sleep || wait_for_images {
                 std::thread::sleep(Duration::from_secs_f32(self.step_dt));
@@ -694,6 +806,19 @@ impl<'a, State> Harness<'a, State> {
         self.renderer.render(&self.ctx, &output)
     }
 
+    /// Has any viewport asked to be repainted right away?
+    fn needs_immediate_repaint(&self) -> bool {
+        if let Some(viewports) = &self.viewports {
+            viewports.needs_repaint
+                || viewports
+                    .outputs
+                    .values()
+                    .any(|output| output.repaint_delay == Duration::ZERO)
+        } else {
+            self.root_viewport_output().repaint_delay == Duration::ZERO
+        }
+    }
+
     /// Get the root viewport output
     fn root_viewport_output(&self) -> &egui::ViewportOutput {
         self.output
@@ -714,6 +839,103 @@ impl<'a, State> Harness<'a, State> {
     pub fn node(&self) -> Node<'_> {
         self.root()
     }
+
+    /// The whole AccessKit tree as text, see [`Node::accesskit_tree`].
+    pub fn accesskit_tree(&self) -> String {
+        self.root().accesskit_tree()
+    }
+
+    /// All nodes matching the query, see [`Node::find_all`].
+    pub fn find_all(&self, query: &NodeQuery) -> Vec<Node<'_>> {
+        self.root().find_all(query)
+    }
+
+    /// The node matching the query, if any, see [`Node::try_find`].
+    ///
+    /// # Panics
+    /// If more than one node matches.
+    #[track_caller]
+    pub fn try_find(&self, query: &NodeQuery) -> Option<Node<'_>> {
+        self.root().try_find(query)
+    }
+
+    /// The node matching the query, see [`Node::find`].
+    ///
+    /// # Panics
+    /// If no node or more than one node matches.
+    #[track_caller]
+    pub fn find(&self, query: &NodeQuery) -> Node<'_> {
+        self.root().find(query)
+    }
+
+    /// Step until a node matching the query appears, e.g. after an animation or a background task.
+    ///
+    /// Runs at most `max_steps` steps (see [`Harness::step`]).
+    /// Returns immediately if the node is already there.
+    ///
+    /// # Errors
+    /// If the node didn't appear within `max_steps` steps.
+    #[track_caller]
+    pub fn try_wait_for(
+        &mut self,
+        query: &NodeQuery,
+        max_steps: u64,
+    ) -> Result<Node<'_>, WaitForError> {
+        let mut steps = 0;
+        while self.try_find(query).is_none() {
+            if steps == max_steps {
+                return Err(WaitForError {
+                    steps,
+                    message: self.root().explain_no_match(query),
+                });
+            }
+            self.step();
+            steps += 1;
+        }
+        Ok(self.find(query))
+    }
+
+    /// Step until a node matching the query appears, see [`Harness::try_wait_for`].
+    ///
+    /// # Panics
+    /// If the node didn't appear within `max_steps` steps.
+    #[track_caller]
+    pub fn wait_for(&mut self, query: &NodeQuery, max_steps: u64) -> Node<'_> {
+        match self.try_wait_for(query, max_steps) {
+            Ok(node) => node,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Run the accessibility audit on the current ui, see [`egui::accessibility_audit`].
+    ///
+    /// This turns on [`egui::Options::audit_accessibility`], and runs a step if it wasn't already on.
+    pub fn accessibility_issues(&mut self) -> Vec<AccessibilityIssue> {
+        let was_auditing = self.ctx.options(|options| options.audit_accessibility);
+        if !was_auditing {
+            self.ctx
+                .options_mut(|options| options.audit_accessibility = true);
+            self.step();
+        }
+        self.ctx.accessibility_issues()
+    }
+
+    /// Assert that the accessibility audit finds no issues, see [`Harness::accessibility_issues`].
+    ///
+    /// # Panics
+    /// If there are any issues, listing all of them.
+    #[track_caller]
+    pub fn assert_accessible(&mut self) {
+        let issues = self.accessibility_issues();
+        if !issues.is_empty() {
+            let list: Vec<String> = issues.iter().map(|issue| format!("  {issue}")).collect();
+            panic!(
+                "Found {} accessibility issue(s):\n{}",
+                issues.len(),
+                list.join("\n")
+            );
+        }
+    }
 }
 
 /// Utilities for stateless harnesses.
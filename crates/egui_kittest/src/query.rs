@@ -0,0 +1,683 @@
+//! A richer way to find nodes than kittest's label based queries.
+//!
+//! See [`NodeQuery`].
+
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter, Write as _};
+use std::ops::RangeInclusive;
+
+use egui::accesskit::{Role, Toggled};
+use kittest::{AccessKitNode, NodeT as _};
+
+use crate::Node;
+
+/// Describes the nodes you are looking for: role, label, value and state,
+/// and optionally the node they must be inside of.
+///
+/// All conditions must match.
+/// Use it with [`Node::find`], [`crate::Harness::find`], [`crate::Harness::wait_for`], etc.
+///
+/// ```
+/// # use egui_kittest::{Harness, NodeQuery};
+/// let harness = Harness::new_ui(|ui| {
+///     ui.checkbox(&mut true, "Dark mode");
+/// });
+///
+/// let query = NodeQuery::checkbox("Dark mode").checked(true).enabled(true);
+/// harness.find(&query).assert_checked();
+/// ```
+///
+/// If no node matches, the panic message lists the nodes that almost matched
+/// (same role and label), why they didn't match, and the relevant part of the AccessKit tree.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NodeQuery {
+    role: Option<Role>,
+    label: Option<String>,
+    label_contains: bool,
+    value: Option<String>,
+    toggled: Option<Toggled>,
+    enabled: Option<bool>,
+    selected: Option<bool>,
+    expanded: Option<bool>,
+    focused: Option<bool>,
+    numeric_range: Option<RangeInclusive<f64>>,
+    inside: Option<Box<Self>>,
+}
+
+impl NodeQuery {
+    /// A query matching any node.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A [`Role::Button`] with this exact label.
+    pub fn button(label: impl Into<String>) -> Self {
+        Self::new().role(Role::Button).label(label)
+    }
+
+    /// A [`Role::CheckBox`] with this exact label.
+    pub fn checkbox(label: impl Into<String>) -> Self {
+        Self::new().role(Role::CheckBox).label(label)
+    }
+
+    /// A [`Role::Window`] with this exact title.
+    pub fn window(title: impl Into<String>) -> Self {
+        Self::new().role(Role::Window).label(title)
+    }
+
+    /// The node must have this role.
+    #[inline]
+    pub fn role(mut self, role: Role) -> Self {
+        self.role = Some(role);
+        self
+    }
+
+    /// The label must match exactly.
+    ///
+    /// Like in kittest, the label of a [`Role::Label`] is its value,
+    /// and label nodes that label another matching node are skipped.
+    #[inline]
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self.label_contains = false;
+        self
+    }
+
+    /// The label must contain this text.
+    #[inline]
+    pub fn label_contains(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self.label_contains = true;
+        self
+    }
+
+    /// The (text) value must match exactly, e.g. the text of a [`egui::TextEdit`].
+    #[inline]
+    pub fn value(mut self, value: impl Into<String>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    /// The toggled state must match, e.g. [`Toggled::Mixed`] for an indeterminate checkbox.
+    #[inline]
+    pub fn toggled(mut self, toggled: Toggled) -> Self {
+        self.toggled = Some(toggled);
+        self
+    }
+
+    /// Shorthand for [`Self::toggled`] with [`Toggled::True`] or [`Toggled::False`].
+    #[inline]
+    pub fn checked(self, checked: bool) -> Self {
+        self.toggled(if checked {
+            Toggled::True
+        } else {
+            Toggled::False
+        })
+    }
+
+    /// The node must be enabled (`true`) or disabled (`false`).
+    #[inline]
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = Some(enabled);
+        self
+    }
+
+    /// The node must be selected or not, see [`Node::is_selected`].
+    #[inline]
+    pub fn selected(mut self, selected: bool) -> Self {
+        self.selected = Some(selected);
+        self
+    }
+
+    /// The node must be expanded or collapsed, e.g. a [`egui::CollapsingHeader`].
+    ///
+    /// Nodes that can't be expanded never match.
+    #[inline]
+    pub fn expanded(mut self, expanded: bool) -> Self {
+        self.expanded = Some(expanded);
+        self
+    }
+
+    /// The node must have keyboard focus, or not.
+    #[inline]
+    pub fn focused(mut self, focused: bool) -> Self {
+        self.focused = Some(focused);
+        self
+    }
+
+    /// The numeric value must be within this range, e.g. for a [`egui::Slider`].
+    #[inline]
+    pub fn numeric_value_in(mut self, range: RangeInclusive<f64>) -> Self {
+        self.numeric_range = Some(range);
+        self
+    }
+
+    /// The node must be a descendant of a node matching `ancestor`.
+    #[inline]
+    pub fn inside(mut self, ancestor: Self) -> Self {
+        self.inside = Some(Box::new(ancestor));
+        self
+    }
+
+    /// Does this node match all conditions?
+    pub fn matches(&self, node: &AccessKitNode<'_>) -> bool {
+        self.matches_identity(node) && self.state_mismatches(node).is_empty()
+    }
+
+    /// Does the role and label match?
+    fn matches_identity(&self, node: &AccessKitNode<'_>) -> bool {
+        if self.role.is_some_and(|role| node.role() != role) {
+            return false;
+        }
+        if let Some(label) = &self.label {
+            let Some(node_label) = node_label(node) else {
+                return false;
+            };
+            if self.label_contains {
+                node_label.contains(label.as_str())
+            } else {
+                node_label == *label
+            }
+        } else {
+            true
+        }
+    }
+
+    /// Describes each condition (apart from role and label) that this node doesn't fulfill.
+    fn state_mismatches(&self, node: &AccessKitNode<'_>) -> Vec<String> {
+        let mut mismatches = vec![];
+
+        if let Some(value) = &self.value
+            && node.value().as_ref() != Some(value)
+        {
+            mismatches.push(format!("value is {:?}, expected {value:?}", node.value()));
+        }
+        if let Some(toggled) = self.toggled
+            && node.toggled() != Some(toggled)
+        {
+            mismatches.push(format!(
+                "toggled is {:?}, expected {toggled:?}",
+                node.toggled()
+            ));
+        }
+        if let Some(enabled) = self.enabled
+            && node.is_disabled() == enabled
+        {
+            mismatches.push(if enabled { "is disabled" } else { "is enabled" }.to_owned());
+        }
+        if let Some(selected) = self.selected
+            && is_selected(node) != selected
+        {
+            mismatches.push(
+                if selected {
+                    "is not selected"
+                } else {
+                    "is selected"
+                }
+                .to_owned(),
+            );
+        }
+        if let Some(expanded) = self.expanded
+            && node.data().is_expanded() != Some(expanded)
+        {
+            mismatches.push(match node.data().is_expanded() {
+                None => "can't be expanded".to_owned(),
+                Some(true) => "is expanded".to_owned(),
+                Some(false) => "is collapsed".to_owned(),
+            });
+        }
+        if let Some(focused) = self.focused
+            && node.is_focused() != focused
+        {
+            mismatches.push(
+                if focused {
+                    "is not focused"
+                } else {
+                    "is focused"
+                }
+                .to_owned(),
+            );
+        }
+        if let Some(range) = &self.numeric_range
+            && !node
+                .numeric_value()
+                .is_some_and(|value| range.contains(&value))
+        {
+            mismatches.push(format!(
+                "numeric value is {:?}, expected it in {range:?}",
+                node.numeric_value()
+            ));
+        }
+        if let Some(ancestor) = &self.inside {
+            let mut parent = node.parent();
+            let mut found = false;
+            while let Some(node) = parent {
+                if ancestor.matches(&node) {
+                    found = true;
+                    break;
+                }
+                parent = node.parent();
+            }
+            if !found {
+                mismatches.push(format!("is not inside {ancestor}"));
+            }
+        }
+
+        mismatches
+    }
+}
+
+impl Display for NodeQuery {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            role,
+            label,
+            label_contains,
+            value,
+            toggled,
+            enabled,
+            selected,
+            expanded,
+            focused,
+            numeric_range,
+            inside,
+        } = self;
+
+        let mut states = vec![];
+        if let Some(toggled) = toggled {
+            states.push(match toggled {
+                Toggled::True => "checked",
+                Toggled::False => "unchecked",
+                Toggled::Mixed => "mixed",
+            });
+        }
+        if let Some(enabled) = enabled {
+            states.push(if *enabled { "enabled" } else { "disabled" });
+        }
+        if let Some(selected) = selected {
+            states.push(if *selected { "selected" } else { "unselected" });
+        }
+        if let Some(expanded) = expanded {
+            states.push(if *expanded { "expanded" } else { "collapsed" });
+        }
+        if let Some(focused) = focused {
+            states.push(if *focused { "focused" } else { "unfocused" });
+        }
+        for state in states {
+            write!(f, "{state} ")?;
+        }
+
+        if let Some(role) = role {
+            write!(f, "{role:?}")?;
+        } else {
+            write!(f, "node")?;
+        }
+        if let Some(label) = label {
+            if *label_contains {
+                write!(f, " with label containing {label:?}")?;
+            } else {
+                write!(f, " {label:?}")?;
+            }
+        }
+        if let Some(value) = value {
+            write!(f, " with value {value:?}")?;
+        }
+        if let Some(range) = numeric_range {
+            write!(f, " with numeric value in {range:?}")?;
+        }
+        if let Some(inside) = inside {
+            write!(f, " inside {inside}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The label as used for queries: a [`Role::Label`] stores its text in the value.
+fn node_label(node: &AccessKitNode<'_>) -> Option<String> {
+    if node.role() == Role::Label {
+        node.value()
+    } else {
+        node.label()
+    }
+}
+
+/// egui reports the selected state of e.g. selectable labels as toggled,
+/// so this falls back to that.
+fn is_selected(node: &AccessKitNode<'_>) -> bool {
+    node.is_selected()
+        .unwrap_or_else(|| node.toggled() == Some(Toggled::True))
+}
+
+impl Node<'_> {
+    /// All descendants of this node matching the query, in tree order.
+    pub fn find_all(&self, query: &NodeQuery) -> Vec<Self> {
+        let nodes: Vec<_> = self
+            .children_recursive()
+            .filter(|node| query.matches(&node.accesskit_node))
+            .collect();
+
+        if query.label.is_none() {
+            return nodes;
+        }
+
+        // Like kittest: if a widget is labelled by a label node, only return the widget.
+        let labels: BTreeSet<_> = nodes
+            .iter()
+            .flat_map(|node| node.accesskit_node.labelled_by())
+            .map(|node| node.id())
+            .collect();
+        nodes
+            .into_iter()
+            .filter(|node| !labels.contains(&node.accesskit_node.id()))
+            .collect()
+    }
+
+    /// The descendant matching the query, if any.
+    ///
+    /// # Panics
+    /// If more than one node matches.
+    #[track_caller]
+    pub fn try_find(&self, query: &NodeQuery) -> Option<Self> {
+        let mut nodes = self.find_all(query);
+        if nodes.len() > 1 {
+            let mut message = format!(
+                "Found {} nodes matching {query}, expected at most one:",
+                nodes.len()
+            );
+            for node in &nodes {
+                message += "\n\n";
+                message += &node.context_tree();
+            }
+            panic!("{message}\n\nMake the query more specific, or use find_all instead.");
+        }
+        nodes.pop()
+    }
+
+    /// The descendant matching the query.
+    ///
+    /// # Panics
+    /// If no node or more than one node matches.
+    /// The message explains which nodes almost matched, and shows the surrounding tree.
+    #[track_caller]
+    pub fn find(&self, query: &NodeQuery) -> Self {
+        if let Some(node) = self.try_find(query) {
+            node
+        } else {
+            panic!("{}", self.explain_no_match(query));
+        }
+    }
+
+    /// Explains why no descendant matches the query.
+    pub(crate) fn explain_no_match(&self, query: &NodeQuery) -> String {
+        let mut message = format!("No node found matching {query}.");
+
+        let near_misses: Vec<_> = self
+            .children_recursive()
+            .filter(|node| query.matches_identity(&node.accesskit_node))
+            .collect();
+        if !near_misses.is_empty() {
+            message += "\n\nNodes with the same role and label:";
+            for node in &near_misses {
+                let mismatches = query.state_mismatches(&node.accesskit_node);
+                write!(message, "\n\n{}: {}", summary(node), mismatches.join(", ")).ok();
+                message += "\n";
+                message += &node.context_tree();
+            }
+            return message;
+        }
+
+        // Show where we looked: inside the matching ancestors, or the whole tree.
+        let scopes = query
+            .inside
+            .as_ref()
+            .map(|ancestor| self.find_all(ancestor))
+            .unwrap_or_default();
+        if let Some(ancestor) = &query.inside
+            && scopes.is_empty()
+        {
+            write!(message, " There is no {ancestor}.").ok();
+        }
+        if scopes.is_empty() {
+            message += "\n\nTree:\n";
+            message += &tree_string(self, None);
+        } else {
+            for scope in scopes {
+                message += "\n\nInside:\n";
+                message += &tree_string(&scope, None);
+            }
+        }
+        message
+    }
+
+    /// The subtree of the parent, with this node marked.
+    fn context_tree(&self) -> String {
+        let parent = self.parent().unwrap_or(*self);
+        tree_string(&parent, Some(self.accesskit_node.id()))
+    }
+
+    /// The subtree of this node as text, one node per line, indented by depth.
+    ///
+    /// Each line has the role, label, value, state and bounds (rounded to whole points), e.g.
+    /// `CheckBox "Dark mode" [checked] @ 8,8 120x18`.
+    /// Node ids are left out, so the text is stable across runs.
+    ///
+    /// This is what [`crate::Harness::accesskit_snapshot`] compares.
+    pub fn accesskit_tree(&self) -> String {
+        fn add(out: &mut String, node: &Node<'_>, depth: usize) {
+            write!(out, "{}{}", "  ".repeat(depth), summary(node)).ok();
+            if let Some(rect) = node.accesskit_node.bounding_box() {
+                write!(
+                    out,
+                    " @ {},{} {}x{}",
+                    rect.x0.round(),
+                    rect.y0.round(),
+                    rect.width().round(),
+                    rect.height().round()
+                )
+                .ok();
+            }
+            out.push('\n');
+            for child in node.children() {
+                add(out, &child, depth + 1);
+            }
+        }
+
+        let mut out = String::new();
+        add(&mut out, self, 0);
+        out
+    }
+
+    /// The role of the node.
+    pub fn role(&self) -> Role {
+        self.accesskit_node.role()
+    }
+
+    /// The label of the node.
+    ///
+    /// For a [`Role::Label`], this is its text.
+    pub fn label(&self) -> Option<String> {
+        node_label(&self.accesskit_node)
+    }
+
+    /// The numeric value, e.g. of a [`egui::Slider`] or [`egui::DragValue`].
+    pub fn numeric_value(&self) -> Option<f64> {
+        self.accesskit_node.numeric_value()
+    }
+
+    /// The toggled state, e.g. of a checkbox.
+    pub fn toggled(&self) -> Option<Toggled> {
+        self.accesskit_node.toggled()
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.accesskit_node.is_disabled()
+    }
+
+    /// Is the node selected?
+    ///
+    /// egui reports the selected state of e.g. selectable labels as toggled,
+    /// so this falls back to [`Self::toggled`].
+    pub fn is_selected(&self) -> bool {
+        is_selected(&self.accesskit_node)
+    }
+
+    /// Is the node expanded, e.g. an open [`egui::CollapsingHeader`]?
+    ///
+    /// `None` if the node can't be expanded.
+    pub fn is_expanded(&self) -> Option<bool> {
+        self.accesskit_node.data().is_expanded()
+    }
+
+    #[track_caller]
+    fn assert_query(&self, query: &NodeQuery, what: &str) {
+        let mismatches = query.state_mismatches(&self.accesskit_node);
+        assert!(
+            mismatches.is_empty(),
+            "Expected {} to be {what}, but it {}.\n{}",
+            summary(self),
+            mismatches.join(", "),
+            self.context_tree()
+        );
+    }
+
+    #[track_caller]
+    pub fn assert_enabled(&self) {
+        self.assert_query(&NodeQuery::new().enabled(true), "enabled");
+    }
+
+    #[track_caller]
+    pub fn assert_disabled(&self) {
+        self.assert_query(&NodeQuery::new().enabled(false), "disabled");
+    }
+
+    #[track_caller]
+    pub fn assert_checked(&self) {
+        self.assert_query(&NodeQuery::new().checked(true), "checked");
+    }
+
+    #[track_caller]
+    pub fn assert_unchecked(&self) {
+        self.assert_query(&NodeQuery::new().checked(false), "unchecked");
+    }
+
+    #[track_caller]
+    pub fn assert_selected(&self) {
+        self.assert_query(&NodeQuery::new().selected(true), "selected");
+    }
+
+    #[track_caller]
+    pub fn assert_not_selected(&self) {
+        self.assert_query(&NodeQuery::new().selected(false), "not selected");
+    }
+
+    #[track_caller]
+    pub fn assert_expanded(&self) {
+        self.assert_query(&NodeQuery::new().expanded(true), "expanded");
+    }
+
+    #[track_caller]
+    pub fn assert_collapsed(&self) {
+        self.assert_query(&NodeQuery::new().expanded(false), "collapsed");
+    }
+
+    /// Assert that the numeric value is within the range, e.g. for a [`egui::Slider`].
+    #[track_caller]
+    pub fn assert_value_in(&self, range: RangeInclusive<f64>) {
+        let what = format!("in {range:?}");
+        self.assert_query(&NodeQuery::new().numeric_value_in(range), &what);
+    }
+}
+
+/// One line describing the node, e.g. `CheckBox "Dark mode" [checked, disabled]`.
+pub(crate) fn summary(node: &Node<'_>) -> String {
+    let node = &node.accesskit_node;
+    let mut line = format!("{:?}", node.role());
+    if let Some(label) = node_label(node) {
+        write!(line, " {label:?}").ok();
+    }
+    if node.role() != Role::Label
+        && let Some(value) = node.value()
+    {
+        write!(line, " value={value:?}").ok();
+    }
+    if let Some(value) = node.numeric_value() {
+        write!(line, " value={value}").ok();
+    }
+
+    let mut states = vec![];
+    match node.toggled() {
+        Some(Toggled::True) => states.push("checked"),
+        Some(Toggled::False) => states.push("unchecked"),
+        Some(Toggled::Mixed) => states.push("mixed"),
+        None => {}
+    }
+    if node.is_selected() == Some(true) {
+        states.push("selected");
+    }
+    match node.data().is_expanded() {
+        Some(true) => states.push("expanded"),
+        Some(false) => states.push("collapsed"),
+        None => {}
+    }
+    if node.is_disabled() {
+        states.push("disabled");
+    }
+    if node.is_focused() {
+        states.push("focused");
+    }
+    if node.is_hidden() {
+        states.push("hidden");
+    }
+    if !states.is_empty() {
+        write!(line, " [{}]", states.join(", ")).ok();
+    }
+    line
+}
+
+/// The subtree as an indented outline, one node per line.
+///
+/// The node with the `highlight` id is marked with `>`.
+fn tree_string(root: &Node<'_>, highlight: Option<egui::accesskit::NodeId>) -> String {
+    fn add(
+        out: &mut String,
+        node: &Node<'_>,
+        depth: usize,
+        highlight: Option<egui::accesskit::NodeId>,
+    ) {
+        let marker = if Some(node.accesskit_node.id()) == highlight {
+            "> "
+        } else {
+            "  "
+        };
+        writeln!(out, "{}{marker}{}", "  ".repeat(depth), summary(node)).ok();
+        for child in node.children() {
+            add(out, &child, depth + 1, highlight);
+        }
+    }
+
+    let mut out = String::new();
+    add(&mut out, root, 0, highlight);
+    out
+}
+
+/// Returned by [`crate::Harness::try_wait_for`] if the node didn't appear in time.
+#[derive(Clone, Debug)]
+pub struct WaitForError {
+    /// The number of steps that were run.
+    pub steps: u64,
+
+    /// Why no node matched, with the relevant part of the tree after the last step.
+    pub message: String,
+}
+
+impl Display for WaitForError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Waited {} steps without success. {}",
+            self.steps, self.message
+        )
+    }
+}
+
+impl std::error::Error for WaitForError {}
@@ -18,11 +18,35 @@ pub trait TestRenderer {
         ctx: &egui::Context,
         output: &egui::FullOutput,
     ) -> Result<image::RgbaImage, String>;
+
+    /// Render the output of a single viewport, with the size and `pixels_per_point` of that viewport.
+    ///
+    /// Used by [`crate::Harness::render_viewport`].
+    /// The default implementation can only render the root viewport.
+    ///
+    /// # Errors
+    /// Returns an error if the rendering fails.
+    #[cfg(any(feature = "wgpu", feature = "snapshot"))]
+    fn render_viewport(
+        &mut self,
+        ctx: &egui::Context,
+        output: &egui::FullOutput,
+        viewport_id: egui::ViewportId,
+    ) -> Result<image::RgbaImage, String> {
+        if viewport_id == egui::ViewportId::ROOT {
+            self.render(ctx, output)
+        } else {
+            Err(format!(
+                "This renderer can't render other viewports than the root, got {viewport_id:?}"
+            ))
+        }
+    }
 }
 
 /// A lazy renderer that initializes the renderer on the first render call.
 ///
-/// By default, this will create a wgpu renderer if the wgpu feature is enabled.
+/// By default, this will create a wgpu renderer if the wgpu feature is enabled,
+/// and otherwise a [`crate::SoftwareTestRenderer`] if the snapshot feature is enabled.
 pub enum LazyRenderer {
     Uninitialized {
         texture_ops: Vec<egui::TexturesDelta>,
@@ -37,7 +61,9 @@ impl Default for LazyRenderer {
     fn default() -> Self {
         #[cfg(feature = "wgpu")]
         return Self::new(crate::wgpu::WgpuTestRenderer::new);
-        #[cfg(not(feature = "wgpu"))]
+        #[cfg(all(not(feature = "wgpu"), feature = "snapshot"))]
+        return Self::new(crate::SoftwareTestRenderer::new);
+        #[cfg(not(any(feature = "wgpu", feature = "snapshot")))]
         return Self::Uninitialized {
             texture_ops: Vec::new(),
             builder: None,
@@ -67,6 +93,16 @@ impl TestRenderer for LazyRenderer {
         &mut self,
         ctx: &egui::Context,
         output: &egui::FullOutput,
+    ) -> Result<image::RgbaImage, String> {
+        self.render_viewport(ctx, output, egui::ViewportId::ROOT)
+    }
+
+    #[cfg(any(feature = "wgpu", feature = "snapshot"))]
+    fn render_viewport(
+        &mut self,
+        ctx: &egui::Context,
+        output: &egui::FullOutput,
+        viewport_id: egui::ViewportId,
     ) -> Result<image::RgbaImage, String> {
         match self {
             Self::Uninitialized {
@@ -75,16 +111,16 @@ impl TestRenderer for LazyRenderer {
             } => {
                 let mut renderer = build.take().ok_or({
                     "No default renderer available. \
-                    Enable the wgpu feature or set one via HarnessBuilder::renderer"
+                    Enable the wgpu or snapshot feature or set one via HarnessBuilder::renderer"
                 })?();
                 for delta in texture_ops.drain(..) {
                     renderer.handle_delta(&delta);
                 }
-                let image = renderer.render(ctx, output)?;
+                let image = renderer.render_viewport(ctx, output, viewport_id)?;
                 *self = Self::Initialized { renderer };
                 Ok(image)
             }
-            Self::Initialized { renderer } => renderer.render(ctx, output),
+            Self::Initialized { renderer } => renderer.render_viewport(ctx, output, viewport_id),
         }
     }
 }
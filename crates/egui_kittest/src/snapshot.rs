@@ -237,6 +237,57 @@ pub enum SnapshotError {
         actual: (u32, u32),
     },
 
+    /// A frame of an animated snapshot did not match
+    FrameDiff {
+        /// Name of the test
+        name: String,
+
+        /// Index of the first frame that did not match.
+        frame: usize,
+
+        /// Count of pixels that were different in that frame (above the per-pixel threshold).
+        diff: i32,
+
+        /// How many frames did not match in total.
+        differing_frames: usize,
+
+        /// Number of frames in the animation.
+        frame_count: usize,
+
+        /// Path where the diff image of the first differing frame was saved
+        diff_path: PathBuf,
+    },
+
+    /// The number of frames of an animated snapshot did not match
+    FrameCountMismatch {
+        /// Name of the test
+        name: String,
+
+        /// Expected number of frames
+        expected: usize,
+
+        /// Actual number of frames
+        actual: usize,
+    },
+
+    /// A text snapshot did not match
+    TextDiff {
+        /// Name of the test
+        name: String,
+
+        /// The first line (1-based) that differs.
+        line: usize,
+
+        /// That line in the snapshot, if the snapshot has that many lines.
+        expected: Option<String>,
+
+        /// That line in the new text, if the new text has that many lines.
+        actual: Option<String>,
+
+        /// Path where the new text was saved
+        new_path: PathBuf,
+    },
+
     /// Error writing the snapshot output
     WriteSnapshot {
         /// Path where a file was expected to be written
@@ -253,7 +304,7 @@ pub enum SnapshotError {
     },
 }
 
-const HOW_TO_UPDATE_SCREENSHOTS: &str =
+pub(crate) const HOW_TO_UPDATE_SCREENSHOTS: &str =
     "Run `UPDATE_SNAPSHOTS=1 cargo test --all-features` to update the snapshots.";
 
 impl Display for SnapshotError {
@@ -310,6 +361,50 @@ impl Display for SnapshotError {
                     "'{name}' Image size did not match snapshot. Expected: {expected:?}, Actual: {actual:?}. {HOW_TO_UPDATE_SCREENSHOTS}"
                 )
             }
+            Self::FrameDiff {
+                name,
+                frame,
+                diff,
+                differing_frames,
+                frame_count,
+                diff_path,
+            } => {
+                let diff_path =
+                    std::path::absolute(diff_path).unwrap_or_else(|_| diff_path.clone());
+                write!(
+                    f,
+                    "'{name}' Animation did not match snapshot: {differing_frames} of {frame_count} frames differ. \
+                    First differing frame: {frame} (diff: {diff}), {}. {HOW_TO_UPDATE_SCREENSHOTS}",
+                    diff_path.display()
+                )
+            }
+            Self::FrameCountMismatch {
+                name,
+                expected,
+                actual,
+            } => {
+                write!(
+                    f,
+                    "'{name}' Number of frames did not match snapshot. Expected: {expected}, Actual: {actual}. {HOW_TO_UPDATE_SCREENSHOTS}"
+                )
+            }
+            Self::TextDiff {
+                name,
+                line,
+                expected,
+                actual,
+                new_path,
+            } => {
+                let new_path = std::path::absolute(new_path).unwrap_or_else(|_| new_path.clone());
+                let expected = expected.as_deref().unwrap_or("<end of file>");
+                let actual = actual.as_deref().unwrap_or("<end of file>");
+                write!(
+                    f,
+                    "'{name}' Text did not match snapshot at line {line}:\n- {expected}\n+ {actual}\n\
+                    New text: {}. {HOW_TO_UPDATE_SCREENSHOTS}",
+                    new_path.display()
+                )
+            }
             Self::WriteSnapshot { path, err } => {
                 let path = std::path::absolute(path).unwrap_or_else(|_| path.clone());
                 write!(f, "Error writing snapshot: {err}\nAt: {}", path.display())
@@ -322,14 +417,14 @@ impl Display for SnapshotError {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum Mode {
+pub(crate) enum Mode {
     Test,
     UpdateFailing,
     UpdateAll,
 }
 
 impl Mode {
-    fn from_env() -> Self {
+    pub(crate) fn from_env() -> Self {
         let Ok(value) = std::env::var("UPDATE_SNAPSHOTS") else {
             return Self::Test;
         };
@@ -344,7 +439,7 @@ impl Mode {
         }
     }
 
-    fn is_update(&self) -> bool {
+    pub(crate) fn is_update(&self) -> bool {
         match self {
             Self::Test => false,
             Self::UpdateFailing | Self::UpdateAll => true,
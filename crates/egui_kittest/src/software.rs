@@ -0,0 +1,73 @@
+use egui::{TextureId, TexturesDelta, emath::GuiRounding as _, epaint::SoftwareRenderer};
+use image::RgbaImage;
+
+/// Utility to render snapshots from a [`crate::Harness`] on the CPU,
+/// using [`egui::epaint::SoftwareRenderer`].
+///
+/// This needs no GPU, and produces the same images on every machine,
+/// which makes it a good fit for CI.
+/// It is slower than the [`crate::wgpu::WgpuTestRenderer`], and doesn't support paint callbacks.
+#[derive(Default)]
+pub struct SoftwareTestRenderer {
+    renderer: SoftwareRenderer,
+
+    /// Textures freed by the last frame, which we free before the next one.
+    pending_free: Vec<TextureId>,
+}
+
+impl SoftwareTestRenderer {
+    /// Create a new [`SoftwareTestRenderer`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl crate::TestRenderer for SoftwareTestRenderer {
+    fn handle_delta(&mut self, delta: &TexturesDelta) {
+        for id in self.pending_free.drain(..) {
+            self.renderer.free_texture(id);
+        }
+        for (id, image) in &delta.set {
+            self.renderer.set_texture(*id, image);
+        }
+        self.pending_free.extend_from_slice(&delta.free);
+    }
+
+    /// Render the [`crate::Harness`] and return the resulting image.
+    fn render(
+        &mut self,
+        ctx: &egui::Context,
+        output: &egui::FullOutput,
+    ) -> Result<RgbaImage, String> {
+        self.render_viewport(ctx, output, egui::ViewportId::ROOT)
+    }
+
+    fn render_viewport(
+        &mut self,
+        ctx: &egui::Context,
+        output: &egui::FullOutput,
+        viewport_id: egui::ViewportId,
+    ) -> Result<RgbaImage, String> {
+        let (content_rect, pixels_per_point) = ctx.input_for(viewport_id, |i| {
+            (i.content_rect().round_ui(), i.pixels_per_point)
+        });
+        let size = content_rect.size() * pixels_per_point;
+        let size_in_pixels = [size.x.round() as usize, size.y.round() as usize];
+
+        let tessellated = ctx.tessellate(output.shapes.clone(), pixels_per_point);
+        let image = self
+            .renderer
+            .render(&tessellated, size_in_pixels, pixels_per_point);
+
+        RgbaImage::from_raw(
+            size_in_pixels[0] as u32,
+            size_in_pixels[1] as u32,
+            image
+                .pixels
+                .iter()
+                .flat_map(|pixel| pixel.to_array())
+                .collect(),
+        )
+        .ok_or_else(|| "Failed to convert the rendered image".to_owned())
+    }
+}
@@ -0,0 +1,223 @@
+//! Text snapshot tests, e.g. of the AccessKit tree.
+
+use std::path::PathBuf;
+
+use image::ImageError;
+
+use crate::snapshot::Mode;
+use crate::{Harness, SnapshotError, SnapshotOptions, SnapshotResult};
+
+/// Text snapshot test with custom options.
+///
+/// Only [`SnapshotOptions::output_path`] is used: the text has to match exactly.
+///
+/// The snapshot will be saved under `{output_path}/{name}.txt`.
+/// The new text from the most recent test run will be saved under `{output_path}/{name}.new.txt`.
+///
+/// If the env-var `UPDATE_SNAPSHOTS` is set, then the old text will backed up under `{output_path}/{name}.old.txt`.
+/// and then new text will be written to `{output_path}/{name}.txt`
+///
+/// # Errors
+/// Returns a [`SnapshotError`] if the text does not match the snapshot or if there was an error
+/// reading or writing the snapshot.
+pub fn try_text_snapshot_options(
+    text: &str,
+    name: impl Into<String>,
+    options: &SnapshotOptions,
+) -> SnapshotResult {
+    try_text_snapshot_options_impl(text, name.into(), options)
+}
+
+fn try_text_snapshot_options_impl(
+    new: &str,
+    name: String,
+    options: &SnapshotOptions,
+) -> SnapshotResult {
+    #![expect(clippy::print_stdout)]
+
+    let mode = Mode::from_env();
+
+    let output_path = &options.output_path;
+
+    let parent_path = if let Some(parent) = PathBuf::from(&name).parent() {
+        output_path.join(parent)
+    } else {
+        output_path.clone()
+    };
+    std::fs::create_dir_all(parent_path).ok();
+
+    // The one that is checked in to git
+    let snapshot_path = output_path.join(format!("{name}.txt"));
+
+    // These should be in .gitignore:
+    let old_backup_path = output_path.join(format!("{name}.old.txt"));
+    let new_path = output_path.join(format!("{name}.new.txt"));
+
+    // Delete old temporary files if they exist:
+    std::fs::remove_file(&old_backup_path).ok();
+    std::fs::remove_file(&new_path).ok();
+
+    let update_snapshot = || {
+        // Keep the old version so the user can compare it:
+        std::fs::rename(&snapshot_path, &old_backup_path).ok();
+
+        // Write the new file to the checked in path:
+        std::fs::write(&snapshot_path, new).map_err(|err| SnapshotError::WriteSnapshot {
+            err: ImageError::IoError(err),
+            path: snapshot_path.clone(),
+        })?;
+
+        println!("Updated snapshot: {}", snapshot_path.display());
+
+        Ok(())
+    };
+
+    let write_new_text = || {
+        std::fs::write(&new_path, new).map_err(|err| SnapshotError::WriteSnapshot {
+            err: ImageError::IoError(err),
+            path: new_path.clone(),
+        })
+    };
+
+    let previous = match std::fs::read_to_string(&snapshot_path) {
+        // Don't fail on a checkout with Windows line endings:
+        Ok(previous) => previous.replace("\r\n", "\n"),
+        Err(err) => {
+            // No previous snapshot - probably a new test.
+            if mode.is_update() {
+                return update_snapshot();
+            } else {
+                write_new_text()?;
+
+                return Err(SnapshotError::OpenSnapshot {
+                    path: snapshot_path.clone(),
+                    err: ImageError::IoError(err),
+                });
+            }
+        }
+    };
+
+    if previous == new {
+        return if mode == Mode::UpdateAll {
+            update_snapshot()
+        } else {
+            Ok(())
+        };
+    }
+
+    match mode {
+        Mode::Test => {
+            write_new_text()?;
+
+            let mut previous_lines = previous.lines();
+            let mut new_lines = new.lines();
+            let mut line = 1;
+            loop {
+                let expected = previous_lines.next();
+                let actual = new_lines.next();
+                if expected != actual {
+                    return Err(SnapshotError::TextDiff {
+                        name,
+                        line,
+                        expected: expected.map(ToOwned::to_owned),
+                        actual: actual.map(ToOwned::to_owned),
+                        new_path,
+                    });
+                }
+                if expected.is_none() {
+                    // Only the trailing newline differs:
+                    return Err(SnapshotError::TextDiff {
+                        name,
+                        line,
+                        expected: None,
+                        actual: None,
+                        new_path,
+                    });
+                }
+                line += 1;
+            }
+        }
+        Mode::UpdateFailing | Mode::UpdateAll => update_snapshot(),
+    }
+}
+
+/// Text snapshot test.
+///
+/// This uses the default [`SnapshotOptions`].
+/// See [`try_text_snapshot_options`] for details.
+///
+/// # Errors
+/// Returns a [`SnapshotError`] if the text does not match the snapshot or if there was an error
+/// reading or writing the snapshot.
+pub fn try_text_snapshot(text: &str, name: impl Into<String>) -> SnapshotResult {
+    try_text_snapshot_options(text, name, &SnapshotOptions::default())
+}
+
+impl<State> Harness<'_, State> {
+    /// Compare the AccessKit tree (see [`Harness::accesskit_tree`]) to the text snapshot
+    /// with custom options.
+    ///
+    /// These options will override the ones set by [`crate::HarnessBuilder::with_options`].
+    ///
+    /// The snapshot will be saved under `{output_path}/{name}.accesskit.txt`,
+    /// next to the image snapshot of the same name.
+    /// The new tree from the most recent test run will be saved under `{output_path}/{name}.accesskit.new.txt`.
+    ///
+    /// Use this to catch accidental changes to labels, roles or states,
+    /// that an image snapshot can't see.
+    ///
+    /// # Errors
+    /// Returns a [`SnapshotError`] if the tree does not match the snapshot or if there was an
+    /// error reading or writing the snapshot.
+    pub fn try_accesskit_snapshot_options(
+        &self,
+        name: impl Into<String>,
+        options: &SnapshotOptions,
+    ) -> SnapshotResult {
+        let name = format!("{}.accesskit", name.into());
+        try_text_snapshot_options(&self.accesskit_tree(), name, options)
+    }
+
+    /// Compare the AccessKit tree to the text snapshot.
+    ///
+    /// This is like [`Self::try_accesskit_snapshot_options`] but will use the options set by [`crate::HarnessBuilder::with_options`].
+    ///
+    /// # Errors
+    /// Returns a [`SnapshotError`] if the tree does not match the snapshot or if there was an
+    /// error reading or writing the snapshot.
+    pub fn try_accesskit_snapshot(&self, name: impl Into<String>) -> SnapshotResult {
+        self.try_accesskit_snapshot_options(name, &self.default_snapshot_options)
+    }
+
+    /// Compare the AccessKit tree to the text snapshot with custom options.
+    ///
+    /// See [`Self::try_accesskit_snapshot_options`].
+    ///
+    /// # Panics
+    /// The result is added to the [`Harness`]'s internal [`crate::SnapshotResults`].
+    ///
+    /// The harness will panic when dropped if there were any snapshot errors.
+    #[track_caller]
+    pub fn accesskit_snapshot_options(
+        &mut self,
+        name: impl Into<String>,
+        options: &SnapshotOptions,
+    ) {
+        let result = self.try_accesskit_snapshot_options(name, options);
+        self.snapshot_results.add(result);
+    }
+
+    /// Compare the AccessKit tree to the text snapshot.
+    ///
+    /// This is like [`Self::accesskit_snapshot_options`] but will use the options set by [`crate::HarnessBuilder::with_options`].
+    ///
+    /// # Panics
+    /// The result is added to the [`Harness`]'s internal [`crate::SnapshotResults`].
+    ///
+    /// The harness will panic when dropped if there were any snapshot errors.
+    #[track_caller]
+    pub fn accesskit_snapshot(&mut self, name: impl Into<String>) {
+        let result = self.try_accesskit_snapshot(name);
+        self.snapshot_results.add(result);
+    }
+}
@@ -0,0 +1,465 @@
+//! Running immediate and deferred viewports as separate viewports, like a native backend would.
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+use egui::{
+    FullOutput, ImmediateViewport, ImmediateViewportRendererCallback, OrderedViewportIdMap, Pos2,
+    RawInput, Rect, Vec2, ViewportCommand, ViewportEvent, ViewportId, ViewportIdMap, ViewportInfo,
+    ViewportOutput,
+};
+
+use crate::node::{EventQueue, EventType};
+
+thread_local! {
+    /// The [`ViewportIo`] of each [`crate::Harness`] with multiple viewports on this thread.
+    ///
+    /// The immediate viewport renderer is global per thread, so it needs to find the right one.
+    static HARNESS_VIEWPORTS: RefCell<Vec<Weak<RefCell<ViewportIo>>>> = const { RefCell::new(Vec::new()) };
+
+    /// The immediate viewport renderer from before the first harness with multiple viewports,
+    /// to put back when the last one is dropped.
+    static PREVIOUS_RENDERER: RefCell<Option<Box<ImmediateViewportRendererCallback>>> = const { RefCell::new(None) };
+}
+
+/// Input and output of the viewports other than the root.
+///
+/// This is shared with the immediate viewport renderer.
+struct ViewportIo {
+    ctx: egui::Context,
+
+    /// Input for the next frame of each viewport.
+    inputs: ViewportIdMap<RawInput>,
+
+    /// Outputs of the immediate viewports that ran since the last time we looked, in order.
+    immediate_outputs: Vec<(ViewportId, FullOutput)>,
+
+    /// Did any viewport get input since the last time we looked?
+    ///
+    /// Native backends repaint after input, so we need to run another step.
+    had_input: bool,
+
+    default_size: Vec2,
+    pixels_per_point: f32,
+    step_dt: f32,
+}
+
+impl ViewportIo {
+    /// Take the input for the next frame of a viewport, creating it the first time.
+    fn take_input(&mut self, id: ViewportId, builder: &egui::ViewportBuilder) -> RawInput {
+        let Self {
+            inputs,
+            default_size,
+            pixels_per_point,
+            step_dt,
+            ..
+        } = self;
+        let input = inputs.entry(id).or_insert_with(|| {
+            let size = builder.inner_size.unwrap_or(*default_size);
+            let info = ViewportInfo {
+                title: builder.title.clone(),
+                native_pixels_per_point: Some(*pixels_per_point),
+                inner_rect: Some(Rect::from_min_size(Pos2::ZERO, size)),
+                focused: Some(true),
+                ..Default::default()
+            };
+            RawInput {
+                viewport_id: id,
+                viewports: std::iter::once((id, info)).collect(),
+                screen_rect: Some(Rect::from_min_size(Pos2::ZERO, size)),
+                ..Default::default()
+            }
+        });
+        input.predicted_dt = *step_dt;
+        let input = input.take();
+        self.had_input |= !input.events.is_empty()
+            || input.viewports.values().any(|info| !info.events.is_empty());
+        input
+    }
+
+    /// Act on a [`ViewportCommand`] the way a native backend would, as far as it matters for tests.
+    fn handle_command(input: &mut RawInput, command: &ViewportCommand) {
+        let viewport_id = input.viewport_id;
+        match command {
+            ViewportCommand::Close => {
+                if let Some(info) = input.viewports.get_mut(&viewport_id) {
+                    info.events.push(ViewportEvent::Close);
+                }
+            }
+            ViewportCommand::InnerSize(size) => {
+                let rect = Rect::from_min_size(Pos2::ZERO, *size);
+                input.screen_rect = Some(rect);
+                if let Some(info) = input.viewports.get_mut(&viewport_id) {
+                    info.inner_rect = Some(rect);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Called by egui (via [`egui::Context::set_immediate_viewport_renderer`]) to show an immediate viewport.
+fn render_immediate_viewport(ctx: &egui::Context, viewport: ImmediateViewport<'_>) {
+    let ImmediateViewport {
+        ids,
+        builder,
+        mut viewport_ui_cb,
+    } = viewport;
+
+    let io = HARNESS_VIEWPORTS.with_borrow(|list| {
+        list.iter()
+            .filter_map(Weak::upgrade)
+            .find(|io| io.borrow().ctx == *ctx)
+    });
+
+    let Some(io) = io else {
+        // Not a context of a harness (anymore), but we still need to call the ui code:
+        let input = RawInput {
+            viewport_id: ids.this,
+            ..Default::default()
+        };
+        _ = ctx.run_ui(input, |ui| viewport_ui_cb(ui));
+        return;
+    };
+
+    let input = io.borrow_mut().take_input(ids.this, &builder);
+
+    // The ui code may show more immediate viewports, so we can't hold the borrow here:
+    let output = ctx.run_ui(input, |ui| viewport_ui_cb(ui));
+
+    io.borrow_mut().immediate_outputs.push((ids.this, output));
+}
+
+/// A viewport other than the root, as seen by the [`crate::Harness`].
+pub(crate) struct ViewportState {
+    pub kittest: kittest::State,
+    pub output: FullOutput,
+    pub queued_events: EventQueue,
+}
+
+/// The viewports of a [`crate::Harness`] built with
+/// [`crate::HarnessBuilder::with_multiple_viewports`].
+pub(crate) struct Viewports {
+    io: Rc<RefCell<ViewportIo>>,
+
+    /// All viewports except the root, in the order they were first shown.
+    pub order: Vec<ViewportId>,
+    pub states: ViewportIdMap<ViewportState>,
+
+    /// The output of each viewport (including the root) from the last step,
+    /// with the commands of all passes of the step.
+    pub outputs: OrderedViewportIdMap<ViewportOutput>,
+
+    /// Should we run another step, because a viewport got input during the last one?
+    pub needs_repaint: bool,
+}
+
+impl Viewports {
+    pub fn new(
+        ctx: &egui::Context,
+        default_size: Vec2,
+        pixels_per_point: f32,
+        step_dt: f32,
+    ) -> Self {
+        ctx.set_embed_viewports(false);
+
+        let io = Rc::new(RefCell::new(ViewportIo {
+            ctx: ctx.clone(),
+            inputs: Default::default(),
+            immediate_outputs: Default::default(),
+            had_input: false,
+            default_size,
+            pixels_per_point,
+            step_dt,
+        }));
+        let is_first = HARNESS_VIEWPORTS.with_borrow_mut(|list| {
+            list.retain(|io| io.strong_count() > 0);
+            list.push(Rc::downgrade(&io));
+            list.len() == 1
+        });
+        if is_first {
+            PREVIOUS_RENDERER.set(egui::Context::take_immediate_viewport_renderer());
+            egui::Context::set_immediate_viewport_renderer(render_immediate_viewport);
+        }
+
+        Self {
+            io,
+            order: Default::default(),
+            states: Default::default(),
+            outputs: Default::default(),
+            needs_repaint: false,
+        }
+    }
+
+    /// Is there a viewport (other than the root) with this id?
+    pub fn contains(&self, id: ViewportId) -> bool {
+        self.states.contains_key(&id)
+    }
+
+    /// Move the next queued event of each viewport to its input.
+    ///
+    /// Returns `false` if there were no more events.
+    pub fn apply_next_events(&self) -> bool {
+        let mut io = self.io.borrow_mut();
+        let mut any = false;
+        for id in &self.order {
+            let Some(state) = self.states.get(id) else {
+                continue;
+            };
+            let mut queue = state.queued_events.lock();
+            if queue.is_empty() {
+                continue;
+            }
+            any = true;
+            let event = queue.remove(0);
+            if let Some(input) = io.inputs.get_mut(id) {
+                match event {
+                    EventType::Event(event) => input.events.push(event),
+                    EventType::Modifiers(modifiers) => input.modifiers = modifiers,
+                }
+            }
+        }
+        any
+    }
+
+    /// Act on a command sent to a viewport (other than the root).
+    pub fn handle_command(&self, id: ViewportId, command: &ViewportCommand) {
+        if let Some(input) = self.io.borrow_mut().inputs.get_mut(&id) {
+            ViewportIo::handle_command(input, command);
+        }
+    }
+
+    /// Take the outputs of the immediate viewports that ran since the last call.
+    pub fn take_immediate_outputs(&self) -> Vec<(ViewportId, FullOutput)> {
+        std::mem::take(&mut self.io.borrow_mut().immediate_outputs)
+    }
+
+    /// Run a deferred viewport.
+    pub fn run_deferred(
+        &self,
+        ctx: &egui::Context,
+        id: ViewportId,
+        viewport_output: &ViewportOutput,
+    ) -> Option<FullOutput> {
+        let viewport_ui_cb = viewport_output.viewport_ui_cb.clone()?;
+        let input = self
+            .io
+            .borrow_mut()
+            .take_input(id, &viewport_output.builder);
+        Some(ctx.run_ui(input, |ui| viewport_ui_cb(ui)))
+    }
+
+    /// Update the state of all viewports with the outputs of one step, in the order they ran.
+    ///
+    /// Returns the commands sent to the root viewport.
+    pub fn update(
+        &mut self,
+        root_output: &FullOutput,
+        outputs: Vec<(ViewportId, FullOutput)>,
+    ) -> Vec<ViewportCommand> {
+        // Only the viewports still shown by the root are alive:
+        let mut viewport_outputs = root_output.viewport_output.clone();
+        for (_, output) in &outputs {
+            for (id, viewport_output) in &output.viewport_output {
+                if let Some(existing) = viewport_outputs.get_mut(id) {
+                    existing.append(viewport_output.clone());
+                }
+            }
+        }
+
+        for (id, mut output) in outputs {
+            if !viewport_outputs.contains_key(&id) {
+                continue;
+            }
+            let update = output
+                .platform_output
+                .accesskit_update
+                .take()
+                .expect("AccessKit was disabled");
+            if let Some(state) = self.states.get_mut(&id) {
+                state.kittest.update(update);
+                state.output = output;
+            } else {
+                self.order.push(id);
+                self.states.insert(
+                    id,
+                    ViewportState {
+                        kittest: kittest::State::new(update),
+                        output,
+                        queued_events: Default::default(),
+                    },
+                );
+            }
+        }
+
+        // Forget closed viewports:
+        self.order.retain(|id| viewport_outputs.contains_key(id));
+        self.states
+            .retain(|id, _| viewport_outputs.contains_key(id));
+        self.io
+            .borrow_mut()
+            .inputs
+            .retain(|id, _| viewport_outputs.contains_key(id));
+
+        let mut root_commands = vec![];
+        for (id, viewport_output) in &viewport_outputs {
+            if *id == ViewportId::ROOT {
+                root_commands.extend(viewport_output.commands.iter().cloned());
+            } else {
+                for command in &viewport_output.commands {
+                    self.handle_command(*id, command);
+                }
+            }
+        }
+
+        self.outputs = viewport_outputs;
+        self.needs_repaint = std::mem::take(&mut self.io.borrow_mut().had_input);
+        root_commands
+    }
+}
+
+impl Drop for Viewports {
+    fn drop(&mut self) {
+        let this = Rc::downgrade(&self.io);
+        let is_last = HARNESS_VIEWPORTS.with_borrow_mut(|list| {
+            list.retain(|io| io.strong_count() > 0 && !io.ptr_eq(&this));
+            list.is_empty()
+        });
+        if is_last {
+            // Put back whatever was there before the harnesses, if anything:
+            match PREVIOUS_RENDERER.take() {
+                Some(previous) => egui::Context::set_immediate_viewport_renderer(previous),
+                None => drop(egui::Context::take_immediate_viewport_renderer()),
+            }
+        }
+    }
+}
+
+/// Act on a command sent to the root viewport, see [`ViewportIo::handle_command`].
+pub(crate) fn handle_root_command(input: &mut RawInput, command: &ViewportCommand) {
+    ViewportIo::handle_command(input, command);
+}
+
+impl<State> crate::Harness<'_, State> {
+    /// The ids of all viewports, starting with [`ViewportId::ROOT`],
+    /// followed by the other viewports in the order they were first shown.
+    ///
+    /// Only the root viewport exists unless the harness was built with
+    /// [`crate::HarnessBuilder::with_multiple_viewports`].
+    pub fn viewport_ids(&self) -> Vec<ViewportId> {
+        let mut ids = vec![ViewportId::ROOT];
+        if let Some(viewports) = &self.viewports {
+            ids.extend(viewports.order.iter().copied());
+        }
+        ids
+    }
+
+    /// The [`ViewportOutput`] of a viewport from the last step,
+    /// with the commands it got during all passes of the step.
+    pub fn viewport_output(&self, id: ViewportId) -> Option<&ViewportOutput> {
+        if let Some(viewports) = &self.viewports {
+            viewports.outputs.get(&id)
+        } else {
+            self.output.viewport_output.get(&id)
+        }
+    }
+
+    fn viewport_state(&self, id: ViewportId) -> Option<&ViewportState> {
+        self.viewports.as_ref()?.states.get(&id)
+    }
+
+    /// The root node of the AccessKit tree of a viewport, if it exists.
+    pub fn try_viewport_root(&self, id: ViewportId) -> Option<crate::Node<'_>> {
+        if id == ViewportId::ROOT {
+            return Some(self.root());
+        }
+        let state = self.viewport_state(id)?;
+        Some(crate::Node {
+            accesskit_node: state.kittest.root(),
+            queue: &state.queued_events,
+        })
+    }
+
+    /// The root node of the AccessKit tree of a viewport.
+    ///
+    /// Events from interacting with the nodes (e.g. [`crate::Node::click`])
+    /// are sent to that viewport.
+    ///
+    /// # Panics
+    /// If there is no such viewport.
+    #[track_caller]
+    pub fn viewport_root(&self, id: ViewportId) -> crate::Node<'_> {
+        self.try_viewport_root(id).unwrap_or_else(|| {
+            panic!(
+                "No viewport {id:?}. The viewports are {:?}",
+                self.viewport_ids()
+            )
+        })
+    }
+
+    /// The AccessKit tree of a viewport as text, see [`crate::Node::accesskit_tree`].
+    ///
+    /// # Panics
+    /// If there is no such viewport.
+    #[track_caller]
+    pub fn viewport_accesskit_tree(&self, id: ViewportId) -> String {
+        self.viewport_root(id).accesskit_tree()
+    }
+
+    /// Queue an event for a viewport, like [`crate::Harness::event`] does for the root viewport.
+    ///
+    /// # Panics
+    /// If there is no such viewport.
+    #[track_caller]
+    pub fn viewport_event(&self, id: ViewportId, event: egui::Event) {
+        self.viewport_root(id)
+            .queue
+            .lock()
+            .push(EventType::Event(event));
+    }
+
+    /// Send a command to a viewport, as if the app sent it with
+    /// [`egui::Context::send_viewport_cmd_to`].
+    ///
+    /// The harness acts on the commands like a native backend would:
+    /// * [`ViewportCommand::Close`] makes [`egui::ViewportInfo::close_requested`] true for the next frame
+    ///   of that viewport, like when the user clicks the close button of the window.
+    ///   The app is then expected to stop showing the viewport.
+    /// * [`ViewportCommand::InnerSize`] resizes the viewport.
+    ///
+    /// Other commands are ignored.
+    ///
+    /// # Panics
+    /// If there is no such viewport.
+    #[track_caller]
+    pub fn send_viewport_cmd_to(&mut self, id: ViewportId, command: &ViewportCommand) {
+        if id == ViewportId::ROOT {
+            handle_root_command(&mut self.input, command);
+            return;
+        }
+        match &self.viewports {
+            Some(viewports) if viewports.contains(id) => viewports.handle_command(id, command),
+            _ => panic!(
+                "No viewport {id:?}. The viewports are {:?}",
+                self.viewport_ids()
+            ),
+        }
+    }
+
+    /// Render the last output of a viewport to an image.
+    ///
+    /// # Errors
+    /// Returns an error if there is no such viewport or if the rendering fails.
+    #[cfg(any(feature = "wgpu", feature = "snapshot"))]
+    pub fn render_viewport(&mut self, id: ViewportId) -> Result<image::RgbaImage, String> {
+        if id == ViewportId::ROOT {
+            return self.render();
+        }
+        let state = self
+            .viewports
+            .as_ref()
+            .and_then(|viewports| viewports.states.get(&id))
+            .ok_or_else(|| format!("No viewport {id:?}"))?;
+        self.renderer.render_viewport(&self.ctx, &state.output, id)
+    }
+}
@@ -0,0 +1,68 @@
+use egui::accessibility_audit::AccessibilityIssueKind;
+use egui::{Color32, Id, Sense, WidgetInfo, WidgetType};
+use egui_kittest::Harness;
+
+#[test]
+fn test_accessible_ui_has_no_issues() {
+    let mut harness = Harness::new_ui(|ui| {
+        ui.heading("Settings");
+        ui.checkbox(&mut true, "Enabled");
+        ui.add(egui::Slider::new(&mut 0.25, 0.0..=1.0).text("Volume"));
+        let label = ui.label("Name");
+        ui.text_edit_singleline(&mut String::from("Ferris"))
+            .labelled_by(label.id);
+        ui.add_enabled(false, egui::Button::new("Apply"));
+        if ui.button("Save").clicked() {}
+    });
+
+    harness.assert_accessible();
+}
+
+#[test]
+fn test_audit_finds_issues() {
+    let mut harness = Harness::new_ui(|ui| {
+        // Icon-only button without a name:
+        ui.add(egui::Button::new(""));
+
+        // Clickable, but not focusable:
+        let response = ui.allocate_response(egui::vec2(100.0, 30.0), Sense::CLICK);
+        response.widget_info(|| WidgetInfo::labeled(WidgetType::Button, true, "Custom"));
+
+        // Too small to hit comfortably:
+        ui.add(egui::Button::new("x").min_size(egui::Vec2::ZERO).small());
+
+        // Hard to read:
+        ui.colored_label(Color32::from_gray(40), "Dim text");
+
+        // The same id twice:
+        for offset in [0.0, 50.0] {
+            let rect = egui::Rect::from_min_size(egui::pos2(offset, 300.0), egui::vec2(40.0, 40.0));
+            ui.interact(rect, Id::new("clash"), Sense::click());
+        }
+    });
+
+    let issues = harness.accessibility_issues();
+    let kinds: Vec<AccessibilityIssueKind> = issues.iter().map(|issue| issue.kind).collect();
+    for kind in [
+        AccessibilityIssueKind::MissingName,
+        AccessibilityIssueKind::NotFocusable,
+        AccessibilityIssueKind::SmallHitTarget,
+        AccessibilityIssueKind::LowContrast,
+        AccessibilityIssueKind::DuplicateId,
+    ] {
+        assert!(
+            kinds.contains(&kind),
+            "Expected a {kind:?} issue, got: {issues:#?}"
+        );
+    }
+
+    let low_contrast = issues
+        .iter()
+        .find(|issue| issue.kind == AccessibilityIssueKind::LowContrast)
+        .unwrap();
+    assert!(
+        low_contrast.message.contains("Dim text"),
+        "{}",
+        low_contrast.message
+    );
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "snapshot")]
+
+use egui_kittest::{Harness, SnapshotError, SnapshotOptions};
+
+fn settings_ui(ui: &mut egui::Ui, label: &mut &'static str) {
+    ui.heading("Settings");
+    ui.checkbox(&mut true, *label);
+    ui.add(egui::Slider::new(&mut 0.5, 0.0..=1.0).text("Volume"));
+    ui.add_enabled(false, egui::Button::new("Apply"));
+}
+
+#[test]
+fn test_accesskit_snapshot() {
+    let mut harness = Harness::new_ui_state(settings_ui, "Dark mode");
+    harness.accesskit_snapshot("accesskit_tree");
+}
+
+#[test]
+fn test_accesskit_snapshot_diff() {
+    let dir = tempfile::tempdir().unwrap();
+    let options = SnapshotOptions::new().output_path(dir.path());
+
+    let mut harness = Harness::new_ui_state(settings_ui, "Dark mode");
+    let result = harness.try_accesskit_snapshot_options("settings", &options);
+    assert!(matches!(result, Err(SnapshotError::OpenSnapshot { .. })));
+    std::fs::rename(
+        dir.path().join("settings.accesskit.new.txt"),
+        dir.path().join("settings.accesskit.txt"),
+    )
+    .unwrap();
+
+    harness
+        .try_accesskit_snapshot_options("settings", &options)
+        .unwrap();
+
+    // Renaming the checkbox changes the tree, even if it would look almost the same:
+    *harness.state_mut() = "Dark mode ";
+    harness.run();
+    let result = harness.try_accesskit_snapshot_options("settings", &options);
+    let Err(SnapshotError::TextDiff {
+        expected, actual, ..
+    }) = result
+    else {
+        panic!("Expected a text diff, got {result:?}");
+    };
+    assert!(
+        expected
+            .unwrap()
+            .contains(r#"CheckBox "Dark mode" [checked]"#)
+    );
+    assert!(
+        actual
+            .unwrap()
+            .contains(r#"CheckBox "Dark mode " [checked]"#)
+    );
+}
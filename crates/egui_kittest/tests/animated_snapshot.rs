@@ -0,0 +1,73 @@
+#![cfg(feature = "snapshot")]
+
+use egui_kittest::{Harness, SnapshotError, SnapshotOptions};
+
+fn collapsing_harness(open: bool) -> Harness<'static, bool> {
+    let mut harness = Harness::builder()
+        .with_size(egui::vec2(120.0, 80.0))
+        .with_step_dt(0.05)
+        .software()
+        .build_ui_state(
+            |ui, open| {
+                egui::CollapsingHeader::new("Details")
+                    .open(Some(*open))
+                    .show(ui, |ui| {
+                        ui.label("Hello");
+                        ui.label("World");
+                    });
+            },
+            false,
+        );
+    harness
+        .ctx
+        .all_styles_mut(|style| style.animation_time = 0.2);
+    *harness.state_mut() = open;
+    harness
+}
+
+#[test]
+fn test_animated_snapshot() {
+    let dir = tempfile::tempdir().unwrap();
+    let options = SnapshotOptions::new().output_path(dir.path());
+
+    let mut harness = collapsing_harness(true);
+    let frames = harness.render_frames(6).unwrap();
+    assert_ne!(frames[0], frames[5], "The header should animate open");
+
+    // The first run fails, and writes the new animation:
+    let mut harness = collapsing_harness(true);
+    let result = harness.try_animated_snapshot_options("collapsing", 6, &options);
+    assert!(matches!(result, Err(SnapshotError::OpenSnapshot { .. })));
+    std::fs::rename(
+        dir.path().join("collapsing.new.png"),
+        dir.path().join("collapsing.png"),
+    )
+    .unwrap();
+
+    // The same animation matches:
+    let mut harness = collapsing_harness(true);
+    harness
+        .try_animated_snapshot_options("collapsing", 6, &options)
+        .unwrap();
+
+    // Fewer frames don't:
+    let mut harness = collapsing_harness(true);
+    let result = harness.try_animated_snapshot_options("collapsing", 4, &options);
+    assert!(matches!(
+        result,
+        Err(SnapshotError::FrameCountMismatch {
+            expected: 6,
+            actual: 4,
+            ..
+        })
+    ));
+
+    // Without the animation, the first frame is the same, but the second isn't:
+    let mut harness = collapsing_harness(false);
+    let result = harness.try_animated_snapshot_options("collapsing", 6, &options);
+    let Err(SnapshotError::FrameDiff { frame, .. }) = result else {
+        panic!("Expected a frame diff, got {result:?}");
+    };
+    assert_eq!(frame, 1);
+    assert!(dir.path().join("collapsing.diff.png").exists());
+}
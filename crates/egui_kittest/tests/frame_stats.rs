@@ -0,0 +1,55 @@
+use egui_kittest::{FrameBudget, Harness};
+
+#[test]
+fn test_static_ui_is_cached() {
+    let mut harness = Harness::new_ui(|ui| {
+        ui.heading("Settings");
+        ui.checkbox(&mut true, "Enabled");
+        ui.label("Nothing changes here");
+    });
+
+    let profile = harness.profile_frames(5);
+    assert_eq!(profile.frames.len(), 5);
+    assert!(0 < profile.frames[0].num_text_shapes, "{profile}");
+    assert!(0 < profile.frames[0].num_vertices, "{profile}");
+    profile.assert_within(
+        &FrameBudget::default()
+            .max_vertices(10_000)
+            .no_texture_uploads()
+            .no_galley_cache_misses(),
+    );
+}
+
+#[test]
+fn test_budget_violations() {
+    let mut frame = 0;
+    let mut harness = Harness::new_ui(move |ui| {
+        frame += 1;
+        ui.label(format!("Frame {frame}"));
+    });
+
+    let profile = harness.profile_frames(3);
+    assert!(
+        profile.max_of(1, |frame| frame.galley_cache_misses) > 0,
+        "{profile}"
+    );
+
+    let err = profile
+        .check(
+            &FrameBudget::default()
+                .max_vertices(1)
+                .no_galley_cache_misses(),
+        )
+        .unwrap_err();
+    assert!(
+        err.violations.iter().any(|v| v.contains("vertices")),
+        "{err}"
+    );
+    assert!(
+        err.violations
+            .iter()
+            .any(|v| v.contains("galley cache misses")),
+        "{err}"
+    );
+    assert!(err.to_string().contains("Frame budget exceeded"), "{err}");
+}
@@ -0,0 +1,83 @@
+use egui::accesskit::Role;
+use egui_kittest::{Harness, NodeQuery};
+
+#[test]
+fn test_query_by_role_label_and_state() {
+    let mut harness = Harness::new_ui(|ui| {
+        egui::Window::new("Settings").show(ui.ctx(), |ui| {
+            ui.checkbox(&mut true, "Enabled");
+            ui.add_enabled(false, egui::Button::new("Apply"));
+            ui.add(egui::Slider::new(&mut 0.25, 0.0..=1.0).text("Volume"));
+            ui.collapsing("Advanced", |ui| ui.label("Hidden"));
+        });
+        egui::Window::new("Other").show(ui.ctx(), |ui| {
+            ui.checkbox(&mut false, "Enabled");
+        });
+    });
+    harness.run();
+
+    let in_settings = NodeQuery::checkbox("Enabled").inside(NodeQuery::window("Settings"));
+    harness
+        .find(&in_settings.clone().checked(true))
+        .assert_enabled();
+    assert!(harness.try_find(&in_settings.checked(false)).is_none());
+    assert_eq!(harness.find_all(&NodeQuery::checkbox("Enabled")).len(), 2);
+
+    harness.find(&NodeQuery::button("Apply")).assert_disabled();
+    harness
+        .find(&NodeQuery::new().role(Role::Slider))
+        .assert_value_in(0.2..=0.3);
+
+    let advanced = NodeQuery::button("Advanced");
+    harness.find(&advanced).assert_collapsed();
+    harness.find(&advanced).click();
+    harness.run();
+    harness.find(&advanced).assert_expanded();
+    harness.find(
+        &NodeQuery::new()
+            .label("Hidden")
+            .inside(NodeQuery::window("Settings")),
+    );
+}
+
+#[test]
+fn test_wait_for() {
+    // Simulates loading that takes a number of frames:
+    let mut harness = Harness::new_ui_state(
+        |ui, frames_left: &mut Option<u32>| match frames_left {
+            Some(0) => {
+                ui.label("Loaded");
+            }
+            Some(n) => {
+                *n -= 1;
+                ui.ctx().request_repaint();
+            }
+            None => {}
+        },
+        None,
+    );
+    *harness.state_mut() = Some(5);
+
+    let loaded = NodeQuery::new().label("Loaded");
+    let err = harness.try_wait_for(&loaded, 2).unwrap_err();
+    assert_eq!(err.steps, 2);
+    harness.wait_for(&loaded, 10);
+}
+
+#[test]
+#[should_panic(expected = "toggled is Some(False), expected True")]
+fn test_failure_message_explains_mismatch() {
+    let harness = Harness::new_ui(|ui| {
+        ui.checkbox(&mut false, "Dark mode");
+    });
+    harness.find(&NodeQuery::checkbox("Dark mode").checked(true));
+}
+
+#[test]
+#[should_panic(expected = r#"  > CheckBox "Dark mode" [unchecked]"#)]
+fn test_failure_message_shows_tree() {
+    let harness = Harness::new_ui(|ui| {
+        ui.checkbox(&mut false, "Dark mode");
+    });
+    harness.find(&NodeQuery::checkbox("Dark mode").checked(true));
+}
@@ -0,0 +1,30 @@
+use egui::recording::InputRecorder;
+use egui_kittest::{Harness, kittest::Queryable as _};
+
+fn checkbox_app(ui: &mut egui::Ui, checked: &mut bool) {
+    ui.checkbox(checked, "Check me!");
+}
+
+#[test]
+fn test_replay_recording() {
+    let mut harness = Harness::new_ui_state(checkbox_app, false);
+    harness.ctx.add_plugin(InputRecorder::default());
+
+    harness.get_by_label("Check me!").click();
+    harness.run();
+    assert!(*harness.state());
+
+    let recording = harness
+        .ctx
+        .plugin::<InputRecorder>()
+        .lock()
+        .take_recording();
+    assert!(!recording.is_empty());
+
+    let mut replay = Harness::new_ui_state(checkbox_app, false);
+    replay.replay(&recording);
+    assert!(
+        *replay.state(),
+        "The replayed click should check the checkbox"
+    );
+}
@@ -0,0 +1,29 @@
+#![cfg(feature = "snapshot")]
+
+use egui_kittest::Harness;
+
+#[test]
+fn test_software_renderer() {
+    let mut harness = Harness::builder()
+        .with_size(egui::vec2(120.0, 40.0))
+        .software()
+        .build_ui(|ui| {
+            egui::CentralPanel::default().show_inside(ui, |ui| {
+                ui.label("Hello, world!");
+                let _ = ui.button("Click me");
+            });
+        });
+
+    let image = harness.render().expect("Failed to render");
+    assert_eq!(image.dimensions(), (120, 40));
+
+    // The central panel fills the background:
+    let panel_fill = harness.ctx.global_style().visuals.panel_fill;
+    assert_eq!(image.get_pixel(100, 30).0, panel_fill.to_array());
+
+    // The text is painted:
+    assert!(image.pixels().any(|pixel| pixel.0 != panel_fill.to_array()));
+
+    // Rendering is deterministic:
+    assert_eq!(image, harness.render().expect("Failed to render"));
+}
@@ -0,0 +1,146 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use egui::{ViewportBuilder, ViewportClass, ViewportCommand, ViewportId};
+use egui_kittest::{Harness, kittest::Queryable as _};
+
+#[derive(Default)]
+struct State {
+    show_child: bool,
+    checked: bool,
+}
+
+#[test]
+fn test_immediate_viewport() {
+    let child_id = ViewportId::from_hash_of("child");
+    let mut harness = Harness::builder()
+        .with_multiple_viewports(true)
+        .build_ui_state(
+            |ui, state: &mut State| {
+                ui.checkbox(&mut state.show_child, "Show child");
+                if state.show_child {
+                    ui.ctx().show_viewport_immediate(
+                        child_id,
+                        ViewportBuilder::default()
+                            .with_title("Child")
+                            .with_inner_size([200.0, 100.0]),
+                        |ui, _class| {
+                            ui.checkbox(&mut state.checked, "Inside child");
+                            if ui.input(|i| i.viewport().close_requested()) {
+                                state.show_child = false;
+                            }
+                        },
+                    );
+                }
+            },
+            State::default(),
+        );
+
+    assert_eq!(harness.viewport_ids(), vec![ViewportId::ROOT]);
+
+    harness.get_by_label("Show child").click();
+    harness.run();
+
+    assert_eq!(harness.viewport_ids(), vec![ViewportId::ROOT, child_id]);
+    assert_eq!(
+        harness.viewport_output(child_id).unwrap().parent,
+        ViewportId::ROOT
+    );
+    assert!(harness.query_by_label("Inside child").is_none());
+
+    harness
+        .viewport_root(child_id)
+        .get_by_label("Inside child")
+        .click();
+    harness.run();
+    assert!(harness.state().checked);
+
+    let image = harness.render_viewport(child_id).unwrap();
+    assert_eq!(image.dimensions(), (200, 100));
+
+    harness.send_viewport_cmd_to(child_id, &ViewportCommand::Close);
+    harness.run();
+    assert!(!harness.state().show_child);
+    assert_eq!(harness.viewport_ids(), vec![ViewportId::ROOT]);
+    assert!(harness.try_viewport_root(child_id).is_none());
+}
+
+#[test]
+fn test_deferred_viewport() {
+    let deferred_id = ViewportId::from_hash_of("deferred");
+    let closed = Arc::new(AtomicBool::new(false));
+    let mut harness = Harness::builder().with_multiple_viewports(true).build_ui({
+        let closed = Arc::clone(&closed);
+        move |ui| {
+            ui.label("Root");
+            if closed.load(Ordering::Relaxed) {
+                return;
+            }
+            let closed = Arc::clone(&closed);
+            ui.ctx().show_viewport_deferred(
+                deferred_id,
+                ViewportBuilder::default().with_title("Deferred"),
+                move |ui, _class| {
+                    ui.label("Deferred content");
+                    if ui.button("Close").clicked() {
+                        ui.send_viewport_cmd(ViewportCommand::Close);
+                    }
+                    if ui.input(|i| i.viewport().close_requested()) {
+                        closed.store(true, Ordering::Relaxed);
+                    }
+                },
+            );
+        }
+    });
+
+    assert_eq!(harness.viewport_ids(), vec![ViewportId::ROOT, deferred_id]);
+    let tree = harness.viewport_accesskit_tree(deferred_id);
+    assert!(tree.contains("Deferred content"), "{tree}");
+    assert!(!harness.accesskit_tree().contains("Deferred content"));
+
+    // New viewports get the size of the harness, unless they ask for a size:
+    let image = harness.render_viewport(deferred_id).unwrap();
+    assert_eq!(image.dimensions(), (800, 600));
+
+    harness
+        .viewport_root(deferred_id)
+        .get_by_label("Close")
+        .click();
+    harness.run();
+    assert!(closed.load(Ordering::Relaxed));
+    assert_eq!(harness.viewport_ids(), vec![ViewportId::ROOT]);
+}
+
+#[test]
+fn test_viewport_renderer_is_removed_on_drop() {
+    let harness = Harness::builder()
+        .with_multiple_viewports(true)
+        .build_ui(|ui| {
+            ui.label("Root");
+        });
+    drop(harness);
+
+    // Without a renderer, immediate viewports are embedded:
+    let ctx = egui::Context::default();
+    ctx.set_embed_viewports(false);
+    let mut class = None;
+    let _ = ctx.run_ui(Default::default(), |ui| {
+        ui.ctx().show_viewport_immediate(
+            ViewportId::from_hash_of("child"),
+            ViewportBuilder::default(),
+            |_ui, viewport_class| class = Some(viewport_class),
+        );
+    });
+    assert!(matches!(class, Some(ViewportClass::EmbeddedWindow)));
+}
+
+#[test]
+#[should_panic(expected = "No viewport")]
+fn test_send_viewport_cmd_to_unknown_viewport() {
+    let mut harness = Harness::builder()
+        .with_multiple_viewports(true)
+        .build_ui(|ui| {
+            ui.label("Root");
+        });
+    harness.send_viewport_cmd_to(ViewportId::from_hash_of("nope"), &ViewportCommand::Close);
+}
@@ -0,0 +1,75 @@
+//! Encoders needed to embed images and fonts in SVG and PDF documents.
+
+use base64::Engine as _;
+
+use crate::ColorImage;
+
+/// Standard base64, with padding.
+pub fn base64(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+/// Compress the data into a `zlib` stream, as used by the PDF `/FlateDecode` filter.
+pub fn zlib(data: &[u8]) -> Vec<u8> {
+    miniz_oxide::deflate::compress_to_vec_zlib(data, 6)
+}
+
+/// Encode the image as an RGBA PNG, with unmultiplied sRGB colors.
+pub fn png(image: &ColorImage) -> Vec<u8> {
+    let [width, height] = image.size;
+    let rgba: Vec<u8> = image
+        .pixels
+        .iter()
+        .flat_map(|pixel| pixel.to_srgba_unmultiplied())
+        .collect();
+
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let result = encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&rgba));
+    // We write to memory, and the size of the data matches the header:
+    debug_assert!(result.is_ok(), "Failed to encode PNG: {result:?}");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color32;
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn test_zlib_roundtrip() {
+        let data = b"egui ".repeat(1000);
+        let compressed = zlib(&data);
+        assert!(compressed.len() < data.len() / 10);
+        assert_eq!(
+            miniz_oxide::inflate::decompress_to_vec_zlib(&compressed).unwrap(),
+            data
+        );
+    }
+
+    #[test]
+    fn test_png_roundtrip() {
+        let image = ColorImage::new([3, 2], vec![Color32::from_rgb(10, 20, 30); 6]);
+        let png_data = png(&image);
+
+        let decoder = png::Decoder::new(png_data.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        assert_eq!((info.width, info.height), (3, 2));
+        assert_eq!(&buf[..4], &[10, 20, 30, 255]);
+    }
+}
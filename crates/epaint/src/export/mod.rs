@@ -0,0 +1,1017 @@
+//! Exporting [`Shape`]s as vector graphics (SVG and PDF).
+//!
+//! Unlike a screenshot, the result can be scaled without loss of quality,
+//! and text stays text that can be searched and copied.
+//!
+//! The [`VectorExporter`] takes the [`ClippedShape`]s of a frame
+//! (e.g. `egui::FullOutput::shapes`) before they are tessellated,
+//! and converts them to paths, text and images.
+
+mod encode;
+mod pdf;
+mod svg;
+
+use std::sync::Arc;
+
+use ecolor::Color32;
+use emath::{Pos2, Rect, Rot2, Vec2, pos2, vec2};
+
+use crate::{
+    CircleShape, ClippedPrimitive, ClippedShape, ColorImage, ColorMode, CornerRadiusF32,
+    EllipseShape, ImageDelta, Mesh, PathShape, PathStroke, Primitive, RectShape, Shape, Stroke,
+    StrokeKind, TessellationOptions, Tessellator, TextShape, TextureId,
+    software_renderer::SoftwareRenderer,
+    text::{FontData, FontDefinitions, FontFamily},
+    textures::TexturesDelta,
+};
+
+/// Converts [`ClippedShape`]s to SVG or PDF.
+///
+/// * Rectangles, circles, ellipses, paths and Bézier curves become vector paths.
+///   Blurred rectangles ([`RectShape::blur_width`]) are rasterized like meshes.
+/// * Text becomes real text, referencing the fonts by name.
+///   Call [`Self::fonts`] to use the names of the fonts in your [`FontDefinitions`],
+///   and [`Self::embed_fonts`] to include the fonts in the SVG.
+/// * Textured rectangles (images) become embedded bitmaps.
+///   The exporter needs to know the textures for this, see [`Self::update_textures`].
+/// * Any other [`Mesh`] is rasterized into an embedded bitmap, see [`Self::mesh_pixels_per_point`].
+/// * Clip rectangles become clip paths.
+///
+/// [`Shape::Callback`]s are skipped.
+///
+/// To export only part of a frame, pass the [`Rect`] of that part as the `region`,
+/// e.g. the rectangle of a `Ui`.
+/// To export a single layer, pass only the shapes of that layer,
+/// e.g. `ctx.graphics(|g| g.get(layer_id).map(|l| l.all_entries().cloned().collect()))`
+/// before the end of the pass.
+///
+/// The PDF export uses the standard PDF fonts (Helvetica and Courier),
+/// which only cover Latin text. Other characters are replaced with `?`.
+///
+/// ```
+/// # use epaint::{*, export::VectorExporter};
+/// let shapes = vec![ClippedShape {
+///     clip_rect: Rect::EVERYTHING,
+///     shape: Shape::circle_filled(pos2(50.0, 50.0), 25.0, Color32::RED),
+/// }];
+/// let region = Rect::from_min_size(Pos2::ZERO, vec2(100.0, 100.0));
+///
+/// let exporter = VectorExporter::default();
+/// let svg = exporter.svg(&shapes, region);
+/// assert!(svg.contains(r##"fill="#ff0000""##));
+/// let pdf = exporter.pdf(&shapes, region);
+/// assert!(pdf.starts_with(b"%PDF"));
+/// ```
+pub struct VectorExporter {
+    /// Stores the textures, and rasterizes meshes.
+    renderer: SoftwareRenderer,
+    font_definitions: Option<FontDefinitions>,
+    embed_fonts: bool,
+    mesh_pixels_per_point: f32,
+}
+
+impl Default for VectorExporter {
+    fn default() -> Self {
+        Self {
+            renderer: Default::default(),
+            font_definitions: None,
+            embed_fonts: false,
+            mesh_pixels_per_point: 2.0,
+        }
+    }
+}
+
+impl VectorExporter {
+    /// An exporter without any textures, using the generic fonts of the viewer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Refer to the fonts by the names they have in these [`FontDefinitions`],
+    /// falling back to the generic `sans-serif` and `monospace` fonts of the viewer.
+    ///
+    /// Without this, only the generic fonts are used.
+    #[inline]
+    pub fn fonts(mut self, font_definitions: FontDefinitions) -> Self {
+        self.font_definitions = Some(font_definitions);
+        self
+    }
+
+    /// Include the data of the used fonts in the SVG, so that it looks the same everywhere.
+    ///
+    /// This only has an effect together with [`Self::fonts`], and makes the SVG a lot larger.
+    /// Default: `false`.
+    #[inline]
+    pub fn embed_fonts(mut self, embed_fonts: bool) -> Self {
+        self.embed_fonts = embed_fonts;
+        self
+    }
+
+    /// The resolution to rasterize [`Mesh`]es with, in pixels per point.
+    ///
+    /// Default: `2.0`.
+    #[inline]
+    pub fn mesh_pixels_per_point(mut self, mesh_pixels_per_point: f32) -> Self {
+        self.mesh_pixels_per_point = mesh_pixels_per_point;
+        self
+    }
+
+    /// Keep track of the textures, so that images can be embedded.
+    ///
+    /// Call this with the [`TexturesDelta`] of every frame, e.g. `egui::FullOutput::textures_delta`,
+    /// after exporting that frame.
+    pub fn update_textures(&mut self, textures_delta: &TexturesDelta) {
+        for (id, image_delta) in &textures_delta.set {
+            self.set_texture(*id, image_delta);
+        }
+        for &id in &textures_delta.free {
+            self.renderer.free_texture(id);
+        }
+    }
+
+    /// Create, replace or update part of a texture.
+    pub fn set_texture(&mut self, id: TextureId, image_delta: &ImageDelta) {
+        self.renderer.set_texture(id, image_delta);
+    }
+
+    /// Export the part of the shapes that is inside `region` (in points) as an SVG document.
+    pub fn svg(&self, shapes: &[ClippedShape], region: Rect) -> String {
+        profiling::function_scope!();
+        let items = self.items(shapes, region);
+        svg::write_svg(&items, region.size(), &self.fonts_info(&items))
+    }
+
+    /// Export the part of the shapes that is inside `region` (in points) as a one-page PDF document.
+    ///
+    /// One point is one PDF unit (1/72 inch).
+    pub fn pdf(&self, shapes: &[ClippedShape], region: Rect) -> Vec<u8> {
+        profiling::function_scope!();
+        let items = self.items(shapes, region);
+        pdf::write_pdf(&items, region.size())
+    }
+
+    fn fonts_info(&self, items: &[ClippedItem]) -> FontsInfo {
+        let mut info = FontsInfo::default();
+        let Some(definitions) = &self.font_definitions else {
+            return info;
+        };
+
+        for item in items {
+            if let Item::Text(text) = &item.item
+                && !info.family_names.contains_key(&text.family)
+                && let Some(names) = definitions.families.get(&text.family)
+            {
+                info.family_names.insert(text.family.clone(), names.clone());
+                if self.embed_fonts {
+                    for name in names {
+                        if let Some(data) = definitions.font_data.get(name)
+                            && !info.embedded.iter().any(|(n, _)| n == name)
+                        {
+                            info.embedded.push((name.clone(), Arc::clone(data)));
+                        }
+                    }
+                }
+            }
+        }
+        info
+    }
+
+    /// Convert the shapes to items, relative to the top left of the region.
+    fn items(&self, shapes: &[ClippedShape], region: Rect) -> Vec<ClippedItem> {
+        let mut items = vec![];
+        for ClippedShape { clip_rect, shape } in shapes {
+            let clip_rect = clip_rect.intersect(region);
+            if clip_rect.is_positive() && shape.visual_bounding_rect().intersects(clip_rect) {
+                let mut out = vec![];
+                self.add_shape(shape, clip_rect, &mut out);
+                let offset = -region.min.to_vec2();
+                items.extend(out.into_iter().map(|item| ClippedItem {
+                    clip_rect: clip_rect.translate(offset),
+                    item: item.translate(offset),
+                }));
+            }
+        }
+        items
+    }
+
+    fn add_shape(&self, shape: &Shape, clip_rect: Rect, out: &mut Vec<Item>) {
+        match shape {
+            Shape::Noop | Shape::Callback(_) => {}
+            Shape::Vec(shapes) => {
+                for shape in shapes {
+                    self.add_shape(shape, clip_rect, out);
+                }
+            }
+            Shape::Circle(CircleShape {
+                center,
+                radius,
+                fill,
+                stroke,
+            }) => {
+                let path = ellipse_path(*center, Vec2::splat(*radius));
+                out.extend(PathItem::new(path, *fill, *stroke).map(Item::Path));
+            }
+            Shape::Ellipse(EllipseShape {
+                center,
+                radius,
+                fill,
+                stroke,
+            }) => {
+                let path = ellipse_path(*center, *radius);
+                out.extend(PathItem::new(path, *fill, *stroke).map(Item::Path));
+            }
+            Shape::LineSegment { points, stroke } => {
+                let path = vec![
+                    PathCommand::MoveTo(points[0]),
+                    PathCommand::LineTo(points[1]),
+                ];
+                out.extend(PathItem::new(path, Color32::TRANSPARENT, *stroke).map(Item::Path));
+            }
+            Shape::Path(PathShape {
+                points,
+                closed,
+                fill,
+                stroke,
+            }) => {
+                let Some((first, rest)) = points.split_first() else {
+                    return;
+                };
+                let mut path = vec![PathCommand::MoveTo(*first)];
+                path.extend(rest.iter().map(|p| PathCommand::LineTo(*p)));
+                let bounds = Rect::from_points(points);
+                add_path(path, *closed, *fill, stroke, bounds, out);
+            }
+            Shape::QuadraticBezier(bezier) => {
+                let [p0, p1, p2] = bezier.points;
+                // Elevate to a cubic curve:
+                let path = vec![
+                    PathCommand::MoveTo(p0),
+                    PathCommand::CubicTo(
+                        p0 + (p1 - p0) * (2.0 / 3.0),
+                        p2 + (p1 - p2) * (2.0 / 3.0),
+                        p2,
+                    ),
+                ];
+                let bounds = Rect::from_points(&bezier.points);
+                add_path(
+                    path,
+                    bezier.closed,
+                    bezier.fill,
+                    &bezier.stroke,
+                    bounds,
+                    out,
+                );
+            }
+            Shape::CubicBezier(bezier) => {
+                let [p0, p1, p2, p3] = bezier.points;
+                let path = vec![PathCommand::MoveTo(p0), PathCommand::CubicTo(p1, p2, p3)];
+                let bounds = Rect::from_points(&bezier.points);
+                add_path(
+                    path,
+                    bezier.closed,
+                    bezier.fill,
+                    &bezier.stroke,
+                    bounds,
+                    out,
+                );
+            }
+            Shape::Rect(rect_shape) => self.add_rect(rect_shape, clip_rect, out),
+            Shape::Text(text_shape) => add_text(text_shape, out),
+            Shape::Mesh(mesh) => self.add_mesh(mesh, clip_rect, out),
+        }
+    }
+
+    fn add_rect(&self, rect_shape: &RectShape, clip_rect: Rect, out: &mut Vec<Item>) {
+        if 0.0 < rect_shape.blur_width {
+            // Neither SVG paths nor PDF can blur, so we rasterize the blurred rectangle instead:
+            let mut tessellator = Tessellator::new(
+                self.mesh_pixels_per_point,
+                TessellationOptions::default(),
+                [1, 1],
+                vec![],
+            );
+            let mut mesh = Mesh::default();
+            tessellator.tessellate_rect(rect_shape, &mut mesh);
+            self.add_mesh(&mesh, clip_rect, out);
+            return;
+        }
+
+        let RectShape {
+            rect,
+            corner_radius,
+            fill,
+            stroke,
+            stroke_kind,
+            brush,
+            ..
+        } = rect_shape;
+        let corner_radius = CornerRadiusF32::from(*corner_radius);
+
+        let texture = brush.as_ref().filter(|brush| brush.uv != Rect::ZERO);
+        if let Some(brush) = texture {
+            if let Some(image) = self.texture_region(brush.fill_texture_id, brush.uv, *fill) {
+                let clip = (corner_radius != CornerRadiusF32::ZERO)
+                    .then(|| rounded_rect_path(*rect, corner_radius));
+                out.push(Item::Image(ImageItem {
+                    rect: *rect,
+                    image: Arc::new(image),
+                    clip,
+                }));
+            }
+        } else if *fill != Color32::TRANSPARENT {
+            let path = rounded_rect_path(*rect, corner_radius);
+            out.extend(PathItem::new(path, *fill, Stroke::NONE).map(Item::Path));
+        }
+
+        if !stroke.is_empty() {
+            let expand = match stroke_kind {
+                StrokeKind::Inside => -stroke.width / 2.0,
+                StrokeKind::Middle => 0.0,
+                StrokeKind::Outside => stroke.width / 2.0,
+            };
+            let stroke_rect = rect.expand(expand);
+            let mut stroke_radius = corner_radius;
+            stroke_radius += expand;
+            let path = rounded_rect_path(stroke_rect, stroke_radius.at_least(0.0));
+            out.extend(PathItem::new(path, Color32::TRANSPARENT, *stroke).map(Item::Path));
+        }
+    }
+
+    /// The part of a texture covered by `uv`, tinted.
+    fn texture_region(&self, texture_id: TextureId, uv: Rect, tint: Color32) -> Option<ColorImage> {
+        let Some(texture) = self.renderer.texture(texture_id) else {
+            log::warn!("Can't export image with unknown texture {texture_id:?}");
+            return None;
+        };
+        let [width, height] = texture.size;
+        let min_x = ((uv.min.x * width as f32).floor().max(0.0) as usize).min(width);
+        let min_y = ((uv.min.y * height as f32).floor().max(0.0) as usize).min(height);
+        let max_x = ((uv.max.x * width as f32).ceil().max(0.0) as usize).clamp(min_x, width);
+        let max_y = ((uv.max.y * height as f32).ceil().max(0.0) as usize).clamp(min_y, height);
+        if min_x == max_x || min_y == max_y {
+            return None;
+        }
+
+        let mut region = texture.region_by_pixels([min_x, min_y], [max_x - min_x, max_y - min_y]);
+        if tint != Color32::WHITE {
+            for pixel in &mut region.pixels {
+                *pixel = *pixel * tint;
+            }
+        }
+        Some(region)
+    }
+
+    /// Rasterize a mesh into an image.
+    fn add_mesh(&self, mesh: &Mesh, clip_rect: Rect, out: &mut Vec<Item>) {
+        if let Some((rect, uv, tint)) = image_rect(mesh) {
+            // E.g. from `Shape::image`: embed the texture instead of rasterizing it.
+            if let Some(image) = self.texture_region(mesh.texture_id, uv, tint) {
+                out.push(Item::Image(ImageItem {
+                    rect,
+                    image: Arc::new(image),
+                    clip: None,
+                }));
+            }
+            return;
+        }
+
+        let rect = mesh.calc_bounds().intersect(clip_rect);
+        if !rect.is_positive() {
+            return;
+        }
+        let pixels_per_point = self.mesh_pixels_per_point;
+        let rect = Rect::from_min_max(
+            (rect.min * pixels_per_point).floor() / pixels_per_point,
+            (rect.max * pixels_per_point).ceil() / pixels_per_point,
+        );
+        let size = rect.size() * pixels_per_point;
+        let size_in_pixels = [size.x.round() as usize, size.y.round() as usize];
+        if size_in_pixels.contains(&0) {
+            return;
+        }
+
+        let mut mesh = mesh.clone();
+        mesh.translate(-rect.min.to_vec2());
+        let primitive = ClippedPrimitive {
+            clip_rect: Rect::EVERYTHING,
+            primitive: Primitive::Mesh(mesh),
+        };
+        let image = self
+            .renderer
+            .render(&[primitive], size_in_pixels, pixels_per_point);
+
+        out.push(Item::Image(ImageItem {
+            rect,
+            image: Arc::new(image),
+            clip: None,
+        }));
+    }
+}
+
+/// If the mesh is a single textured rectangle, as created by [`Mesh::add_rect_with_uv`],
+/// return its rectangle, uv rectangle and tint.
+fn image_rect(mesh: &Mesh) -> Option<(Rect, Rect, Color32)> {
+    let [lt, rt, lb, rb] = mesh.vertices.as_slice() else {
+        return None;
+    };
+    let rect = Rect::from_min_max(lt.pos, rb.pos);
+    let uv = Rect::from_min_max(lt.uv, rb.uv);
+    let is_rect = mesh.indices == [0, 1, 2, 2, 1, 3]
+        && rect.is_positive()
+        && uv.is_positive()
+        && rt.pos == rect.right_top()
+        && lb.pos == rect.left_bottom()
+        && rt.uv == uv.right_top()
+        && lb.uv == uv.left_bottom()
+        && [rt, lb, rb].iter().all(|v| v.color == lt.color);
+    is_rect.then_some((rect, uv, lt.color))
+}
+
+fn add_path(
+    mut path: Vec<PathCommand>,
+    closed: bool,
+    fill: Color32,
+    stroke: &PathStroke,
+    bounds: Rect,
+    out: &mut Vec<Item>,
+) {
+    let fill = if closed { fill } else { Color32::TRANSPARENT };
+    if closed {
+        path.push(PathCommand::Close);
+    }
+    let color = match &stroke.color {
+        ColorMode::Solid(color) => *color,
+        ColorMode::UV(callback) => callback(bounds, bounds.center()),
+    };
+    let stroke = Stroke::new(stroke.width, color);
+    out.extend(PathItem::new(path, fill, stroke).map(Item::Path));
+}
+
+fn add_text(text_shape: &TextShape, out: &mut Vec<Item>) {
+    let TextShape {
+        pos,
+        galley,
+        underline,
+        fallback_color,
+        override_text_color,
+        opacity_factor,
+        angle,
+    } = text_shape;
+
+    if galley.is_empty() || *opacity_factor <= 0.0 {
+        return;
+    }
+
+    let rotation = (*angle != 0.0).then_some(*angle);
+    let rotator = Rot2::from_angle(*angle);
+    let transform = |p: Pos2| *pos + rotator * p.to_vec2();
+
+    let finish_color = |color: Color32| {
+        let color = if color == Color32::PLACEHOLDER {
+            *fallback_color
+        } else {
+            color
+        };
+        if *opacity_factor < 1.0 {
+            color.gamma_multiply(*opacity_factor)
+        } else {
+            color
+        }
+    };
+
+    let add_line = |[a, b]: [Pos2; 2], stroke: Stroke, out: &mut Vec<Item>| {
+        let stroke = Stroke::new(stroke.width, finish_color(stroke.color));
+        let path = vec![
+            PathCommand::MoveTo(transform(a)),
+            PathCommand::LineTo(transform(b)),
+        ];
+        out.extend(PathItem::new(path, Color32::TRANSPARENT, stroke).map(Item::Path));
+    };
+
+    for placed_row in &galley.rows {
+        let row = &placed_row.row;
+        let offset = placed_row.pos.to_vec2();
+        let section_of = |glyph_index: usize| row.glyphs[glyph_index].section_index as usize;
+
+        // Backgrounds:
+        for (run, color) in runs(row.glyphs.len(), |i| {
+            let format = &galley.job.sections[section_of(i)].format;
+            (format.background != Color32::TRANSPARENT)
+                .then_some((format.background, format.expand_bg))
+        }) {
+            let (color, expand) = color;
+            let first = &row.glyphs[run.start];
+            let last = &row.glyphs[run.end - 1];
+            let rect = Rect::from_min_max(
+                first.logical_rect().left_top(),
+                pos2(last.max_x(), first.logical_rect().bottom()),
+            )
+            .expand(expand)
+            .translate(offset);
+            let corners = [
+                rect.left_top(),
+                rect.right_top(),
+                rect.right_bottom(),
+                rect.left_bottom(),
+            ]
+            .map(transform);
+            let path = vec![
+                PathCommand::MoveTo(corners[0]),
+                PathCommand::LineTo(corners[1]),
+                PathCommand::LineTo(corners[2]),
+                PathCommand::LineTo(corners[3]),
+                PathCommand::Close,
+            ];
+            out.extend(PathItem::new(path, finish_color(color), Stroke::NONE).map(Item::Path));
+        }
+
+        // Glyphs, in runs of the same font and color:
+        let glyph_style = |i: usize| {
+            let glyph = &row.glyphs[i];
+            if glyph.chr == crate::text::Placeholder::CHAR || glyph.chr.is_control() {
+                return None;
+            }
+            let format = &galley.job.sections[section_of(i)].format;
+            let mesh_color = (!glyph.uv_rect.is_nothing())
+                .then(|| row.visuals.mesh.vertices.get(glyph.first_vertex as usize))
+                .flatten()
+                .map_or(format.color, |vertex| vertex.color);
+            let color = match override_text_color {
+                Some(color) if !glyph.uv_rect.is_color => *color,
+                _ => mesh_color,
+            };
+            Some((
+                format.font_id.family.clone(),
+                OrderedF32(format.font_id.size),
+                format.italics,
+                finish_color(color),
+                OrderedF32(glyph.pos.y),
+            ))
+        };
+        for (run, (family, size, italics, color, baseline)) in runs(row.glyphs.len(), glyph_style) {
+            let glyphs = &row.glyphs[run];
+            let text: String = glyphs.iter().map(|glyph| glyph.chr).collect();
+            let origin = *pos + offset;
+            out.push(Item::Text(TextItem {
+                text,
+                xs: glyphs.iter().map(|glyph| origin.x + glyph.pos.x).collect(),
+                baseline: origin.y + baseline.0,
+                family,
+                size: size.0,
+                italics,
+                color,
+                origin: *pos,
+                rotation,
+            }));
+        }
+
+        // Underline and strikethrough:
+        for (get_stroke, y_of) in [
+            (
+                (|format: &crate::text::TextFormat| format.underline)
+                    as fn(&crate::text::TextFormat) -> Stroke,
+                (|rect: Rect| rect.bottom()) as fn(Rect) -> f32,
+            ),
+            (|format| format.strikethrough, |rect| rect.center().y),
+        ] {
+            for (run, stroke) in runs(row.glyphs.len(), |i| {
+                let stroke = get_stroke(&galley.job.sections[section_of(i)].format);
+                (!stroke.is_empty())
+                    .then_some((stroke, OrderedF32(y_of(row.glyphs[i].logical_rect()))))
+            }) {
+                let (stroke, y) = stroke;
+                let start = pos2(row.glyphs[run.start].pos.x, y.0) + offset;
+                let end = pos2(row.glyphs[run.end - 1].max_x(), y.0) + offset;
+                add_line([start, end], stroke, out);
+            }
+        }
+
+        if !underline.is_empty() && !row.glyphs.is_empty() {
+            let bounds = row.visuals.mesh_bounds.translate(offset);
+            add_line(
+                [bounds.left_bottom(), bounds.right_bottom()],
+                *underline,
+                out,
+            );
+        }
+    }
+}
+
+/// Split `0..len` into runs of consecutive indices with the same `Some` key.
+fn runs<K: PartialEq>(
+    len: usize,
+    key: impl Fn(usize) -> Option<K>,
+) -> Vec<(std::ops::Range<usize>, K)> {
+    let mut runs: Vec<(std::ops::Range<usize>, K)> = vec![];
+    for i in 0..len {
+        let Some(k) = key(i) else {
+            continue;
+        };
+        match runs.last_mut() {
+            Some((range, last)) if range.end == i && *last == k => range.end = i + 1,
+            _ => runs.push((i..i + 1, k)),
+        }
+    }
+    runs
+}
+
+/// `f32` that can be compared for equality, for [`runs`].
+#[derive(Clone, Copy, PartialEq)]
+struct OrderedF32(f32);
+
+// ----------------------------------------------------------------------------
+
+/// How to refer to the fonts used by the text.
+#[derive(Default)]
+struct FontsInfo {
+    /// The names of the fonts of each family, in fallback order.
+    family_names: std::collections::BTreeMap<FontFamily, Vec<String>>,
+
+    /// Fonts to embed.
+    embedded: Vec<(String, Arc<FontData>)>,
+}
+
+struct ClippedItem {
+    clip_rect: Rect,
+    item: Item,
+}
+
+/// Something to paint, in a form that is easy to write as SVG or PDF.
+enum Item {
+    Path(PathItem),
+    Text(TextItem),
+    Image(ImageItem),
+}
+
+impl Item {
+    fn translate(self, offset: Vec2) -> Self {
+        match self {
+            Self::Path(mut path) => {
+                translate_path(&mut path.commands, offset);
+                Self::Path(path)
+            }
+            Self::Text(mut text) => {
+                for x in &mut text.xs {
+                    *x += offset.x;
+                }
+                text.baseline += offset.y;
+                text.origin += offset;
+                Self::Text(text)
+            }
+            Self::Image(mut image) => {
+                image.rect = image.rect.translate(offset);
+                if let Some(clip) = &mut image.clip {
+                    translate_path(clip, offset);
+                }
+                Self::Image(image)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PathCommand {
+    MoveTo(Pos2),
+    LineTo(Pos2),
+    CubicTo(Pos2, Pos2, Pos2),
+    Close,
+}
+
+fn translate_path(path: &mut [PathCommand], offset: Vec2) {
+    for command in path {
+        match command {
+            PathCommand::MoveTo(p) | PathCommand::LineTo(p) => *p += offset,
+            PathCommand::CubicTo(a, b, c) => {
+                *a += offset;
+                *b += offset;
+                *c += offset;
+            }
+            PathCommand::Close => {}
+        }
+    }
+}
+
+struct PathItem {
+    commands: Vec<PathCommand>,
+    fill: Color32,
+    stroke: Stroke,
+}
+
+impl PathItem {
+    /// `None` if there is nothing to paint.
+    fn new(commands: Vec<PathCommand>, fill: Color32, stroke: Stroke) -> Option<Self> {
+        let stroke = if stroke.is_empty() {
+            Stroke::NONE
+        } else {
+            stroke
+        };
+        (fill != Color32::TRANSPARENT || !stroke.is_empty()).then_some(Self {
+            commands,
+            fill,
+            stroke,
+        })
+    }
+}
+
+/// A run of glyphs with the same style.
+struct TextItem {
+    text: String,
+
+    /// The x coordinate of each char.
+    xs: Vec<f32>,
+    baseline: f32,
+
+    family: FontFamily,
+    size: f32,
+    italics: bool,
+    color: Color32,
+
+    /// What the rotation is around.
+    origin: Pos2,
+
+    /// Clockwise angle in radians, around [`Self::origin`].
+    rotation: Option<f32>,
+}
+
+struct ImageItem {
+    rect: Rect,
+    image: Arc<ColorImage>,
+
+    /// Only show the part of the image inside this path, e.g. for rounded corners.
+    clip: Option<Vec<PathCommand>>,
+}
+
+/// Magic number for approximating a quarter circle with a cubic Bézier curve.
+const KAPPA: f32 = 0.552_284_8;
+
+fn ellipse_path(center: Pos2, radius: Vec2) -> Vec<PathCommand> {
+    let k = radius * KAPPA;
+    let (rx, ry) = (radius.x, radius.y);
+    let c = center;
+    vec![
+        PathCommand::MoveTo(c + vec2(rx, 0.0)),
+        PathCommand::CubicTo(c + vec2(rx, k.y), c + vec2(k.x, ry), c + vec2(0.0, ry)),
+        PathCommand::CubicTo(c + vec2(-k.x, ry), c + vec2(-rx, k.y), c + vec2(-rx, 0.0)),
+        PathCommand::CubicTo(c + vec2(-rx, -k.y), c + vec2(-k.x, -ry), c + vec2(0.0, -ry)),
+        PathCommand::CubicTo(c + vec2(k.x, -ry), c + vec2(rx, -k.y), c + vec2(rx, 0.0)),
+        PathCommand::Close,
+    ]
+}
+
+/// A closed rectangle path, with rounded corners.
+fn rounded_rect_path(rect: Rect, corner_radius: CornerRadiusF32) -> Vec<PathCommand> {
+    let max_radius = rect.width().min(rect.height()) / 2.0;
+    let CornerRadiusF32 { nw, ne, sw, se } = corner_radius.at_most(max_radius);
+    let (min, max) = (rect.min, rect.max);
+
+    // Cubic control points for a corner going from `from` to `to`, curving around `corner`:
+    let corner = |from: Pos2, corner: Pos2, to: Pos2| {
+        PathCommand::CubicTo(
+            from + (corner - from) * KAPPA,
+            to + (corner - to) * KAPPA,
+            to,
+        )
+    };
+
+    let mut path = vec![PathCommand::MoveTo(pos2(min.x + nw, min.y))];
+    path.push(PathCommand::LineTo(pos2(max.x - ne, min.y)));
+    if ne > 0.0 {
+        path.push(corner(
+            pos2(max.x - ne, min.y),
+            pos2(max.x, min.y),
+            pos2(max.x, min.y + ne),
+        ));
+    }
+    path.push(PathCommand::LineTo(pos2(max.x, max.y - se)));
+    if se > 0.0 {
+        path.push(corner(
+            pos2(max.x, max.y - se),
+            pos2(max.x, max.y),
+            pos2(max.x - se, max.y),
+        ));
+    }
+    path.push(PathCommand::LineTo(pos2(min.x + sw, max.y)));
+    if sw > 0.0 {
+        path.push(corner(
+            pos2(min.x + sw, max.y),
+            pos2(min.x, max.y),
+            pos2(min.x, max.y - sw),
+        ));
+    }
+    path.push(PathCommand::LineTo(pos2(min.x, min.y + nw)));
+    if nw > 0.0 {
+        path.push(corner(
+            pos2(min.x, min.y + nw),
+            pos2(min.x, min.y),
+            pos2(min.x + nw, min.y),
+        ));
+    }
+    path.push(PathCommand::Close);
+    path
+}
+
+/// Format a coordinate with at most three decimals, without trailing zeros.
+fn fmt_num(value: f32) -> String {
+    let value = if value.is_finite() { value } else { 0.0 };
+    let s = format!("{value:.3}");
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" {
+        "0".to_owned()
+    } else {
+        s.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Fonts, TextOptions,
+        text::{LayoutJob, TextFormat},
+    };
+
+    fn region() -> Rect {
+        Rect::from_min_size(Pos2::ZERO, vec2(200.0, 100.0))
+    }
+
+    #[test]
+    fn test_svg_shapes() {
+        let shapes = vec![
+            ClippedShape {
+                clip_rect: Rect::from_min_size(Pos2::ZERO, vec2(100.0, 100.0)),
+                shape: Shape::Rect(RectShape::new(
+                    Rect::from_min_size(pos2(10.0, 10.0), vec2(50.0, 20.0)),
+                    4.0,
+                    Color32::from_rgb(10, 20, 30),
+                    Stroke::new(2.0, Color32::WHITE),
+                    StrokeKind::Inside,
+                )),
+            },
+            ClippedShape {
+                clip_rect: Rect::EVERYTHING,
+                shape: Shape::line_segment(
+                    [pos2(0.0, 0.0), pos2(100.0, 50.0)],
+                    Stroke::new(1.5, Color32::from_black_alpha(128)),
+                ),
+            },
+            ClippedShape {
+                // Outside of the region:
+                clip_rect: Rect::EVERYTHING,
+                shape: Shape::circle_filled(pos2(500.0, 500.0), 10.0, Color32::RED),
+            },
+        ];
+
+        let svg = VectorExporter::default().svg(&shapes, region());
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(r#"viewBox="0 0 200 100""#));
+        assert!(svg.contains(r##"fill="#0a141e""##));
+        assert!(svg.contains(r##"stroke="#ffffff""##));
+        assert!(svg.contains(r#"stroke-width="1.5""#));
+        assert!(svg.contains(r#"stroke-opacity="0.502""#));
+        assert!(svg.contains("<clipPath"));
+        assert!(!svg.contains(r##"fill="#ff0000""##));
+    }
+
+    #[cfg(feature = "default_fonts")]
+    #[test]
+    fn test_text_export() {
+        let mut fonts = Fonts::new(TextOptions::default(), FontDefinitions::default());
+
+        let mut job = LayoutJob::default();
+        job.append(
+            "Hello ",
+            0.0,
+            TextFormat::simple(crate::FontId::proportional(14.0), Color32::RED),
+        );
+        job.append(
+            "<world>",
+            0.0,
+            TextFormat {
+                underline: Stroke::new(1.0, Color32::BLUE),
+                ..TextFormat::simple(crate::FontId::monospace(12.0), Color32::GREEN)
+            },
+        );
+        let galley = fonts.with_pixels_per_point(1.0).layout_job(job);
+        let shapes = vec![ClippedShape {
+            clip_rect: Rect::EVERYTHING,
+            shape: Shape::galley(pos2(10.0, 20.0), galley, Color32::WHITE),
+        }];
+
+        let exporter = VectorExporter::default().fonts(FontDefinitions::default());
+        let svg = exporter.svg(&shapes, region());
+        assert!(svg.contains(">Hello </text>"), "{svg}");
+        assert!(svg.contains(">&lt;world&gt;</text>"), "{svg}");
+        assert!(svg.contains("font-family=\"'Hack', "), "{svg}");
+        assert!(svg.contains(r##"stroke="#0000ff""##), "{svg}");
+        assert!(!svg.contains("@font-face"));
+
+        let svg = exporter.embed_fonts(true).svg(&shapes, region());
+        assert!(svg.contains("@font-face"));
+
+        let pdf = VectorExporter::default().pdf(&shapes, region());
+        let pdf = String::from_utf8_lossy(&pdf);
+        assert!(pdf.contains("(H) Tj"), "{pdf}");
+        assert!(pdf.contains("(<) Tj"), "{pdf}");
+        assert!(pdf.contains("/BaseFont /Courier"));
+    }
+
+    #[cfg(feature = "default_fonts")]
+    #[test]
+    fn test_text_export_paragraphs() {
+        let mut fonts = Fonts::new(TextOptions::default(), FontDefinitions::default());
+
+        // Each paragraph is laid out on its own, then concatenated:
+        let mut job = LayoutJob::default();
+        let font_id = crate::FontId::proportional(14.0);
+        job.append(
+            "red\n",
+            0.0,
+            TextFormat::simple(font_id.clone(), Color32::RED),
+        );
+        job.append(
+            "green",
+            0.0,
+            TextFormat::simple(font_id.clone(), Color32::GREEN),
+        );
+        job.append("\nblue", 0.0, TextFormat::simple(font_id, Color32::BLUE));
+        let galley = fonts.with_pixels_per_point(1.0).layout_job(job);
+        let shapes = vec![ClippedShape {
+            clip_rect: Rect::EVERYTHING,
+            shape: Shape::galley(pos2(10.0, 20.0), galley, Color32::WHITE),
+        }];
+
+        let svg = VectorExporter::default().svg(&shapes, region());
+        for (color, text) in [("ff0000", "red"), ("00ff00", "green"), ("0000ff", "blue")] {
+            let text_element = svg
+                .split("<text")
+                .find(|element| element.contains(&format!(">{text}</text>")))
+                .unwrap_or_else(|| panic!("{text} missing from {svg}"));
+            assert!(
+                text_element.contains(&format!("#{color}")),
+                "{text_element}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_image_export() {
+        let mut exporter = VectorExporter::default();
+        let id = TextureId::User(7);
+        exporter.set_texture(
+            id,
+            &ImageDelta::full(ColorImage::filled([4, 2], Color32::RED), Default::default()),
+        );
+        let shapes = vec![ClippedShape {
+            clip_rect: Rect::EVERYTHING,
+            shape: Shape::image(
+                id,
+                Rect::from_min_size(pos2(10.0, 10.0), vec2(40.0, 20.0)),
+                Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0)),
+                Color32::WHITE,
+            ),
+        }];
+
+        let svg = exporter.svg(&shapes, region());
+        assert!(svg.contains("data:image/png;base64,"));
+        assert!(svg.contains(r#"width="40""#));
+
+        let pdf = exporter.pdf(&shapes, region());
+        let pdf = String::from_utf8_lossy(&pdf);
+        assert!(pdf.contains("/Subtype /Image"));
+        assert!(pdf.contains("/SMask"));
+    }
+
+    #[test]
+    fn test_blurred_rect_export() {
+        let mut rect_shape = RectShape::filled(
+            Rect::from_min_size(pos2(10.0, 10.0), vec2(40.0, 20.0)),
+            0.0,
+            Color32::RED,
+        );
+        rect_shape.blur_width = 8.0;
+        let shapes = vec![ClippedShape {
+            clip_rect: Rect::EVERYTHING,
+            shape: Shape::Rect(rect_shape),
+        }];
+
+        let mut exporter = VectorExporter::default();
+        exporter.set_texture(
+            TextureId::default(),
+            &ImageDelta::full(
+                ColorImage::filled([1, 1], Color32::WHITE),
+                Default::default(),
+            ),
+        );
+        let svg = exporter.svg(&shapes, region());
+        assert!(svg.contains("data:image/png;base64,"), "{svg}");
+        assert!(!svg.contains("<path"), "{svg}");
+    }
+
+    #[test]
+    fn test_fmt_num() {
+        assert_eq!(fmt_num(1.0), "1");
+        assert_eq!(fmt_num(1.25), "1.25");
+        assert_eq!(fmt_num(-0.0001), "0");
+        assert_eq!(fmt_num(1.0 / 3.0), "0.333");
+    }
+}
@@ -0,0 +1,355 @@
+use std::fmt::Write as _;
+
+use ecolor::Color32;
+use emath::{Pos2, Rot2, Vec2, vec2};
+
+use super::{ClippedItem, ImageItem, Item, PathCommand, PathItem, TextItem, encode, fmt_num};
+use crate::text::FontFamily;
+
+/// The standard fonts every PDF viewer has, in the order of their resource names `/F0`…`/F3`.
+const FONTS: [&str; 4] = [
+    "Helvetica",
+    "Helvetica-Oblique",
+    "Courier",
+    "Courier-Oblique",
+];
+
+/// Objects with fixed numbers. Fonts and images come after these.
+const CATALOG: usize = 1;
+const PAGES: usize = 2;
+const PAGE: usize = 3;
+const CONTENTS: usize = 4;
+const FIRST_FONT: usize = 5;
+const FIRST_IMAGE: usize = FIRST_FONT + FONTS.len();
+
+pub fn write_pdf(items: &[ClippedItem], size: Vec2) -> Vec<u8> {
+    let mut content = Content::default();
+
+    // Flip the y axis, so that we can use the same coordinates as egui:
+    writeln!(content.ops, "1 0 0 -1 0 {} cm", fmt_num(size.y)).ok();
+
+    let mut open_clip = None;
+    for ClippedItem { clip_rect, item } in items {
+        if open_clip != Some(*clip_rect) {
+            if open_clip.is_some() {
+                content.ops += "Q\n";
+            }
+            open_clip = Some(*clip_rect);
+            writeln!(
+                content.ops,
+                "q {} {} {} {} re W n",
+                fmt_num(clip_rect.min.x),
+                fmt_num(clip_rect.min.y),
+                fmt_num(clip_rect.width()),
+                fmt_num(clip_rect.height()),
+            )
+            .ok();
+        }
+
+        match item {
+            Item::Path(path) => content.path(path),
+            Item::Text(text) => content.text(text),
+            Item::Image(image) => content.image(image),
+        }
+    }
+    if open_clip.is_some() {
+        content.ops += "Q\n";
+    }
+
+    content.finish(size)
+}
+
+#[derive(Default)]
+struct Content {
+    ops: String,
+
+    /// Alpha values used, for `/ExtGState`.
+    alphas: Vec<u8>,
+    images: Vec<(Vec<u8>, Vec<u8>, [usize; 2])>,
+}
+
+impl Content {
+    fn path(&mut self, path: &PathItem) {
+        let PathItem {
+            commands,
+            fill,
+            stroke,
+        } = path;
+        let has_fill = *fill != Color32::TRANSPARENT;
+        let has_stroke = !stroke.is_empty();
+
+        self.ops += "q ";
+        if has_fill {
+            self.color(*fill, false);
+        }
+        if has_stroke {
+            self.color(stroke.color, true);
+            write!(self.ops, "{} w ", fmt_num(stroke.width)).ok();
+        }
+        write_path(&mut self.ops, commands);
+        let paint = match (has_fill, has_stroke) {
+            (true, true) => "B",
+            (true, false) => "f",
+            (false, _) => "S",
+        };
+        writeln!(self.ops, "{paint} Q").ok();
+    }
+
+    fn text(&mut self, text: &TextItem) {
+        let TextItem {
+            text,
+            xs,
+            baseline,
+            family,
+            size,
+            italics,
+            color,
+            origin,
+            rotation,
+        } = text;
+
+        let font = 2 * usize::from(*family == FontFamily::Monospace) + usize::from(*italics);
+        let rot = Rot2::from_angle(rotation.unwrap_or(0.0));
+
+        self.ops += "q ";
+        self.color(*color, false);
+        writeln!(self.ops, "BT /F{font} {} Tf", fmt_num(*size)).ok();
+        for (c, x) in text.chars().zip(xs) {
+            if c.is_whitespace() {
+                continue;
+            }
+            let pos: Pos2 = *origin + rot * (Pos2::new(*x, *baseline) - *origin);
+            // Undo the flipped y axis, so the glyphs are upright:
+            let x_axis = rot * vec2(1.0, 0.0);
+            let y_axis = rot * vec2(0.0, -1.0);
+            writeln!(
+                self.ops,
+                "{} {} {} {} {} {} Tm ({}) Tj",
+                fmt_num(x_axis.x),
+                fmt_num(x_axis.y),
+                fmt_num(y_axis.x),
+                fmt_num(y_axis.y),
+                fmt_num(pos.x),
+                fmt_num(pos.y),
+                escape_string(win_ansi(c)),
+            )
+            .ok();
+        }
+        self.ops += "ET Q\n";
+    }
+
+    fn image(&mut self, image: &ImageItem) {
+        let ImageItem { rect, image, clip } = image;
+        let [width, height] = image.size;
+
+        let mut rgb = Vec::with_capacity(width * height * 3);
+        let mut alpha = Vec::with_capacity(width * height);
+        for pixel in &image.pixels {
+            let [r, g, b, a] = pixel.to_srgba_unmultiplied();
+            rgb.extend_from_slice(&[r, g, b]);
+            alpha.push(a);
+        }
+        let index = self.images.len();
+        self.images.push((rgb, alpha, image.size));
+
+        self.ops += "q ";
+        if let Some(clip) = clip {
+            write_path(&mut self.ops, clip);
+            self.ops += "W n ";
+        }
+        writeln!(
+            self.ops,
+            "{} 0 0 {} {} {} cm /Im{index} Do Q",
+            fmt_num(rect.width()),
+            fmt_num(-rect.height()),
+            fmt_num(rect.min.x),
+            fmt_num(rect.max.y),
+        )
+        .ok();
+    }
+
+    /// Set the fill or stroke color, including alpha.
+    fn color(&mut self, color: Color32, stroke: bool) {
+        let [r, g, b, a] = color.to_srgba_unmultiplied();
+        let [r, g, b] = [r, g, b].map(|c| fmt_num(f32::from(c) / 255.0));
+        let op = if stroke { "RG" } else { "rg" };
+        write!(self.ops, "{r} {g} {b} {op} ").ok();
+        if a < 255 {
+            let index = self.alphas.iter().position(|&x| x == a).unwrap_or_else(|| {
+                self.alphas.push(a);
+                self.alphas.len() - 1
+            });
+            let kind = if stroke { "S" } else { "F" };
+            write!(self.ops, "/GS{kind}{index} gs ").ok();
+        }
+    }
+
+    fn finish(self, size: Vec2) -> Vec<u8> {
+        let Self {
+            ops,
+            alphas,
+            images,
+        } = self;
+
+        let mut objects: Vec<Vec<u8>> = vec![];
+
+        objects.push(format!("<< /Type /Catalog /Pages {PAGES} 0 R >>").into_bytes());
+        objects.push(format!("<< /Type /Pages /Kids [{PAGE} 0 R] /Count 1 >>").into_bytes());
+
+        let mut resources = String::from("<< /Font <<");
+        for i in 0..FONTS.len() {
+            write!(resources, " /F{i} {} 0 R", FIRST_FONT + i).ok();
+        }
+        resources += " >>";
+        if !alphas.is_empty() {
+            resources += " /ExtGState <<";
+            for (i, alpha) in alphas.iter().enumerate() {
+                let alpha = fmt_num(f32::from(*alpha) / 255.0);
+                write!(
+                    resources,
+                    " /GSF{i} << /ca {alpha} >> /GSS{i} << /CA {alpha} >>"
+                )
+                .ok();
+            }
+            resources += " >>";
+        }
+        if !images.is_empty() {
+            resources += " /XObject <<";
+            for i in 0..images.len() {
+                write!(resources, " /Im{i} {} 0 R", FIRST_IMAGE + 2 * i).ok();
+            }
+            resources += " >>";
+        }
+        resources += " >>";
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent {PAGES} 0 R /MediaBox [0 0 {} {}] /Resources {resources} /Contents {CONTENTS} 0 R >>",
+                fmt_num(size.x),
+                fmt_num(size.y)
+            )
+            .into_bytes(),
+        );
+
+        objects.push(stream("", ops.as_bytes()));
+
+        for font in FONTS {
+            objects.push(
+                format!(
+                    "<< /Type /Font /Subtype /Type1 /BaseFont /{font} /Encoding /WinAnsiEncoding >>"
+                )
+                .into_bytes(),
+            );
+        }
+
+        for (i, (rgb, alpha, [width, height])) in images.iter().enumerate() {
+            let mask = FIRST_IMAGE + 2 * i + 1;
+            objects.push(stream(
+                &format!(
+                    "/Type /XObject /Subtype /Image /Width {width} /Height {height} /ColorSpace /DeviceRGB /BitsPerComponent 8 /SMask {mask} 0 R /Filter /FlateDecode"
+                ),
+                &encode::zlib(rgb),
+            ));
+            objects.push(stream(
+                &format!(
+                    "/Type /XObject /Subtype /Image /Width {width} /Height {height} /ColorSpace /DeviceGray /BitsPerComponent 8 /Filter /FlateDecode"
+                ),
+                &encode::zlib(alpha),
+            ));
+        }
+
+        let mut pdf = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            pdf.extend_from_slice(object);
+            pdf.extend_from_slice(b"\nendobj\n");
+        }
+
+        let xref = pdf.len();
+        let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            writeln!(trailer, "{offset:010} 00000 n ").ok();
+        }
+        write!(
+            trailer,
+            "trailer\n<< /Size {} /Root {CATALOG} 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        )
+        .ok();
+        pdf.extend_from_slice(trailer.as_bytes());
+        pdf
+    }
+}
+
+fn stream(dict: &str, data: &[u8]) -> Vec<u8> {
+    let mut out = format!("<< {dict} /Length {} >>\nstream\n", data.len()).into_bytes();
+    out.extend_from_slice(data);
+    out.extend_from_slice(b"\nendstream");
+    out
+}
+
+fn write_path(ops: &mut String, commands: &[PathCommand]) {
+    for command in commands {
+        match command {
+            PathCommand::MoveTo(p) => write!(ops, "{} {} m ", fmt_num(p.x), fmt_num(p.y)),
+            PathCommand::LineTo(p) => write!(ops, "{} {} l ", fmt_num(p.x), fmt_num(p.y)),
+            PathCommand::CubicTo(a, b, c) => write!(
+                ops,
+                "{} {} {} {} {} {} c ",
+                fmt_num(a.x),
+                fmt_num(a.y),
+                fmt_num(b.x),
+                fmt_num(b.y),
+                fmt_num(c.x),
+                fmt_num(c.y)
+            ),
+            PathCommand::Close => write!(ops, "h "),
+        }
+        .ok();
+    }
+}
+
+/// The `WinAnsiEncoding` byte for this character, or `?` if there is none.
+fn win_ansi(c: char) -> u8 {
+    match c {
+        ' '..='~' | '\u{a0}'..='\u{ff}' => c as u8,
+        '€' => 0x80,
+        '‚' => 0x82,
+        'ƒ' => 0x83,
+        '„' => 0x84,
+        '…' => 0x85,
+        '†' => 0x86,
+        '‡' => 0x87,
+        'ˆ' => 0x88,
+        '‰' => 0x89,
+        'Š' => 0x8a,
+        '‹' => 0x8b,
+        'Œ' => 0x8c,
+        'Ž' => 0x8e,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        '˜' => 0x98,
+        '™' => 0x99,
+        'š' => 0x9a,
+        '›' => 0x9b,
+        'œ' => 0x9c,
+        'ž' => 0x9e,
+        'Ÿ' => 0x9f,
+        _ => b'?',
+    }
+}
+
+/// The contents of a PDF string literal, for a single byte.
+fn escape_string(byte: u8) -> String {
+    match byte {
+        b'(' | b')' | b'\\' => format!("\\{}", byte as char),
+        b' '..=b'~' => (byte as char).to_string(),
+        _ => format!("\\{byte:03o}"),
+    }
+}
@@ -0,0 +1,241 @@
+use std::fmt::Write as _;
+
+use ecolor::Color32;
+use emath::{Rect, Vec2};
+
+use super::{
+    ClippedItem, FontsInfo, ImageItem, Item, PathCommand, PathItem, TextItem, encode, fmt_num,
+};
+use crate::text::FontFamily;
+
+pub fn write_svg(items: &[ClippedItem], size: Vec2, fonts: &FontsInfo) -> String {
+    let (width, height) = (fmt_num(size.x), fmt_num(size.y));
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    );
+    svg.push('\n');
+
+    if !fonts.embedded.is_empty() {
+        svg += "<defs><style>\n";
+        for (name, data) in &fonts.embedded {
+            let mime = if data.font.starts_with(b"OTTO") {
+                "font/otf"
+            } else {
+                "font/ttf"
+            };
+            writeln!(
+                svg,
+                "@font-face {{ font-family: '{}'; src: url(data:{mime};base64,{}); }}",
+                escape_font_name(name),
+                encode::base64(&data.font)
+            )
+            .ok();
+        }
+        svg += "</style></defs>\n";
+    }
+
+    let page = Rect::from_min_size(emath::Pos2::ZERO, size);
+    let mut next_id = 0;
+    let mut open_clip = None;
+
+    for ClippedItem { clip_rect, item } in items {
+        if open_clip != Some(*clip_rect) {
+            if open_clip.is_some() {
+                svg += "</g>\n";
+            }
+            open_clip = Some(*clip_rect);
+            if clip_rect.contains_rect(page) {
+                svg += "<g>\n";
+            } else {
+                let id = next_id;
+                next_id += 1;
+                writeln!(
+                    svg,
+                    r#"<clipPath id="clip{id}"><rect x="{}" y="{}" width="{}" height="{}"/></clipPath>"#,
+                    fmt_num(clip_rect.min.x),
+                    fmt_num(clip_rect.min.y),
+                    fmt_num(clip_rect.width()),
+                    fmt_num(clip_rect.height()),
+                )
+                .ok();
+                writeln!(svg, r#"<g clip-path="url(#clip{id})">"#).ok();
+            }
+        }
+
+        match item {
+            Item::Path(path) => write_path(&mut svg, path),
+            Item::Text(text) => write_text(&mut svg, text, fonts),
+            Item::Image(image) => write_image(&mut svg, image, &mut next_id),
+        }
+    }
+    if open_clip.is_some() {
+        svg += "</g>\n";
+    }
+
+    svg += "</svg>\n";
+    svg
+}
+
+fn write_path(svg: &mut String, path: &PathItem) {
+    let PathItem {
+        commands,
+        fill,
+        stroke,
+    } = path;
+    write!(svg, r#"<path d="{}""#, path_data(commands)).ok();
+    if *fill == Color32::TRANSPARENT {
+        *svg += r#" fill="none""#;
+    } else {
+        write_color(svg, "fill", *fill);
+    }
+    if !stroke.is_empty() {
+        write_color(svg, "stroke", stroke.color);
+        write!(svg, r#" stroke-width="{}""#, fmt_num(stroke.width)).ok();
+    }
+    *svg += "/>\n";
+}
+
+fn write_text(svg: &mut String, text: &TextItem, fonts: &FontsInfo) {
+    let TextItem {
+        text,
+        xs,
+        baseline,
+        family,
+        size,
+        italics,
+        color,
+        origin,
+        rotation,
+    } = text;
+
+    let xs = xs.iter().map(|x| fmt_num(*x)).collect::<Vec<_>>().join(" ");
+    write!(
+        svg,
+        r#"<text x="{xs}" y="{}" font-family="{}" font-size="{}""#,
+        fmt_num(*baseline),
+        font_family(family, fonts),
+        fmt_num(*size),
+    )
+    .ok();
+    if *italics {
+        *svg += r#" font-style="italic""#;
+    }
+    write_color(svg, "fill", *color);
+    if let Some(angle) = rotation {
+        write!(
+            svg,
+            r#" transform="rotate({} {} {})""#,
+            fmt_num(angle.to_degrees()),
+            fmt_num(origin.x),
+            fmt_num(origin.y)
+        )
+        .ok();
+    }
+    writeln!(svg, r#" xml:space="preserve">{}</text>"#, escape(text)).ok();
+}
+
+fn write_image(svg: &mut String, image: &ImageItem, next_id: &mut usize) {
+    let ImageItem { rect, image, clip } = image;
+
+    if let Some(clip) = clip {
+        let id = *next_id;
+        *next_id += 1;
+        writeln!(
+            svg,
+            r#"<clipPath id="clip{id}"><path d="{}"/></clipPath>"#,
+            path_data(clip)
+        )
+        .ok();
+        write!(svg, r#"<g clip-path="url(#clip{id})">"#).ok();
+    }
+    write!(
+        svg,
+        r#"<image x="{}" y="{}" width="{}" height="{}" preserveAspectRatio="none" xlink:href="data:image/png;base64,{}"/>"#,
+        fmt_num(rect.min.x),
+        fmt_num(rect.min.y),
+        fmt_num(rect.width()),
+        fmt_num(rect.height()),
+        encode::base64(&encode::png(image)),
+    )
+    .ok();
+    if clip.is_some() {
+        *svg += "</g>";
+    }
+    svg.push('\n');
+}
+
+fn path_data(commands: &[PathCommand]) -> String {
+    let mut d = String::new();
+    for command in commands {
+        if !d.is_empty() {
+            d.push(' ');
+        }
+        match command {
+            PathCommand::MoveTo(p) => write!(d, "M{} {}", fmt_num(p.x), fmt_num(p.y)),
+            PathCommand::LineTo(p) => write!(d, "L{} {}", fmt_num(p.x), fmt_num(p.y)),
+            PathCommand::CubicTo(a, b, c) => write!(
+                d,
+                "C{} {} {} {} {} {}",
+                fmt_num(a.x),
+                fmt_num(a.y),
+                fmt_num(b.x),
+                fmt_num(b.y),
+                fmt_num(c.x),
+                fmt_num(c.y)
+            ),
+            PathCommand::Close => write!(d, "Z"),
+        }
+        .ok();
+    }
+    d
+}
+
+/// E.g. ` fill="#ff0000" fill-opacity="0.5"`.
+fn write_color(svg: &mut String, attribute: &str, color: Color32) {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    write!(svg, r##" {attribute}="#{r:02x}{g:02x}{b:02x}""##).ok();
+    if a < 255 {
+        write!(
+            svg,
+            r#" {attribute}-opacity="{}""#,
+            fmt_num(f32::from(a) / 255.0)
+        )
+        .ok();
+    }
+}
+
+/// The value of the `font-family` attribute: the known font names, then a generic family.
+fn font_family(family: &FontFamily, fonts: &FontsInfo) -> String {
+    let generic = match family {
+        FontFamily::Monospace => "monospace",
+        FontFamily::Proportional | FontFamily::Name(_) => "sans-serif",
+    };
+    let mut names: Vec<String> = fonts
+        .family_names
+        .get(family)
+        .into_iter()
+        .flatten()
+        .map(|name| format!("'{}'", escape(&escape_font_name(name))))
+        .collect();
+    names.push(generic.to_owned());
+    names.join(", ")
+}
+
+fn escape_font_name(name: &str) -> String {
+    name.replace(['\'', '\\'], "")
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped += "&amp;",
+            '<' => escaped += "&lt;",
+            '>' => escaped += "&gt;",
+            '"' => escaped += "&quot;",
+            '\'' => escaped += "&apos;",
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
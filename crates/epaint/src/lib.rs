@@ -27,6 +27,8 @@ mod brush;
 pub mod color;
 mod corner_radius;
 mod corner_radius_f32;
+#[cfg(feature = "export")]
+pub mod export;
 pub mod image;
 mod margin;
 mod margin_f32;
@@ -35,6 +37,7 @@ pub mod mutex;
 mod shadow;
 pub mod shape_transform;
 mod shapes;
+pub mod software_renderer;
 pub mod stats;
 mod stroke;
 pub mod tessellator;
@@ -59,6 +62,7 @@ pub use self::{
         CircleShape, CubicBezierShape, EllipseShape, PaintCallback, PaintCallbackInfo, PathShape,
         QuadraticBezierShape, RectShape, Shape, TextShape,
     },
+    software_renderer::SoftwareRenderer,
     stats::PaintStats,
     stroke::{PathStroke, Stroke, StrokeKind},
     tessellator::{TessellationOptions, Tessellator},
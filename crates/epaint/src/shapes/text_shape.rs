@@ -148,6 +148,7 @@ impl TextShape {
             let text::Row {
                 section_index_at_start: _,
                 glyphs: _, // TODO(emilk): would it make sense to transform these?
+                placeholders: _,
                 size,
                 visuals,
             } = Arc::make_mut(row);
@@ -0,0 +1,561 @@
+//! Turning [`ClippedPrimitive`]s into pixels on the CPU, without a GPU.
+
+use ecolor::Color32;
+use emath::{Pos2, Rect};
+
+use crate::{
+    ClippedPrimitive, ColorImage, ImageData, ImageDelta, Mesh, Primitive, TextureId, Vertex,
+    textures::{TextureFilter, TextureOptions, TextureWrapMode, TexturesDelta},
+};
+
+/// Vertex positions are snapped to this fraction of a pixel, like GPUs do.
+const SUBPIXEL_PRECISION: f64 = 256.0;
+
+/// A deterministic software rasterizer for egui.
+///
+/// It paints the same triangles as the GPU backends do,
+/// with the same rules for which pixels a triangle covers,
+/// the same clip (scissor) rectangles,
+/// and the same premultiplied alpha blending in gamma space as `egui-wgpu` and `egui_glow`.
+///
+/// Texture sampling matches the "predictable texture filtering" of `egui-wgpu`.
+/// Mipmaps are not supported, and [`Primitive::Callback`]s are ignored.
+///
+/// ```
+/// # use epaint::{*, software_renderer::SoftwareRenderer};
+/// let mut renderer = SoftwareRenderer::default();
+///
+/// // The font texture, with a white pixel at `WHITE_UV`:
+/// let font_image = ColorImage::filled([1, 1], Color32::WHITE);
+/// renderer.set_texture(TextureId::default(), &ImageDelta::full(font_image, Default::default()));
+///
+/// let mut mesh = Mesh::default();
+/// mesh.add_colored_rect(Rect::from_min_max(pos2(1.0, 1.0), pos2(3.0, 3.0)), Color32::RED);
+/// let primitives = [ClippedPrimitive {
+///     clip_rect: Rect::EVERYTHING,
+///     primitive: Primitive::Mesh(mesh),
+/// }];
+/// let image = renderer.render(&primitives, [4, 4], 1.0);
+/// assert_eq!(image[(0, 0)], Color32::TRANSPARENT);
+/// assert_eq!(image[(2, 2)], Color32::RED);
+/// ```
+#[derive(Default)]
+pub struct SoftwareRenderer {
+    textures: ahash::HashMap<TextureId, SoftwareTexture>,
+}
+
+struct SoftwareTexture {
+    image: ColorImage,
+    options: TextureOptions,
+}
+
+impl SoftwareRenderer {
+    /// A renderer without any textures.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create, replace or update part of a texture.
+    pub fn set_texture(&mut self, id: TextureId, delta: &ImageDelta) {
+        let ImageData::Color(image) = &delta.image;
+
+        if let Some(pos) = delta.pos {
+            let Some(texture) = self.textures.get_mut(&id) else {
+                log::warn!("Tried to update a part of the unknown texture {id:?}");
+                return;
+            };
+            let [x, y] = pos;
+            let [w, h] = image.size;
+            let [texture_w, texture_h] = texture.image.size;
+            if texture_w < x + w || texture_h < y + h {
+                log::warn!("Texture update of {id:?} is out of bounds");
+                return;
+            }
+            for row in 0..h {
+                let src = &image.pixels[row * w..(row + 1) * w];
+                let dst_start = (y + row) * texture_w + x;
+                texture.image.pixels[dst_start..dst_start + w].copy_from_slice(src);
+            }
+            texture.options = delta.options;
+        } else {
+            self.textures.insert(
+                id,
+                SoftwareTexture {
+                    image: (**image).clone(),
+                    options: delta.options,
+                },
+            );
+        }
+    }
+
+    /// Forget a texture, e.g. one listed in [`crate::textures::TexturesDelta::free`].
+    pub fn free_texture(&mut self, id: TextureId) {
+        self.textures.remove(&id);
+    }
+
+    /// The current contents of a texture, if it exists.
+    pub fn texture(&self, id: TextureId) -> Option<&ColorImage> {
+        self.textures.get(&id).map(|texture| &texture.image)
+    }
+
+    /// Paint the primitives onto a new transparent image of the given size.
+    ///
+    /// The result has premultiplied alpha, just like a GPU framebuffer would.
+    pub fn render(
+        &self,
+        primitives: &[ClippedPrimitive],
+        size_in_pixels: [usize; 2],
+        pixels_per_point: f32,
+    ) -> ColorImage {
+        let mut target = ColorImage::filled(size_in_pixels, Color32::TRANSPARENT);
+        self.paint_primitives(&mut target, primitives, pixels_per_point);
+        target
+    }
+
+    /// Update the textures, paint the primitives on top of what is already in `target`,
+    /// and then free the textures that are no longer needed.
+    pub fn paint_and_update_textures(
+        &mut self,
+        target: &mut ColorImage,
+        pixels_per_point: f32,
+        primitives: &[ClippedPrimitive],
+        textures_delta: &TexturesDelta,
+    ) {
+        for (id, image_delta) in &textures_delta.set {
+            self.set_texture(*id, image_delta);
+        }
+
+        self.paint_primitives(target, primitives, pixels_per_point);
+
+        for &id in &textures_delta.free {
+            self.free_texture(id);
+        }
+    }
+
+    /// Paint the primitives on top of what is already in `target`.
+    pub fn paint_primitives(
+        &self,
+        target: &mut ColorImage,
+        primitives: &[ClippedPrimitive],
+        pixels_per_point: f32,
+    ) {
+        profiling::function_scope!();
+
+        for ClippedPrimitive {
+            clip_rect,
+            primitive,
+        } in primitives
+        {
+            match primitive {
+                Primitive::Mesh(mesh) => {
+                    let scissor = ScissorRect::new(*clip_rect, pixels_per_point, target.size);
+                    if !scissor.is_empty() {
+                        self.paint_mesh(target, scissor, mesh, pixels_per_point);
+                    }
+                }
+                Primitive::Callback(_) => {
+                    log::debug!("The software renderer does not support paint callbacks");
+                }
+            }
+        }
+    }
+
+    fn paint_mesh(
+        &self,
+        target: &mut ColorImage,
+        scissor: ScissorRect,
+        mesh: &Mesh,
+        pixels_per_point: f32,
+    ) {
+        let Some(texture) = self.textures.get(&mesh.texture_id) else {
+            log::warn!("Failed to find texture {:?}", mesh.texture_id);
+            return;
+        };
+
+        for triangle in mesh.indices.chunks_exact(3) {
+            let vertex = |i: usize| mesh.vertices.get(triangle[i] as usize);
+            if let (Some(a), Some(b), Some(c)) = (vertex(0), vertex(1), vertex(2)) {
+                paint_triangle(target, scissor, texture, [a, b, c], pixels_per_point);
+            }
+        }
+    }
+}
+
+/// The clip rectangle in whole pixels, rounded the same way as in the GPU backends.
+#[derive(Clone, Copy, Debug)]
+struct ScissorRect {
+    min_x: usize,
+    min_y: usize,
+    max_x: usize,
+    max_y: usize,
+}
+
+impl ScissorRect {
+    fn new(clip_rect: Rect, pixels_per_point: f32, [width, height]: [usize; 2]) -> Self {
+        let round = |points: f32, max: usize| {
+            ((pixels_per_point * points).round().max(0.0) as usize).min(max)
+        };
+        let min_x = round(clip_rect.min.x, width);
+        let min_y = round(clip_rect.min.y, height);
+        let max_x = round(clip_rect.max.x, width).max(min_x);
+        let max_y = round(clip_rect.max.y, height).max(min_y);
+        Self {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.min_x == self.max_x || self.min_y == self.max_y
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Point {
+    x: f64,
+    y: f64,
+}
+
+impl Point {
+    fn snapped(pos: Pos2, pixels_per_point: f32) -> Self {
+        let snap = |v: f32| {
+            (f64::from(v * pixels_per_point) * SUBPIXEL_PRECISION).round() / SUBPIXEL_PRECISION
+        };
+        Self {
+            x: snap(pos.x),
+            y: snap(pos.y),
+        }
+    }
+}
+
+/// Twice the signed area of the triangle `a, b, p`.
+///
+/// Positive if `p` is to the right of `a -> b` on screen (where y goes down).
+fn edge_function(a: Point, b: Point, p: Point) -> f64 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+/// The top-left fill rule: pixel centers exactly on an edge are only painted
+/// if the edge is a top or a left edge, so that touching triangles never paint a pixel twice.
+fn is_top_left(a: Point, b: Point) -> bool {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    (dy == 0.0 && dx > 0.0) || dy < 0.0
+}
+
+fn paint_triangle(
+    target: &mut ColorImage,
+    scissor: ScissorRect,
+    texture: &SoftwareTexture,
+    vertices: [&Vertex; 3],
+    pixels_per_point: f32,
+) {
+    let mut vertices = vertices;
+    let mut points = vertices.map(|v| Point::snapped(v.pos, pixels_per_point));
+
+    let mut area = edge_function(points[0], points[1], points[2]);
+    if area == 0.0 || !area.is_finite() {
+        return;
+    }
+    if area < 0.0 {
+        // egui uses both winding orders:
+        vertices.swap(1, 2);
+        points.swap(1, 2);
+        area = -area;
+    }
+    let [p0, p1, p2] = points;
+
+    let min_x = p0.x.min(p1.x).min(p2.x).floor().max(scissor.min_x as f64) as usize;
+    let min_y = p0.y.min(p1.y).min(p2.y).floor().max(scissor.min_y as f64) as usize;
+    let max_x = (p0.x.max(p1.x).max(p2.x).ceil() as usize).min(scissor.max_x);
+    let max_y = (p0.y.max(p1.y).max(p2.y).ceil() as usize).min(scissor.max_y);
+    if max_x <= min_x || max_y <= min_y {
+        return;
+    }
+
+    let edges = [(p1, p2), (p2, p0), (p0, p1)];
+    let top_left = edges.map(|(a, b)| is_top_left(a, b));
+
+    let colors = vertices.map(|v| color_to_f32(v.color));
+    let texture_size = [texture.image.width() as f32, texture.image.height() as f32];
+    let texel_positions = vertices.map(|v| [v.uv.x * texture_size[0], v.uv.y * texture_size[1]]);
+    let filter = if texels_per_pixel(points, texel_positions) > 1.0 {
+        texture.options.minification
+    } else {
+        texture.options.magnification
+    };
+
+    let width = target.width();
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            let p = Point {
+                x: x as f64 + 0.5,
+                y: y as f64 + 0.5,
+            };
+
+            let mut weights = [0.0_f64; 3];
+            let mut inside = true;
+            for (i, (a, b)) in edges.iter().enumerate() {
+                let w = edge_function(*a, *b, p);
+                inside &= w > 0.0 || (w == 0.0 && top_left[i]);
+                weights[i] = w / area;
+            }
+            if !inside {
+                continue;
+            }
+            let weights = weights.map(|w| w as f32);
+
+            let interpolate = |values: [[f32; 4]; 3]| -> [f32; 4] {
+                std::array::from_fn(|c| {
+                    weights[0] * values[0][c]
+                        + weights[1] * values[1][c]
+                        + weights[2] * values[2][c]
+                })
+            };
+            let color = interpolate(colors);
+            let texel = [
+                weights[0] * texel_positions[0][0]
+                    + weights[1] * texel_positions[1][0]
+                    + weights[2] * texel_positions[2][0],
+                weights[0] * texel_positions[0][1]
+                    + weights[1] * texel_positions[1][1]
+                    + weights[2] * texel_positions[2][1],
+            ];
+            let texture_color = sample(texture, filter, texel);
+
+            let src: [f32; 4] = std::array::from_fn(|c| color[c] * texture_color[c]);
+            let dst = &mut target.pixels[y * width + x];
+            *dst = blend(src, *dst);
+        }
+    }
+}
+
+/// How many texels one pixel covers, to pick between minification and magnification.
+fn texels_per_pixel(points: [Point; 3], texels: [[f32; 2]; 3]) -> f64 {
+    // The texel position is an affine function of the pixel position within the triangle:
+    let [p0, p1, p2] = points;
+    let du = [
+        f64::from(texels[1][0] - texels[0][0]),
+        f64::from(texels[2][0] - texels[0][0]),
+    ];
+    let dv = [
+        f64::from(texels[1][1] - texels[0][1]),
+        f64::from(texels[2][1] - texels[0][1]),
+    ];
+    let (e1x, e1y) = (p1.x - p0.x, p1.y - p0.y);
+    let (e2x, e2y) = (p2.x - p0.x, p2.y - p0.y);
+    let det = e1x * e2y - e2x * e1y;
+    if det == 0.0 {
+        return 0.0;
+    }
+    let du_dx = (du[0] * e2y - du[1] * e1y) / det;
+    let du_dy = (du[1] * e1x - du[0] * e2x) / det;
+    let dv_dx = (dv[0] * e2y - dv[1] * e1y) / det;
+    let dv_dy = (dv[1] * e1x - dv[0] * e2x) / det;
+    f64::hypot(du_dx, dv_dx).max(f64::hypot(du_dy, dv_dy))
+}
+
+/// Sample the texture at a position in texels, returning premultiplied gamma-space RGBA in 0-1.
+fn sample(texture: &SoftwareTexture, filter: TextureFilter, [u, v]: [f32; 2]) -> [f32; 4] {
+    let image = &texture.image;
+    let [width, height] = image.size;
+    if width == 0 || height == 0 {
+        return [0.0; 4];
+    }
+    let wrap_mode = texture.options.wrap_mode;
+    let texel = |x: i64, y: i64| {
+        let x = wrap(x, width, wrap_mode);
+        let y = wrap(y, height, wrap_mode);
+        color_to_f32(image.pixels[y * width + x])
+    };
+
+    match filter {
+        TextureFilter::Nearest => texel(u.floor() as i64, v.floor() as i64),
+        TextureFilter::Linear => {
+            // Four taps at texel centers, exactly like the predictable filtering in `egui-wgpu`:
+            let (u, v) = (u - 0.5, v - 0.5);
+            let (x, y) = (u.floor(), v.floor());
+            let (fx, fy) = (u - x, v - y);
+            let (x, y) = (x as i64, y as i64);
+
+            let tl = texel(x, y);
+            let tr = texel(x + 1, y);
+            let bl = texel(x, y + 1);
+            let br = texel(x + 1, y + 1);
+
+            std::array::from_fn(|c| {
+                let top = mix(tl[c], tr[c], fx);
+                let bottom = mix(bl[c], br[c], fx);
+                mix(top, bottom, fy)
+            })
+        }
+    }
+}
+
+fn wrap(i: i64, size: usize, wrap_mode: TextureWrapMode) -> usize {
+    let size = size as i64;
+    let i = match wrap_mode {
+        TextureWrapMode::ClampToEdge => i.clamp(0, size - 1),
+        TextureWrapMode::Repeat => i.rem_euclid(size),
+        TextureWrapMode::MirroredRepeat => {
+            let i = i.rem_euclid(2 * size);
+            if i < size { i } else { 2 * size - 1 - i }
+        }
+    };
+    i as usize
+}
+
+/// Same as GLSL/WGSL `mix`.
+#[inline]
+fn mix(a: f32, b: f32, t: f32) -> f32 {
+    a * (1.0 - t) + b * t
+}
+
+#[inline]
+fn color_to_f32(color: Color32) -> [f32; 4] {
+    color.to_array().map(|c| f32::from(c) / 255.0)
+}
+
+/// Premultiplied alpha blending, with the same blend state as the GPU backends.
+#[inline]
+fn blend(src: [f32; 4], dst: Color32) -> Color32 {
+    let dst = color_to_f32(dst);
+    let src_alpha = src[3];
+    let out: [f32; 4] = [
+        src[0] + dst[0] * (1.0 - src_alpha),
+        src[1] + dst[1] * (1.0 - src_alpha),
+        src[2] + dst[2] * (1.0 - src_alpha),
+        src_alpha * (1.0 - dst[3]) + dst[3],
+    ];
+    let [r, g, b, a] = out.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+    Color32::from_rgba_premultiplied(r, g, b, a)
+}
+
+#[cfg(test)]
+mod tests {
+    use emath::{pos2, vec2};
+
+    use super::*;
+
+    fn renderer_with_white_texture() -> SoftwareRenderer {
+        let mut renderer = SoftwareRenderer::new();
+        renderer.set_texture(
+            TextureId::default(),
+            &ImageDelta::full(
+                ColorImage::filled([1, 1], Color32::WHITE),
+                TextureOptions::LINEAR,
+            ),
+        );
+        renderer
+    }
+
+    fn rect_primitive(rect: Rect, clip_rect: Rect, color: Color32) -> ClippedPrimitive {
+        let mut mesh = Mesh::default();
+        mesh.add_colored_rect(rect, color);
+        ClippedPrimitive {
+            clip_rect,
+            primitive: Primitive::Mesh(mesh),
+        }
+    }
+
+    #[test]
+    fn test_fill_rule() {
+        let renderer = renderer_with_white_texture();
+
+        // Two rectangles that share an edge, and together cover the whole image:
+        let half_transparent = Color32::from_black_alpha(128);
+        let primitives = [
+            rect_primitive(
+                Rect::from_min_size(pos2(0.0, 0.0), vec2(2.5, 4.0)),
+                Rect::EVERYTHING,
+                half_transparent,
+            ),
+            rect_primitive(
+                Rect::from_min_size(pos2(2.5, 0.0), vec2(1.5, 4.0)),
+                Rect::EVERYTHING,
+                half_transparent,
+            ),
+        ];
+        let image = renderer.render(&primitives, [4, 4], 1.0);
+
+        // Every pixel is painted exactly once:
+        assert!(image.pixels.iter().all(|&pixel| pixel == half_transparent));
+    }
+
+    #[test]
+    fn test_clip_rect_and_pixels_per_point() {
+        let renderer = renderer_with_white_texture();
+        let primitives = [rect_primitive(
+            Rect::from_min_size(pos2(0.0, 0.0), vec2(4.0, 4.0)),
+            Rect::from_min_size(pos2(1.0, 1.0), vec2(1.0, 2.0)),
+            Color32::RED,
+        )];
+        let image = renderer.render(&primitives, [8, 8], 2.0);
+
+        for y in 0..8 {
+            for x in 0..8 {
+                let inside = (2..4).contains(&x) && (2..6).contains(&y);
+                let expected = if inside {
+                    Color32::RED
+                } else {
+                    Color32::TRANSPARENT
+                };
+                assert_eq!(image[(x, y)], expected, "pixel {x}, {y}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_blending() {
+        let renderer = renderer_with_white_texture();
+        let everything = Rect::from_min_size(Pos2::ZERO, vec2(1.0, 1.0));
+        let primitives = [
+            rect_primitive(everything, Rect::EVERYTHING, Color32::BLUE),
+            rect_primitive(everything, Rect::EVERYTHING, Color32::from_white_alpha(64)),
+        ];
+        let image = renderer.render(&primitives, [1, 1], 1.0);
+        let expected_blue = (255.0_f32 * (1.0 - 64.0 / 255.0)).round() as u8;
+        assert_eq!(
+            image[(0, 0)],
+            Color32::from_rgba_premultiplied(64, 64, 64 + expected_blue, 255)
+        );
+    }
+
+    #[test]
+    fn test_texture_sampling() {
+        let mut renderer = SoftwareRenderer::new();
+        let id = TextureId::User(1);
+        let mut checkerboard = ColorImage::filled([2, 2], Color32::BLACK);
+        checkerboard[(0, 0)] = Color32::WHITE;
+        checkerboard[(1, 1)] = Color32::WHITE;
+        renderer.set_texture(id, &ImageDelta::full(checkerboard, TextureOptions::NEAREST));
+
+        // Update one texel:
+        renderer.set_texture(
+            id,
+            &ImageDelta::partial(
+                [1, 0],
+                ColorImage::filled([1, 1], Color32::RED),
+                TextureOptions::NEAREST,
+            ),
+        );
+
+        let mut mesh = Mesh::with_texture(id);
+        mesh.add_rect_with_uv(
+            Rect::from_min_size(Pos2::ZERO, vec2(4.0, 4.0)),
+            Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0)),
+            Color32::WHITE,
+        );
+        let primitives = [ClippedPrimitive {
+            clip_rect: Rect::EVERYTHING,
+            primitive: Primitive::Mesh(mesh),
+        }];
+        let image = renderer.render(&primitives, [4, 4], 1.0);
+
+        assert_eq!(image[(0, 0)], Color32::WHITE);
+        assert_eq!(image[(3, 0)], Color32::RED);
+        assert_eq!(image[(0, 3)], Color32::BLACK);
+        assert_eq!(image[(3, 3)], Color32::WHITE);
+    }
+}
@@ -4,15 +4,18 @@ use emath::{GuiRounding as _, OrderedFloat, Vec2, vec2};
 use self_cell::self_cell;
 use skrifa::{
     MetadataProvider as _,
-    raw::{TableProvider as _, tables::kern::SubtableKind},
+    raw::{
+        TableProvider as _,
+        tables::{gsub::SubstitutionSubtables, kern::SubtableKind},
+    },
 };
-use std::collections::BTreeMap;
-use vello_cpu::{color, kurbo};
+use std::{collections::BTreeMap, sync::Arc};
+use vello_cpu::{color, kurbo, peniko};
 
 use crate::{
-    TextOptions, TextureAtlas,
+    Color32, TextOptions, TextureAtlas,
     text::{
-        FontTweak,
+        FontProvider, FontTweak,
         fonts::{Blob, CachedFamily, FontFaceKey},
     },
 };
@@ -34,6 +37,10 @@ pub struct UvRect {
 
     /// Bottom right corner (exclusive).
     pub max: [u16; 2],
+
+    /// The texels are already colored (e.g. a color emoji),
+    /// and should not be tinted by the text color.
+    pub is_color: bool,
 }
 
 impl UvRect {
@@ -177,6 +184,9 @@ struct DependentFontData<'a> {
     metrics: skrifa::metrics::Metrics,
     glyph_metrics: skrifa::metrics::GlyphMetrics<'a>,
     hinting_instance: Option<skrifa::outline::HintingInstance>,
+    color_glyphs: skrifa::color::ColorGlyphCollection<'a>,
+    #[cfg(feature = "color_bitmap_glyphs")]
+    bitmap_strikes: skrifa::bitmap::BitmapStrikes<'a>,
 }
 
 self_cell! {
@@ -194,6 +204,65 @@ impl FontCell {
         scale / units_per_em
     }
 
+    /// Does the font have any color glyphs (COLR, or CBDT or sbix with the `color_bitmap_glyphs` feature)?
+    fn has_color_glyphs(&self) -> bool {
+        let font_data = self.borrow_dependent();
+        #[cfg(feature = "color_bitmap_glyphs")]
+        if !font_data.bitmap_strikes.is_empty() {
+            return true;
+        }
+        font_data.skrifa.colr().is_ok()
+    }
+
+    /// Should this glyph be painted with [`Self::allocate_color_glyph_uncached`]?
+    fn is_color_glyph(&self, glyph_id: skrifa::GlyphId) -> bool {
+        let font_data = self.borrow_dependent();
+        #[cfg(feature = "color_bitmap_glyphs")]
+        if font_data
+            .bitmap_strikes
+            .glyph_for_size(skrifa::instance::Size::unscaled(), glyph_id)
+            .is_some_and(|glyph| matches!(glyph.data, skrifa::bitmap::BitmapData::Png(_)))
+        {
+            return true;
+        }
+        font_data.color_glyphs.get(glyph_id).is_some()
+    }
+
+    /// Look for a ligature glyph (GSUB lookup type 4) that replaces all of `glyphs`.
+    ///
+    /// This is how fonts map emoji ZWJ sequences, flags and keycaps to a single glyph.
+    /// We don't do proper shaping, so we ignore scripts, features and lookup flags.
+    fn find_ligature(&self, glyphs: &[skrifa::GlyphId]) -> Option<skrifa::GlyphId> {
+        let (first, rest) = glyphs.split_first()?;
+        let gsub = self.borrow_dependent().skrifa.gsub().ok()?;
+        let lookups = gsub.lookup_list().ok()?;
+        for lookup in lookups.lookups().iter().flatten() {
+            let Ok(SubstitutionSubtables::Ligature(subtables)) = lookup.subtables() else {
+                continue;
+            };
+            for subtable in subtables.iter().flatten() {
+                let Some(coverage_index) = subtable.coverage().ok().and_then(|c| c.get(*first))
+                else {
+                    continue;
+                };
+                let Ok(ligature_set) = subtable.ligature_sets().get(coverage_index as usize) else {
+                    continue;
+                };
+                for ligature in ligature_set.ligatures().iter().flatten() {
+                    let components = ligature.component_glyph_ids();
+                    if components.len() == rest.len()
+                        && components.iter().zip(rest).all(|(component, glyph)| {
+                            skrifa::GlyphId::from(component.get()) == *glyph
+                        })
+                    {
+                        return Some(ligature.ligature_glyph().into());
+                    }
+                }
+            }
+        }
+        None
+    }
+
     fn allocate_glyph_uncached(
         &mut self,
         atlas: &mut TextureAtlas,
@@ -285,6 +354,7 @@ impl FontCell {
                     (glyph_pos.0 + width as usize) as u16,
                     (glyph_pos.1 + height as usize) as u16,
                 ],
+                is_color: false,
             }
         };
 
@@ -294,6 +364,103 @@ impl FontCell {
             uv_rect,
         })
     }
+
+    /// Paint a COLR or bitmap glyph in full color.
+    fn allocate_color_glyph_uncached(
+        &self,
+        atlas: &mut TextureAtlas,
+        metrics: &ScaledMetrics,
+        glyph_info: &GlyphInfo,
+        bin: SubpixelBin,
+        vello_font: &peniko::FontData,
+        location: &skrifa::instance::Location,
+    ) -> Option<GlyphAllocation> {
+        let glyph_id = glyph_info.id?;
+
+        // We don't know the exact bounds of a color glyph up front,
+        // so we paint into the em box with some margin, and then trim it:
+        let font_metrics = &self.borrow_dependent().metrics;
+        let advance_width_px = glyph_info.advance_width_unscaled.0 * metrics.px_scale_factor;
+        let margin = 0.25 * metrics.scale as f64;
+        let bounds = kurbo::Rect::new(
+            -margin,
+            -(font_metrics.ascent * metrics.px_scale_factor) as f64 - margin,
+            advance_width_px as f64 + margin,
+            -(font_metrics.descent * metrics.px_scale_factor) as f64 + margin,
+        )
+        .expand();
+        let width = bounds.width() as u16;
+        let height = bounds.height() as u16;
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        let normalized_coords: Vec<i16> = location.coords().iter().map(|c| c.to_bits()).collect();
+
+        let mut ctx = vello_cpu::RenderContext::new(width, height);
+        ctx.set_transform(kurbo::Affine::translate((-bounds.x0, -bounds.y0)));
+        ctx.set_paint(color::OpaqueColor::<color::Srgb>::WHITE); // For COLR "foreground" layers
+        ctx.glyph_run(vello_font)
+            .font_size(metrics.scale)
+            .hint(false)
+            .normalized_coords(&normalized_coords)
+            .fill_glyphs(std::iter::once(vello_cpu::Glyph {
+                id: glyph_id.to_u32(),
+                x: bin.as_float(),
+                y: 0.0,
+            }));
+        let mut dest = vello_cpu::Pixmap::new(width, height);
+        ctx.render_to_pixmap(&mut dest);
+
+        let pixels = dest.data();
+        let (width, height) = (width as usize, height as usize);
+        let mut min = [width, height];
+        let mut max = [0, 0];
+        for y in 0..height {
+            for x in 0..width {
+                if pixels[y * width + x].a != 0 {
+                    min = [min[0].min(x), min[1].min(y)];
+                    max = [max[0].max(x + 1), max[1].max(y + 1)];
+                }
+            }
+        }
+
+        let uv_rect = if max[0] <= min[0] || max[1] <= min[1] {
+            UvRect::default()
+        } else {
+            let size = [max[0] - min[0], max[1] - min[1]];
+            let (glyph_pos, image) = atlas.allocate((size[0], size[1]));
+            for y in 0..size[1] {
+                for x in 0..size[0] {
+                    let p = pixels[(y + min[1]) * width + x + min[0]];
+                    image[(x + glyph_pos.0, y + glyph_pos.1)] =
+                        Color32::from_rgba_premultiplied(p.r, p.g, p.b, p.a);
+                }
+            }
+            let offset_in_pixels = vec2(
+                bounds.x0 as f32 + min[0] as f32,
+                bounds.y0 as f32 + min[1] as f32,
+            );
+            let offset =
+                offset_in_pixels / metrics.pixels_per_point + metrics.y_offset_in_points * Vec2::Y;
+            UvRect {
+                offset,
+                size: vec2(size[0] as f32, size[1] as f32) / metrics.pixels_per_point,
+                min: [glyph_pos.0 as u16, glyph_pos.1 as u16],
+                max: [
+                    (glyph_pos.0 + size[0]) as u16,
+                    (glyph_pos.1 + size[1]) as u16,
+                ],
+                is_color: true,
+            }
+        };
+
+        Some(GlyphAllocation {
+            id: glyph_id,
+            advance_width_px,
+            uv_rect,
+        })
+    }
 }
 
 struct VelloPen<'a> {
@@ -337,6 +504,9 @@ pub struct FontFace {
     font: FontCell,
     tweak: FontTweak,
 
+    /// The same font data, for painting color glyphs with [`vello_cpu`].
+    vello_font: peniko::FontData,
+
     /// Variable font location (for weight axis, etc.)
     location: skrifa::instance::Location,
     glyph_info_cache: ahash::HashMap<char, GlyphInfo>,
@@ -352,12 +522,17 @@ impl FontFace {
         tweak: FontTweak,
         preferred_weight: Option<u16>,
     ) -> Result<Self, Box<dyn std::error::Error>> {
+        let vello_font = peniko::FontData::new(peniko::Blob::new(Arc::clone(&font_data)), index);
+
         let font = FontCell::try_new(font_data, |font_data| {
             let skrifa_font =
                 skrifa::FontRef::from_index(AsRef::<[u8]>::as_ref(font_data.as_ref()), index)?;
 
             let charmap = skrifa_font.charmap();
             let glyphs = skrifa_font.outline_glyphs();
+            let color_glyphs = skrifa_font.color_glyphs();
+            #[cfg(feature = "color_bitmap_glyphs")]
+            let bitmap_strikes = skrifa_font.bitmap_strikes();
 
             // Note: We use default location here during initialization because
             // the actual weight will be applied via the stored location during rendering.
@@ -393,6 +568,9 @@ impl FontFace {
                 metrics,
                 glyph_metrics,
                 hinting_instance,
+                color_glyphs,
+                #[cfg(feature = "color_bitmap_glyphs")]
+                bitmap_strikes,
             })
         })?;
 
@@ -433,6 +611,7 @@ impl FontFace {
             name,
             font,
             tweak,
+            vello_font,
             location,
             glyph_info_cache: Default::default(),
             glyph_alloc_cache: Default::default(),
@@ -534,6 +713,53 @@ impl FontFace {
         Some(glyph_info)
     }
 
+    /// Does this font have color glyphs that we should use?
+    ///
+    /// See [`FontTweak::prefer_color_glyphs`].
+    pub(super) fn has_color_glyphs(&self) -> bool {
+        self.tweak.prefer_color_glyphs && self.font.has_color_glyphs()
+    }
+
+    /// Will this glyph be painted in color?
+    ///
+    /// See [`FontTweak::prefer_color_glyphs`].
+    pub(super) fn is_color_glyph(&self, glyph_id: skrifa::GlyphId) -> bool {
+        self.tweak.prefer_color_glyphs && self.font.is_color_glyph(glyph_id)
+    }
+
+    /// A single ligature glyph for a whole emoji sequence (see [`emoji_sequence`]), if the font has one.
+    ///
+    /// Variation selectors are dropped if the font doesn't include them in its ligatures.
+    pub(super) fn sequence_glyph_info(&self, sequence: &str) -> Option<GlyphInfo> {
+        let font_data = self.font.borrow_dependent();
+        let glyph_ids = |skip_variation_selectors: bool| -> Option<Vec<skrifa::GlyphId>> {
+            sequence
+                .chars()
+                .filter(|&c| !(skip_variation_selectors && is_variation_selector(c)))
+                .map(|c| {
+                    font_data
+                        .charmap
+                        .map(c)
+                        .filter(|id| *id != skrifa::GlyphId::NOTDEF)
+                })
+                .collect()
+        };
+
+        let ligature = [false, true]
+            .into_iter()
+            .filter_map(glyph_ids)
+            .find_map(|glyphs| self.font.find_ligature(&glyphs))?;
+
+        Some(GlyphInfo {
+            id: Some(ligature),
+            advance_width_unscaled: font_data
+                .glyph_metrics
+                .advance_width(ligature)
+                .unwrap_or_default()
+                .into(),
+        })
+    }
+
     #[inline]
     pub(super) fn pair_kerning_pixels(
         &self,
@@ -626,10 +852,22 @@ impl FontFace {
             std::collections::hash_map::Entry::Vacant(entry) => entry,
         };
 
-        let allocation = self
-            .font
-            .allocate_glyph_uncached(atlas, metrics, &glyph_info, bin, &self.location)
-            .unwrap_or_default();
+        // Only look up the color tables on a cache miss, since this is on the hot path of text layout:
+        let is_color = self.tweak.prefer_color_glyphs && self.font.is_color_glyph(glyph_id);
+        let allocation = if is_color {
+            self.font.allocate_color_glyph_uncached(
+                atlas,
+                metrics,
+                &glyph_info,
+                bin,
+                &self.vello_font,
+                &self.location,
+            )
+        } else {
+            self.font
+                .allocate_glyph_uncached(atlas, metrics, &glyph_info, bin, &self.location)
+        }
+        .unwrap_or_default();
 
         entry.insert(allocation);
         (allocation, h_pos_round)
@@ -640,6 +878,8 @@ impl FontFace {
 /// Wrapper over multiple [`FontFace`] (e.g. a primary + fallbacks for emojis)
 pub struct Font<'a> {
     pub(super) fonts_by_id: &'a mut nohash_hasher::IntMap<FontFaceKey, FontFace>,
+    pub(super) fonts_by_name: &'a mut ahash::HashMap<String, FontFaceKey>,
+    pub(super) font_provider: Option<&'a dyn FontProvider>,
     pub(super) cached_family: &'a mut CachedFamily,
     pub(super) atlas: &'a mut TextureAtlas,
 }
@@ -702,7 +942,8 @@ impl Font<'_> {
 
         let font_index_glyph_info = self
             .cached_family
-            .glyph_info_no_cache_or_fallback(c, self.fonts_by_id);
+            .glyph_info_no_cache_or_fallback(c, self.fonts_by_id)
+            .or_else(|| self.glyph_info_from_provider(c));
         let font_index_glyph_info =
             font_index_glyph_info.unwrap_or(self.cached_family.replacement_glyph);
         self.cached_family
@@ -710,6 +951,180 @@ impl Font<'_> {
             .insert(c, font_index_glyph_info);
         font_index_glyph_info
     }
+
+    /// Ask the [`FontProvider`] for a font with this glyph,
+    /// and append that font to the fallback chain of this family.
+    fn glyph_info_from_provider(&mut self, c: char) -> Option<(FontFaceKey, GlyphInfo)> {
+        let font_provider = self.font_provider?;
+        if c.is_control() || invisible_char(c) {
+            return None; // No point in looking
+        }
+
+        let (name, font_data) = font_provider.font_for_char(c)?;
+
+        let key = if let Some(key) = self.fonts_by_name.get(&name) {
+            *key
+        } else {
+            let blob: Blob = Arc::clone(&font_data) as Blob;
+            let font_face = FontFace::new(
+                *self.atlas.options(),
+                name.clone(),
+                blob,
+                font_data.index,
+                font_data.tweak,
+                font_data.weight,
+            )
+            .inspect_err(|err| log::warn!("Error parsing font {name:?}: {err}"))
+            .ok()?;
+            let key = FontFaceKey::new();
+            self.fonts_by_id.insert(key, font_face);
+            self.fonts_by_name.insert(name, key);
+            key
+        };
+
+        if !self.cached_family.fonts.contains(&key) {
+            self.cached_family.fonts.push(key);
+            self.cached_family.characters = None;
+            self.cached_family.sequence_glyph_info_cache.clear();
+        }
+
+        let glyph_info = self.fonts_by_id.get_mut(&key)?.glyph_info(c)?;
+        Some((key, glyph_info))
+    }
+}
+
+impl Font<'_> {
+    /// A single glyph for an emoji sequence (see [`emoji_sequence`]),
+    /// e.g. a ZWJ sequence, a flag, or an emoji with a variation selector.
+    ///
+    /// Returns `None` if the sequence should be laid out one character at a time.
+    pub(crate) fn sequence_glyph_info(
+        &mut self,
+        sequence: &str,
+    ) -> Option<(FontFaceKey, GlyphInfo)> {
+        if let Some(font_index_glyph_info) =
+            self.cached_family.sequence_glyph_info_cache.get(sequence)
+        {
+            return *font_index_glyph_info;
+        }
+
+        let font_index_glyph_info = self.sequence_glyph_info_no_cache(sequence);
+        self.cached_family
+            .sequence_glyph_info_cache
+            .insert(sequence.to_owned(), font_index_glyph_info);
+        font_index_glyph_info
+    }
+
+    fn sequence_glyph_info_no_cache(&mut self, sequence: &str) -> Option<(FontFaceKey, GlyphInfo)> {
+        let text_presentation = sequence.ends_with(TEXT_PRESENTATION_SELECTOR);
+
+        // Unless asked for text presentation, look in the fonts with color glyphs first:
+        let mut font_keys = self.cached_family.fonts.clone();
+        if !text_presentation {
+            font_keys.sort_by_key(|key| {
+                !self
+                    .fonts_by_id
+                    .get(key)
+                    .is_some_and(|font_face| font_face.has_color_glyphs())
+            });
+        }
+
+        for key in &font_keys {
+            if let Some(glyph_info) = self
+                .fonts_by_id
+                .get(key)
+                .and_then(|font_face| font_face.sequence_glyph_info(sequence))
+            {
+                return Some((*key, glyph_info));
+            }
+        }
+
+        // A single emoji followed by a variation selector picks the color or the monochrome glyph:
+        let mut chars = sequence.chars();
+        let base = chars.next()?;
+        if !chars.all(is_variation_selector) {
+            return None;
+        }
+        for key in &font_keys {
+            let Some(font_face) = self.fonts_by_id.get_mut(key) else {
+                continue;
+            };
+            if let Some(glyph_info) = font_face.glyph_info(base)
+                && let Some(glyph_id) = glyph_info.id
+                && font_face.is_color_glyph(glyph_id) != text_presentation
+            {
+                return Some((*key, glyph_info));
+            }
+        }
+        None
+    }
+}
+
+/// Selects the monochrome ("text") version of the preceding emoji.
+const TEXT_PRESENTATION_SELECTOR: char = '\u{FE0E}';
+
+/// Selects the color ("emoji") version of the preceding emoji.
+const EMOJI_PRESENTATION_SELECTOR: char = '\u{FE0F}';
+
+const ZERO_WIDTH_JOINER: char = '\u{200D}';
+
+#[inline]
+fn is_variation_selector(c: char) -> bool {
+    c == TEXT_PRESENTATION_SELECTOR || c == EMOJI_PRESENTATION_SELECTOR
+}
+
+#[inline]
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+/// Characters that modify the emoji before them.
+#[inline]
+fn is_emoji_modifier(c: char) -> bool {
+    is_variation_selector(c)
+        || ('\u{1F3FB}'..='\u{1F3FF}').contains(&c) // Skin tones
+        || c == '\u{20E3}' // Combining enclosing keycap
+        || ('\u{E0020}'..='\u{E007F}').contains(&c) // Tags, used for subdivision flags
+}
+
+/// The emoji sequence at the start of `text`, which a font may render as a single glyph.
+///
+/// This covers ZWJ sequences (👩‍💻), flags (🇸🇪), keycaps (1️⃣), skin tones (👋🏽),
+/// and variation selectors.
+///
+/// Returns just the first character if there is no such sequence.
+pub(super) fn emoji_sequence(text: &str) -> &str {
+    let mut chars = text.char_indices().peekable();
+    let Some((_, first)) = chars.next() else {
+        return text;
+    };
+    let mut end = first.len_utf8();
+
+    if is_regional_indicator(first) {
+        if let Some(&(i, c)) = chars.peek()
+            && is_regional_indicator(c)
+        {
+            end = i + c.len_utf8();
+        }
+        return &text[..end];
+    }
+
+    while let Some(&(i, c)) = chars.peek() {
+        if is_emoji_modifier(c) {
+            chars.next();
+            end = i + c.len_utf8();
+        } else if c == ZERO_WIDTH_JOINER {
+            chars.next();
+            let Some((i, c)) = chars.next().filter(|(_, c)| !c.is_whitespace()) else {
+                break;
+            };
+            end = i + c.len_utf8();
+        } else {
+            break;
+        }
+    }
+
+    &text[..end]
 }
 
 /// Metrics for a font at a specific screen-space scale.
@@ -783,7 +1198,10 @@ fn invisible_char(c: char) -> bool {
             | '\u{206D}' // ACTIVATE ARABIC FORM SHAPING
             | '\u{206E}' // NATIONAL DIGIT SHAPES
             | '\u{206F}' // NOMINAL DIGIT SHAPES
+            | '\u{FE0E}' // VARIATION SELECTOR-15 (text presentation)
+            | '\u{FE0F}' // VARIATION SELECTOR-16 (emoji presentation)
             | '\u{FEFF}' // ZERO WIDTH NO-BREAK SPACE
+            | '\u{E0020}'..='\u{E007F}' // TAG characters
     )
 }
 
@@ -811,3 +1229,29 @@ pub(super) fn is_cjk_break_allowed(c: char) -> bool {
     // See: https://en.wikipedia.org/wiki/Line_breaking_rules_in_East_Asian_languages#Characters_not_permitted_on_the_start_of_a_line.
     !")]｝〕〉》」』】〙〗〟'\"｠»ヽヾーァィゥェォッャュョヮヵヶぁぃぅぇぉっゃゅょゎゕゖㇰㇱㇲㇳㇴㇵㇶㇷㇸㇹㇺㇻㇼㇽㇾㇿ々〻‐゠–〜?!‼⁇⁈⁉・、:;,。.".contains(c)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emoji_sequence() {
+        assert_eq!(emoji_sequence(""), "");
+        assert_eq!(emoji_sequence("abc"), "a");
+        assert_eq!(emoji_sequence("\u{2764}\u{FE0F}!"), "\u{2764}\u{FE0F}"); // ❤️
+        assert_eq!(emoji_sequence("1\u{FE0F}\u{20E3}2"), "1\u{FE0F}\u{20E3}"); // 1️⃣
+        assert_eq!(emoji_sequence("🇸🇪🇩🇪"), "🇸🇪");
+        assert_eq!(emoji_sequence("👋🏽 hi"), "👋🏽");
+        assert_eq!(
+            emoji_sequence("👩\u{200D}💻 at work"),
+            "👩\u{200D}💻",
+            "ZWJ sequence"
+        );
+        assert_eq!(
+            emoji_sequence("🏳\u{FE0F}\u{200D}🌈"),
+            "🏳\u{FE0F}\u{200D}🌈",
+            "ZWJ sequence with variation selector"
+        );
+        assert_eq!(emoji_sequence("👩\u{200D}\n"), "👩");
+    }
+}
@@ -0,0 +1,704 @@
+//! Finding installed system fonts for characters that none of the [`FontDefinitions`] cover.
+//!
+//! [`FontDefinitions`]: crate::text::FontDefinitions
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use skrifa::{FontRef, MetadataProvider as _, Tag};
+
+use crate::{mutex::Mutex, text::FontData};
+
+/// Finds fonts for characters that none of the fonts in a family cover.
+///
+/// Install one with [`crate::text::Fonts::set_font_provider`].
+/// When a glyph is missing, the font returned by the provider is loaded
+/// and appended to the fallback chain of the font family.
+///
+/// See [`SystemFontProvider`] for an implementation that looks at the installed system fonts.
+pub trait FontProvider: Send + Sync {
+    /// Find a font that has a glyph for `c`.
+    ///
+    /// Returns a name that uniquely identifies the font face, together with its data.
+    /// Returning the same name again will reuse the already loaded font.
+    ///
+    /// This is called at most once per missing character and font family,
+    /// but may be called from within text layout, so it should cache what it can.
+    fn font_for_char(&self, c: char) -> Option<(String, Arc<FontData>)>;
+}
+
+// ----------------------------------------------------------------------------
+
+/// A [`FontProvider`] that looks for fonts installed on the system.
+///
+/// On the first missing glyph, all font files (`.ttf`, `.otf`, `.ttc`, `.otc`) in the
+/// font directories are indexed for which characters they cover.
+/// This only reads the few small tables needed for that, not the glyphs.
+/// Only the font files that cover a missing character are then read and kept in memory.
+///
+/// Use [`Self::from_directories`] to look in a specific set of directories instead,
+/// e.g. in tests.
+pub struct SystemFontProvider {
+    directories: Vec<PathBuf>,
+    state: Mutex<ProviderState>,
+}
+
+#[derive(Default)]
+struct ProviderState {
+    /// Lazily scanned, best face first.
+    faces: Option<Vec<IndexedFace>>,
+
+    /// Loaded font data, by name.
+    loaded: ahash::HashMap<String, Arc<FontData>>,
+
+    /// Characters that none of the fonts have.
+    missing: ahash::HashSet<char>,
+}
+
+/// What characters a font face in a font file covers.
+struct IndexedFace {
+    path: PathBuf,
+    index: u32,
+
+    /// Sorted, non-overlapping, inclusive ranges of codepoints.
+    coverage: Vec<(u32, u32)>,
+}
+
+impl IndexedFace {
+    fn name(&self) -> String {
+        format!("system:{}#{}", self.path.display(), self.index)
+    }
+
+    fn covers(&self, c: char) -> bool {
+        let c = c as u32;
+        let i = self.coverage.partition_point(|&(_, last)| last < c);
+        self.coverage.get(i).is_some_and(|&(first, _)| first <= c)
+    }
+}
+
+impl Default for SystemFontProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SystemFontProvider {
+    /// Look in the font directories of the current platform, see [`system_font_directories`].
+    pub fn new() -> Self {
+        Self::from_directories(system_font_directories())
+    }
+
+    /// Look for fonts in these directories (and their subdirectories) only.
+    pub fn from_directories(directories: impl IntoIterator<Item = impl Into<PathBuf>>) -> Self {
+        Self {
+            directories: directories.into_iter().map(Into::into).collect(),
+            state: Default::default(),
+        }
+    }
+
+    /// The directories we look for fonts in.
+    pub fn directories(&self) -> &[PathBuf] {
+        &self.directories
+    }
+}
+
+impl FontProvider for SystemFontProvider {
+    fn font_for_char(&self, c: char) -> Option<(String, Arc<FontData>)> {
+        let mut state = self.state.lock();
+        let ProviderState {
+            faces,
+            loaded,
+            missing,
+        } = &mut *state;
+        if missing.contains(&c) {
+            return None;
+        }
+        let faces = faces.get_or_insert_with(|| {
+            profiling::scope!("index system fonts");
+            index_font_directories(&self.directories)
+        });
+
+        for face in faces.iter().filter(|face| face.covers(c)) {
+            let name = face.name();
+            if let Some(font_data) = loaded.get(&name) {
+                return Some((name, Arc::clone(font_data)));
+            }
+            match std::fs::read(&face.path) {
+                Ok(bytes) => {
+                    log::debug!("Loading system font {} for {c:?}", face.path.display());
+                    let font_data = Arc::new(FontData {
+                        index: face.index,
+                        ..FontData::from_owned(bytes)
+                    });
+                    loaded.insert(name.clone(), Arc::clone(&font_data));
+                    return Some((name, font_data));
+                }
+                Err(err) => {
+                    log::debug!("Failed to read font file {}: {err}", face.path.display());
+                }
+            }
+        }
+
+        missing.insert(c);
+        None
+    }
+}
+
+/// Scan the directories for font files, and sort the faces so that the
+/// regular upright ones come first.
+fn index_font_directories(directories: &[PathBuf]) -> Vec<IndexedFace> {
+    let mut files = vec![];
+    for directory in directories {
+        collect_font_files(directory, 0, &mut files);
+    }
+    files.sort();
+    files.dedup();
+
+    let mut faces = vec![];
+    for path in files {
+        let fonts = match read_index_tables(&path) {
+            Ok(fonts) => fonts,
+            Err(err) => {
+                log::debug!("Failed to index font file {}: {err}", path.display());
+                continue;
+            }
+        };
+        let fonts: Vec<_> = fonts
+            .iter()
+            .enumerate()
+            .filter_map(|(index, data)| Some((index as u32, FontRef::new(data).ok()?)))
+            .collect();
+
+        for (index, font) in fonts {
+            let mut codepoints: Vec<u32> = font.charmap().mappings().map(|(c, _)| c).collect();
+            codepoints.sort_unstable();
+
+            let mut coverage: Vec<(u32, u32)> = vec![];
+            for c in codepoints {
+                match coverage.last_mut() {
+                    Some((_, last)) if c <= *last + 1 => *last = (*last).max(c),
+                    _ => coverage.push((c, c)),
+                }
+            }
+
+            let attributes = font.attributes();
+            let is_upright = attributes.style == skrifa::attribute::Style::Normal;
+            let weight_distance = (attributes.weight.value() - 400.0).abs() as u32;
+
+            faces.push((
+                (!is_upright, weight_distance),
+                IndexedFace {
+                    path: path.clone(),
+                    index,
+                    coverage,
+                },
+            ));
+        }
+    }
+
+    // Stable, so ties are broken by path:
+    faces.sort_by_key(|(rank, _)| *rank);
+    faces.into_iter().map(|(_, face)| face).collect()
+}
+
+/// Read only the tables needed for indexing a font file: the character map and the style.
+///
+/// Returns a minimal font with just those tables for each face in the file (one, unless it is a collection),
+/// so we don't have to read the glyphs of every installed font.
+/// Faces that can't be read are left empty, so the others keep their index in the collection.
+fn read_index_tables(path: &Path) -> std::io::Result<Vec<Vec<u8>>> {
+    use std::io::{Error, Read as _, Seek as _, SeekFrom};
+
+    /// Don't trust the sizes in the file too much:
+    const MAX_TABLE_LEN: u32 = 64 * 1024 * 1024;
+    const MAX_FACES: u32 = 1024;
+    const INDEX_TABLES: [Tag; 3] = [Tag::new(b"OS/2"), Tag::new(b"cmap"), Tag::new(b"head")];
+
+    let mut file = std::fs::File::open(path)?;
+    let mut read_at = |offset: u32, len: u32| -> std::io::Result<Vec<u8>> {
+        if len > MAX_TABLE_LEN {
+            return Err(Error::other("table too large"));
+        }
+        file.seek(SeekFrom::Start(offset.into()))?;
+        let mut data = vec![0; len as usize];
+        file.read_exact(&mut data)?;
+        Ok(data)
+    };
+    let u16_at = |data: &[u8], i: usize| u16::from_be_bytes([data[i], data[i + 1]]);
+    let u32_at = |data: &[u8], i: usize| {
+        u32::from_be_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]])
+    };
+
+    let header = read_at(0, 12)?;
+    let face_offsets = if &header[..4] == b"ttcf" {
+        let num_faces = u32_at(&header, 8).min(MAX_FACES);
+        let offsets = read_at(12, 4 * num_faces)?;
+        (0..num_faces as usize)
+            .map(|i| u32_at(&offsets, 4 * i))
+            .collect()
+    } else {
+        vec![0]
+    };
+
+    let mut read_face = |face_offset: u32| -> std::io::Result<Vec<u8>> {
+        let out_of_range = || Error::other("table directory out of range");
+        let num_tables = u16_at(&read_at(face_offset, 12)?, 4);
+        let records = read_at(
+            face_offset.checked_add(12).ok_or_else(out_of_range)?,
+            u32::from(num_tables)
+                .checked_mul(16)
+                .ok_or_else(out_of_range)?,
+        )?;
+        let mut tables = vec![];
+        for record in records.chunks_exact(16) {
+            let tag = Tag::new(&[record[0], record[1], record[2], record[3]]);
+            if INDEX_TABLES.contains(&tag) {
+                tables.push((tag, read_at(u32_at(record, 8), u32_at(record, 12))?));
+            }
+        }
+        Ok(minimal_font(tables))
+    };
+
+    let faces = face_offsets
+        .into_iter()
+        .enumerate()
+        .map(|(index, face_offset)| {
+            read_face(face_offset).unwrap_or_else(|err| {
+                log::debug!("Failed to read face {index} of {}: {err}", path.display());
+                vec![]
+            })
+        })
+        .collect();
+    Ok(faces)
+}
+
+/// An OpenType font file with just the given tables.
+fn minimal_font(mut tables: Vec<(Tag, Vec<u8>)>) -> Vec<u8> {
+    tables.sort_by_key(|(tag, _)| *tag); // Tables are looked up with a binary search
+
+    let mut font = vec![];
+    font.extend(0x0001_0000_u32.to_be_bytes()); // TrueType outlines (which we don't include)
+    font.extend((tables.len() as u16).to_be_bytes());
+    font.extend([0; 6]); // Search range, entry selector and range shift, which are not used
+
+    let mut offset = 12 + 16 * tables.len();
+    for (tag, data) in &tables {
+        font.extend(tag.to_be_bytes());
+        font.extend(0_u32.to_be_bytes()); // Checksum, not checked
+        font.extend((offset as u32).to_be_bytes());
+        font.extend((data.len() as u32).to_be_bytes());
+        offset += data.len().next_multiple_of(4);
+    }
+    for (_, data) in tables {
+        font.extend(data);
+        font.resize(font.len().next_multiple_of(4), 0);
+    }
+    font
+}
+
+fn collect_font_files(directory: &Path, depth: usize, files: &mut Vec<PathBuf>) {
+    const MAX_DEPTH: usize = 8; // protect against symlink loops
+
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if depth < MAX_DEPTH {
+                collect_font_files(&path, depth + 1, files);
+            }
+        } else if path.extension().is_some_and(|ext| {
+            ["ttf", "otf", "ttc", "otc"]
+                .iter()
+                .any(|font_ext| ext.eq_ignore_ascii_case(font_ext))
+        }) {
+            files.push(path);
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// The directories where fonts are installed on the current platform.
+///
+/// On Linux and BSDs these are the `<dir>` entries of the fontconfig configuration,
+/// plus the usual font directories.
+/// Directories that don't exist are included too.
+pub fn system_font_directories() -> Vec<PathBuf> {
+    let mut unique = vec![];
+    for directory in platform_font_directories() {
+        if !unique.contains(&directory) {
+            unique.push(directory);
+        }
+    }
+    unique
+}
+
+#[cfg(all(
+    unix,
+    not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+))]
+fn platform_font_directories() -> Vec<PathBuf> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| home.as_ref().map(|home| home.join(".local/share")));
+
+    let config_file = std::env::var_os("FONTCONFIG_FILE")
+        .map_or_else(|| PathBuf::from("/etc/fonts/fonts.conf"), PathBuf::from);
+    let mut directories = vec![];
+    fontconfig::read_fontconfig_file(&config_file, 0, &mut directories);
+
+    directories.push("/usr/share/fonts".into());
+    directories.push("/usr/local/share/fonts".into());
+    directories.extend(data_home.map(|dir| dir.join("fonts")));
+    directories.extend(home.map(|home| home.join(".fonts")));
+    directories
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn platform_font_directories() -> Vec<PathBuf> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let mut directories = vec!["/System/Library/Fonts".into(), "/Library/Fonts".into()];
+    directories.extend(home.map(|home| home.join("Library/Fonts")));
+    directories
+}
+
+#[cfg(target_os = "android")]
+fn platform_font_directories() -> Vec<PathBuf> {
+    vec!["/system/fonts".into()]
+}
+
+#[cfg(windows)]
+fn platform_font_directories() -> Vec<PathBuf> {
+    let windows_dir =
+        std::env::var_os("WINDIR").map_or_else(|| PathBuf::from("C:\\Windows"), PathBuf::from);
+    let mut directories = vec![windows_dir.join("Fonts")];
+    if let Some(local_app_data) = std::env::var_os("LOCALAPPDATA") {
+        directories.push(PathBuf::from(local_app_data).join("Microsoft\\Windows\\Fonts"));
+    }
+    directories
+}
+
+/// No known font directories, e.g. on the web.
+#[cfg(not(any(unix, windows)))]
+fn platform_font_directories() -> Vec<PathBuf> {
+    vec![]
+}
+
+/// Reading the font directories from the fontconfig configuration.
+#[cfg(all(
+    unix,
+    not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+))]
+mod fontconfig {
+    use std::path::{Path, PathBuf};
+
+    /// Add the `<dir>` entries of a fontconfig configuration file,
+    /// following its `<include>`s.
+    pub(super) fn read_fontconfig_file(path: &Path, depth: usize, directories: &mut Vec<PathBuf>) {
+        const MAX_DEPTH: usize = 4;
+
+        if depth > MAX_DEPTH {
+            return;
+        }
+
+        if path.is_dir() {
+            let Ok(entries) = std::fs::read_dir(path) else {
+                return;
+            };
+            let mut files: Vec<PathBuf> = entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "conf"))
+                .collect();
+            files.sort();
+            for file in files {
+                read_fontconfig_file(&file, depth + 1, directories);
+            }
+        } else if let Ok(config) = std::fs::read_to_string(path) {
+            let config_dir = path.parent().unwrap_or_else(|| Path::new("/"));
+            let entries = parse_fontconfig(&config);
+            for entry in entries {
+                let Some(resolved) = entry.resolve(config_dir) else {
+                    continue;
+                };
+                match entry.kind {
+                    FontconfigEntryKind::Dir => directories.push(resolved),
+                    FontconfigEntryKind::Include => {
+                        read_fontconfig_file(&resolved, depth + 1, directories);
+                    }
+                }
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    enum FontconfigEntryKind {
+        Dir,
+        Include,
+    }
+
+    /// A `<dir>` or `<include>` element of a fontconfig configuration file.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct FontconfigEntry {
+        kind: FontconfigEntryKind,
+
+        /// The `prefix` attribute, e.g. `xdg`.
+        prefix: Option<String>,
+
+        path: String,
+    }
+
+    impl FontconfigEntry {
+        /// The absolute path, or `None` if it can't be resolved.
+        fn resolve(&self, config_dir: &Path) -> Option<PathBuf> {
+            let home = || std::env::var_os("HOME").map(PathBuf::from);
+            let path = &self.path;
+
+            if let Some(rest) = path.strip_prefix("~/") {
+                return Some(home()?.join(rest));
+            }
+
+            match self.prefix.as_deref() {
+                Some("xdg") => {
+                    let base = if self.kind == FontconfigEntryKind::Dir {
+                        std::env::var_os("XDG_DATA_HOME")
+                            .map(PathBuf::from)
+                            .or_else(|| Some(home()?.join(".local/share")))?
+                    } else {
+                        std::env::var_os("XDG_CONFIG_HOME")
+                            .map(PathBuf::from)
+                            .or_else(|| Some(home()?.join(".config")))?
+                    };
+                    Some(base.join(path))
+                }
+                Some("default" | "cwd") => Some(std::env::current_dir().ok()?.join(path)),
+                _ => Some(config_dir.join(path)), // Absolute paths replace the config dir
+            }
+        }
+    }
+
+    /// Find all `<dir>` and `<include>` elements, ignoring comments.
+    ///
+    /// This is not a full XML parser, but fontconfig files are simple enough.
+    fn parse_fontconfig(config: &str) -> Vec<FontconfigEntry> {
+        let mut entries = vec![];
+        let mut rest = config;
+
+        while let Some(start) = rest.find('<') {
+            rest = &rest[start..];
+
+            if let Some(comment) = rest.strip_prefix("<!--") {
+                rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+                continue;
+            }
+
+            let kind = if rest.starts_with("<dir") {
+                Some((FontconfigEntryKind::Dir, "</dir>"))
+            } else if rest.starts_with("<include") {
+                Some((FontconfigEntryKind::Include, "</include>"))
+            } else {
+                None
+            };
+            let tag_end = rest.find('>').map_or(rest.len(), |end| end + 1);
+            let tag = &rest[..tag_end];
+            rest = &rest[tag_end..];
+
+            let Some((kind, closing_tag)) = kind else {
+                continue;
+            };
+            let tag_name_len = if kind == FontconfigEntryKind::Dir {
+                4
+            } else {
+                8
+            };
+            let after_name = tag[tag_name_len..].chars().next();
+            if !after_name.is_some_and(|c| c == '>' || c.is_whitespace()) || tag.ends_with("/>") {
+                continue;
+            }
+
+            let Some(end) = rest.find(closing_tag) else {
+                break;
+            };
+            let path = rest[..end].trim();
+            rest = &rest[end + closing_tag.len()..];
+
+            if !path.is_empty() {
+                entries.push(FontconfigEntry {
+                    kind,
+                    prefix: xml_attribute(tag, "prefix").map(ToOwned::to_owned),
+                    path: path.replace("&amp;", "&"),
+                });
+            }
+        }
+
+        entries
+    }
+
+    fn xml_attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+        let mut rest = tag;
+        while let Some(start) = rest.find(name) {
+            let preceded_by_space = rest[..start]
+                .chars()
+                .next_back()
+                .is_some_and(char::is_whitespace);
+            rest = &rest[start + name.len()..];
+            if !preceded_by_space {
+                continue;
+            }
+            let Some(value) = rest.trim_start().strip_prefix('=') else {
+                continue;
+            };
+            let value = value.trim_start();
+            let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+            let value = &value[1..];
+            return value.find(quote).map(|end| &value[..end]);
+        }
+        None
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_parse_fontconfig() {
+            let config = r#"<?xml version="1.0"?>
+    <!DOCTYPE fontconfig SYSTEM "urn:fontconfig:fonts.dtd">
+    <fontconfig>
+        <!-- Font directory list -->
+        <dir>/usr/share/fonts</dir>
+        <dir prefix="xdg">fonts</dir>
+        <!-- the following element will be removed in the future -->
+        <!-- <dir>~/.old-fonts</dir> -->
+        <dir>~/.fonts</dir>
+        <cachedir>/var/cache/fontconfig</cachedir>
+        <include ignore_missing="yes">conf.d</include>
+        <directory>not a dir</directory>
+    </fontconfig>
+    "#;
+
+            let entry = |kind, prefix: Option<&str>, path: &str| FontconfigEntry {
+                kind,
+                prefix: prefix.map(ToOwned::to_owned),
+                path: path.to_owned(),
+            };
+
+            assert_eq!(
+                parse_fontconfig(config),
+                vec![
+                    entry(FontconfigEntryKind::Dir, None, "/usr/share/fonts"),
+                    entry(FontconfigEntryKind::Dir, Some("xdg"), "fonts"),
+                    entry(FontconfigEntryKind::Dir, None, "~/.fonts"),
+                    entry(FontconfigEntryKind::Include, None, "conf.d"),
+                ]
+            );
+
+            assert_eq!(
+                entry(FontconfigEntryKind::Include, None, "conf.d")
+                    .resolve(Path::new("/etc/fonts")),
+                Some(PathBuf::from("/etc/fonts/conf.d"))
+            );
+            assert_eq!(
+                entry(FontconfigEntryKind::Dir, None, "/usr/share/fonts")
+                    .resolve(Path::new("/etc/fonts")),
+                Some(PathBuf::from("/usr/share/fonts"))
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "default_fonts")]
+    #[test]
+    fn test_system_font_fallback() {
+        use crate::text::{FontDefinitions, FontFamily, FontId, Fonts, TextOptions};
+
+        // A stand-in for the system font directory:
+        let temp_dir = tempfile::tempdir().unwrap();
+        let directory = temp_dir.path();
+        std::fs::create_dir_all(directory.join("truetype")).unwrap();
+        std::fs::write(
+            directory.join("truetype/NotoEmoji-Regular.ttf"),
+            epaint_default_fonts::NOTO_EMOJI_REGULAR,
+        )
+        .unwrap();
+        std::fs::write(directory.join("README.txt"), "not a font").unwrap();
+
+        let mut definitions = FontDefinitions::default();
+        for fonts in definitions.families.values_mut() {
+            fonts.retain(|name| name != "NotoEmoji-Regular" && name != "emoji-icon-font");
+        }
+        let mut fonts = Fonts::new(TextOptions::default(), definitions);
+
+        let font_id = FontId::proportional(14.0);
+        assert!(fonts.has_glyph(&font_id, 'a'));
+        assert!(!fonts.has_glyph(&font_id, '🐱'));
+
+        let provider = Arc::new(SystemFontProvider::from_directories([directory]));
+        fonts.set_font_provider(Some(provider));
+        assert!(fonts.has_glyph(&font_id, '🐱'));
+        assert!(fonts.has_glyph(&FontId::monospace(14.0), '🐱'));
+        assert!(!fonts.has_glyph(&font_id, '\u{E000}')); // private use area
+
+        // The provider survives recreating the fonts:
+        fonts.begin_pass(TextOptions {
+            font_hinting: false,
+            ..Default::default()
+        });
+        assert!(fonts.has_glyph(&font_id, '🐱'));
+
+        // Characters nobody has are remembered:
+        assert!(!fonts.has_glyph(&FontId::monospace(14.0), '\u{E000}'));
+
+        // The fallback is not part of the definitions:
+        assert!(
+            !fonts.definitions().families[&FontFamily::Proportional]
+                .iter()
+                .any(|name| name.starts_with("system:"))
+        );
+    }
+
+    #[cfg(feature = "default_fonts")]
+    #[test]
+    fn test_read_index_tables() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("NotoEmoji-Regular.ttf");
+        std::fs::write(&path, epaint_default_fonts::NOTO_EMOJI_REGULAR).unwrap();
+
+        let faces = read_index_tables(&path).unwrap();
+        assert_eq!(faces.len(), 1);
+        assert!(faces[0].len() < epaint_default_fonts::NOTO_EMOJI_REGULAR.len() / 10);
+
+        let minimal = FontRef::new(&faces[0]).unwrap();
+        let full = FontRef::new(epaint_default_fonts::NOTO_EMOJI_REGULAR).unwrap();
+        assert!(minimal.charmap().mappings().eq(full.charmap().mappings()));
+        assert_eq!(minimal.attributes(), full.attributes());
+    }
+
+    #[test]
+    fn test_read_index_tables_malformed_collection() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("malformed.ttc");
+
+        // A collection of two faces, at offsets past the end of the file:
+        let mut collection = b"ttcf".to_vec();
+        collection.extend(0x0001_0000_u32.to_be_bytes()); // version
+        collection.extend(2_u32.to_be_bytes());
+        collection.extend((u32::MAX - 4).to_be_bytes());
+        collection.extend(1000_u32.to_be_bytes());
+        std::fs::write(&path, &collection).unwrap();
+        assert_eq!(read_index_tables(&path).unwrap(), vec![vec![], vec![]]);
+
+        // The header is cut off before the offsets of the faces:
+        std::fs::write(&path, &collection[..14]).unwrap();
+        assert!(read_index_tables(&path).is_err());
+    }
+}
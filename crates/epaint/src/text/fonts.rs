@@ -10,7 +10,7 @@ use std::{
 use crate::{
     TextureAtlas,
     text::{
-        Galley, LayoutJob, LayoutSection, TextOptions,
+        FontProvider, Galley, LayoutJob, LayoutSection, TextOptions,
         font::{Font, FontFace, GlyphInfo},
     },
 };
@@ -198,6 +198,7 @@ impl AsRef<[u8]> for FontData {
 /// Extra scale and vertical tweak to apply to all text of a certain font.
 #[derive(Copy, Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct FontTweak {
     /// Scale the font's glyphs by this much.
     /// this is only a visual effect and does not affect the text layout.
@@ -228,6 +229,17 @@ pub struct FontTweak {
     ///
     /// `None` means use the global setting.
     pub hinting_override: Option<bool>,
+
+    /// If the font has color glyphs (COLR, or PNG bitmaps in CBDT or sbix
+    /// with the `color_bitmap_glyphs` feature),
+    /// paint those in full color instead of the monochrome outlines.
+    ///
+    /// Color glyphs ignore the text color.
+    /// Fonts with color glyphs are also preferred for emoji sequences
+    /// and emoji followed by the emoji variation selector (U+FE0F).
+    ///
+    /// Default: `true`.
+    pub prefer_color_glyphs: bool,
 }
 
 impl Default for FontTweak {
@@ -237,6 +249,7 @@ impl Default for FontTweak {
             y_offset_factor: 0.0,
             y_offset: 0.0,
             hinting_override: None,
+            prefer_color_glyphs: true,
         }
     }
 }
@@ -451,7 +464,7 @@ pub(crate) struct FontFaceKey(u64);
 impl FontFaceKey {
     pub const INVALID: Self = Self(0);
 
-    fn new() -> Self {
+    pub(super) fn new() -> Self {
         static KEY_COUNTER: AtomicU64 = AtomicU64::new(1);
         Self(crate::util::hash(
             KEY_COUNTER.fetch_add(1, Ordering::Relaxed),
@@ -473,6 +486,9 @@ pub(super) struct CachedFamily {
     pub replacement_glyph: (FontFaceKey, GlyphInfo),
 
     pub glyph_info_cache: ahash::HashMap<char, (FontFaceKey, GlyphInfo)>,
+
+    /// Emoji sequences, and what single glyph (if any) they map to.
+    pub sequence_glyph_info_cache: ahash::HashMap<String, Option<(FontFaceKey, GlyphInfo)>>,
 }
 
 impl CachedFamily {
@@ -486,6 +502,7 @@ impl CachedFamily {
                 characters: None,
                 replacement_glyph: (FontFaceKey::INVALID, GlyphInfo::INVISIBLE),
                 glyph_info_cache: Default::default(),
+                sequence_glyph_info_cache: Default::default(),
             };
         }
 
@@ -494,6 +511,7 @@ impl CachedFamily {
             characters: None,
             replacement_glyph: (FontFaceKey::INVALID, GlyphInfo::INVISIBLE),
             glyph_info_cache: Default::default(),
+            sequence_glyph_info_cache: Default::default(),
         };
 
         const PRIMARY_REPLACEMENT_CHAR: char = '◻'; // white medium square
@@ -568,11 +586,13 @@ impl Fonts {
 
         if needs_recreate {
             let definitions = self.fonts.definitions.clone();
+            let font_provider = self.fonts.font_provider.take();
 
             *self = Self {
                 fonts: FontsImpl::new(options, definitions),
                 galley_cache: Default::default(),
             };
+            self.fonts.font_provider = font_provider;
         }
 
         self.galley_cache.flush_cache();
@@ -593,6 +613,25 @@ impl Fonts {
         &self.fonts.definitions
     }
 
+    /// Look for fonts with the missing glyphs using this [`FontProvider`],
+    /// e.g. a [`crate::text::SystemFontProvider`].
+    ///
+    /// Found fonts are appended to the fallback chain of the font family that is missing the glyph,
+    /// after all the fonts in the [`FontDefinitions`].
+    /// Set to `None` to only use the fonts in the [`FontDefinitions`].
+    pub fn set_font_provider(&mut self, font_provider: Option<Arc<dyn FontProvider>>) {
+        self.fonts.font_provider = font_provider;
+
+        // Forget about glyphs that were previously missing:
+        self.fonts.family_cache.clear();
+        self.galley_cache = Default::default();
+    }
+
+    /// See [`Self::set_font_provider`].
+    pub fn font_provider(&self) -> Option<&Arc<dyn FontProvider>> {
+        self.fonts.font_provider.as_ref()
+    }
+
     /// The font atlas.
     /// Pass this to [`crate::Tessellator`].
     pub fn texture_atlas(&self) -> &TextureAtlas {
@@ -625,6 +664,14 @@ impl Fonts {
         self.galley_cache.num_galleys_in_cache()
     }
 
+    /// How many times text had to be laid out because it wasn't in the galley cache.
+    ///
+    /// This counts up forever, so compare it between frames to find text
+    /// that is laid out anew every frame.
+    pub fn num_galley_cache_misses(&self) -> u64 {
+        self.galley_cache.num_misses
+    }
+
     /// How full is the font atlas?
     ///
     /// This increases as new fonts and/or glyphs are used,
@@ -732,6 +779,14 @@ impl FontsView<'_> {
         self.galley_cache.num_galleys_in_cache()
     }
 
+    /// How many times text had to be laid out because it wasn't in the galley cache.
+    ///
+    /// This counts up forever, so compare it between frames to find text
+    /// that is laid out anew every frame.
+    pub fn num_galley_cache_misses(&self) -> u64 {
+        self.galley_cache.num_misses
+    }
+
     /// How full is the font atlas?
     ///
     /// This increases as new fonts and/or glyphs are used,
@@ -794,6 +849,9 @@ pub struct FontsImpl {
     fonts_by_id: nohash_hasher::IntMap<FontFaceKey, FontFace>,
     fonts_by_name: ahash::HashMap<String, FontFaceKey>,
     family_cache: ahash::HashMap<FontFamily, CachedFamily>,
+
+    /// Used to look for missing glyphs.
+    font_provider: Option<Arc<dyn FontProvider>>,
 }
 
 impl FontsImpl {
@@ -829,6 +887,7 @@ impl FontsImpl {
             fonts_by_id,
             fonts_by_name,
             family_cache: Default::default(),
+            font_provider: None,
         }
     }
 
@@ -857,6 +916,8 @@ impl FontsImpl {
         });
         Font {
             fonts_by_id: &mut self.fonts_by_id,
+            fonts_by_name: &mut self.fonts_by_name,
+            font_provider: self.font_provider.as_deref(),
             cached_family,
             atlas: &mut self.atlas,
         }
@@ -882,6 +943,9 @@ struct GalleyCache {
     /// Frame counter used to do garbage collection on the cache
     generation: u32,
     cache: nohash_hasher::IntMap<u64, CachedGalley>,
+
+    /// Total number of layouts that were not in the cache.
+    num_misses: u64,
 }
 
 impl GalleyCache {
@@ -942,6 +1006,7 @@ impl GalleyCache {
                 galley
             }
             std::collections::hash_map::Entry::Vacant(entry) => {
+                self.num_misses += 1;
                 let job = Arc::new(job);
                 if allow_split_paragraphs && should_cache_each_paragraph_individually(&job) {
                     let (child_galleys, child_hashes) =
@@ -1038,6 +1103,7 @@ impl GalleyCache {
                 let LayoutSection {
                     leading_space,
                     byte_range: section_range,
+                    placeholder,
                     format,
                 } = section;
 
@@ -1068,6 +1134,7 @@ impl GalleyCache {
                             0.0
                         },
                         byte_range: new_range,
+                        placeholder: *placeholder,
                         format: format.clone(),
                     });
                 }
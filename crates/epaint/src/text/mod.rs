@@ -2,6 +2,7 @@
 
 pub mod cursor;
 mod font;
+mod font_provider;
 mod fonts;
 mod text_layout;
 mod text_layout_types;
@@ -10,6 +11,7 @@ mod text_layout_types;
 pub const TAB_SIZE: usize = 4;
 
 pub use {
+    font_provider::{FontProvider, SystemFontProvider, system_font_directories},
     fonts::{
         FontData, FontDefinitions, FontFamily, FontId, FontInsert, FontPriority, FontTweak, Fonts,
         FontsImpl, FontsView, InsertFontFamily,
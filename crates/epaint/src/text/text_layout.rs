@@ -8,12 +8,15 @@ use crate::{
     Color32, Mesh, Stroke, Vertex,
     stroke::PathStroke,
     text::{
-        font::{ScaledMetrics, is_cjk, is_cjk_break_allowed},
+        font::{ScaledMetrics, emoji_sequence, is_cjk, is_cjk_break_allowed},
         fonts::FontFaceKey,
     },
 };
 
-use super::{FontsImpl, Galley, Glyph, LayoutJob, LayoutSection, PlacedRow, Row, RowVisuals};
+use super::{
+    FontsImpl, Galley, Glyph, LayoutJob, LayoutSection, PlacedRow, PlaceholderAlign, Row,
+    RowVisuals,
+};
 
 // ----------------------------------------------------------------------------
 
@@ -156,6 +159,7 @@ fn layout_section(
     let LayoutSection {
         leading_space,
         byte_range,
+        placeholder,
         format,
     } = section;
     let mut font = fonts.font(&format.font_id.family);
@@ -180,13 +184,44 @@ fn layout_section(
     let mut current_font = FontFaceKey::INVALID;
     let mut current_font_face_metrics = ScaledMetrics::default();
 
-    for chr in job.text[byte_range.clone()].chars() {
+    let section_text = &job.text[byte_range.clone()];
+    let mut char_indices = section_text.char_indices();
+    while let Some((byte_index, chr)) = char_indices.next() {
         if job.break_on_newline && chr == '\n' {
             out_paragraphs.push(Paragraph::from_section_index(section_index));
             paragraph = out_paragraphs.last_mut().unwrap();
             paragraph.empty_paragraph_height = line_height; // TODO(emilk): replace this hack with actually including `\n` in the glyphs?
+        } else if let Some(placeholder) = placeholder {
+            // An empty box instead of a glyph. The Y position is set in `galley_from_rows`.
+            let size = placeholder.size;
+            paragraph.glyphs.push(Glyph {
+                chr,
+                pos: pos2(paragraph.cursor_x_px / pixels_per_point, f32::NAN),
+                advance_width: size.x,
+                line_height: size.y,
+                font_face_height: size.y,
+                font_face_ascent: size.y,
+                font_height: size.y,
+                font_ascent: size.y,
+                uv_rect: Default::default(),
+                section_index,
+                first_vertex: 0, // filled in later
+            });
+
+            paragraph.cursor_x_px += size.x * pixels_per_point;
+            last_glyph_id = None;
         } else {
-            let (font_id, glyph_info) = font.glyph_info(chr);
+            // Emoji sequences (e.g. ZWJ sequences and flags) can map to a single glyph,
+            // in which case the rest of the characters become invisible:
+            let sequence = emoji_sequence(&section_text[byte_index..]);
+            let (font_id, glyph_info, num_hidden_chars) = if chr.len_utf8() < sequence.len()
+                && let Some((font_id, glyph_info)) = font.sequence_glyph_info(sequence)
+            {
+                (font_id, glyph_info, sequence.chars().count() - 1)
+            } else {
+                let (font_id, glyph_info) = font.glyph_info(chr);
+                (font_id, glyph_info, 0)
+            };
             let mut font_face = font.fonts_by_id.get_mut(&font_id);
             if current_font != font_id {
                 current_font = font_id;
@@ -237,6 +272,20 @@ fn layout_section(
 
             paragraph.cursor_x_px += glyph_alloc.advance_width_px;
             last_glyph_id = Some(glyph_alloc.id);
+
+            // We still want one glyph per character:
+            for _ in 0..num_hidden_chars {
+                let Some((_, chr)) = char_indices.next() else {
+                    break;
+                };
+                paragraph.glyphs.push(Glyph {
+                    chr,
+                    pos: pos2(paragraph.cursor_x_px / pixels_per_point, f32::NAN),
+                    advance_width: 0.0,
+                    uv_rect: Default::default(),
+                    ..*paragraph.glyphs.last().expect("We just pushed a glyph")
+                });
+            }
         }
     }
 }
@@ -297,6 +346,7 @@ fn rows_from_paragraphs(
                 row: Arc::new(Row {
                     section_index_at_start: paragraph.section_index_at_start,
                     glyphs: vec![],
+                    placeholders: vec![],
                     visuals: Default::default(),
                     size: vec2(0.0, paragraph.empty_paragraph_height),
                 }),
@@ -311,6 +361,7 @@ fn rows_from_paragraphs(
                     row: Arc::new(Row {
                         section_index_at_start: paragraph.section_index_at_start,
                         glyphs: paragraph.glyphs,
+                        placeholders: vec![], // filled in later
                         visuals: Default::default(),
                         size: vec2(paragraph_max_x, 0.0),
                     }),
@@ -363,6 +414,7 @@ fn line_break(
                     row: Arc::new(Row {
                         section_index_at_start: paragraph.section_index_at_start,
                         glyphs: vec![],
+                        placeholders: vec![],
                         visuals: Default::default(),
                         size: Vec2::ZERO,
                     }),
@@ -389,6 +441,7 @@ fn line_break(
                     row: Arc::new(Row {
                         section_index_at_start,
                         glyphs,
+                        placeholders: vec![], // filled in later
                         visuals: Default::default(),
                         size: vec2(paragraph_max_x, 0.0),
                     }),
@@ -431,6 +484,7 @@ fn line_break(
                 row: Arc::new(Row {
                     section_index_at_start,
                     glyphs,
+                    placeholders: vec![], // filled in later
                     visuals: Default::default(),
                     size: vec2(paragraph_max_x - paragraph_min_x, 0.0),
                 }),
@@ -661,14 +715,27 @@ fn galley_from_rows(
         let row = Arc::make_mut(&mut placed_row.row);
 
         first_row_min_height = 0.0;
-        for glyph in &row.glyphs {
-            max_row_height = max_row_height.at_least(glyph.line_height);
+        row.placeholders.clear();
+        for (i, glyph) in row.glyphs.iter().enumerate() {
+            if let Some(placeholder) = &job.sections[glyph.section_index as usize].placeholder {
+                row.placeholders.push(i);
+                if let PlaceholderAlign::Row(_) = placeholder.valign {
+                    max_row_height = max_row_height.at_least(placeholder.size.y);
+                }
+            } else {
+                max_row_height = max_row_height.at_least(glyph.line_height);
+            }
         }
         max_row_height = point_scale.round_to_pixel(max_row_height);
 
         // Now position each glyph vertically:
+        let mut baseline = None;
         for glyph in &mut row.glyphs {
-            let format = &job.sections[glyph.section_index as usize].format;
+            let section = &job.sections[glyph.section_index as usize];
+            if section.placeholder.is_some() {
+                continue;
+            }
+            let format = &section.format;
 
             glyph.pos.y = glyph.font_face_ascent
 
@@ -680,6 +747,12 @@ fn galley_from_rows(
                 + 0.5 * (glyph.font_height - glyph.font_face_height);
 
             glyph.pos.y = point_scale.round_to_pixel(glyph.pos.y);
+            baseline = Some(baseline.map_or(glyph.pos.y, |y: f32| y.max(glyph.pos.y)));
+        }
+
+        if !row.placeholders.is_empty() {
+            max_row_height =
+                position_placeholders(point_scale, &job, row, baseline, max_row_height);
         }
 
         placed_row.pos.y = cursor_y;
@@ -705,11 +778,6 @@ fn galley_from_rows(
         mesh_bounds |= row.visuals.mesh_bounds.translate(placed_row.pos.to_vec2());
         num_vertices += row.visuals.mesh.vertices.len();
         num_indices += row.visuals.mesh.indices.len();
-
-        row.section_index_at_start = u32::MAX; // No longer in use.
-        for glyph in &mut row.glyphs {
-            glyph.section_index = u32::MAX; // No longer in use.
-        }
     }
 
     let mut galley = Galley {
@@ -731,6 +799,66 @@ fn galley_from_rows(
     galley
 }
 
+/// Position the [`Placeholder`](super::Placeholder)s of a row vertically,
+/// after the text glyphs have been positioned.
+///
+/// Baseline-aligned placeholders that stick out above or below the row will grow it.
+/// Returns the new row height.
+fn position_placeholders(
+    point_scale: PointScale,
+    job: &LayoutJob,
+    row: &mut Row,
+    baseline: Option<f32>,
+    mut row_height: f32,
+) -> f32 {
+    let placeholder = |glyph: &Glyph| {
+        job.sections[glyph.section_index as usize]
+            .placeholder
+            .expect("Row::placeholders should only point to placeholders")
+    };
+
+    // Rows without any text have their baseline at the bottom:
+    let mut baseline = baseline.unwrap_or(row_height);
+
+    // Make room above the baseline:
+    let mut shift_down = 0.0_f32;
+    for &i in &row.placeholders {
+        let placeholder = placeholder(&row.glyphs[i]);
+        if let PlaceholderAlign::Baseline { descent } = placeholder.valign {
+            shift_down = shift_down.max(placeholder.size.y - descent - baseline);
+        }
+    }
+    let shift_down = point_scale.round_to_pixel(shift_down);
+    if 0.0 < shift_down {
+        for glyph in &mut row.glyphs {
+            glyph.pos.y += shift_down;
+        }
+        baseline += shift_down;
+        row_height += shift_down;
+    }
+
+    // …and below it:
+    for &i in &row.placeholders {
+        let placeholder = placeholder(&row.glyphs[i]);
+        if let PlaceholderAlign::Baseline { descent } = placeholder.valign {
+            row_height = row_height.at_least(point_scale.round_to_pixel(baseline + descent));
+        }
+    }
+
+    for &i in &row.placeholders {
+        let placeholder = placeholder(&row.glyphs[i]);
+        let size = placeholder.size;
+        let top = match placeholder.valign {
+            PlaceholderAlign::Baseline { descent } => baseline + descent - size.y,
+            PlaceholderAlign::Row(align) => align.to_factor() * (row_height - size.y),
+        };
+        // The glyph `pos` is at the bottom of the box, since `font_ascent == size.y`:
+        row.glyphs[i].pos.y = point_scale.round_to_pixel(top) + size.y;
+    }
+
+    row_height
+}
+
 #[derive(Default)]
 struct FormatSummary {
     any_background: bool,
@@ -769,9 +897,13 @@ fn tessellate_row(
 
     let glyph_index_start = mesh.indices.len();
     let glyph_vertex_start = mesh.vertices.len();
-    tessellate_glyphs(point_scale, job, row, &mut mesh);
+    tessellate_glyphs(point_scale, job, row, &mut mesh, false);
     let glyph_vertex_end = mesh.vertices.len();
 
+    // Color glyphs (emojis) go after the `glyph_vertex_range`,
+    // so that they are not tinted when overriding the text color:
+    tessellate_glyphs(point_scale, job, row, &mut mesh, true);
+
     if format_summary.any_underline {
         add_row_hline(point_scale, row, &mut mesh, |glyph| {
             let format = &job.sections[glyph.section_index as usize].format;
@@ -847,8 +979,18 @@ fn add_row_backgrounds(point_scale: PointScale, job: &LayoutJob, row: &Row, mesh
     end_run(run_start.take(), last_rect.right());
 }
 
-fn tessellate_glyphs(point_scale: PointScale, job: &LayoutJob, row: &mut Row, mesh: &mut Mesh) {
+/// Tessellate either the monochrome glyphs or the color glyphs of the row.
+fn tessellate_glyphs(
+    point_scale: PointScale,
+    job: &LayoutJob,
+    row: &mut Row,
+    mesh: &mut Mesh,
+    color_glyphs: bool,
+) {
     for glyph in &mut row.glyphs {
+        if glyph.uv_rect.is_color != color_glyphs {
+            continue;
+        }
         glyph.first_vertex = mesh.vertices.len() as u32;
         let uv_rect = glyph.uv_rect;
         if !uv_rect.is_nothing() {
@@ -864,7 +1006,11 @@ fn tessellate_glyphs(point_scale: PointScale, job: &LayoutJob, row: &mut Row, me
 
             let format = &job.sections[glyph.section_index as usize].format;
 
-            let color = format.color;
+            let color = if uv_rect.is_color {
+                Color32::WHITE
+            } else {
+                format.color
+            };
 
             if format.italics {
                 let idx = mesh.vertices.len() as u32;
@@ -1166,6 +1312,67 @@ mod tests {
         assert_eq!(row.rect().max.x, row.glyphs.last().unwrap().max_x());
     }
 
+    #[test]
+    fn test_placeholder() {
+        let pixels_per_point = 1.0;
+        let mut fonts = FontsImpl::new(TextOptions::default(), FontDefinitions::default());
+        let format = TextFormat::default();
+
+        let mut job = LayoutJob::default();
+        job.append("Hello ", 0.0, format.clone());
+        job.append_placeholder(Placeholder::new(vec2(40.0, 40.0)), format.clone());
+        job.append(" world ", 0.0, format.clone());
+        job.append_placeholder(
+            Placeholder::new(vec2(20.0, 4.0)).valign(PlaceholderAlign::Row(Align::Center)),
+            format,
+        );
+        job.wrap.max_width = 100.0;
+
+        let galley = layout(&mut fonts, pixels_per_point, job.into());
+        assert_eq!(
+            galley.rows.iter().map(|row| row.text()).collect::<Vec<_>>(),
+            vec!["Hello \u{FFFC} ", "world \u{FFFC}"]
+        );
+
+        let rects: Vec<Rect> = galley.placeholder_rects().collect();
+        assert_eq!(rects.len(), 2);
+        assert_eq!(rects[0].size(), vec2(40.0, 40.0));
+        assert_eq!(rects[1].size(), vec2(20.0, 4.0));
+
+        // The tall placeholder sits on the baseline and grows the first row:
+        let first_row = &galley.rows[0];
+        assert_eq!(first_row.rect().top(), rects[0].top());
+        assert_eq!(first_row.glyphs[0].pos.y, rects[0].bottom());
+
+        // The second one is centered in its row:
+        let second_row = &galley.rows[1];
+        assert_eq!(second_row.rect().center().y, rects[1].center().y);
+    }
+
+    #[test]
+    fn test_emoji_sequence() {
+        let pixels_per_point = 1.0;
+        let mut fonts = FontsImpl::new(TextOptions::default(), FontDefinitions::default());
+
+        // A keycap sequence, which the default emoji font has a ligature for:
+        let text = "1\u{FE0F}\u{20E3}";
+        let job = LayoutJob::single_section(text.into(), TextFormat::default());
+        let galley = layout(&mut fonts, pixels_per_point, job.into());
+
+        let glyphs = &galley.rows[0].glyphs;
+        assert_eq!(glyphs.len(), 3, "Expected one glyph per char");
+        assert!(0.0 < glyphs[0].advance_width);
+        assert_eq!(glyphs[1].advance_width, 0.0);
+        assert_eq!(glyphs[2].advance_width, 0.0);
+
+        let digit = LayoutJob::single_section("1".into(), TextFormat::default());
+        let digit = layout(&mut fonts, pixels_per_point, digit.into());
+        assert_ne!(
+            glyphs[0].uv_rect, digit.rows[0].glyphs[0].uv_rect,
+            "Expected the keycap glyph, not the digit"
+        );
+    }
+
     #[test]
     fn test_empty_row() {
         let pixels_per_point = 1.0;
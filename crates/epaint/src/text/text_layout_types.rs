@@ -103,6 +103,7 @@ impl LayoutJob {
             sections: vec![LayoutSection {
                 leading_space: 0.0,
                 byte_range: 0..text.len(),
+                placeholder: None,
                 format: TextFormat::simple(font_id, color),
             }],
             text,
@@ -122,6 +123,7 @@ impl LayoutJob {
             sections: vec![LayoutSection {
                 leading_space: 0.0,
                 byte_range: 0..text.len(),
+                placeholder: None,
                 format,
             }],
             text,
@@ -137,6 +139,7 @@ impl LayoutJob {
             sections: vec![LayoutSection {
                 leading_space: 0.0,
                 byte_range: 0..text.len(),
+                placeholder: None,
                 format: TextFormat::simple(font_id, color),
             }],
             text,
@@ -152,6 +155,7 @@ impl LayoutJob {
             sections: vec![LayoutSection {
                 leading_space: 0.0,
                 byte_range: 0..text.len(),
+                placeholder: None,
                 format,
             }],
             text,
@@ -174,6 +178,27 @@ impl LayoutJob {
         self.sections.push(LayoutSection {
             leading_space,
             byte_range,
+            placeholder: None,
+            format,
+        });
+    }
+
+    /// Helper for adding an inline [`Placeholder`] when building a [`LayoutJob`].
+    ///
+    /// This reserves a box in the text flow that wraps like a character.
+    /// After layout, use [`Galley::placeholder_rects`] to find out where it ended up,
+    /// and paint an image or a widget there.
+    ///
+    /// The `format` is used for the background and underline of the placeholder,
+    /// and for the height of an otherwise empty row.
+    pub fn append_placeholder(&mut self, placeholder: Placeholder, format: TextFormat) {
+        let start = self.text.len();
+        self.text.push(Placeholder::CHAR);
+        let byte_range = start..self.text.len();
+        self.sections.push(LayoutSection {
+            leading_space: 0.0,
+            byte_range,
+            placeholder: Some(placeholder),
             format,
         });
     }
@@ -238,6 +263,12 @@ pub struct LayoutSection {
     /// Range into the galley text
     pub byte_range: Range<usize>,
 
+    /// If set, each character in [`Self::byte_range`] is laid out as an empty box
+    /// of the given size instead of a glyph.
+    ///
+    /// See [`LayoutJob::append_placeholder`].
+    pub placeholder: Option<Placeholder>,
+
     pub format: TextFormat,
 }
 
@@ -247,16 +278,111 @@ impl std::hash::Hash for LayoutSection {
         let Self {
             leading_space,
             byte_range,
+            placeholder,
             format,
         } = self;
         OrderedFloat(*leading_space).hash(state);
         byte_range.hash(state);
+        placeholder.hash(state);
         format.hash(state);
     }
 }
 
 // ----------------------------------------------------------------------------
 
+/// An empty box reserved in the text flow, e.g. for an inline image or widget.
+///
+/// The placeholder wraps together with the surrounding text like any other character.
+/// The text layout does not paint anything for it;
+/// instead you look up where it landed with [`Galley::placeholder_rects`]
+/// and paint there yourself.
+///
+/// ## Example:
+/// ```
+/// use epaint::{text::{LayoutJob, Placeholder, TextFormat}, vec2};
+///
+/// let mut job = LayoutJob::default();
+/// job.append("Press ", 0.0, TextFormat::default());
+/// job.append_placeholder(Placeholder::new(vec2(16.0, 16.0)), TextFormat::default());
+/// job.append(" to continue", 0.0, TextFormat::default());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Placeholder {
+    /// The size of the box, in points.
+    pub size: Vec2,
+
+    /// How the box is placed vertically in its row.
+    pub valign: PlaceholderAlign,
+}
+
+impl Placeholder {
+    /// The character used in [`LayoutJob::text`] for each placeholder:
+    /// the Unicode object replacement character.
+    pub const CHAR: char = '\u{FFFC}';
+
+    /// A placeholder sitting on the text baseline.
+    #[inline]
+    pub fn new(size: Vec2) -> Self {
+        Self {
+            size,
+            valign: PlaceholderAlign::default(),
+        }
+    }
+
+    #[inline]
+    pub fn valign(mut self, valign: PlaceholderAlign) -> Self {
+        self.valign = valign;
+        self
+    }
+}
+
+impl std::hash::Hash for Placeholder {
+    #[inline]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let Self { size, valign } = self;
+        OrderedFloat(size.x).hash(state);
+        OrderedFloat(size.y).hash(state);
+        valign.hash(state);
+    }
+}
+
+/// How a [`Placeholder`] is placed vertically in its row.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum PlaceholderAlign {
+    /// The bottom of the box is `descent` points below the baseline of the text in the row.
+    ///
+    /// A `descent` of zero makes the box sit on the baseline, like an uppercase letter.
+    /// The row grows if the box doesn't fit.
+    Baseline { descent: f32 },
+
+    /// Align the box within the full height of the row.
+    ///
+    /// The row grows to at least the height of the box.
+    Row(Align),
+}
+
+impl Default for PlaceholderAlign {
+    #[inline]
+    fn default() -> Self {
+        Self::Baseline { descent: 0.0 }
+    }
+}
+
+impl std::hash::Hash for PlaceholderAlign {
+    #[inline]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Self::Baseline { descent } => OrderedFloat(*descent).hash(state),
+            Self::Row(align) => align.hash(state),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
 /// Formatting option for a section of text.
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -605,16 +731,17 @@ impl std::ops::Deref for PlacedRow {
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Row {
-    /// This is included in case there are no glyphs.
+    /// Index into [`LayoutJob::sections`] of the start of the row.
     ///
-    /// Only used during layout, then set to an invalid value in order to
-    /// enable the paragraph-concat optimization path without having to
-    /// adjust `section_index` when concatting.
+    /// This is included in case there are no glyphs.
     pub(crate) section_index_at_start: u32,
 
     /// One for each `char`.
     pub glyphs: Vec<Glyph>,
 
+    /// Indices into [`Self::glyphs`] of the glyphs that are [`Placeholder`]s, in order.
+    pub(crate) placeholders: Vec<usize>,
+
     /// Logical size based on font heights etc.
     /// Includes leading and trailing whitespace.
     pub size: Vec2,
@@ -644,6 +771,9 @@ pub struct RowVisuals {
     /// The range of vertices in the mesh that contain glyphs (as opposed to background, underlines, strikethorugh, etc).
     ///
     /// The glyph vertices comes after backgrounds (if any), but before any underlines and strikethrough.
+    ///
+    /// Color glyphs (see [`super::FontTweak::prefer_color_glyphs`]) are not included,
+    /// since they should not be tinted with the text color.
     pub glyph_vertex_range: Range<usize>,
 }
 
@@ -693,10 +823,6 @@ pub struct Glyph {
     pub uv_rect: UvRect,
 
     /// Index into [`LayoutJob::sections`]. Decides color etc.
-    ///
-    /// Only used during layout, then set to an invalid value in order to
-    /// enable the paragraph-concat optimization path without having to
-    /// adjust `section_index` when concatting.
     pub(crate) section_index: u32,
 
     /// Which is our first vertex in [`RowVisuals::mesh`].
@@ -758,6 +884,12 @@ impl Row {
     pub fn height(&self) -> f32 {
         self.size.y
     }
+
+    /// Indices into [`Self::glyphs`] of the glyphs that are [`Placeholder`]s, in order.
+    #[inline]
+    pub fn placeholders(&self) -> &[usize] {
+        &self.placeholders
+    }
 }
 
 impl PlacedRow {
@@ -776,6 +908,15 @@ impl PlacedRow {
     pub fn char_count_including_newline(&self) -> usize {
         self.row.glyphs.len() + (self.ends_with_newline as usize)
     }
+
+    /// Where the [`Placeholder`]s on this row ended up, relative to the galley.
+    pub fn placeholder_rects(&self) -> impl Iterator<Item = Rect> + '_ {
+        self.row.placeholders.iter().map(|&i| {
+            self.row.glyphs[i]
+                .logical_rect()
+                .translate(self.pos.to_vec2())
+        })
+    }
 }
 
 impl Galley {
@@ -795,6 +936,16 @@ impl Galley {
         self.rect.size()
     }
 
+    /// Where each [`Placeholder`] of the job ended up, relative to the galley, in text order.
+    ///
+    /// If the text was [`Self::elided`], the last placeholders may be missing.
+    ///
+    /// When showing the galley with a `galley_pos`, translate these rects by it
+    /// and paint your images or widgets there.
+    pub fn placeholder_rects(&self) -> impl Iterator<Item = Rect> + '_ {
+        self.rows.iter().flat_map(|row| row.placeholder_rects())
+    }
+
     /// This is the size that a non-wrapped, non-truncated, non-justified version of the text
     /// would have.
     ///
@@ -839,6 +990,8 @@ impl Galley {
     }
 
     /// Append each galley under the previous one.
+    ///
+    /// The galleys must be the paragraphs of `job` (split on `\n`), each laid out on its own.
     pub fn concat(job: Arc<LayoutJob>, galleys: &[Arc<Self>], pixels_per_point: f32) -> Self {
         profiling::function_scope!();
 
@@ -854,10 +1007,23 @@ impl Galley {
             intrinsic_size: Vec2::ZERO,
         };
 
+        let mut paragraph_start = 0;
         for (i, galley) in galleys.iter().enumerate() {
             let current_y_offset = merged_galley.rect.height();
             let is_last_galley = i + 1 == galleys.len();
 
+            // The paragraph only got the sections overlapping it, so its section indices are off by
+            // the number of sections before it:
+            let sections = &merged_galley.job.sections;
+            let section_offset = sections
+                .partition_point(|section| section.byte_range.end <= paragraph_start)
+                as u32;
+            // A row after a `\n` starts in the section of the `\n`:
+            let newline_section = (0 < i).then(|| {
+                sections.partition_point(|section| section.byte_range.end < paragraph_start) as u32
+            });
+            paragraph_start += galley.job.text.len() + 1; // +1 for the `\n`
+
             merged_galley
                 .rows
                 .extend(galley.rows.iter().enumerate().map(|(row_idx, placed_row)| {
@@ -871,9 +1037,20 @@ impl Galley {
                     let is_last_row_in_galley = row_idx + 1 == galley.rows.len();
                     // Since we remove the `\n` when splitting rows, we need to add it back here
                     ends_with_newline |= !is_last_galley && is_last_row_in_galley;
+                    let mut row = Arc::clone(&placed_row.row);
+                    let section_index_at_start = newline_section
+                        .filter(|_| row_idx == 0)
+                        .unwrap_or(row.section_index_at_start + section_offset);
+                    if section_offset != 0 || section_index_at_start != row.section_index_at_start {
+                        let row = Arc::make_mut(&mut row);
+                        row.section_index_at_start = section_index_at_start;
+                        for glyph in &mut row.glyphs {
+                            glyph.section_index += section_offset;
+                        }
+                    }
                     super::PlacedRow {
                         pos: new_pos,
-                        row: Arc::clone(&placed_row.row),
+                        row,
                         ends_with_newline,
                     }
                 }));
@@ -0,0 +1,95 @@
+use egui::{Autocomplete, Key, accesskit::Role};
+use egui_kittest::{Harness, kittest::Queryable as _};
+
+const FRUITS: [&str; 4] = ["Apple", "Banana", "Mango", "Orange"];
+
+#[test]
+fn test_autocomplete_keyboard() {
+    let mut harness = Harness::new_ui_state(
+        |ui, text: &mut String| {
+            ui.add(Autocomplete::new(text, &FRUITS));
+        },
+        String::new(),
+    );
+    harness.get_by_role(Role::TextInput).focus();
+    harness.run();
+    harness.get_by_role(Role::TextInput).type_text("an");
+    harness.run();
+
+    // Apple doesn't match, and the others are sorted by where the match starts:
+    assert!(harness.query_by_label("Apple").is_none());
+    let tops = ["Banana", "Mango", "Orange"].map(|fruit| harness.get_by_label(fruit).rect().top());
+    assert!(tops.is_sorted(), "{tops:?}");
+
+    harness.key_press(Key::ArrowDown);
+    harness.run();
+    harness.key_press(Key::ArrowDown);
+    harness.run();
+    harness.key_press(Key::Enter);
+    harness.run();
+    assert_eq!(harness.state(), "Mango");
+    assert!(harness.query_by_label("Banana").is_none());
+    assert!(harness.get_by_role(Role::TextInput).is_focused());
+}
+
+#[test]
+fn test_autocomplete_click_suggestion() {
+    let mut harness = Harness::new_ui_state(
+        |ui, text: &mut String| {
+            ui.add(Autocomplete::new(text, &FRUITS));
+        },
+        String::new(),
+    );
+    harness.get_by_role(Role::TextInput).focus();
+    harness.run();
+    harness.get_by_role(Role::TextInput).type_text("rng");
+    harness.run();
+
+    harness.get_by_label("Orange").click();
+    harness.run();
+    assert_eq!(harness.state(), "Orange");
+}
+
+#[test]
+fn test_autocomplete_tokens() {
+    let mut harness = Harness::new_ui_state(
+        |ui, (text, tokens): &mut (String, Vec<String>)| {
+            ui.add(Autocomplete::new(text, &FRUITS).tokens(tokens));
+        },
+        (String::new(), Vec::new()),
+    );
+    let input =
+        |harness: &Harness<'_, _>, text| harness.get_by_role(Role::TextInput).type_text(text);
+    harness.get_by_role(Role::TextInput).focus();
+    harness.run();
+
+    // Tab accepts the first suggestion:
+    input(&harness, "ap");
+    harness.run();
+    harness.key_press(Key::Tab);
+    harness.run();
+
+    // Enter adds what was typed:
+    input(&harness, "Kiwi");
+    harness.run();
+    harness.key_press(Key::Enter);
+    harness.run();
+    assert_eq!(harness.state().1, ["Apple", "Kiwi"]);
+    assert_eq!(harness.state().0, "");
+
+    // Tokens aren't suggested again:
+    assert!(harness.query_by_label("Banana").is_some());
+    assert!(
+        harness
+            .query_by_label("Apple")
+            .is_some_and(|node| node.role() != Role::Button)
+    );
+
+    harness.key_press(Key::Backspace);
+    harness.run();
+    assert_eq!(harness.state().1, ["Apple"]);
+
+    harness.get_by_label("🗙").click();
+    harness.run();
+    assert!(harness.state().1.is_empty());
+}
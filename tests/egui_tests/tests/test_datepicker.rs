@@ -0,0 +1,302 @@
+#![expect(clippy::unwrap_used)] // it's a test
+
+use std::ops::RangeInclusive;
+
+use chrono::{
+    DateTime, FixedOffset, MappedLocalTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone,
+};
+use egui::accesskit::Role;
+use egui::{Ui, Vec2};
+use egui_extras::{DateRangePickerButton, DateTimePickerButton, TimePicker};
+use egui_kittest::{Harness, NodeQuery, kittest::Queryable as _};
+
+fn march(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+}
+
+fn time(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+}
+
+/// The button that opens the picker, which shows the selection.
+fn picker_button() -> NodeQuery {
+    NodeQuery::new().role(Role::Button).label_contains("📆")
+}
+
+/// The day of the first month shown by the picker.
+fn day<'h, State>(harness: &'h Harness<'_, State>, day: u32) -> egui_kittest::Node<'h> {
+    harness
+        .find_all(&NodeQuery::button(day.to_string()))
+        .swap_remove(0)
+}
+
+#[test]
+fn test_time_picker_twelve_hour_round_trip() {
+    for hour in 0..24 {
+        let mut harness = Harness::new_ui_state(
+            |ui, (time, twelve_hour): &mut (NaiveTime, bool)| {
+                ui.add(TimePicker::new(time).twelve_hour(*twelve_hour));
+            },
+            (time(hour, 30), true),
+        );
+        harness.run();
+        assert_eq!(harness.state().0, time(hour, 30), "Showing it is no change");
+
+        let hour12 = harness.find(
+            &NodeQuery::new()
+                .role(Role::SpinButton)
+                .numeric_value_in(1.0..=12.0),
+        );
+        assert_eq!(
+            hour12.numeric_value(),
+            Some(f64::from((hour + 11) % 12 + 1))
+        );
+        // Clicking the half of the day the time is in changes nothing:
+        harness
+            .get_by_label(if hour < 12 { "AM" } else { "PM" })
+            .click();
+        harness.run();
+        assert_eq!(harness.state().0, time(hour, 30));
+
+        harness.state_mut().1 = false;
+        harness.run();
+        assert_eq!(harness.state().0, time(hour, 30));
+        let hour24 = harness.find(
+            &NodeQuery::new()
+                .role(Role::SpinButton)
+                .numeric_value_in(0.0..=23.0),
+        );
+        assert_eq!(hour24.numeric_value(), Some(f64::from(hour)));
+    }
+}
+
+#[test]
+fn test_time_picker_am_pm_around_noon() {
+    for (from, click, to) in [
+        (time(11, 59), "PM", time(23, 59)),
+        (time(12, 0), "AM", time(0, 0)),
+        (time(0, 0), "PM", time(12, 0)),
+        (time(12, 30), "PM", time(12, 30)),
+        (time(0, 30), "AM", time(0, 30)),
+    ] {
+        let mut harness = Harness::new_ui_state(
+            |ui, time: &mut NaiveTime| {
+                ui.add(TimePicker::new(time).twelve_hour(true));
+            },
+            from,
+        );
+        harness.run();
+        harness.get_by_label(click).click();
+        harness.run();
+        assert_eq!(*harness.state(), to, "Clicking {click} at {from}");
+    }
+}
+
+struct RangeState {
+    range: RangeInclusive<NaiveDate>,
+    changes: usize,
+}
+
+/// Only days from the 5th to the 25th of March, except the 15th, can be picked.
+fn range_picker(ui: &mut Ui, state: &mut RangeState) {
+    let response = ui.add(
+        DateRangePickerButton::new(&mut state.range)
+            .min_date(march(5))
+            .max_date(march(25))
+            .disabled_dates(|date| date == march(15)),
+    );
+    state.changes += usize::from(response.changed());
+}
+
+#[test]
+fn test_date_range_picker() {
+    let mut harness = Harness::builder()
+        .with_size(Vec2::new(600.0, 400.0))
+        .build_ui_state(
+            range_picker,
+            RangeState {
+                range: march(10)..=march(12),
+                changes: 0,
+            },
+        );
+    harness.run();
+    harness.find(&picker_button()).click();
+    harness.run();
+
+    day(&harness, 4).assert_disabled();
+    day(&harness, 5).assert_enabled();
+    day(&harness, 15).assert_disabled();
+    day(&harness, 25).assert_enabled();
+    day(&harness, 26).assert_disabled();
+
+    // The days can be clicked in reverse order:
+    day(&harness, 20).click();
+    harness.run();
+    harness.find(&NodeQuery::button("Save")).assert_disabled();
+    day(&harness, 8).click();
+    harness.run();
+    harness.get_by_label("Save").click();
+    harness.run();
+    assert_eq!(harness.state().range, march(8)..=march(20));
+    assert_eq!(harness.state().changes, 1);
+    assert!(
+        harness.query_by_label("Save").is_none(),
+        "Saving closes the picker"
+    );
+
+    // Cancel keeps the selection:
+    harness.find(&picker_button()).click();
+    harness.run();
+    day(&harness, 6).click();
+    harness.run();
+    day(&harness, 7).click();
+    harness.run();
+    harness.get_by_label("Cancel").click();
+    harness.run();
+    assert_eq!(harness.state().range, march(8)..=march(20));
+    assert_eq!(harness.state().changes, 1);
+    assert!(harness.query_by_label("Cancel").is_none());
+}
+
+#[test]
+fn test_date_range_picker_selection_outside_the_constraints() {
+    let mut harness = Harness::builder()
+        .with_size(Vec2::new(600.0, 400.0))
+        .build_ui_state(
+            range_picker,
+            RangeState {
+                range: march(1)..=march(10),
+                changes: 0,
+            },
+        );
+    harness.run();
+    harness.find(&picker_button()).click();
+    harness.run();
+    harness.find(&NodeQuery::button("Save")).assert_disabled();
+
+    day(&harness, 5).click();
+    harness.run();
+    day(&harness, 10).click();
+    harness.run();
+    harness.get_by_label("Save").click();
+    harness.run();
+    assert_eq!(harness.state().range, march(5)..=march(10));
+}
+
+/// A time zone like Central Europe's, which sets the clocks forward from 02:00 to 03:00
+/// on the 31st of March 2024, so e.g. 02:30 doesn't exist on that day.
+#[derive(Clone, Copy, Debug)]
+struct SpringForward;
+
+impl SpringForward {
+    /// When the clocks are set forward, in UTC.
+    fn switch() -> NaiveDateTime {
+        march(31).and_time(time(1, 0))
+    }
+
+    fn winter() -> FixedOffset {
+        FixedOffset::east_opt(3600).unwrap()
+    }
+
+    fn summer() -> FixedOffset {
+        FixedOffset::east_opt(2 * 3600).unwrap()
+    }
+}
+
+impl TimeZone for SpringForward {
+    type Offset = FixedOffset;
+
+    fn from_offset(_offset: &FixedOffset) -> Self {
+        Self
+    }
+
+    fn offset_from_local_date(&self, local: &NaiveDate) -> MappedLocalTime<FixedOffset> {
+        self.offset_from_local_datetime(&local.and_time(NaiveTime::MIN))
+    }
+
+    fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> MappedLocalTime<FixedOffset> {
+        let winter = *local - Self::winter() < Self::switch();
+        let summer = Self::switch() <= *local - Self::summer();
+        match (winter, summer) {
+            (true, true) => MappedLocalTime::Ambiguous(Self::winter(), Self::summer()),
+            (true, false) => MappedLocalTime::Single(Self::winter()),
+            (false, true) => MappedLocalTime::Single(Self::summer()),
+            (false, false) => MappedLocalTime::None,
+        }
+    }
+
+    fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+        self.offset_from_utc_datetime(&utc.and_time(NaiveTime::MIN))
+    }
+
+    fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+        if *utc < Self::switch() {
+            Self::winter()
+        } else {
+            Self::summer()
+        }
+    }
+}
+
+#[test]
+fn test_date_time_picker_non_existent_time() {
+    let selection = SpringForward
+        .from_local_datetime(&march(30).and_time(time(2, 30)))
+        .unwrap();
+    let mut harness = Harness::builder()
+        .with_size(Vec2::new(400.0, 400.0))
+        .build_ui_state(
+            |ui, selection: &mut DateTime<SpringForward>| {
+                ui.add(DateTimePickerButton::new(selection));
+            },
+            selection,
+        );
+    harness.run();
+    harness.find(&picker_button()).click();
+    harness.run();
+    harness.find(&NodeQuery::button("Save")).assert_enabled();
+
+    // 02:30 doesn't exist on the day the clocks are set forward:
+    day(&harness, 31).click();
+    harness.run();
+    assert!(harness.query_by_label("This time doesn't exist").is_some());
+    harness.find(&NodeQuery::button("Save")).assert_disabled();
+
+    day(&harness, 29).click();
+    harness.run();
+    assert!(harness.query_by_label("This time doesn't exist").is_none());
+    harness.get_by_label("Save").click();
+    harness.run();
+    assert_eq!(
+        harness.state().naive_local(),
+        march(29).and_time(time(2, 30))
+    );
+}
+
+#[test]
+fn test_date_time_picker_selection_outside_the_constraints() {
+    let selection = SpringForward
+        .from_local_datetime(&march(30).and_time(time(12, 0)))
+        .unwrap();
+    let mut harness = Harness::builder()
+        .with_size(Vec2::new(400.0, 400.0))
+        .build_ui_state(
+            |ui, selection: &mut DateTime<SpringForward>| {
+                ui.add(DateTimePickerButton::new(selection).max_date(march(20)));
+            },
+            selection,
+        );
+    harness.run();
+    harness.find(&picker_button()).click();
+    harness.run();
+    harness.find(&NodeQuery::button("Save")).assert_disabled();
+
+    day(&harness, 20).click();
+    harness.run();
+    harness.get_by_label("Save").click();
+    harness.run();
+    assert_eq!(
+        harness.state().naive_local(),
+        march(20).and_time(time(12, 0))
+    );
+}
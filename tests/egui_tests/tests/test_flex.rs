@@ -0,0 +1,128 @@
+use egui::{Button, Flex, FlexAlign, FlexItem, FlexJustify, Vec2};
+use egui_kittest::{Harness, kittest::Queryable as _};
+
+#[test]
+fn test_flex_grow() {
+    let mut harness = Harness::builder()
+        .with_size(Vec2::new(400.0, 100.0))
+        .build_ui(|ui| {
+            Flex::horizontal().gap(Vec2::splat(10.0)).show(ui, |flex| {
+                flex.add(FlexItem::new(), Button::new("Fixed"));
+                // With a basis of zero, only the grow factors matter:
+                let item = FlexItem::new().basis(0.0);
+                flex.add(item.grow(1.0), Button::new("One"));
+                flex.add(item.grow(2.0), Button::new("Two"));
+                flex.add(item.grow(1.0), Button::new("Three"));
+            });
+        });
+    harness.run();
+
+    let fixed = harness.get_by_label("Fixed").rect();
+    let one = harness.get_by_label("One").rect();
+    let two = harness.get_by_label("Two").rect();
+    let three = harness.get_by_label("Three").rect();
+
+    // The growing buttons fill the rest of the row:
+    assert!(fixed.width() < one.width(), "{fixed:?} {one:?}");
+    assert!((three.max.x - harness.ctx.content_rect().max.x).abs() < 10.0);
+    assert!((one.min.x - fixed.max.x - 10.0).abs() < 1.0);
+
+    // …sharing the left over space 1:2:1:
+    assert!(
+        (two.width() - 2.0 * one.width()).abs() < 2.0,
+        "{one:?} {two:?}"
+    );
+    assert!(
+        (three.width() - one.width()).abs() < 2.0,
+        "{one:?} {three:?}"
+    );
+}
+
+#[test]
+fn test_flex_wrap_and_align() {
+    let mut harness = Harness::builder()
+        .with_size(Vec2::new(200.0, 300.0))
+        .build_ui(|ui| {
+            Flex::horizontal()
+                .wrap(true)
+                .justify(FlexJustify::End)
+                .align_items(FlexAlign::Start)
+                .show(ui, |flex| {
+                    for i in 0..6 {
+                        flex.add(FlexItem::new(), Button::new(format!("Item {i}")));
+                    }
+                });
+
+            Flex::horizontal()
+                .id_salt("align")
+                .align_items(FlexAlign::Start)
+                .show(ui, |flex| {
+                    flex.add_ui(FlexItem::new(), |ui| {
+                        ui.label("Tall\nlabel");
+                    });
+                    flex.add(
+                        FlexItem::new().align_self(FlexAlign::End),
+                        Button::new("End"),
+                    );
+                    flex.add(FlexItem::new(), Button::new("Start"));
+                });
+        });
+    harness.run();
+
+    let rects: Vec<egui::Rect> = (0..6)
+        .map(|i| harness.get_by_label(&format!("Item {i}")).rect())
+        .collect();
+    let right = harness.ctx.content_rect().max.x;
+
+    // Several rows, each ending at the right edge:
+    assert!(rects[0].min.y < rects[5].min.y, "{rects:?}");
+    for rect in &rects {
+        assert!(rect.max.x <= right, "{rect:?} is outside the ui");
+    }
+    let first_row: Vec<_> = rects.iter().filter(|r| r.min.y == rects[0].min.y).collect();
+    assert!(1 < first_row.len() && first_row.len() < 6, "{rects:?}");
+    assert!(right - first_row.last().unwrap().max.x < 10.0, "{rects:?}");
+
+    // Short items next to a tall one are aligned to the start of the row,
+    // unless they override it:
+    let label = harness.get_by_label("Tall\nlabel").rect();
+    let end = harness.get_by_label("End").rect();
+    let start = harness.get_by_label("Start").rect();
+    assert!(
+        (start.min.y - label.min.y).abs() < 1.0,
+        "{start:?} {label:?}"
+    );
+    assert!((end.max.y - label.max.y).abs() < 1.0, "{end:?} {label:?}");
+    assert!(start.min.y < end.min.y, "{start:?} {end:?}");
+}
+
+#[test]
+fn test_flex_remeasures_natural_items() {
+    let mut long = false;
+    let mut harness = Harness::builder()
+        .with_size(Vec2::new(400.0, 100.0))
+        .build_ui_state(
+            |ui, long: &mut bool| {
+                Flex::horizontal().show(ui, |flex| {
+                    let text = if *long {
+                        "A much longer label"
+                    } else {
+                        "Short"
+                    };
+                    flex.add(FlexItem::new(), egui::Label::new(text));
+                    flex.add(FlexItem::new().grow(1.0), Button::new("Rest"));
+                });
+            },
+            long,
+        );
+    harness.run();
+    let before = harness.get_by_label("Rest").rect();
+
+    long = true;
+    *harness.state_mut() = long;
+    harness.run();
+    let label = harness.get_by_label("A much longer label").rect();
+    let after = harness.get_by_label("Rest").rect();
+    assert!(before.min.x < after.min.x, "{before:?} {after:?}");
+    assert!(label.max.x <= after.min.x, "{label:?} {after:?}");
+}
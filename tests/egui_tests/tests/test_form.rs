@@ -0,0 +1,108 @@
+use egui::{Form, Stepper, accesskit::Role, form};
+use egui_kittest::{Harness, kittest::Queryable as _};
+
+#[derive(Default)]
+struct Fields {
+    name: String,
+    email: String,
+    submitted: bool,
+}
+
+fn text_input<'a>(harness: &'a Harness<'_, Fields>, label: &'a str) -> egui_kittest::Node<'a> {
+    harness.get_by_role_and_label(Role::TextInput, label)
+}
+
+#[test]
+fn test_form_shows_errors_and_focuses_first_invalid_on_submit() {
+    let mut harness = Harness::new_ui_state(
+        |ui, fields: &mut Fields| {
+            let response = Form::new().show(ui, |form| {
+                form.field("Name", form::required(&fields.name), |ui| {
+                    ui.text_edit_singleline(&mut fields.name)
+                });
+                form.field("Email", form::required(&fields.email), |ui| {
+                    ui.text_edit_singleline(&mut fields.email)
+                });
+                form.error_summary();
+                form.submit_button("Submit");
+            });
+            fields.submitted |= response.submitted;
+        },
+        Fields::default(),
+    );
+    harness.run();
+
+    // Untouched fields don't show their errors:
+    assert!(harness.query_by_label("This field is required").is_none());
+
+    harness.get_by_label("Submit").click();
+    harness.run();
+    assert!(!harness.state().submitted);
+    assert_eq!(
+        harness.query_all_by_label("This field is required").count(),
+        2
+    );
+    assert!(
+        harness
+            .query_by_label("Name: This field is required")
+            .is_some()
+    );
+    assert!(text_input(&harness, "Name").is_focused());
+
+    text_input(&harness, "Name").type_text("Ferris");
+    text_input(&harness, "Email").focus();
+    harness.run();
+    text_input(&harness, "Email").type_text("ferris@example.com");
+    harness.run();
+    assert!(harness.query_by_label("This field is required").is_none());
+
+    harness.get_by_label("Submit").click();
+    harness.run();
+    assert!(harness.state().submitted);
+}
+
+#[test]
+fn test_stepper_blocks_next_while_invalid() {
+    let mut harness = Harness::new_ui_state(
+        |ui, (step, fields): &mut (usize, Fields)| {
+            let response = Stepper::new(["Name", "Email"]).show(ui, step, |form, step| {
+                if step == 0 {
+                    form.field("Name", form::required(&fields.name), |ui| {
+                        ui.text_edit_singleline(&mut fields.name)
+                    });
+                } else {
+                    form.field("Email", form::required(&fields.email), |ui| {
+                        ui.text_edit_singleline(&mut fields.email)
+                    });
+                }
+            });
+            fields.submitted |= response.finished;
+        },
+        (0, Fields::default()),
+    );
+    harness.run();
+
+    harness.get_by_label("Next").click();
+    harness.run();
+    assert_eq!(harness.state().0, 0);
+    assert!(harness.query_by_label("This field is required").is_some());
+
+    harness
+        .get_by_role_and_label(Role::TextInput, "Name")
+        .type_text("Ferris");
+    harness.run();
+    harness.get_by_label("Next").click();
+    harness.run();
+    assert_eq!(harness.state().0, 1);
+
+    // The errors of a new step are hidden until the user tries to continue:
+    assert!(harness.query_by_label("This field is required").is_none());
+    harness.get_by_label("Finish").click();
+    harness.run();
+    assert!(!harness.state().1.submitted);
+
+    harness.get_by_label("Back").click();
+    harness.run();
+    assert_eq!(harness.state().0, 0);
+    assert_eq!(harness.state().1.name, "Ferris");
+}
@@ -0,0 +1,107 @@
+use egui::{Align, Align2, Grid, GridCell, GridColumn, TextEdit};
+use egui_kittest::{Harness, kittest::Queryable as _};
+
+#[test]
+fn test_grid_col_span() {
+    let mut harness = Harness::new_ui(|ui| {
+        Grid::new("grid").show(ui, |ui| {
+            ui.grid_cell(GridCell::new().col_span(2), |ui| {
+                ui.label("A heading that is wider than one column");
+            });
+            ui.label("C");
+            ui.end_row();
+
+            ui.label("a");
+            ui.label("b");
+            ui.label("c");
+            ui.end_row();
+        });
+    });
+    harness.run();
+
+    let heading = harness
+        .get_by_label("A heading that is wider than one column")
+        .rect();
+    let a = harness.get_by_label("a").rect();
+    let b = harness.get_by_label("b").rect();
+    let c = harness.get_by_label("c").rect();
+    let upper_c = harness.get_by_label("C").rect();
+
+    assert_eq!(heading.left(), a.left());
+    assert!(b.left() < heading.right(), "{heading:?} {b:?}");
+    assert!(heading.right() < c.left(), "{heading:?} {c:?}");
+    assert_eq!(upper_c.left(), c.left());
+}
+
+#[test]
+fn test_grid_row_span_and_col() {
+    let mut harness = Harness::new_ui(|ui| {
+        Grid::new("grid").show(ui, |ui| {
+            ui.label("Name");
+            ui.grid_cell(GridCell::new().row_span(2), |ui| {
+                ui.add(TextEdit::multiline(&mut String::from("Notes")).desired_rows(3));
+            });
+            ui.label("first");
+            ui.end_row();
+
+            ui.label("Email");
+            ui.label("second");
+            ui.end_row();
+
+            ui.label("Phone");
+            ui.grid_cell(GridCell::new().col(2), |ui| ui.label("third"));
+            ui.end_row();
+        });
+    });
+    harness.run();
+
+    let editor = harness.get_by_value("Notes").rect();
+    let first = harness.get_by_label("first").rect();
+    let second = harness.get_by_label("second").rect();
+    let third = harness.get_by_label("third").rect();
+    let email = harness.get_by_label("Email").rect();
+    let phone = harness.get_by_label("Phone").rect();
+
+    // "second" skips the column covered by the editor:
+    assert_eq!(first.left(), second.left());
+    assert!(editor.right() < second.left());
+    assert!(email.top() < editor.bottom(), "{email:?} {editor:?}");
+
+    // The next row starts below the editor:
+    assert!(editor.bottom() < phone.top(), "{editor:?} {phone:?}");
+    assert_eq!(third.left(), first.left());
+}
+
+#[test]
+fn test_grid_alignment() {
+    let mut harness = Harness::new_ui(|ui| {
+        Grid::new("grid")
+            .column(1, GridColumn::new().align(Align::RIGHT).min_width(100.0))
+            .show(ui, |ui| {
+                ui.label("Apples");
+                ui.label("1.50");
+                ui.end_row();
+
+                ui.label("Melons");
+                ui.label("112.00");
+                ui.end_row();
+
+                ui.label("Total");
+                ui.grid_cell(GridCell::new().align(Align2::LEFT_CENTER), |ui| {
+                    ui.label("113.50");
+                });
+                ui.end_row();
+            });
+    });
+    harness.run();
+
+    let apples = harness.get_by_label("1.50").rect();
+    let melons = harness.get_by_label("112.00").rect();
+    let total = harness.get_by_label("113.50").rect();
+    assert_eq!(apples.right(), melons.right());
+    assert!(
+        100.0 <= melons.right() - total.left(),
+        "{melons:?} {total:?}"
+    );
+    assert!(total.width() < 100.0);
+}
@@ -0,0 +1,74 @@
+use egui::{
+    Color32, ScrollArea,
+    accesskit::Role,
+    scroll_area::{Minimap, MinimapMarker},
+};
+use egui_extras::{Column, TableBuilder};
+use egui_kittest::{Harness, kittest::Queryable as _};
+
+#[test]
+fn test_minimap_click_to_jump() {
+    let mut harness = Harness::builder()
+        .with_size(egui::vec2(300.0, 300.0))
+        .build_ui_state(
+            |ui, offset: &mut f32| {
+                let minimap = Minimap::new().marker(MinimapMarker::new(0.5..=0.51, Color32::RED));
+                let output = ScrollArea::vertical()
+                    .animated(false)
+                    .minimap(minimap)
+                    .show(ui, |ui| {
+                        for line in 0..100 {
+                            ui.label(format!("Line {line}"));
+                        }
+                    });
+                *offset = output.state.offset.y;
+            },
+            0.0,
+        );
+    harness.run();
+    assert_eq!(*harness.state(), 0.0);
+
+    // Clicking the middle of the minimap scrolls to the middle of the content:
+    harness.get_by_role(Role::ScrollBar).click();
+    harness.run();
+
+    assert!(0.0 < *harness.state());
+    let top = harness.get_by_label("Line 50").rect().top();
+    assert!(
+        (0.0..300.0).contains(&top),
+        "Line 50 should be visible, but is at {top}"
+    );
+}
+
+#[test]
+fn test_table_minimap() {
+    let mut harness = Harness::builder()
+        .with_size(egui::vec2(300.0, 300.0))
+        .build_ui_state(
+            |ui, offset: &mut f32| {
+                let output = TableBuilder::new(ui)
+                    .animate_scrolling(false)
+                    .minimap(Minimap::new())
+                    .column(Column::remainder())
+                    .body(|body| {
+                        body.rows(18.0, 100, |mut row| {
+                            let index = row.index();
+                            row.col(|ui| {
+                                ui.label(format!("Row {index}"));
+                            });
+                        });
+                    });
+                *offset = output.state.offset.y;
+            },
+            0.0,
+        );
+    harness.run();
+    assert_eq!(*harness.state(), 0.0);
+
+    harness.get_by_role(Role::ScrollBar).click();
+    harness.run();
+
+    assert!(0.0 < *harness.state());
+    assert!(harness.query_by_label("Row 0").is_none());
+    assert!(harness.query_by_label("Row 50").is_some());
+}
@@ -0,0 +1,127 @@
+use egui::{
+    Align, Button, FontSelection, Label, Rect, RichText, TextEdit, Vec2,
+    text::{LayoutJob, Placeholder},
+    vec2,
+};
+use egui_kittest::{Harness, kittest::Queryable as _};
+
+fn assert_rect_eq(a: Rect, b: Rect) {
+    assert!(
+        (a.min - b.min).length() < 0.01 && (a.max - b.max).length() < 0.01,
+        "{a:?} != {b:?}"
+    );
+}
+
+#[derive(Default)]
+struct State {
+    clicks: usize,
+    placeholder: Option<Rect>,
+    label: Option<Rect>,
+}
+
+#[test]
+fn button_inside_label_placeholder() {
+    let mut harness = Harness::builder()
+        .with_size(Vec2::new(300.0, 100.0))
+        .build_ui_state(
+            |ui, state: &mut State| {
+                let style = ui.style();
+                let mut job = LayoutJob::default();
+                RichText::new("Press ").append_to(
+                    &mut job,
+                    style,
+                    FontSelection::Default,
+                    Align::Center,
+                );
+                RichText::new("").append_placeholder_to(
+                    &mut job,
+                    Placeholder::new(vec2(40.0, 20.0)),
+                    style,
+                    FontSelection::Default,
+                    Align::Center,
+                );
+                RichText::new(" to continue").append_to(
+                    &mut job,
+                    style,
+                    FontSelection::Default,
+                    Align::Center,
+                );
+
+                let output = Label::new(job).selectable(false).show(ui);
+                let rects: Vec<Rect> = output.placeholder_rects().collect();
+                assert_eq!(rects.len(), 1);
+                if ui.put(rects[0], Button::new("OK")).clicked() {
+                    state.clicks += 1;
+                }
+                state.placeholder = Some(rects[0]);
+                state.label = Some(output.response.rect);
+            },
+            State::default(),
+        );
+
+    let placeholder = harness.state().placeholder.unwrap();
+    let label = harness.state().label.unwrap();
+    assert!((placeholder.size() - vec2(40.0, 20.0)).length() < 0.01);
+    assert!(
+        label.contains_rect(placeholder),
+        "{placeholder:?} not in {label:?}"
+    );
+
+    // The text continues after the placeholder:
+    let press = harness.get_by_label("Press \u{FFFC} to continue").rect();
+    assert!(placeholder.left() > press.left());
+    assert!(placeholder.right() < press.right());
+
+    let button = harness.get_by_label("OK");
+    assert_rect_eq(button.rect(), placeholder);
+    button.click();
+    harness.run();
+    assert_eq!(harness.state().clicks, 1);
+}
+
+#[test]
+fn widget_inside_text_edit_placeholder() {
+    let harness = Harness::builder()
+        .with_size(Vec2::new(300.0, 100.0))
+        .build_ui_state(
+            |ui, state: &mut State| {
+                let mut text = format!("Name: {} !", Placeholder::CHAR);
+                let mut layouter = |ui: &egui::Ui, buf: &dyn egui::TextBuffer, _wrap_width| {
+                    let style = ui.style();
+                    let mut job = LayoutJob::default();
+                    for (i, part) in buf.as_str().split(Placeholder::CHAR).enumerate() {
+                        if 0 < i {
+                            RichText::new("").append_placeholder_to(
+                                &mut job,
+                                Placeholder::new(vec2(60.0, 20.0)),
+                                style,
+                                FontSelection::Default,
+                                Align::Center,
+                            );
+                        }
+                        RichText::new(part).append_to(
+                            &mut job,
+                            style,
+                            FontSelection::Default,
+                            Align::Center,
+                        );
+                    }
+                    ui.fonts_mut(|f| f.layout_job(job))
+                };
+                let output = TextEdit::singleline(&mut text)
+                    .layouter(&mut layouter)
+                    .show(ui);
+                let rect = output.placeholder_rects().next().unwrap();
+                ui.put(rect, Button::new("Add"));
+                state.placeholder = Some(rect);
+                state.label = Some(output.response.rect);
+            },
+            State::default(),
+        );
+
+    let placeholder = harness.state().placeholder.unwrap();
+    let text_edit = harness.state().label.unwrap();
+    assert!((placeholder.size() - vec2(60.0, 20.0)).length() < 0.01);
+    assert!(text_edit.contains_rect(placeholder));
+    assert_rect_eq(harness.get_by_label("Add").rect(), placeholder);
+}
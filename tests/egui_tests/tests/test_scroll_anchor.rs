@@ -0,0 +1,83 @@
+use egui::{Id, ScrollArea, UiBuilder, Vec2b};
+use egui_kittest::{Harness, kittest::Queryable as _};
+
+struct State {
+    /// The first message in the log; lower numbers are older messages.
+    first: i32,
+    scroll_to: Option<i32>,
+    near_start: Vec2b,
+    near_end: Vec2b,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            first: 0,
+            scroll_to: None,
+            near_start: Vec2b::FALSE,
+            near_end: Vec2b::FALSE,
+        }
+    }
+}
+
+/// A message log that keeps message 50 in place.
+fn message_log(ui: &mut egui::Ui, state: &mut State) {
+    let anchor = Id::new(50);
+    let output = ScrollArea::vertical()
+        .animated(false)
+        .anchor_id(anchor)
+        .show(ui, |ui| {
+            for message in state.first..100 {
+                let response = ui
+                    .scope_builder(UiBuilder::new().id(message), |ui| {
+                        ui.label(format!("Message {message}"));
+                    })
+                    .response;
+                if state.scroll_to == Some(message) {
+                    response.scroll_to_me(Some(egui::Align::TOP));
+                }
+            }
+        });
+    state.scroll_to = None;
+    state.near_start = output.is_near_start(50.0);
+    state.near_end = output.is_near_end(50.0);
+}
+
+#[test]
+fn test_anchor_stays_in_place_when_content_is_prepended() {
+    let mut harness = Harness::builder()
+        .with_size(egui::vec2(200.0, 300.0))
+        .build_ui_state(message_log, State::default());
+    harness.state_mut().scroll_to = Some(50);
+    harness.run();
+
+    let top = harness.get_by_label("Message 50").rect().top();
+    assert!(
+        top < 20.0,
+        "Message 50 should be at the top, but is at {top}"
+    );
+    assert!(!harness.state().near_start.y);
+    assert!(!harness.state().near_end.y);
+
+    // Load older messages:
+    harness.state_mut().first = -100;
+    harness.run();
+
+    let new_top = harness.get_by_label("Message 50").rect().top();
+    assert!((new_top - top).abs() < 1.0, "{top} != {new_top}");
+}
+
+#[test]
+fn test_near_edges() {
+    let mut harness = Harness::builder()
+        .with_size(egui::vec2(200.0, 300.0))
+        .build_ui_state(message_log, State::default());
+    harness.run();
+    assert!(harness.state().near_start.y);
+    assert!(!harness.state().near_end.y);
+
+    harness.state_mut().scroll_to = Some(99);
+    harness.run();
+    assert!(!harness.state().near_start.y);
+    assert!(harness.state().near_end.y);
+}
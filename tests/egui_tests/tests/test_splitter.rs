@@ -0,0 +1,132 @@
+use egui::{Rect, Splitter, SplitterPane, Ui, Vec2, pos2};
+use egui_kittest::Harness;
+
+/// The rect of each pane, or `None` if it is collapsed.
+type Panes = Vec<Option<Rect>>;
+
+/// Three panes sized 1:2:1, where the first can be collapsed.
+fn three_panes(ui: &mut Ui, panes: &mut Panes) {
+    *panes = Splitter::horizontal()
+        .gap(10.0)
+        .pane(SplitterPane::new().min_size(50.0).collapsible(true))
+        .pane(SplitterPane::new().weight(2.0))
+        .pane(SplitterPane::new())
+        .show(ui, |splitter| {
+            (0..3)
+                .map(|_| splitter.pane(|ui| ui.max_rect()).map(|r| r.inner))
+                .collect()
+        })
+        .inner;
+}
+
+fn width(harness: &Harness<'_, Panes>, pane: usize) -> f32 {
+    harness.state()[pane].map_or(0.0, |rect| rect.width())
+}
+
+fn drag_handle(harness: &mut Harness<'_, Panes>, dx: f32) {
+    let first = harness.state()[0].unwrap_or(Rect::NOTHING);
+    let handle = pos2(first.max.x + 5.0, first.center().y);
+    harness.hover_at(handle);
+    harness.drag_at(handle);
+    harness.run();
+    harness.hover_at(handle + egui::vec2(dx, 0.0));
+    harness.run();
+    harness.drop_at(handle + egui::vec2(dx, 0.0));
+    harness.run();
+}
+
+#[test]
+fn test_splitter_weights() {
+    let mut harness = Harness::builder()
+        .with_size(Vec2::new(400.0, 200.0))
+        .build_ui_state(three_panes, Panes::new());
+    harness.run();
+
+    let space = width(&harness, 0) + width(&harness, 1) + width(&harness, 2);
+    assert!((space + 20.0 - harness.ctx.content_rect().width()).abs() < 20.0);
+    assert!((width(&harness, 1) - 2.0 * width(&harness, 0)).abs() < 1.0);
+    assert!((width(&harness, 2) - width(&harness, 0)).abs() < 1.0);
+}
+
+#[test]
+fn test_splitter_drag_and_min_size() {
+    let mut harness = Harness::builder()
+        .with_size(Vec2::new(400.0, 200.0))
+        .build_ui_state(three_panes, Panes::new());
+    harness.run();
+    let [first, second] = [0, 1].map(|pane| width(&harness, pane));
+
+    drag_handle(&mut harness, 30.0);
+    assert!((width(&harness, 0) - first - 30.0).abs() < 1.0);
+    assert!((width(&harness, 1) - second + 30.0).abs() < 1.0);
+
+    // Can't make the first pane smaller than its min size:
+    drag_handle(&mut harness, -200.0);
+    assert!((width(&harness, 0) - 50.0).abs() < 1.0);
+}
+
+#[test]
+fn test_splitter_double_click_to_collapse() {
+    let mut harness = Harness::builder()
+        .with_size(Vec2::new(400.0, 200.0))
+        // Short enough for two clicks to be a double-click:
+        .with_step_dt(1.0 / 60.0)
+        .build_ui_state(three_panes, Panes::new());
+    harness.run();
+    let first = width(&harness, 0);
+
+    let double_click = |harness: &mut Harness<'_, Panes>, pos| {
+        harness.hover_at(pos);
+        for pressed in [true, false, true, false] {
+            harness.event(egui::Event::PointerButton {
+                pos,
+                button: egui::PointerButton::Primary,
+                pressed,
+                modifiers: egui::Modifiers::NONE,
+            });
+        }
+        harness.run();
+    };
+
+    let rect = harness.state()[0].unwrap_or(Rect::NOTHING);
+    double_click(&mut harness, pos2(rect.max.x + 5.0, rect.center().y));
+    assert_eq!(harness.state()[0], None);
+
+    // Double-click again to bring it back at the same size
+    // (after waiting, so it isn't a triple-click):
+    harness.run_steps(60);
+    let rect = harness.state()[1].unwrap_or(Rect::NOTHING);
+    double_click(&mut harness, pos2(rect.min.x - 5.0, rect.center().y));
+    assert!((width(&harness, 0) - first).abs() < 1.0);
+}
+
+#[test]
+fn test_splitter_panes_come_from_the_builder() {
+    let mut harness = Harness::builder()
+        .with_size(Vec2::new(400.0, 200.0))
+        .build_ui_state(
+            |ui, (min_size, panes): &mut (f32, Panes)| {
+                *panes = Splitter::horizontal()
+                    .id_salt("splitter")
+                    .gap(10.0)
+                    .pane(SplitterPane::new())
+                    .pane(SplitterPane::new().weight(3.0).min_size(*min_size))
+                    .show(ui, |splitter| {
+                        (0..2)
+                            .map(|_| splitter.pane(|ui| ui.max_rect()).map(|r| r.inner))
+                            .collect()
+                    })
+                    .inner;
+            },
+            (0.0, Panes::new()),
+        );
+    harness.run();
+    let [first, second] = [0, 1].map(|pane| harness.state().1[pane].map_or(0.0, |r| r.width()));
+    assert!((second - 3.0 * first).abs() < 1.0, "{first} {second}");
+
+    // A new min size applies right away, without resetting the sizes:
+    harness.state_mut().0 = 350.0;
+    harness.run();
+    let second = harness.state().1[1].map_or(0.0, |rect| rect.width());
+    assert!((second - 350.0).abs() < 1.0, "{second}");
+}
@@ -0,0 +1,149 @@
+use egui::{Key, Modifiers, PointerButton, Tab, TabBar, Vec2};
+use egui_kittest::{Harness, kittest::Queryable as _};
+
+struct State {
+    tabs: Vec<&'static str>,
+    active: Option<usize>,
+    closed: Vec<&'static str>,
+
+    /// How many times the active tab changed.
+    changes: usize,
+}
+
+impl State {
+    fn new(tabs: &[&'static str]) -> Self {
+        Self {
+            tabs: tabs.to_vec(),
+            active: None,
+            closed: Vec::new(),
+            changes: 0,
+        }
+    }
+}
+
+fn tab_bar(ui: &mut egui::Ui, state: &mut State) {
+    let response = TabBar::new().show(ui, &mut state.tabs, |&title| {
+        Tab::new(title, title).pinned(title == "pinned")
+    });
+    state.active = response.active;
+    state.closed.extend(response.closed);
+    state.changes += usize::from(response.changed);
+}
+
+fn active(harness: &Harness<'_, State>) -> Option<&'static str> {
+    harness
+        .state()
+        .active
+        .map(|active| harness.state().tabs[active])
+}
+
+#[test]
+fn test_tab_bar_select_and_keyboard() {
+    let mut harness = Harness::builder()
+        .with_size(Vec2::new(400.0, 100.0))
+        .build_ui_state(tab_bar, State::new(&["a.rs", "b.rs", "c.rs"]));
+    harness.run();
+    assert_eq!(active(&harness), Some("a.rs"));
+    assert_eq!(
+        harness.state().changes,
+        0,
+        "Showing the first tab is not a change"
+    );
+
+    harness.get_by_label("c.rs").click();
+    harness.run();
+    assert_eq!(active(&harness), Some("c.rs"));
+    assert_eq!(harness.state().changes, 1);
+
+    harness.key_press_modifiers(Modifiers::CTRL, Key::Tab);
+    harness.run();
+    assert_eq!(active(&harness), Some("a.rs"));
+
+    harness.key_press_modifiers(Modifiers::CTRL, Key::PageUp);
+    harness.run();
+    assert_eq!(active(&harness), Some("c.rs"));
+}
+
+#[test]
+fn test_tab_bar_close() {
+    let mut harness = Harness::builder()
+        .with_size(Vec2::new(400.0, 100.0))
+        .build_ui_state(tab_bar, State::new(&["pinned", "a.rs", "b.rs", "c.rs"]));
+    harness.run();
+    harness.get_by_label("b.rs").click();
+    harness.run();
+
+    // Pinned tabs have no close button:
+    let close_buttons = harness.get_all_by_label("Close tab").count();
+    assert_eq!(close_buttons, 3);
+
+    // Closing the active tab makes the next one active:
+    harness
+        .get_all_by_label("Close tab")
+        .nth(1)
+        .unwrap()
+        .click();
+    harness.run();
+    assert_eq!(harness.state().tabs, ["pinned", "a.rs", "c.rs"]);
+    assert_eq!(harness.state().closed, ["b.rs"]);
+    assert_eq!(active(&harness), Some("c.rs"));
+
+    harness
+        .get_by_label("a.rs")
+        .click_button(PointerButton::Middle);
+    harness.run();
+    assert_eq!(harness.state().tabs, ["pinned", "c.rs"]);
+    assert_eq!(active(&harness), Some("c.rs"));
+}
+
+#[test]
+fn test_tab_bar_drag_to_move() {
+    let mut harness = Harness::builder()
+        .with_size(Vec2::new(400.0, 100.0))
+        .build_ui_state(tab_bar, State::new(&["pinned", "a.rs", "b.rs", "c.rs"]));
+    harness.run();
+
+    let from = harness.get_by_label("a.rs").rect().center();
+    let to = harness.get_by_label("c.rs").rect().right_center();
+    harness.hover_at(from);
+    harness.drag_at(from);
+    harness.run();
+    harness.hover_at(to);
+    harness.run();
+    harness.drop_at(to);
+    harness.run();
+    assert_eq!(harness.state().tabs, ["pinned", "b.rs", "c.rs", "a.rs"]);
+
+    // Other tabs can't be moved in front of the pinned ones:
+    let from = harness.get_by_label("c.rs").rect().center();
+    let to = harness.get_by_label("pinned").rect().left_center();
+    harness.hover_at(from);
+    harness.drag_at(from);
+    harness.run();
+    harness.hover_at(to);
+    harness.run();
+    harness.drop_at(to);
+    harness.run();
+    assert_eq!(harness.state().tabs, ["pinned", "c.rs", "b.rs", "a.rs"]);
+}
+
+#[test]
+fn test_tab_bar_overflow_menu() {
+    let tabs = ["first.rs", "second.rs", "third.rs", "fourth.rs", "fifth.rs"];
+    let mut harness = Harness::builder()
+        .with_size(Vec2::new(250.0, 100.0))
+        .build_ui_state(tab_bar, State::new(&tabs));
+    harness.run();
+    assert!(harness.query_by_label("first.rs").is_some());
+    assert!(harness.query_by_label("fifth.rs").is_none());
+
+    harness.get_by_label("⏷").click();
+    harness.run();
+    harness.get_by_label("fifth.rs").click();
+    harness.run();
+
+    // The active tab is always shown in the bar:
+    assert_eq!(active(&harness), Some("fifth.rs"));
+    assert!(harness.query_by_label("fifth.rs").is_some());
+    assert!(harness.query_by_label("first.rs").is_some());
+}
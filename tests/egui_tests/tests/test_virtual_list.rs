@@ -0,0 +1,135 @@
+use egui::{Align, VirtualList};
+use egui_kittest::{Harness, kittest::Queryable as _};
+
+/// Rows of different heights.
+fn add_row(ui: &mut egui::Ui, label: usize, extra_lines: usize) {
+    ui.label(format!("Row {label}"));
+    for line in 0..label % 3 + extra_lines {
+        ui.label(format!("line {line}"));
+    }
+}
+
+#[test]
+fn test_only_visible_rows_are_shown() {
+    let mut harness = Harness::builder()
+        .with_size(egui::vec2(300.0, 400.0))
+        .build_ui_state(
+            |ui, shown: &mut std::ops::Range<usize>| {
+                *shown = VirtualList::new()
+                    .show(ui, 100_000, |ui, index| add_row(ui, index, 0))
+                    .inner;
+            },
+            0..0,
+        );
+    harness.run();
+
+    let shown = harness.state().clone();
+    assert_eq!(shown.start, 0);
+    assert!(5 < shown.len() && shown.len() < 50, "{shown:?}");
+    assert!(harness.query_by_label("Row 0").is_some());
+    assert!(harness.query_by_label("Row 500").is_none());
+}
+
+#[test]
+fn test_scroll_to_item_and_keep_anchor() {
+    struct State {
+        tall: bool,
+        scroll_to: Option<usize>,
+    }
+
+    let mut harness = Harness::builder()
+        .with_size(egui::vec2(300.0, 400.0))
+        .build_ui_state(
+            |ui, state: &mut State| {
+                let mut list = VirtualList::new();
+                if let Some(index) = state.scroll_to.take() {
+                    list = list.scroll_to_item(index, Align::TOP);
+                }
+                let extra_lines = usize::from(state.tall) * 4;
+                list.show(ui, 100_000, |ui, index| add_row(ui, index, extra_lines));
+            },
+            State {
+                tall: false,
+                scroll_to: None,
+            },
+        );
+    harness.run();
+
+    harness.state_mut().scroll_to = Some(50_000);
+    harness.run();
+    let top = harness.get_by_label("Row 50000").rect().top();
+    assert!(
+        top < 20.0,
+        "Row 50000 should be at the top, but is at {top}"
+    );
+
+    // All rows get taller, which changes the estimated height of the rows above,
+    // but the row at the top should stay where it is:
+    harness.state_mut().tall = true;
+    harness.run();
+    let new_top = harness.get_by_label("Row 50000").rect().top();
+    assert!((new_top - top).abs() < 1.0, "{top} != {new_top}");
+}
+
+#[test]
+fn test_rows_inserted_at_start() {
+    struct State {
+        labels: Vec<usize>,
+        inserted: usize,
+        scroll_to: Option<usize>,
+    }
+
+    let mut harness = Harness::builder()
+        .with_size(egui::vec2(300.0, 400.0))
+        .build_ui_state(
+            |ui, state: &mut State| {
+                let mut list =
+                    VirtualList::new().rows_inserted_at_start(std::mem::take(&mut state.inserted));
+                if let Some(index) = state.scroll_to.take() {
+                    list = list.scroll_to_item(index, Align::TOP);
+                }
+                list.show(ui, state.labels.len(), |ui, index| {
+                    add_row(ui, state.labels[index], 0);
+                });
+            },
+            State {
+                labels: (1_000..2_000).collect(),
+                inserted: 0,
+                scroll_to: None,
+            },
+        );
+    harness.run();
+
+    harness.state_mut().scroll_to = Some(500);
+    harness.run();
+    let top = harness.get_by_label("Row 1500").rect().top();
+
+    // Load older rows, which the user doesn't see yet:
+    let state = harness.state_mut();
+    state.labels.splice(0..0, 0..1_000);
+    state.inserted = 1_000;
+    harness.run();
+    let new_top = harness.get_by_label("Row 1500").rect().top();
+    assert!((new_top - top).abs() < 1.0, "{top} != {new_top}");
+}
+
+#[test]
+fn test_stick_to_bottom() {
+    let mut harness = Harness::builder()
+        .with_size(egui::vec2(300.0, 400.0))
+        .build_ui_state(
+            |ui, rows: &mut usize| {
+                VirtualList::new()
+                    .stick_to_bottom(true)
+                    .show(ui, *rows, |ui, index| add_row(ui, index, 0));
+            },
+            100,
+        );
+    harness.run();
+    assert!(harness.query_by_label("Row 99").is_some());
+
+    *harness.state_mut() = 200;
+    harness.run();
+    assert!(harness.query_by_label("Row 199").is_some());
+    assert!(harness.query_by_label("Row 99").is_none());
+}